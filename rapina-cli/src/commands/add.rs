@@ -2,14 +2,14 @@ use colored::Colorize;
 use std::fs;
 use std::path::Path;
 
-struct FieldInfo {
+pub(crate) struct FieldInfo {
     name: String,
     rust_type: String,
     schema_type: String,
     column_method: String,
 }
 
-fn parse_field(input: &str) -> Result<FieldInfo, String> {
+pub(crate) fn parse_field(input: &str) -> Result<FieldInfo, String> {
     let parts: Vec<&str> = input.splitn(2, ':').collect();
     if parts.len() != 2 {
         return Err(format!(
@@ -92,7 +92,7 @@ fn validate_resource_name(name: &str) -> Result<(), String> {
     Ok(())
 }
 
-fn to_pascal_case(s: &str) -> String {
+pub(crate) fn to_pascal_case(s: &str) -> String {
     s.split('_')
         .map(|part| {
             let mut chars = part.chars();
@@ -108,7 +108,7 @@ fn to_pascal_case(s: &str) -> String {
         .collect()
 }
 
-fn pluralize(s: &str) -> String {
+pub(crate) fn pluralize(s: &str) -> String {
     format!("{}s", s)
 }
 
@@ -130,11 +130,17 @@ fn verify_rapina_project() -> Result<(), String> {
     Ok(())
 }
 
-fn generate_mod_rs() -> String {
+pub(crate) fn generate_mod_rs() -> String {
     "pub mod dto;\npub mod error;\npub mod handlers;\n".to_string()
 }
 
-fn generate_handlers(singular: &str, plural: &str, pascal: &str, fields: &[FieldInfo]) -> String {
+pub(crate) fn generate_handlers(
+    singular: &str,
+    plural: &str,
+    pascal: &str,
+    fields: &[FieldInfo],
+    versioned: bool,
+) -> String {
     let create_fields: Vec<String> = fields
         .iter()
         .map(|f| format!("        {}: Set(input.{}),", f.name, f.name))
@@ -152,9 +158,22 @@ fn generate_handlers(singular: &str, plural: &str, pascal: &str, fields: &[Field
         .collect();
     let update_body = update_checks.join("\n");
 
+    // Versioned resources only save when the client sent the current version
+    let (database_imports, update_save) = if versioned {
+        (
+            "Db, DbError, update_versioned",
+            "let result = update_versioned(active, update.version, db.conn()).await?;",
+        )
+    } else {
+        (
+            "Db, DbError",
+            "let result = active.update(db.conn()).await.map_err(DbError)?;",
+        )
+    };
+
     format!(
         r#"use rapina::prelude::*;
-use rapina::database::{{Db, DbError}};
+use rapina::database::{{{database_imports}}};
 use rapina::sea_orm::{{ActiveModelTrait, EntityTrait, IntoActiveModel, Set}};
 
 use crate::entity::{pascal};
@@ -208,7 +227,7 @@ pub async fn update_{singular}(db: Db, id: Path<i32>, body: Json<Update{pascal}>
     let mut active: ActiveModel = item.into_active_model();
 {update_body}
 
-    let result = active.update(db.conn()).await.map_err(DbError)?;
+    {update_save}
     Ok(Json(result))
 }}
 
@@ -231,19 +250,27 @@ pub async fn delete_{singular}(db: Db, id: Path<i32>) -> Result<Json<serde_json:
         plural = plural,
         create_body = create_body,
         update_body = update_body,
+        database_imports = database_imports,
+        update_save = update_save,
     )
 }
 
-fn generate_dto(pascal: &str, fields: &[FieldInfo]) -> String {
+pub(crate) fn generate_dto(pascal: &str, fields: &[FieldInfo], versioned: bool) -> String {
     let create_fields: Vec<String> = fields
         .iter()
         .map(|f| format!("    pub {}: {},", f.name, f.rust_type))
         .collect();
 
-    let update_fields: Vec<String> = fields
+    let mut update_fields: Vec<String> = fields
         .iter()
         .map(|f| format!("    pub {}: Option<{}>,", f.name, f.rust_type))
         .collect();
+    if versioned {
+        update_fields.push(
+            "    /// The version the client last read; the update is rejected if it changed.\n    pub version: i32,"
+                .to_string(),
+        );
+    }
 
     format!(
         r#"use rapina::schemars::{{self, JsonSchema}};
@@ -265,7 +292,18 @@ pub struct Update{pascal} {{
     )
 }
 
-fn generate_error(pascal: &str) -> String {
+pub(crate) fn generate_error(pascal: &str, versioned: bool) -> String {
+    let conflict_variant = if versioned {
+        r#"
+            ErrorVariant {
+                status: 409,
+                code: "CONFLICT",
+                description: "Version mismatch, the record was modified by another request",
+            },"#
+    } else {
+        ""
+    };
+
     format!(
         r#"use rapina::database::DbError;
 use rapina::prelude::*;
@@ -289,7 +327,7 @@ impl DocumentedError for {pascal}Error {{
                 status: 404,
                 code: "NOT_FOUND",
                 description: "{pascal} not found",
-            }},
+            }},{conflict_variant}
             ErrorVariant {{
                 status: 500,
                 code: "DATABASE_ERROR",
@@ -306,10 +344,11 @@ impl From<DbError> for {pascal}Error {{
 }}
 "#,
         pascal = pascal,
+        conflict_variant = conflict_variant,
     )
 }
 
-fn generate_schema_block(pascal: &str, fields: &[FieldInfo]) -> String {
+pub(crate) fn generate_schema_block(pascal: &str, fields: &[FieldInfo], versioned: bool) -> String {
     let schema_fields: Vec<String> = fields
         .iter()
         .map(|f| format!("        {}: {},", f.name, f.schema_type))
@@ -318,18 +357,24 @@ fn generate_schema_block(pascal: &str, fields: &[FieldInfo]) -> String {
     format!(
         r#"
 schema! {{
-    {pascal} {{
+    {attrs}{pascal} {{
 {fields}
     }}
 }}
 "#,
+        attrs = if versioned { "#[versioned]\n    " } else { "" },
         pascal = pascal,
         fields = schema_fields.join("\n"),
     )
 }
 
-fn generate_migration(plural: &str, pascal_plural: &str, fields: &[FieldInfo]) -> String {
-    let column_defs: Vec<String> = fields
+pub(crate) fn generate_migration(
+    plural: &str,
+    pascal_plural: &str,
+    fields: &[FieldInfo],
+    versioned: bool,
+) -> String {
+    let mut column_defs: Vec<String> = fields
         .iter()
         .map(|f| {
             let iden = to_pascal_case(&f.name);
@@ -342,11 +387,19 @@ fn generate_migration(plural: &str, pascal_plural: &str, fields: &[FieldInfo]) -
         })
         .collect();
 
-    let iden_variants: Vec<String> = fields
+    let mut iden_variants: Vec<String> = fields
         .iter()
         .map(|f| format!("    {},", to_pascal_case(&f.name)))
         .collect();
 
+    if versioned {
+        column_defs.push(format!(
+            "                    .col(ColumnDef::new({}::Version).integer().not_null().default(1))",
+            pascal_plural
+        ));
+        iden_variants.push("    Version,".to_string());
+    }
+
     let readable_name = format!("create {}", plural);
 
     format!(
@@ -373,6 +426,18 @@ impl MigrationTrait for Migration {{
                             .primary_key(),
                     )
 {column_defs}
+                    .col(
+                        ColumnDef::new({pascal_plural}::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new({pascal_plural}::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
                     .to_owned(),
             )
             .await
@@ -390,6 +455,8 @@ enum {pascal_plural} {{
     Table,
     Id,
 {iden_variants}
+    CreatedAt,
+    UpdatedAt,
 }}
 "#,
         readable_name = readable_name,
@@ -399,9 +466,9 @@ enum {pascal_plural} {{
     )
 }
 
-fn update_entity_file(pascal: &str, fields: &[FieldInfo]) -> Result<(), String> {
+fn update_entity_file(pascal: &str, fields: &[FieldInfo], versioned: bool) -> Result<(), String> {
     let entity_path = Path::new("src/entity.rs");
-    let schema_block = generate_schema_block(pascal, fields);
+    let schema_block = generate_schema_block(pascal, fields, versioned);
 
     if entity_path.exists() {
         let content = fs::read_to_string(entity_path)
@@ -422,6 +489,7 @@ fn create_migration_file(
     plural: &str,
     pascal_plural: &str,
     fields: &[FieldInfo],
+    versioned: bool,
 ) -> Result<(), String> {
     let migrations_dir = Path::new("src/migrations");
 
@@ -437,7 +505,7 @@ fn create_migration_file(
     let filename = format!("{}.rs", module_name);
     let filepath = migrations_dir.join(&filename);
 
-    let template = generate_migration(plural, pascal_plural, fields);
+    let template = generate_migration(plural, pascal_plural, fields, versioned);
     fs::write(&filepath, template).map_err(|e| format!("Failed to write migration file: {}", e))?;
     println!(
         "  {} Created {}",
@@ -455,6 +523,7 @@ fn create_feature_module(
     plural: &str,
     pascal: &str,
     fields: &[FieldInfo],
+    versioned: bool,
 ) -> Result<(), String> {
     let module_dir = Path::new("src").join(plural);
 
@@ -483,7 +552,7 @@ fn create_feature_module(
 
     fs::write(
         module_dir.join("handlers.rs"),
-        generate_handlers(singular, plural, pascal, fields),
+        generate_handlers(singular, plural, pascal, fields, versioned),
     )
     .map_err(|e| format!("Failed to write handlers.rs: {}", e))?;
     println!(
@@ -492,16 +561,22 @@ fn create_feature_module(
         format!("src/{}/handlers.rs", plural).cyan()
     );
 
-    fs::write(module_dir.join("dto.rs"), generate_dto(pascal, fields))
-        .map_err(|e| format!("Failed to write dto.rs: {}", e))?;
+    fs::write(
+        module_dir.join("dto.rs"),
+        generate_dto(pascal, fields, versioned),
+    )
+    .map_err(|e| format!("Failed to write dto.rs: {}", e))?;
     println!(
         "  {} Created {}",
         "✓".green(),
         format!("src/{}/dto.rs", plural).cyan()
     );
 
-    fs::write(module_dir.join("error.rs"), generate_error(pascal))
-        .map_err(|e| format!("Failed to write error.rs: {}", e))?;
+    fs::write(
+        module_dir.join("error.rs"),
+        generate_error(pascal, versioned),
+    )
+    .map_err(|e| format!("Failed to write error.rs: {}", e))?;
     println!(
         "  {} Created {}",
         "✓".green(),
@@ -572,7 +647,7 @@ fn print_next_steps(singular: &str, plural: &str, pascal: &str) {
     println!();
 }
 
-pub fn resource(name: &str, field_args: &[String]) -> Result<(), String> {
+pub fn resource(name: &str, field_args: &[String], versioned: bool) -> Result<(), String> {
     validate_resource_name(name)?;
     verify_rapina_project()?;
 
@@ -597,15 +672,363 @@ pub fn resource(name: &str, field_args: &[String]) -> Result<(), String> {
     println!("  {} {}", "Adding resource:".bright_cyan(), pascal.bold());
     println!();
 
-    create_feature_module(singular, plural, pascal, &fields)?;
-    update_entity_file(pascal, &fields)?;
-    create_migration_file(plural, pascal_plural, &fields)?;
+    create_feature_module(singular, plural, pascal, &fields, versioned)?;
+    update_entity_file(pascal, &fields, versioned)?;
+    create_migration_file(plural, pascal_plural, &fields, versioned)?;
 
     print_next_steps(singular, plural, pascal);
 
     Ok(())
 }
 
+/// Number of sample rows a generated seeder inserts.
+const SEED_ROWS: u32 = 3;
+
+/// A Rust expression for the `n`th sample value of a field.
+fn sample_value(field: &FieldInfo, n: u32) -> String {
+    match field.schema_type.as_str() {
+        "String" | "Text" if field.name.contains("email") => {
+            format!("\"user{}@example.com\".to_string()", n)
+        }
+        "String" | "Text" => format!(
+            "\"Sample {} {}\".to_string()",
+            field.name.replace('_', " "),
+            n
+        ),
+        "i32" | "i64" => n.to_string(),
+        "f32" | "f64" => format!("{}.5", n),
+        "bool" => (n % 2 == 1).to_string(),
+        "Uuid" => format!("Uuid::from_u128({})", n),
+        "DateTime" => format!(
+            "DateTimeUtc::from_timestamp({}, 0).unwrap()",
+            1_700_000_000 + n * 86_400
+        ),
+        "Date" => format!("Date::from_ymd_opt(2024, 1, {}).unwrap()", n),
+        "Decimal" => format!("Decimal::new({}, 2)", n * 1000 + 99),
+        "Json" => format!("serde_json::json!({{ \"sample\": {} }})", n),
+        other => unreachable!("unknown schema type {}", other),
+    }
+}
+
+pub(crate) fn generate_seeder(singular: &str, plural: &str, fields: &[FieldInfo]) -> String {
+    // Types from the sea-orm prelude, as the schema! macro uses them
+    let mut prelude_types: Vec<&str> = fields
+        .iter()
+        .filter_map(|f| match f.schema_type.as_str() {
+            "Uuid" => Some("Uuid"),
+            "DateTime" => Some("DateTimeUtc"),
+            "Date" => Some("Date"),
+            "Decimal" => Some("Decimal"),
+            _ => None,
+        })
+        .collect();
+    prelude_types.sort();
+    prelude_types.dedup();
+    let prelude_import = match prelude_types.as_slice() {
+        [] => String::new(),
+        [one] => format!("use rapina::sea_orm::entity::prelude::{};\n", one),
+        many => format!(
+            "use rapina::sea_orm::entity::prelude::{{{}}};\n",
+            many.join(", ")
+        ),
+    };
+
+    let rows: Vec<String> = (1..=SEED_ROWS)
+        .map(|n| {
+            let values: Vec<String> = fields
+                .iter()
+                .map(|f| format!("            {}: Set({}),", f.name, sample_value(f, n)))
+                .collect();
+            format!(
+                "        ActiveModel {{\n{}\n            ..Default::default()\n        }},",
+                values.join("\n")
+            )
+        })
+        .collect();
+
+    format!(
+        r#"//! Sample {plural} for development, inserted by `rapina seed`.
+
+use rapina::sea_orm::{{ActiveModelTrait, DatabaseConnection, DbErr, Set}};
+{prelude_import}
+use crate::entity::{singular}::ActiveModel;
+
+pub async fn seed(db: &DatabaseConnection) -> Result<(), DbErr> {{
+    let rows = [
+{rows}
+    ];
+    for row in rows {{
+        row.insert(db).await?;
+    }}
+    Ok(())
+}}
+"#,
+        plural = plural,
+        singular = singular,
+        prelude_import = prelude_import,
+        rows = rows.join("\n"),
+    )
+}
+
+pub(crate) fn generate_seeds_mod_rs(plural: &str) -> String {
+    format!(
+        r#"//! Development data, inserted by `rapina seed`.
+
+use rapina::sea_orm::{{DatabaseConnection, DbErr}};
+
+pub mod {plural};
+
+/// Runs every seeder in order.
+pub async fn run(db: &DatabaseConnection) -> Result<(), DbErr> {{
+    {plural}::seed(db).await?;
+    Ok(())
+}}
+"#
+    )
+}
+
+/// Registers a seeder module in an existing `seeds/mod.rs`.
+pub(crate) fn add_to_seeds_mod_rs(content: &str, plural: &str) -> String {
+    let declaration = format!("pub mod {};\n", plural);
+    let call = format!("    {}::seed(db).await?;\n", plural);
+
+    let mut result = content.to_string();
+    match result.rfind("pub mod ") {
+        Some(last) => {
+            let line_end = result[last..]
+                .find('\n')
+                .map_or(result.len(), |i| last + i + 1);
+            result.insert_str(line_end, &declaration);
+        }
+        None => result.push_str(&declaration),
+    }
+    if let Some(ok) = result.rfind("    Ok(())") {
+        result.insert_str(ok, &call);
+    }
+    result
+}
+
+pub(crate) fn generate_seed_bin() -> String {
+    r#"//! Inserts development data. Run with `rapina seed`.
+
+#[path = "../entity.rs"]
+mod entity;
+#[path = "../seeds/mod.rs"]
+mod seeds;
+
+use rapina::database::DatabaseConfig;
+use rapina::prelude::*;
+
+#[tokio::main]
+async fn main() -> std::io::Result<()> {
+    load_dotenv();
+
+    let db = DatabaseConfig::from_env()?
+        .connect()
+        .await
+        .map_err(std::io::Error::other)?;
+    seeds::run(&db).await.map_err(std::io::Error::other)?;
+
+    println!("Seeding complete");
+    Ok(())
+}
+"#
+    .to_string()
+}
+
+/// Exit code of the migrate binary when the database can't be reached.
+pub(crate) const MIGRATE_CONNECT_FAILED: i32 = 2;
+
+pub(crate) fn generate_migrate_bin(module_path: &str) -> String {
+    format!(
+        r#"//! Applies pending migrations. Run by `rapina dev` before starting the server.
+
+#[path = "{module_path}"]
+mod migrations;
+
+use rapina::database::DatabaseConfig;
+use rapina::prelude::*;
+
+#[tokio::main]
+async fn main() {{
+    load_dotenv();
+
+    let db = match DatabaseConfig::from_env() {{
+        Ok(config) => config.connect().await.map_err(|e| e.to_string()),
+        Err(e) => Err(e.to_string()),
+    }};
+    let db = match db {{
+        Ok(db) => db,
+        Err(e) => {{
+            eprintln!("{{}}", e);
+            std::process::exit({MIGRATE_CONNECT_FAILED});
+        }}
+    }};
+
+    if let Err(e) = rapina::migration::run_pending::<migrations::Migrator>(&db).await {{
+        eprintln!("{{}}", e);
+        std::process::exit(1);
+    }}
+}}
+"#
+    )
+}
+
+/// Sets `default-run` so `cargo run` keeps starting the server once the
+/// seed binary exists. Returns `None` when the manifest needs no change.
+pub(crate) fn add_default_run(cargo_toml: &str) -> Option<String> {
+    let manifest: toml::Table = toml::from_str(cargo_toml).ok()?;
+    let package = manifest.get("package")?.as_table()?;
+    if package.contains_key("default-run") || manifest.contains_key("bin") {
+        return None;
+    }
+    let name = package.get("name")?.as_str()?;
+
+    let package_start = cargo_toml.find("[package]")?;
+    let name_line = cargo_toml[package_start..]
+        .lines()
+        .position(|line| line.trim_start().starts_with("name"))?;
+    let mut lines: Vec<&str> = cargo_toml.lines().collect();
+    let index = cargo_toml[..package_start].lines().count() + name_line + 1;
+    let default_run = format!("default-run = \"{}\"", name);
+    lines.insert(index, &default_run);
+
+    let mut updated = lines.join("\n");
+    if cargo_toml.ends_with('\n') {
+        updated.push('\n');
+    }
+    Some(updated)
+}
+
+pub fn seeder(name: &str, field_args: &[String]) -> Result<(), String> {
+    validate_resource_name(name)?;
+    verify_rapina_project()?;
+
+    if field_args.is_empty() {
+        return Err(
+            "At least one field is required. Usage: rapina add seeder <name> <field:type> ..."
+                .to_string(),
+        );
+    }
+
+    let fields: Vec<FieldInfo> = field_args
+        .iter()
+        .map(|arg| parse_field(arg))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let plural = &pluralize(name);
+    let seeds_dir = Path::new("src/seeds");
+    let seeder_path = seeds_dir.join(format!("{}.rs", plural));
+    if seeder_path.exists() {
+        return Err(format!(
+            "Seeder 'src/seeds/{}.rs' already exists. Remove it first to regenerate it.",
+            plural
+        ));
+    }
+
+    println!();
+    println!(
+        "  {} {}",
+        "Adding seeder:".bright_cyan(),
+        to_pascal_case(name).bold()
+    );
+    println!();
+
+    fs::create_dir_all(seeds_dir)
+        .map_err(|e| format!("Failed to create seeds directory: {}", e))?;
+    fs::write(&seeder_path, generate_seeder(name, plural, &fields))
+        .map_err(|e| format!("Failed to write seeder: {}", e))?;
+    println!(
+        "  {} Created {}",
+        "✓".green(),
+        format!("src/seeds/{}.rs", plural).cyan()
+    );
+
+    let mod_path = seeds_dir.join("mod.rs");
+    if mod_path.exists() {
+        let content =
+            fs::read_to_string(&mod_path).map_err(|e| format!("Failed to read mod.rs: {}", e))?;
+        fs::write(&mod_path, add_to_seeds_mod_rs(&content, plural))
+            .map_err(|e| format!("Failed to update mod.rs: {}", e))?;
+        println!("  {} Updated {}", "✓".green(), "src/seeds/mod.rs".cyan());
+    } else {
+        fs::write(&mod_path, generate_seeds_mod_rs(plural))
+            .map_err(|e| format!("Failed to create mod.rs: {}", e))?;
+        println!("  {} Created {}", "✓".green(), "src/seeds/mod.rs".cyan());
+    }
+
+    let bin_path = Path::new("src/bin/seed.rs");
+    if !bin_path.exists() {
+        fs::create_dir_all("src/bin")
+            .map_err(|e| format!("Failed to create bin directory: {}", e))?;
+        fs::write(bin_path, generate_seed_bin())
+            .map_err(|e| format!("Failed to write seed binary: {}", e))?;
+        println!("  {} Created {}", "✓".green(), "src/bin/seed.rs".cyan());
+
+        let cargo_toml = fs::read_to_string("Cargo.toml")
+            .map_err(|e| format!("Failed to read Cargo.toml: {}", e))?;
+        if let Some(updated) = add_default_run(&cargo_toml) {
+            fs::write("Cargo.toml", updated)
+                .map_err(|e| format!("Failed to update Cargo.toml: {}", e))?;
+            println!("  {} Updated {}", "✓".green(), "Cargo.toml".cyan());
+        }
+    }
+
+    println!();
+    println!("  {}:", "Next steps".bright_yellow());
+    println!();
+    println!("     rapina seed");
+    println!();
+
+    Ok(())
+}
+
+/// Creates `src/bin/migrate.rs`, which `rapina dev` runs to apply pending
+/// migrations before starting the server.
+pub fn migrator() -> Result<(), String> {
+    verify_rapina_project()?;
+
+    let module_path = if Path::new("src/migrations/mod.rs").is_file() {
+        "../migrations/mod.rs"
+    } else if Path::new("src/migrations.rs").is_file() {
+        "../migrations.rs"
+    } else {
+        return Err(
+            "No migrations module found. Run `rapina migrate new <name>` to create one first."
+                .to_string(),
+        );
+    };
+
+    let bin_path = Path::new("src/bin/migrate.rs");
+    if bin_path.exists() {
+        return Err(
+            "'src/bin/migrate.rs' already exists. Remove it first to regenerate it.".to_string(),
+        );
+    }
+
+    println!();
+    fs::create_dir_all("src/bin").map_err(|e| format!("Failed to create bin directory: {}", e))?;
+    fs::write(bin_path, generate_migrate_bin(module_path))
+        .map_err(|e| format!("Failed to write migrate binary: {}", e))?;
+    println!("  {} Created {}", "✓".green(), "src/bin/migrate.rs".cyan());
+
+    let cargo_toml = fs::read_to_string("Cargo.toml")
+        .map_err(|e| format!("Failed to read Cargo.toml: {}", e))?;
+    if let Some(updated) = add_default_run(&cargo_toml) {
+        fs::write("Cargo.toml", updated)
+            .map_err(|e| format!("Failed to update Cargo.toml: {}", e))?;
+        println!("  {} Updated {}", "✓".green(), "Cargo.toml".cyan());
+    }
+
+    println!();
+    println!("  {}:", "Next steps".bright_yellow());
+    println!();
+    println!("     rapina dev");
+    println!();
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -729,7 +1152,7 @@ mod tests {
                 column_method: ".boolean().not_null()".to_string(),
             },
         ];
-        let content = generate_handlers("post", "posts", "Post", &fields);
+        let content = generate_handlers("post", "posts", "Post", &fields, false);
 
         assert!(content.contains("use crate::entity::Post;"));
         assert!(content.contains("use crate::entity::post::{ActiveModel, Model};"));
@@ -746,6 +1169,8 @@ mod tests {
         assert!(content.contains("active: Set(input.active),"));
         assert!(content.contains("if let Some(val) = update.title"));
         assert!(content.contains("if let Some(val) = update.active"));
+        assert!(content.contains("active.update(db.conn())"));
+        assert!(!content.contains("update_versioned"));
     }
 
     #[test]
@@ -764,7 +1189,7 @@ mod tests {
                 column_method: String::new(),
             },
         ];
-        let content = generate_dto("User", &fields);
+        let content = generate_dto("User", &fields, false);
 
         assert!(content.contains("pub struct CreateUser"));
         assert!(content.contains("pub struct UpdateUser"));
@@ -776,7 +1201,7 @@ mod tests {
 
     #[test]
     fn test_generate_error() {
-        let content = generate_error("User");
+        let content = generate_error("User", false);
 
         assert!(content.contains("pub enum UserError"));
         assert!(content.contains("impl IntoApiError for UserError"));
@@ -801,7 +1226,7 @@ mod tests {
                 column_method: String::new(),
             },
         ];
-        let content = generate_schema_block("Todo", &fields);
+        let content = generate_schema_block("Todo", &fields, false);
 
         assert!(content.contains("schema! {"));
         assert!(content.contains("Todo {"));
@@ -825,16 +1250,139 @@ mod tests {
                 column_method: ".boolean().not_null()".to_string(),
             },
         ];
-        let content = generate_migration("posts", "Posts", &fields);
+        let content = generate_migration("posts", "Posts", &fields, false);
 
         assert!(content.contains("MigrationTrait for Migration"));
         assert!(content.contains("Posts::Table"));
         assert!(content.contains("Posts::Id"));
         assert!(content.contains("Posts::Title"));
         assert!(content.contains("Posts::Published"));
+        assert!(content.contains("Posts::CreatedAt"));
+        assert!(content.contains("Posts::UpdatedAt"));
         assert!(content.contains(".string().not_null()"));
         assert!(content.contains(".boolean().not_null()"));
         assert!(content.contains("enum Posts {"));
         assert!(content.contains("drop_table"));
     }
+
+    #[test]
+    fn test_generate_versioned_resource() {
+        let fields = vec![field("title:string")];
+
+        let handlers = generate_handlers("post", "posts", "Post", &fields, true);
+        assert!(handlers.contains("use rapina::database::{Db, DbError, update_versioned};"));
+        assert!(handlers.contains("update_versioned(active, update.version, db.conn()).await?"));
+        assert!(!handlers.contains("active.update(db.conn())"));
+
+        let dto = generate_dto("Post", &fields, true);
+        assert!(dto.contains("pub version: i32,"));
+
+        let error = generate_error("Post", true);
+        assert!(error.contains("status: 409"));
+
+        let schema = generate_schema_block("Post", &fields, true);
+        assert!(schema.contains("#[versioned]\n    Post {"));
+
+        let migration = generate_migration("posts", "Posts", &fields, true);
+        assert!(
+            migration.contains("ColumnDef::new(Posts::Version).integer().not_null().default(1)")
+        );
+        assert!(migration.contains("    Version,"));
+    }
+
+    fn field(input: &str) -> FieldInfo {
+        parse_field(input).unwrap()
+    }
+
+    #[test]
+    fn test_generate_seeder() {
+        let fields = vec![
+            field("title:string"),
+            field("author_email:string"),
+            field("views:i32"),
+            field("published:bool"),
+        ];
+        let content = generate_seeder("post", "posts", &fields);
+
+        assert!(content.contains("use crate::entity::post::ActiveModel;"));
+        assert!(
+            content.contains("pub async fn seed(db: &DatabaseConnection) -> Result<(), DbErr>")
+        );
+        assert_eq!(content.matches("ActiveModel {").count(), SEED_ROWS as usize);
+        assert!(content.contains("title: Set(\"Sample title 1\".to_string()),"));
+        assert!(content.contains("title: Set(\"Sample title 3\".to_string()),"));
+        assert!(content.contains("author_email: Set(\"user2@example.com\".to_string()),"));
+        assert!(content.contains("views: Set(2),"));
+        assert!(content.contains("published: Set(true),"));
+        assert!(content.contains("published: Set(false),"));
+        assert!(content.contains("row.insert(db).await?;"));
+        assert!(!content.contains("entity::prelude"));
+    }
+
+    #[test]
+    fn test_generate_seeder_imports_prelude_types() {
+        let fields = vec![
+            field("external_id:uuid"),
+            field("due:date"),
+            field("starts_at:datetime"),
+            field("ends_at:datetime"),
+            field("price:decimal"),
+            field("meta:json"),
+            field("ratio:f64"),
+        ];
+        let content = generate_seeder("event", "events", &fields);
+
+        assert!(
+            content.contains(
+                "use rapina::sea_orm::entity::prelude::{Date, DateTimeUtc, Decimal, Uuid};"
+            )
+        );
+        assert!(content.contains("external_id: Set(Uuid::from_u128(1)),"));
+        assert!(content.contains("due: Set(Date::from_ymd_opt(2024, 1, 2).unwrap()),"));
+        assert!(
+            content
+                .contains("starts_at: Set(DateTimeUtc::from_timestamp(1700086400, 0).unwrap()),")
+        );
+        assert!(content.contains("price: Set(Decimal::new(1099, 2)),"));
+        assert!(content.contains("meta: Set(serde_json::json!({ \"sample\": 1 })),"));
+        assert!(content.contains("ratio: Set(3.5),"));
+    }
+
+    #[test]
+    fn test_seeds_mod_rs_registers_seeders() {
+        let content = generate_seeds_mod_rs("posts");
+        assert!(content.contains("pub mod posts;"));
+        assert!(content.contains("    posts::seed(db).await?;\n    Ok(())"));
+
+        let content = add_to_seeds_mod_rs(&content, "users");
+        assert!(content.contains("pub mod posts;\npub mod users;\n"));
+        assert!(
+            content
+                .contains("    posts::seed(db).await?;\n    users::seed(db).await?;\n    Ok(())")
+        );
+    }
+
+    #[test]
+    fn test_generate_migrate_bin() {
+        let content = generate_migrate_bin("../migrations/mod.rs");
+        assert!(content.contains("#[path = \"../migrations/mod.rs\"]"));
+        assert!(content.contains("run_pending::<migrations::Migrator>"));
+        assert!(content.contains("std::process::exit(2);"));
+    }
+
+    #[test]
+    fn test_add_default_run() {
+        let cargo = "[package]\nname = \"my-app\"\nversion = \"0.1.0\"\n\n[dependencies]\nrapina = \"0.5\"\n";
+        assert_eq!(
+            add_default_run(cargo).unwrap(),
+            "[package]\nname = \"my-app\"\ndefault-run = \"my-app\"\nversion = \"0.1.0\"\n\n[dependencies]\nrapina = \"0.5\"\n"
+        );
+
+        let cargo = "[package]\nname = \"my-app\"\ndefault-run = \"server\"\n";
+        assert!(add_default_run(cargo).is_none());
+
+        let cargo =
+            "[package]\nname = \"my-app\"\n\n[[bin]]\nname = \"server\"\npath = \"src/main.rs\"\n";
+        assert!(add_default_run(cargo).is_none());
+    }
 }
@@ -2,7 +2,9 @@
 
 use colored::Colorize;
 use notify_debouncer_mini::{DebounceEventResult, new_debouncer, notify::RecursiveMode};
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+use super::add::MIGRATE_CONNECT_FAILED;
 use std::process::{Child, Command, Stdio};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, mpsc};
@@ -13,6 +15,9 @@ pub struct DevConfig {
     pub host: String,
     pub port: u16,
     pub reload: bool,
+    /// Apply pending migrations before each server start, when the project
+    /// has a `migrate` binary.
+    pub migrate: bool,
 }
 
 impl Default for DevConfig {
@@ -21,6 +26,7 @@ impl Default for DevConfig {
             host: "127.0.0.1".to_string(),
             port: 3000,
             reload: true,
+            migrate: true,
         }
     }
 }
@@ -57,26 +63,27 @@ pub fn execute(config: DevConfig) -> Result<(), String> {
             Duration::from_millis(300),
             move |res: DebounceEventResult| {
                 if let Ok(events) = res {
-                    for event in events {
-                        if event.path.extension().is_some_and(|ext| ext == "rs") {
-                            let _ = tx.send(());
-                            break;
-                        }
+                    if events.iter().any(|event| triggers_reload(&event.path)) {
+                        let _ = tx.send(());
                     }
                 }
             },
         )
         .map_err(|e| format!("Failed to create file watcher: {}", e))?;
 
-        debouncer
-            .watcher()
-            .watch(Path::new("src"), RecursiveMode::Recursive)
-            .map_err(|e| format!("Failed to watch src directory: {}", e))?;
+        let paths = watch_paths(Path::new("."));
+        for path in &paths {
+            debouncer
+                .watcher()
+                .watch(path, RecursiveMode::Recursive)
+                .map_err(|e| format!("Failed to watch {}: {}", path.display(), e))?;
+        }
 
+        let watched: Vec<String> = paths.iter().map(|p| p.display().to_string()).collect();
         println!(
             "{} Watching for changes in: {}",
             "INFO".custom_color(colors::blue()).bold(),
-            "./src".custom_color(colors::sky())
+            watched.join(", ").custom_color(colors::sky())
         );
 
         // Main loop
@@ -163,9 +170,8 @@ fn verify_rapina_project() -> Result<(), String> {
     let content = std::fs::read_to_string(cargo_toml)
         .map_err(|e| format!("Failed to read Cargo.toml: {}", e))?;
 
-    let parsed: toml::Value = content
-        .parse()
-        .map_err(|e| format!("Failed to parse Cargo.toml: {}", e))?;
+    let parsed: toml::Table =
+        toml::from_str(&content).map_err(|e| format!("Failed to parse Cargo.toml: {}", e))?;
 
     // Check for rapina in dependencies
     let has_rapina = parsed
@@ -201,6 +207,10 @@ fn build_and_run(config: &DevConfig) -> Result<Child, String> {
         "INFO".custom_color(colors::green()).bold()
     );
 
+    if config.migrate && has_migrations(Path::new(".")) {
+        run_migrations()?;
+    }
+
     // Get the binary name from Cargo.toml
     let binary_name = get_binary_name()?;
 
@@ -227,9 +237,8 @@ fn get_binary_name() -> Result<String, String> {
     let content = std::fs::read_to_string("Cargo.toml")
         .map_err(|e| format!("Failed to read Cargo.toml: {}", e))?;
 
-    let parsed: toml::Value = content
-        .parse()
-        .map_err(|e| format!("Failed to parse Cargo.toml: {}", e))?;
+    let parsed: toml::Table =
+        toml::from_str(&content).map_err(|e| format!("Failed to parse Cargo.toml: {}", e))?;
 
     // Check for [[bin]] section first
     if let Some(bins) = parsed.get("bin").and_then(|b| b.as_array())
@@ -248,6 +257,134 @@ fn get_binary_name() -> Result<String, String> {
         .ok_or_else(|| "Could not determine binary name from Cargo.toml".to_string())
 }
 
+/// Directories whose changes trigger a rebuild.
+///
+/// `src` covers the app and a `src/migrations` module. A separate migration
+/// crate next to it (`migration/` or `migrations/`) is watched as well.
+fn watch_paths(root: &Path) -> Vec<PathBuf> {
+    let mut paths = vec![root.join("src")];
+    for dir in ["migration", "migrations"] {
+        let path = root.join(dir);
+        if path.is_dir() {
+            paths.push(path);
+        }
+    }
+    paths
+}
+
+/// Whether a changed file should trigger a rebuild: Rust sources and SQL migrations.
+fn triggers_reload(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext == "rs" || ext == "sql")
+}
+
+/// Whether the project declares a `migrations` module.
+fn has_migrations(root: &Path) -> bool {
+    root.join("src/migrations/mod.rs").is_file() || root.join("src/migrations.rs").is_file()
+}
+
+/// Locates the `migrate` binary target through `cargo metadata`, so custom
+/// target directories and workspace layouts are handled.
+fn migrate_binary() -> Result<Option<PathBuf>, String> {
+    let output = Command::new("cargo")
+        .args(["metadata", "--no-deps", "--format-version", "1"])
+        .output()
+        .map_err(|e| format!("Failed to run cargo metadata: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "cargo metadata failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    let metadata: serde_json::Value = serde_json::from_slice(&output.stdout)
+        .map_err(|e| format!("Failed to parse cargo metadata: {}", e))?;
+    Ok(find_migrate_binary(&metadata))
+}
+
+/// Path of the debug build of the `migrate` binary, if any package declares one.
+fn find_migrate_binary(metadata: &serde_json::Value) -> Option<PathBuf> {
+    let has_target = metadata["packages"]
+        .as_array()?
+        .iter()
+        .filter_map(|package| package["targets"].as_array())
+        .flatten()
+        .any(|target| {
+            target["name"] == "migrate"
+                && target["kind"]
+                    .as_array()
+                    .is_some_and(|kinds| kinds.iter().any(|kind| kind == "bin"))
+        });
+    if !has_target {
+        return None;
+    }
+
+    let target_dir = metadata["target_directory"].as_str()?;
+    Some(
+        Path::new(target_dir)
+            .join("debug")
+            .join(format!("migrate{}", std::env::consts::EXE_SUFFIX)),
+    )
+}
+
+/// Apply pending migrations with the migrate binary built alongside the server.
+///
+/// A project without a migrate binary gets a warning pointing at
+/// `rapina add migrator`. An unreachable database is reported and skipped so
+/// the server still starts; a failing migration stops the start like a build
+/// error.
+fn run_migrations() -> Result<(), String> {
+    let Some(migrate) = migrate_binary()? else {
+        eprintln!(
+            "{} Found a migrations module but no migrate binary, so migrations were not applied",
+            "WARN".custom_color(colors::yellow()).bold()
+        );
+        eprintln!(
+            "{} Run `rapina add migrator` to apply them on start, or pass --no-migrate",
+            "WARN".custom_color(colors::yellow()).bold()
+        );
+        return Ok(());
+    };
+    if !migrate.is_file() {
+        return Err(format!(
+            "Migrate binary not found at {} after building",
+            migrate.display()
+        ));
+    }
+
+    println!(
+        "{} Applying pending migrations...",
+        "INFO".custom_color(colors::blue()).bold()
+    );
+    let output = Command::new(&migrate)
+        .output()
+        .map_err(|e| format!("Failed to run migrations: {}", e))?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let message = stderr.trim();
+
+    match output.status.code() {
+        Some(0) => {
+            println!(
+                "{} Migrations up to date",
+                "INFO".custom_color(colors::green()).bold()
+            );
+            Ok(())
+        }
+        Some(MIGRATE_CONNECT_FAILED) => {
+            eprintln!(
+                "{} Skipping migrations, could not connect to the database: {}",
+                "WARN".custom_color(colors::yellow()).bold(),
+                message
+            );
+            eprintln!(
+                "{} Check DATABASE_URL in your environment or .env, or pass --no-migrate",
+                "WARN".custom_color(colors::yellow()).bold()
+            );
+            Ok(())
+        }
+        _ => Err(format!("Migrations failed: {}", message)),
+    }
+}
+
 /// Catppuccin Mocha color palette
 mod colors {
     use colored::CustomColor;
@@ -358,3 +495,96 @@ fn print_banner(config: &DevConfig) {
     );
     println!();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    /// Creates an empty project directory with the given subdirectories and files.
+    fn project(name: &str, dirs: &[&str], files: &[&str]) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("rapina-dev-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        for dir in dirs {
+            fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        for file in files {
+            fs::write(root.join(file), "").unwrap();
+        }
+        root
+    }
+
+    #[test]
+    fn test_watch_paths_default_to_src() {
+        let root = project("src-only", &["src/migrations"], &[]);
+        assert_eq!(watch_paths(&root), [root.join("src")]);
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_watch_paths_include_migration_crate() {
+        let root = project("crate", &["src", "migration/src"], &["migrations"]);
+        // A file named like the directory is not watched
+        assert_eq!(
+            watch_paths(&root),
+            [root.join("src"), root.join("migration")]
+        );
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_triggers_reload() {
+        assert!(triggers_reload(Path::new("src/main.rs")));
+        assert!(triggers_reload(Path::new(
+            "src/migrations/m20260101_000001_create_users.rs"
+        )));
+        assert!(triggers_reload(Path::new("migrations/0001_init.sql")));
+        assert!(!triggers_reload(Path::new("src/notes.md")));
+        assert!(!triggers_reload(Path::new("src/main.rs.swp")));
+        assert!(!triggers_reload(Path::new("src/migrations")));
+    }
+
+    #[test]
+    fn test_has_migrations() {
+        let root = project("mod-rs", &["src/migrations"], &["src/migrations/mod.rs"]);
+        assert!(has_migrations(&root));
+        fs::remove_dir_all(&root).unwrap();
+
+        let root = project("file", &["src"], &["src/migrations.rs"]);
+        assert!(has_migrations(&root));
+        fs::remove_dir_all(&root).unwrap();
+
+        let root = project("none", &["src/migrations"], &[]);
+        assert!(!has_migrations(&root));
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_find_migrate_binary() {
+        let metadata = serde_json::json!({
+            "target_directory": "/work/target",
+            "packages": [{
+                "targets": [
+                    { "name": "app", "kind": ["bin"] },
+                    { "name": "migrate", "kind": ["bin"] },
+                ],
+            }],
+        });
+        assert_eq!(
+            find_migrate_binary(&metadata).unwrap(),
+            Path::new("/work/target/debug")
+                .join(format!("migrate{}", std::env::consts::EXE_SUFFIX))
+        );
+
+        let metadata = serde_json::json!({
+            "target_directory": "/work/target",
+            "packages": [{
+                "targets": [
+                    { "name": "app", "kind": ["bin"] },
+                    { "name": "migrate", "kind": ["test"] },
+                ],
+            }],
+        });
+        assert!(find_migrate_binary(&metadata).is_none());
+    }
+}
@@ -2,10 +2,19 @@
 
 use colored::Colorize;
 use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
 use std::process::Command;
 
 const ROUTES_URL: &str = "http://127.0.0.1:3000/__rapina/routes";
 const OPENAPI_URL: &str = "http://127.0.0.1:3000/__rapina/openapi.json";
+const INFO_URL: &str = "http://127.0.0.1:3000/__rapina/info";
+
+/// Rapina features that pull in the database integration.
+const DATABASE_FEATURES: &[&str] = &["database", "postgres", "mysql", "sqlite"];
+
+/// Methods accepted by the route macros.
+const ROUTE_MACROS: &[&str] = &["get", "post", "put", "patch", "delete"];
 
 struct DiagnosticResult {
     warnings: Vec<String>,
@@ -31,6 +40,9 @@ pub fn execute() -> Result<(), String> {
     check_response_schemas(&routes, &mut result);
     check_error_documentation(&routes, &mut result);
     check_openapi_metadata(&openapi, &mut result);
+    check_route_collisions(&routes, &mut result);
+    check_environment(&fetch_json(INFO_URL).ok(), &mut result);
+    check_public_routes(&routes, &mut result);
 
     print_results(&result);
 
@@ -158,6 +170,254 @@ fn check_openapi_metadata(openapi: &Result<Value, String>, result: &mut Diagnost
     }
 }
 
+/// Check that no two routes share a method and path.
+///
+/// Only the first of them ever handles requests.
+fn check_route_collisions(routes: &Value, result: &mut DiagnosticResult) {
+    let collisions = find_route_collisions(routes);
+    if collisions.is_empty() {
+        result.passed.push("No conflicting routes".to_string());
+    } else {
+        for route in collisions {
+            result
+                .errors
+                .push(format!("Route registered more than once: {}", route));
+        }
+    }
+}
+
+/// Returns each `METHOD path` registered more than once, in registration order.
+///
+/// Parameter names are ignored, so `/users/:id` collides with `/users/:user_id`.
+fn find_route_collisions(routes: &Value) -> Vec<String> {
+    let Some(routes) = routes.as_array() else {
+        return Vec::new();
+    };
+
+    let mut counts: HashMap<(String, String), usize> = HashMap::new();
+    let mut collisions = Vec::new();
+    for route in routes {
+        let path = route.get("path").and_then(|p| p.as_str()).unwrap_or("?");
+        let method = route.get("method").and_then(|m| m.as_str()).unwrap_or("?");
+        let count = counts
+            .entry((method.to_string(), normalize_path(path)))
+            .or_default();
+        *count += 1;
+        if *count == 2 {
+            collisions.push(format!("{} {}", method, path));
+        }
+    }
+    collisions
+}
+
+/// Replaces parameter names so equivalent patterns compare equal.
+fn normalize_path(path: &str) -> String {
+    path.split('/')
+        .map(|s| if s.starts_with(':') { ":" } else { s })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Check that enabled features have the environment variables they read.
+fn check_environment(info: &Option<Value>, result: &mut DiagnosticResult) {
+    // Prefer what the running app reports, falling back to the source
+    let auth_enabled = match info
+        .as_ref()
+        .and_then(|i| i.pointer("/features/auth"))
+        .and_then(|a| a.as_bool())
+    {
+        Some(enabled) => enabled,
+        None => read_sources(Path::new("src"))
+            .iter()
+            .any(|source| source.contains(".with_auth(")),
+    };
+    let database_enabled = std::fs::read_to_string("Cargo.toml")
+        .ok()
+        .and_then(|content| toml::from_str::<toml::Table>(&content).ok())
+        .is_some_and(|cargo| uses_database(&cargo));
+
+    let mut required = Vec::new();
+    if auth_enabled {
+        required.push(("JWT_SECRET", "auth is enabled"));
+    }
+    if database_enabled {
+        required.push(("DATABASE_URL", "the database feature is enabled"));
+    }
+    if required.is_empty() {
+        return;
+    }
+
+    let dotenv = std::fs::read_to_string(".env")
+        .map(|content| dotenv_keys(&content))
+        .unwrap_or_default();
+    let missing = find_missing_env(&required, |key| {
+        std::env::var_os(key).is_some() || dotenv.contains(key)
+    });
+
+    if missing.is_empty() {
+        result
+            .passed
+            .push("Required environment variables are set".to_string());
+    } else {
+        for (key, reason) in missing {
+            result.warnings.push(format!(
+                "{} is not set in the environment or .env, but {}",
+                key, reason
+            ));
+        }
+    }
+}
+
+/// Returns the required variables for which `is_set` is false.
+fn find_missing_env<'a>(
+    required: &[(&'a str, &'a str)],
+    is_set: impl Fn(&str) -> bool,
+) -> Vec<(&'a str, &'a str)> {
+    required
+        .iter()
+        .filter(|(key, _)| !is_set(key))
+        .copied()
+        .collect()
+}
+
+/// Returns the variable names assigned in a `.env` file.
+fn dotenv_keys(content: &str) -> HashSet<String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, _)| key.trim_start_matches("export ").trim().to_string())
+        .filter(|key| !key.is_empty())
+        .collect()
+}
+
+/// Whether the `rapina` dependency enables a database feature.
+fn uses_database(cargo: &toml::Table) -> bool {
+    cargo
+        .get("dependencies")
+        .and_then(|deps| deps.get("rapina"))
+        .and_then(|rapina| rapina.get("features"))
+        .and_then(|features| features.as_array())
+        .is_some_and(|features| {
+            features
+                .iter()
+                .filter_map(|f| f.as_str())
+                .any(|f| DATABASE_FEATURES.contains(&f))
+        })
+}
+
+/// Check that handlers marked `#[public]` are registered at their macro path.
+fn check_public_routes(routes: &Value, result: &mut DiagnosticResult) {
+    let public: Vec<_> = read_sources(Path::new("src"))
+        .iter()
+        .flat_map(|source| find_public_handlers(source))
+        .collect();
+    if public.is_empty() {
+        return;
+    }
+
+    let unregistered = find_unregistered_routes(&public, routes);
+    if unregistered.is_empty() {
+        result
+            .passed
+            .push("All #[public] routes are registered".to_string());
+    } else {
+        for route in unregistered {
+            result
+                .warnings
+                .push(format!("#[public] route is not registered: {}", route));
+        }
+    }
+}
+
+/// Returns `(METHOD, path)` for each route macro marked `#[public]`.
+fn find_public_handlers(source: &str) -> Vec<(String, String)> {
+    let mut found = Vec::new();
+    let mut is_public = false;
+    let mut route = None;
+
+    for line in source.lines().map(str::trim) {
+        if line.starts_with("#[") {
+            if line == "#[public]" {
+                is_public = true;
+            } else if let Some(r) = parse_route_attr(line) {
+                route = Some(r);
+            }
+            continue;
+        }
+        if line.is_empty() || line.starts_with("//") {
+            continue;
+        }
+        // The attributes end at the item they annotate
+        if is_public && let Some(r) = route.take() {
+            found.push(r);
+        }
+        is_public = false;
+        route = None;
+    }
+    found
+}
+
+/// Parses `#[get("/path")]` and the like into `(METHOD, path)`.
+fn parse_route_attr(line: &str) -> Option<(String, String)> {
+    let (name, rest) = line.strip_prefix("#[")?.split_once('(')?;
+    if !ROUTE_MACROS.contains(&name) {
+        return None;
+    }
+    let path = rest.strip_prefix('"')?.split('"').next()?;
+    Some((name.to_uppercase(), path.to_string()))
+}
+
+/// Returns the `METHOD path` entries with no matching registered route.
+///
+/// Routes mounted in a group carry its prefix, so a registered path ending
+/// in the macro path counts as a match.
+fn find_unregistered_routes(expected: &[(String, String)], routes: &Value) -> Vec<String> {
+    let registered: Vec<(&str, String)> = routes
+        .as_array()
+        .map(|routes| {
+            routes
+                .iter()
+                .filter_map(|r| {
+                    let method = r.get("method")?.as_str()?;
+                    let path = r.get("path")?.as_str()?;
+                    Some((method, normalize_path(path)))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    expected
+        .iter()
+        .filter(|(method, path)| {
+            let path = normalize_path(path);
+            !registered
+                .iter()
+                .any(|(m, p)| m == method && p.ends_with(path.as_str()))
+        })
+        .map(|(method, path)| format!("{} {}", method, path))
+        .collect()
+}
+
+/// Reads every `.rs` file below `dir`.
+fn read_sources(dir: &Path) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut sources = Vec::new();
+    for path in entries.filter_map(|e| e.ok()).map(|e| e.path()) {
+        if path.is_dir() {
+            sources.extend(read_sources(&path));
+        } else if path.extension().is_some_and(|ext| ext == "rs")
+            && let Ok(source) = std::fs::read_to_string(&path)
+        {
+            sources.push(source);
+        }
+    }
+    sources
+}
+
 /// Print diagnostic results.
 fn print_results(result: &DiagnosticResult) {
     // Print passed checks
@@ -214,3 +474,130 @@ fn fetch_json(url: &str) -> Result<Value, String> {
 
     serde_json::from_str(&body).map_err(|e| format!("Invalid JSON response: {}", e))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_find_route_collisions() {
+        let routes = json!([
+            {"method": "GET", "path": "/users/:id"},
+            {"method": "DELETE", "path": "/users/:id"},
+            {"method": "GET", "path": "/users/:user_id"},
+            {"method": "GET", "path": "/users/me"},
+            {"method": "POST", "path": "/users"},
+            {"method": "POST", "path": "/users"},
+            {"method": "POST", "path": "/users"},
+        ]);
+        assert_eq!(
+            find_route_collisions(&routes),
+            ["GET /users/:user_id", "POST /users"]
+        );
+    }
+
+    #[test]
+    fn test_find_route_collisions_none() {
+        let routes = json!([
+            {"method": "GET", "path": "/users"},
+            {"method": "POST", "path": "/users"},
+        ]);
+        assert!(find_route_collisions(&routes).is_empty());
+        assert!(find_route_collisions(&json!({})).is_empty());
+    }
+
+    #[test]
+    fn test_find_missing_env() {
+        let required = [
+            ("JWT_SECRET", "auth is enabled"),
+            ("DATABASE_URL", "the database feature is enabled"),
+        ];
+        let missing = find_missing_env(&required, |key| key == "DATABASE_URL");
+        assert_eq!(missing, [("JWT_SECRET", "auth is enabled")]);
+        assert!(find_missing_env(&required, |_| true).is_empty());
+    }
+
+    #[test]
+    fn test_dotenv_keys() {
+        let keys = dotenv_keys(
+            "# secrets\nJWT_SECRET=abc\n\nexport DATABASE_URL = postgres://localhost/db\n#PORT=3000\n",
+        );
+        assert!(keys.contains("JWT_SECRET"));
+        assert!(keys.contains("DATABASE_URL"));
+        assert!(!keys.contains("PORT"));
+    }
+
+    #[test]
+    fn test_uses_database() {
+        let cargo: toml::Table = toml::from_str(
+            r#"
+            [dependencies]
+            rapina = { version = "0.5", features = ["postgres"] }
+        "#,
+        )
+        .unwrap();
+        assert!(uses_database(&cargo));
+
+        let cargo: toml::Table = toml::from_str(
+            r#"
+            [dependencies]
+            rapina = { version = "0.5", features = ["metrics"] }
+        "#,
+        )
+        .unwrap();
+        assert!(!uses_database(&cargo));
+
+        let cargo: toml::Table = toml::from_str("[dependencies]\nrapina = \"0.5\"").unwrap();
+        assert!(!uses_database(&cargo));
+    }
+
+    #[test]
+    fn test_find_public_handlers() {
+        let source = r#"
+            #[public]
+            #[get("/health")]
+            async fn health() -> &'static str { "ok" }
+
+            #[post("/login")]
+            #[public]
+            async fn login() {}
+
+            #[get("/me")]
+            async fn me() {}
+
+            #[public]
+            fn not_a_route() {}
+
+            #[get("/other")]
+            async fn other() {}
+        "#;
+        assert_eq!(
+            find_public_handlers(source),
+            [
+                ("GET".to_string(), "/health".to_string()),
+                ("POST".to_string(), "/login".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_unregistered_routes() {
+        let routes = json!([
+            {"method": "GET", "path": "/health"},
+            {"method": "POST", "path": "/api/login"},
+            {"method": "GET", "path": "/users/:id"},
+        ]);
+        let expected = [
+            ("GET".to_string(), "/health".to_string()),
+            ("POST".to_string(), "/login".to_string()),
+            ("GET".to_string(), "/users/:user_id".to_string()),
+            ("POST".to_string(), "/health".to_string()),
+            ("GET".to_string(), "/signup".to_string()),
+        ];
+        assert_eq!(
+            find_unregistered_routes(&expected, &routes),
+            ["POST /health", "GET /signup"]
+        );
+    }
+}
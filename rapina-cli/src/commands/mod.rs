@@ -7,4 +7,5 @@ pub mod migrate;
 pub mod new;
 pub mod openapi;
 pub mod routes;
+pub mod seed;
 pub mod test;
@@ -4,16 +4,42 @@ use colored::Colorize;
 use std::fs;
 use std::path::Path;
 
-/// Execute the `new` command to create a new Rapina project.
-pub fn execute(name: &str) -> Result<(), String> {
-    // Validate project name
-    validate_project_name(name)?;
+use super::add;
 
-    // Check if directory already exists
-    let project_path = Path::new(name);
-    if project_path.exists() {
-        return Err(format!("Directory '{}' already exists", name));
+/// Project layouts `rapina new` can generate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Template {
+    /// A single `main.rs`.
+    Minimal,
+    /// Separate router, handler and error modules.
+    Api,
+    /// The API layout plus a database, migrations, auth and an example resource.
+    Fullstack,
+}
+
+impl Template {
+    const NAMES: &'static str = "minimal, api, fullstack";
+
+    /// Parses a template name as given to `--template`.
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name {
+            "minimal" => Ok(Template::Minimal),
+            "api" => Ok(Template::Api),
+            "fullstack" => Ok(Template::Fullstack),
+            _ => Err(format!(
+                "Unknown template '{}'. Available templates: {}",
+                name,
+                Self::NAMES
+            )),
+        }
     }
+}
+
+/// Execute the `new` command to create a new Rapina project.
+pub fn execute(name: &str, template: &str) -> Result<(), String> {
+    // Validate project name and template
+    validate_project_name(name)?;
+    let template = Template::from_name(template)?;
 
     println!();
     println!(
@@ -23,40 +49,120 @@ pub fn execute(name: &str) -> Result<(), String> {
     );
     println!();
 
-    // Create project directory structure
-    let src_path = project_path.join("src");
-    fs::create_dir_all(&src_path).map_err(|e| format!("Failed to create directory: {}", e))?;
-
-    // Create Cargo.toml
-    let cargo_toml = generate_cargo_toml(name);
-    let cargo_path = project_path.join("Cargo.toml");
-    fs::write(&cargo_path, cargo_toml).map_err(|e| format!("Failed to write Cargo.toml: {}", e))?;
-    println!("  {} Created {}", "✓".green(), "Cargo.toml".cyan());
-
-    // Create src/main.rs
-    let main_rs = generate_main_rs();
-    let main_path = src_path.join("main.rs");
-    fs::write(&main_path, main_rs).map_err(|e| format!("Failed to write main.rs: {}", e))?;
-    println!("  {} Created {}", "✓".green(), "src/main.rs".cyan());
-
-    // Create .gitignore
-    let gitignore = generate_gitignore();
-    let gitignore_path = project_path.join(".gitignore");
-    fs::write(&gitignore_path, gitignore)
-        .map_err(|e| format!("Failed to write .gitignore: {}", e))?;
-    println!("  {} Created {}", "✓".green(), ".gitignore".cyan());
+    create_project(Path::new(name), name, template)?;
 
     println!();
     println!("  {} Project created successfully!", "🎉".bold());
     println!();
     println!("  {}:", "Next steps".bright_yellow());
     println!("    cd {}", name.cyan());
+    if template == Template::Fullstack {
+        println!("    cp .env.example .env");
+    }
     println!("    rapina dev");
     println!();
 
     Ok(())
 }
 
+/// Write the files of `template` into a new directory at `project_path`.
+fn create_project(project_path: &Path, name: &str, template: Template) -> Result<(), String> {
+    if project_path.exists() {
+        return Err(format!(
+            "Directory '{}' already exists",
+            project_path.display()
+        ));
+    }
+
+    for (file, content) in template_files(name, template) {
+        let path = project_path.join(&file);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {}", e))?;
+        }
+        fs::write(&path, content).map_err(|e| format!("Failed to write {}: {}", file, e))?;
+        println!("  {} Created {}", "✓".green(), file.cyan());
+    }
+
+    Ok(())
+}
+
+/// The files making up a project, as paths relative to its root.
+fn template_files(name: &str, template: Template) -> Vec<(String, String)> {
+    let mut files = vec![(".gitignore".to_string(), generate_gitignore(template))];
+
+    match template {
+        Template::Minimal => {
+            files.push(("Cargo.toml".to_string(), generate_cargo_toml(name, &[])));
+            files.push(("src/main.rs".to_string(), generate_main_rs()));
+        }
+        Template::Api => {
+            files.push(("Cargo.toml".to_string(), generate_cargo_toml(name, &[])));
+            files.push(("src/main.rs".to_string(), generate_api_main_rs(name)));
+            files.push(("src/handlers.rs".to_string(), generate_handlers_rs()));
+            files.push(("src/error.rs".to_string(), generate_error_rs()));
+        }
+        Template::Fullstack => {
+            files.push((
+                "Cargo.toml".to_string(),
+                generate_cargo_toml(name, &["sqlite"]),
+            ));
+            files.push((".env.example".to_string(), generate_env_example(name)));
+            files.push(("src/main.rs".to_string(), generate_fullstack_main_rs(name)));
+            files.push(("src/handlers.rs".to_string(), generate_handlers_rs()));
+            files.push(("src/error.rs".to_string(), generate_error_rs()));
+            files.push(("src/auth.rs".to_string(), generate_auth_rs()));
+            files.extend(example_resource_files());
+        }
+    }
+
+    files
+}
+
+/// The `item` resource of the fullstack template, as `rapina add resource` generates it.
+fn example_resource_files() -> Vec<(String, String)> {
+    let fields: Vec<_> = ["title:string", "done:bool"]
+        .iter()
+        .map(|f| add::parse_field(f).expect("valid example field"))
+        .collect();
+    let (singular, plural) = ("item", add::pluralize("item"));
+    let pascal = add::to_pascal_case(singular);
+    let pascal_plural = add::to_pascal_case(&plural);
+
+    let timestamp = chrono::Utc::now().format("%Y%m%d_%H%M%S");
+    let migration = format!("m{}_create_{}", timestamp, plural);
+
+    vec![
+        (
+            "src/entity.rs".to_string(),
+            format!(
+                "use rapina::prelude::*;\n{}",
+                add::generate_schema_block(&pascal, &fields, false)
+            ),
+        ),
+        (format!("src/{}/mod.rs", plural), add::generate_mod_rs()),
+        (
+            format!("src/{}/handlers.rs", plural),
+            add::generate_handlers(singular, &plural, &pascal, &fields, false),
+        ),
+        (
+            format!("src/{}/dto.rs", plural),
+            add::generate_dto(&pascal, &fields, false),
+        ),
+        (
+            format!("src/{}/error.rs", plural),
+            add::generate_error(&pascal, false),
+        ),
+        (
+            format!("src/migrations/{}.rs", migration),
+            add::generate_migration(&plural, &pascal_plural, &fields, false),
+        ),
+        (
+            "src/migrations/mod.rs".to_string(),
+            format!("mod {migration};\n\nrapina::migrations! {{\n    {migration},\n}}\n"),
+        ),
+    ]
+}
+
 /// Validate that the project name is a valid Rust crate name.
 fn validate_project_name(name: &str) -> Result<(), String> {
     if name.is_empty() {
@@ -87,9 +193,19 @@ fn validate_project_name(name: &str) -> Result<(), String> {
     Ok(())
 }
 
-/// Generate the content for Cargo.toml.
-fn generate_cargo_toml(name: &str) -> String {
+/// Generate the content for Cargo.toml, enabling the given rapina features.
+fn generate_cargo_toml(name: &str, features: &[&str]) -> String {
     let version = env!("CARGO_PKG_VERSION");
+    let rapina = if features.is_empty() {
+        format!("\"{}\"", version)
+    } else {
+        let features: Vec<_> = features.iter().map(|f| format!("\"{}\"", f)).collect();
+        format!(
+            "{{ version = \"{}\", features = [{}] }}",
+            version,
+            features.join(", ")
+        )
+    };
     format!(
         r#"[package]
 name = "{name}"
@@ -97,7 +213,7 @@ version = "0.1.0"
 edition = "2024"
 
 [dependencies]
-rapina = "{version}"
+rapina = {rapina}
 tokio = {{ version = "1", features = ["full"] }}
 serde = {{ version = "1", features = ["derive"] }}
 serde_json = "1"
@@ -155,14 +271,233 @@ async fn main() -> std::io::Result<()> {
     .to_string()
 }
 
-/// Generate the content for .gitignore.
-fn generate_gitignore() -> String {
-    r#"/target
-Cargo.lock
+/// Generate the content for src/main.rs of the api template.
+fn generate_api_main_rs(name: &str) -> String {
+    format!(
+        r#"mod error;
+mod handlers;
+
+use rapina::middleware::RequestLogMiddleware;
+use rapina::prelude::*;
+
+#[tokio::main]
+async fn main() -> std::io::Result<()> {{
+    load_dotenv();
+
+    let router = Router::new()
+        .get("/", handlers::hello)
+        .get("/health", handlers::health)
+        .get("/hello/:name", handlers::greet);
+
+    Rapina::new()
+        .with_tracing(TracingConfig::new())
+        .middleware(RequestLogMiddleware::new())
+        .openapi("{name}", env!("CARGO_PKG_VERSION"))
+        .router(router)
+        .listen("127.0.0.1:3000")
+        .await
+}}
+"#
+    )
+}
+
+/// Generate the content for src/main.rs of the fullstack template.
+fn generate_fullstack_main_rs(name: &str) -> String {
+    format!(
+        r#"mod auth;
+mod entity;
+mod error;
+mod handlers;
+mod items;
+mod migrations;
+
+use rapina::database::DatabaseConfig;
+use rapina::middleware::RequestLogMiddleware;
+use rapina::prelude::*;
+
+use items::handlers::{{create_item, delete_item, get_item, list_items, update_item}};
+
+#[tokio::main]
+async fn main() -> std::io::Result<()> {{
+    load_dotenv();
+
+    let auth_config = AuthConfig::from_env().expect("JWT_SECRET is required");
+    let db_config = DatabaseConfig::from_env()?;
+
+    let router = Router::new()
+        .get("/", handlers::hello)
+        .get("/health", handlers::health)
+        .get("/hello/:name", handlers::greet)
+        .post("/login", auth::login)
+        .get("/me", auth::me)
+        .get("/items", list_items)
+        .get("/items/:id", get_item)
+        .post("/items", create_item)
+        .put("/items/:id", update_item)
+        .delete("/items/:id", delete_item);
+
+    Rapina::new()
+        .with_tracing(TracingConfig::new())
+        .middleware(RequestLogMiddleware::new())
+        .with_auth(auth_config.clone())
+        .public_route("GET", "/")
+        .public_route("GET", "/health")
+        .public_route("GET", "/hello/:name")
+        .public_route("POST", "/login")
+        .state(auth_config)
+        .openapi("{name}", env!("CARGO_PKG_VERSION"))
+        .with_database(db_config)
+        .await?
+        .run_migrations::<migrations::Migrator>()
+        .await?
+        .router(router)
+        .listen("127.0.0.1:3000")
+        .await
+}}
+"#
+    )
+}
+
+/// Generate the content for src/handlers.rs.
+fn generate_handlers_rs() -> String {
+    r#"use rapina::prelude::*;
+use rapina::schemars;
+
+use crate::error::ApiError;
+
+#[derive(Serialize, JsonSchema)]
+pub struct MessageResponse {
+    message: String,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct HealthResponse {
+    status: String,
+    version: String,
+}
+
+#[public]
+#[get("/")]
+pub async fn hello() -> Json<MessageResponse> {
+    Json(MessageResponse {
+        message: "Hello from Rapina!".to_string(),
+    })
+}
+
+#[public]
+#[get("/health")]
+pub async fn health() -> Json<HealthResponse> {
+    Json(HealthResponse {
+        status: "healthy".to_string(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+    })
+}
+
+#[public]
+#[get("/hello/:name")]
+#[errors(ApiError)]
+pub async fn greet(name: Path<String>) -> Result<Json<MessageResponse>> {
+    let name = name.into_inner();
+    if name.len() > 32 {
+        return Err(ApiError::NameTooLong.into());
+    }
+    Ok(Json(MessageResponse {
+        message: format!("Hello, {}!", name),
+    }))
+}
+"#
+    .to_string()
+}
+
+/// Generate the content for src/error.rs.
+fn generate_error_rs() -> String {
+    r#"use rapina::prelude::*;
+
+/// Errors returned by the handlers.
+pub enum ApiError {
+    NameTooLong,
+}
+
+impl IntoApiError for ApiError {
+    fn into_api_error(self) -> Error {
+        match self {
+            ApiError::NameTooLong => Error::bad_request("name must be at most 32 characters"),
+        }
+    }
+}
+
+impl DocumentedError for ApiError {
+    fn error_variants() -> Vec<ErrorVariant> {
+        vec![ErrorVariant {
+            status: 400,
+            code: "BAD_REQUEST",
+            description: "Name is too long",
+        }]
+    }
+}
 "#
     .to_string()
 }
 
+/// Generate the content for src/auth.rs.
+fn generate_auth_rs() -> String {
+    r#"use rapina::prelude::*;
+use rapina::schemars;
+
+#[derive(Deserialize, JsonSchema)]
+pub struct LoginRequest {
+    username: String,
+    password: String,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct UserResponse {
+    id: String,
+}
+
+#[public]
+#[post("/login")]
+pub async fn login(body: Json<LoginRequest>, auth: State<AuthConfig>) -> Result<Json<TokenResponse>> {
+    let req = body.into_inner();
+    let auth_config = auth.into_inner();
+
+    // Replace with a lookup of your users
+    if req.username == "admin" && req.password == "password" {
+        let token = auth_config.create_token(&req.username)?;
+        Ok(Json(TokenResponse::new(token, auth_config.expiration())))
+    } else {
+        Err(Error::unauthorized("invalid credentials"))
+    }
+}
+
+#[get("/me")]
+pub async fn me(user: CurrentUser) -> Json<UserResponse> {
+    Json(UserResponse { id: user.id })
+}
+"#
+    .to_string()
+}
+
+/// Generate the content for .env.example.
+fn generate_env_example(name: &str) -> String {
+    let db_name = name.replace('-', "_");
+    format!(
+        r#"DATABASE_URL=sqlite://{db_name}.db?mode=rwc
+JWT_SECRET=change-me-to-a-long-random-string
+JWT_EXPIRATION=3600
+"#
+    )
+}
+
+/// Generate the content for .gitignore.
+fn generate_gitignore(template: Template) -> String {
+    let mut gitignore = "/target\nCargo.lock\n".to_string();
+    if template == Template::Fullstack {
+        gitignore.push_str(".env\n*.db\n");
+    }
+    gitignore
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -184,4 +519,117 @@ mod tests {
         assert!(validate_project_name("my.app").is_err());
         assert!(validate_project_name("self").is_err());
     }
+
+    #[test]
+    fn test_template_from_name() {
+        assert_eq!(Template::from_name("minimal"), Ok(Template::Minimal));
+        assert_eq!(Template::from_name("api"), Ok(Template::Api));
+        assert_eq!(Template::from_name("fullstack"), Ok(Template::Fullstack));
+
+        let err = Template::from_name("full").unwrap_err();
+        assert!(err.contains("minimal, api, fullstack"), "{}", err);
+    }
+
+    /// Creates `template` in a fresh directory and returns the files written.
+    fn generate(template: Template) -> Vec<String> {
+        let root =
+            std::env::temp_dir().join(format!("rapina-new-{:?}-{}", template, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let project = root.join("my-app");
+        create_project(&project, "my-app", template).unwrap();
+
+        // Creating over an existing directory is refused
+        let err = create_project(&project, "my-app", template).unwrap_err();
+        assert!(err.contains("already exists"), "{}", err);
+
+        let mut files = Vec::new();
+        collect_files(&project, &project, &mut files);
+        fs::remove_dir_all(&root).unwrap();
+        files.sort();
+        files
+    }
+
+    fn collect_files(root: &Path, dir: &Path, files: &mut Vec<String>) {
+        for entry in fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                collect_files(root, &path, files);
+            } else {
+                let relative = path.strip_prefix(root).unwrap();
+                files.push(relative.to_string_lossy().replace('\\', "/"));
+            }
+        }
+    }
+
+    #[test]
+    fn test_minimal_template_files() {
+        assert_eq!(
+            generate(Template::Minimal),
+            [".gitignore", "Cargo.toml", "src/main.rs"]
+        );
+    }
+
+    #[test]
+    fn test_api_template_files() {
+        assert_eq!(
+            generate(Template::Api),
+            [
+                ".gitignore",
+                "Cargo.toml",
+                "src/error.rs",
+                "src/handlers.rs",
+                "src/main.rs"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fullstack_template_files() {
+        let mut files = generate(Template::Fullstack);
+
+        // The migration is named after the time it was generated
+        let migration = files
+            .iter()
+            .position(|f| f.starts_with("src/migrations/m") && f.ends_with("_create_items.rs"))
+            .expect("migration file");
+        files.remove(migration);
+
+        assert_eq!(
+            files,
+            [
+                ".env.example",
+                ".gitignore",
+                "Cargo.toml",
+                "src/auth.rs",
+                "src/entity.rs",
+                "src/error.rs",
+                "src/handlers.rs",
+                "src/items/dto.rs",
+                "src/items/error.rs",
+                "src/items/handlers.rs",
+                "src/items/mod.rs",
+                "src/main.rs",
+                "src/migrations/mod.rs",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fullstack_template_enables_database() {
+        let files = template_files("my-app", Template::Fullstack);
+        let cargo = &files.iter().find(|(f, _)| f == "Cargo.toml").unwrap().1;
+        let cargo: toml::Table = toml::from_str(cargo).unwrap();
+        assert_eq!(
+            cargo["dependencies"]["rapina"]["features"],
+            toml::Value::Array(vec!["sqlite".into()])
+        );
+
+        let migrations = &files
+            .iter()
+            .find(|(f, _)| f == "src/migrations/mod.rs")
+            .unwrap()
+            .1;
+        assert!(migrations.contains("rapina::migrations!"));
+        assert!(migrations.contains("_create_items,"));
+    }
 }
@@ -5,11 +5,16 @@ use serde_json::Value;
 use std::fs;
 use std::process::Command;
 
-const DEFAULT_URL: &str = "http://127.0.0.1:3000/__rapina/openapi.json";
+mod diff;
+
+use diff::{OpenApiSpec, detect_breaking_changes};
+
+/// Tells the app to write its OpenAPI spec to this path instead of serving.
+const EXPORT_ENV: &str = "RAPINA_OPENAPI_EXPORT";
 
 /// Export OpenAPI spec to stdout or file.
 pub fn export(output: Option<String>) -> Result<(), String> {
-    let spec = fetch_openapi_spec()?;
+    let spec = generate_openapi_spec()?;
     let canonical = canonicalize_json(&spec)?;
 
     match output {
@@ -26,6 +31,9 @@ pub fn export(output: Option<String>) -> Result<(), String> {
 }
 
 /// Check if the committed openapi.json matches the current code.
+///
+/// On a mismatch, prints what changed between the committed spec and the
+/// one the code produces, and fails.
 pub fn check(file: &str) -> Result<(), String> {
     println!();
     println!("  {} Checking OpenAPI spec...", "→".cyan());
@@ -36,25 +44,120 @@ pub fn check(file: &str) -> Result<(), String> {
     let committed_json: Value =
         serde_json::from_str(&committed).map_err(|e| format!("Failed to parse {}: {}", file, e))?;
 
-    // Fetch current spec
-    let current = fetch_openapi_spec()?;
+    // Generate current spec
+    let current = generate_openapi_spec()?;
 
-    // Compare canonical versions
-    let committed_canonical = canonicalize_json(&committed_json)?;
-    let current_canonical = canonicalize_json(&current)?;
+    check_spec(&committed_json, &current)
+}
 
-    if committed_canonical == current_canonical {
+/// Compares the committed spec with the current one, printing the drift.
+fn check_spec(committed: &Value, current: &Value) -> Result<(), String> {
+    let Some(drift) = detect_drift(committed, current)? else {
         println!("  {} OpenAPI spec is up to date", "✓".green());
-        Ok(())
-    } else {
-        println!("  {} OpenAPI spec is outdated", "✗".red());
+        return Ok(());
+    };
+
+    println!("  {} OpenAPI spec is outdated", "✗".red());
+    println!();
+    if !drift.breaking.is_empty() {
+        println!("  {} Breaking changes:", "✗".red().bold());
+        for change in &drift.breaking {
+            println!("    {} {}", "•".red(), change);
+        }
         println!();
-        println!(
-            "  Run {} to update it.",
-            "rapina openapi export -o openapi.json".cyan()
-        );
-        Err("OpenAPI spec doesn't match the current code".to_string())
     }
+    if !drift.non_breaking.is_empty() {
+        println!("  {} Non-breaking changes:", "⚠".yellow());
+        for change in &drift.non_breaking {
+            println!("    {} {}", "•".yellow(), change);
+        }
+        println!();
+    }
+    if !drift.other.is_empty() {
+        println!("  {} Other differences:", "⚠".yellow());
+        for change in &drift.other {
+            println!("    {} {}", "•".yellow(), change);
+        }
+        println!();
+    }
+    println!(
+        "  Run {} to update it.",
+        "rapina openapi export -o openapi.json".cyan()
+    );
+    Err("OpenAPI spec doesn't match the current code".to_string())
+}
+
+/// How a committed spec differs from the current one.
+#[derive(Debug, Default)]
+struct Drift {
+    breaking: Vec<String>,
+    non_breaking: Vec<String>,
+    /// Differences the semantic comparison doesn't cover, such as changed
+    /// descriptions, as JSON pointers into the spec.
+    other: Vec<String>,
+}
+
+/// Maximum number of raw differences listed when the semantic comparison
+/// finds nothing.
+const MAX_OTHER_DIFFERENCES: usize = 20;
+
+/// Returns how `committed` differs from `current`, or `None` if they match.
+fn detect_drift(committed: &Value, current: &Value) -> Result<Option<Drift>, String> {
+    if canonicalize_json(committed)? == canonicalize_json(current)? {
+        return Ok(None);
+    }
+
+    let base: OpenApiSpec = serde_json::from_value(committed.clone())
+        .map_err(|e| format!("Invalid committed OpenAPI spec: {}", e))?;
+    let now: OpenApiSpec = serde_json::from_value(current.clone())
+        .map_err(|e| format!("Invalid OpenAPI spec from the app: {}", e))?;
+    let changes = detect_breaking_changes(&base, &now);
+
+    let mut drift = Drift {
+        breaking: changes.breaking,
+        non_breaking: changes.non_breaking,
+        other: Vec::new(),
+    };
+    if drift.breaking.is_empty() && drift.non_breaking.is_empty() {
+        json_differences(committed, current, "", &mut drift.other);
+        if drift.other.len() > MAX_OTHER_DIFFERENCES {
+            let more = drift.other.len() - MAX_OTHER_DIFFERENCES;
+            drift.other.truncate(MAX_OTHER_DIFFERENCES);
+            drift.other.push(format!("... and {} more", more));
+        }
+    }
+    Ok(Some(drift))
+}
+
+/// Lists the JSON pointers at which `base` and `current` differ.
+fn json_differences(base: &Value, current: &Value, pointer: &str, out: &mut Vec<String>) {
+    match (base, current) {
+        (Value::Object(base_fields), Value::Object(current_fields)) => {
+            for (key, base_value) in base_fields {
+                let child = child_pointer(pointer, key);
+                match current_fields.get(key) {
+                    Some(current_value) => json_differences(base_value, current_value, &child, out),
+                    None => out.push(format!("Removed {}", child)),
+                }
+            }
+            for key in current_fields.keys() {
+                if !base_fields.contains_key(key) {
+                    out.push(format!("Added {}", child_pointer(pointer, key)));
+                }
+            }
+        }
+        _ if base != current => out.push(format!("Changed {}", pointer_or_root(pointer))),
+        _ => {}
+    }
+}
+
+/// Appends `key` to a JSON pointer, escaping it as RFC 6901 requires.
+fn child_pointer(pointer: &str, key: &str) -> String {
+    format!("{}/{}", pointer, key.replace('~', "~0").replace('/', "~1"))
+}
+
+fn pointer_or_root(pointer: &str) -> &str {
+    if pointer.is_empty() { "/" } else { pointer }
 }
 
 /// Compare spec with another branch and detect breaking changes.
@@ -69,10 +172,14 @@ pub fn diff(base: &str, file: &str) -> Result<(), String> {
     // Get spec from base branch using git
     let base_spec = get_spec_from_branch(base, file)?;
 
-    // Fetch current spec
-    let current_spec = fetch_openapi_spec()?;
+    // Generate current spec
+    let current_spec = generate_openapi_spec()?;
 
     // Detect breaking changes
+    let base_spec: OpenApiSpec = serde_json::from_value(base_spec)
+        .map_err(|e| format!("Invalid OpenAPI spec in {}: {}", file, e))?;
+    let current_spec: OpenApiSpec = serde_json::from_value(current_spec)
+        .map_err(|e| format!("Invalid OpenAPI spec from the app: {}", e))?;
     let changes = detect_breaking_changes(&base_spec, &current_spec);
 
     if changes.breaking.is_empty() && changes.non_breaking.is_empty() {
@@ -108,24 +215,27 @@ pub fn diff(base: &str, file: &str) -> Result<(), String> {
     }
 }
 
-/// Fetch OpenAPI spec from running application.
-fn fetch_openapi_spec() -> Result<Value, String> {
-    let output = Command::new("curl")
-        .args(["-s", "-f", DEFAULT_URL])
-        .output()
-        .map_err(|e| format!("Failed to run curl: {}", e))?;
-
-    if !output.status.success() {
-        return Err(format!(
-            "Failed to fetch OpenAPI spec. Is the server running on {}?",
-            DEFAULT_URL
-        ));
+/// Builds and runs the app with `RAPINA_OPENAPI_EXPORT` set, so it writes
+/// the spec from `Rapina::export_openapi` to a file and exits instead of
+/// serving.
+fn generate_openapi_spec() -> Result<Value, String> {
+    let path = std::env::temp_dir().join(format!("rapina-openapi-{}.json", std::process::id()));
+    let status = Command::new("cargo")
+        .args(["run", "--quiet"])
+        .env(EXPORT_ENV, &path)
+        .status()
+        .map_err(|e| format!("Failed to run cargo: {}", e))?;
+    if !status.success() {
+        return Err("Failed to generate the OpenAPI spec: the app failed to run".to_string());
     }
 
-    let body =
-        String::from_utf8(output.stdout).map_err(|e| format!("Invalid UTF-8 response: {}", e))?;
+    let body = fs::read_to_string(&path).map_err(|_| {
+        "The app exited without writing an OpenAPI spec. Does main() start it with Rapina::listen?"
+            .to_string()
+    })?;
+    let _ = fs::remove_file(&path);
 
-    serde_json::from_str(&body).map_err(|e| format!("Invalid JSON response: {}", e))
+    serde_json::from_str(&body).map_err(|e| format!("Invalid JSON from the app: {}", e))
 }
 
 /// Get OpenAPI spec from a git branch.
@@ -156,226 +266,82 @@ fn canonicalize_json(value: &Value) -> Result<String, String> {
     serde_json::to_string_pretty(value).map_err(|e| format!("Failed to serialize JSON: {}", e))
 }
 
-/// Result of breaking change detection.
-struct ChangeReport {
-    breaking: Vec<String>,
-    non_breaking: Vec<String>,
-}
-
-/// Detect breaking changes between two OpenAPI specs.
-fn detect_breaking_changes(base: &Value, current: &Value) -> ChangeReport {
-    let mut report = ChangeReport {
-        breaking: Vec::new(),
-        non_breaking: Vec::new(),
-    };
-
-    let base_paths = base.get("paths").and_then(|p| p.as_object());
-    let current_paths = current.get("paths").and_then(|p| p.as_object());
-
-    if let (Some(base_paths), Some(current_paths)) = (base_paths, current_paths) {
-        // Check for removed endpoints
-        for (path, base_item) in base_paths {
-            match current_paths.get(path) {
-                None => {
-                    report.breaking.push(format!("Removed endpoint: {}", path));
-                }
-                Some(current_item) => {
-                    // Check for removed methods
-                    check_removed_methods(path, base_item, current_item, &mut report);
-                    // Check for response schema changes
-                    check_response_changes(path, base_item, current_item, &mut report);
-                }
-            }
-        }
-
-        // Check for new endpoints (non-breaking)
-        for path in current_paths.keys() {
-            if !base_paths.contains_key(path) {
-                report
-                    .non_breaking
-                    .push(format!("Added endpoint: {}", path));
-            }
-        }
-    }
-
-    report
-}
-
-/// Check for removed HTTP methods on an endpoint.
-fn check_removed_methods(
-    path: &str,
-    base_item: &Value,
-    current_item: &Value,
-    report: &mut ChangeReport,
-) {
-    let methods = ["get", "post", "put", "delete", "patch"];
-
-    for method in methods {
-        let base_has = base_item.get(method).is_some();
-        let current_has = current_item.get(method).is_some();
-
-        if base_has && !current_has {
-            report.breaking.push(format!(
-                "Removed method: {} {}",
-                method.to_uppercase(),
-                path
-            ));
-        } else if !base_has && current_has {
-            report
-                .non_breaking
-                .push(format!("Added method: {} {}", method.to_uppercase(), path));
-        }
-    }
-}
-
-/// Check for breaking changes in response schemas.
-fn check_response_changes(
-    path: &str,
-    base_item: &Value,
-    current_item: &Value,
-    report: &mut ChangeReport,
-) {
-    let methods = ["get", "post", "put", "delete", "patch"];
-
-    for method in methods {
-        if let (Some(base_op), Some(current_op)) = (base_item.get(method), current_item.get(method))
-        {
-            // Check for removed required fields in response
-            if let (Some(base_resp), Some(current_resp)) = (
-                base_op
-                    .get("responses")
-                    .and_then(|r| r.get("200"))
-                    .and_then(|r| r.get("content"))
-                    .and_then(|c| c.get("application/json"))
-                    .and_then(|m| m.get("schema")),
-                current_op
-                    .get("responses")
-                    .and_then(|r| r.get("200"))
-                    .and_then(|r| r.get("content"))
-                    .and_then(|c| c.get("application/json"))
-                    .and_then(|m| m.get("schema")),
-            ) {
-                check_schema_changes(
-                    &format!("{} {}", method.to_uppercase(), path),
-                    base_resp,
-                    current_resp,
-                    report,
-                );
-            }
-        }
-    }
-}
-
-/// Check for breaking changes in schemas.
-fn check_schema_changes(
-    context: &str,
-    base_schema: &Value,
-    current_schema: &Value,
-    report: &mut ChangeReport,
-) {
-    // Check for removed required fields
-    if let (Some(base_props), Some(current_props)) = (
-        base_schema.get("properties").and_then(|p| p.as_object()),
-        current_schema.get("properties").and_then(|p| p.as_object()),
-    ) {
-        for prop in base_props.keys() {
-            if !current_props.contains_key(prop) {
-                report
-                    .breaking
-                    .push(format!("{}: removed field '{}'", context, prop));
-            }
-        }
-
-        for prop in current_props.keys() {
-            if !base_props.contains_key(prop) {
-                report
-                    .non_breaking
-                    .push(format!("{}: added field '{}'", context, prop));
-            }
-        }
-    }
-
-    // Check for type changes
-    if let (Some(base_type), Some(current_type)) =
-        (base_schema.get("type"), current_schema.get("type"))
-        && base_type != current_type
-    {
-        report.breaking.push(format!(
-            "{}: type changed from {} to {}",
-            context, base_type, current_type
-        ));
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
     use serde_json::json;
 
-    #[test]
-    fn test_detect_removed_endpoint() {
-        let base = json!({
-            "paths": {
-                "/users": { "get": {} },
-                "/posts": { "get": {} }
-            }
-        });
-        let current = json!({
+    fn spec(name_type: &str, description: &str) -> Value {
+        json!({
+            "openapi": "3.0.3",
+            "info": { "title": "Test", "version": "1.0.0", "description": description },
             "paths": {
-                "/users": { "get": {} }
+                "/users": {
+                    "get": {
+                        "responses": {
+                            "200": {
+                                "description": "OK",
+                                "content": {
+                                    "application/json": {
+                                        "schema": {
+                                            "type": "object",
+                                            "properties": { "name": { "type": name_type } }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
             }
-        });
-
-        let report = detect_breaking_changes(&base, &current);
-        assert!(report.breaking.iter().any(|c| c.contains("/posts")));
+        })
     }
 
     #[test]
-    fn test_detect_added_endpoint() {
-        let base = json!({
-            "paths": {
-                "/users": { "get": {} }
-            }
-        });
-        let current = json!({
-            "paths": {
-                "/users": { "get": {} },
-                "/posts": { "get": {} }
-            }
-        });
-
-        let report = detect_breaking_changes(&base, &current);
-        assert!(report.breaking.is_empty());
-        assert!(report.non_breaking.iter().any(|c| c.contains("/posts")));
+    fn test_check_passes_for_matching_spec() {
+        let committed = spec("string", "Users API");
+        assert!(
+            detect_drift(&committed, &committed.clone())
+                .unwrap()
+                .is_none()
+        );
+        assert!(check_spec(&committed, &committed.clone()).is_ok());
     }
 
     #[test]
-    fn test_detect_removed_method() {
-        let base = json!({
-            "paths": {
-                "/users": { "get": {}, "post": {} }
-            }
-        });
-        let current = json!({
-            "paths": {
-                "/users": { "get": {} }
-            }
-        });
-
-        let report = detect_breaking_changes(&base, &current);
-        assert!(report.breaking.iter().any(|c| c.contains("POST")));
+    fn test_check_fails_with_semantic_diff_for_drifted_spec() {
+        let committed = spec("string", "Users API");
+        let mut current = spec("integer", "Users API");
+        current["paths"]["/teams"] = current["paths"]["/users"].clone();
+
+        let drift = detect_drift(&committed, &current).unwrap().unwrap();
+        assert_eq!(drift.breaking.len(), 1, "{:?}", drift);
+        assert!(drift.breaking[0].contains("GET /users"), "{:?}", drift);
+        assert_eq!(drift.non_breaking, vec!["Added endpoint: /teams"]);
+        assert!(drift.other.is_empty());
+        assert!(check_spec(&committed, &current).is_err());
     }
 
     #[test]
-    fn test_no_changes() {
-        let spec = json!({
-            "paths": {
-                "/users": { "get": {} }
-            }
-        });
+    fn test_check_lists_raw_differences_when_nothing_semantic_changed() {
+        let committed = spec("string", "Users API");
+        let current = spec("string", "User management");
+
+        let drift = detect_drift(&committed, &current).unwrap().unwrap();
+        assert!(drift.breaking.is_empty() && drift.non_breaking.is_empty());
+        assert_eq!(drift.other, vec!["Changed /info/description"]);
+        assert!(check_spec(&committed, &current).is_err());
+    }
 
-        let report = detect_breaking_changes(&spec, &spec);
-        assert!(report.breaking.is_empty());
-        assert!(report.non_breaking.is_empty());
+    #[test]
+    fn test_json_differences_escapes_path_keys() {
+        let mut out = Vec::new();
+        json_differences(
+            &json!({ "paths": { "/a": 1 } }),
+            &json!({ "paths": { "/b": 1 } }),
+            "",
+            &mut out,
+        );
+        assert_eq!(out, vec!["Removed /paths/~1a", "Added /paths/~1b"]);
     }
 }
@@ -0,0 +1,1092 @@
+//! Semantic comparison of OpenAPI specs.
+//!
+//! Changes are classified from a client's point of view: anything that can
+//! make an existing client fail is breaking, everything else is not.
+//! Request schemas break when they accept less (narrowed types, newly
+//! required fields), response schemas break when they promise less (removed
+//! fields or status codes) or may return values clients don't expect
+//! (widened types).
+
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Maximum schema nesting followed, guarding against recursive `$ref`s.
+const MAX_DEPTH: usize = 32;
+
+const METHODS: [&str; 8] = [
+    "get", "post", "put", "patch", "delete", "head", "options", "trace",
+];
+
+/// OpenAPI document, limited to what the comparison needs.
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct OpenApiSpec {
+    #[serde(default)]
+    paths: BTreeMap<String, PathItem>,
+    #[serde(default)]
+    components: Option<Components>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Components {
+    #[serde(default)]
+    schemas: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct PathItem {
+    get: Option<Operation>,
+    post: Option<Operation>,
+    put: Option<Operation>,
+    patch: Option<Operation>,
+    delete: Option<Operation>,
+    head: Option<Operation>,
+    options: Option<Operation>,
+    trace: Option<Operation>,
+}
+
+impl PathItem {
+    fn operation(&self, method: &str) -> Option<&Operation> {
+        match method {
+            "get" => self.get.as_ref(),
+            "post" => self.post.as_ref(),
+            "put" => self.put.as_ref(),
+            "patch" => self.patch.as_ref(),
+            "delete" => self.delete.as_ref(),
+            "head" => self.head.as_ref(),
+            "options" => self.options.as_ref(),
+            "trace" => self.trace.as_ref(),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Operation {
+    #[serde(default)]
+    parameters: Vec<Parameter>,
+    #[serde(rename = "requestBody")]
+    request_body: Option<RequestBody>,
+    #[serde(default)]
+    responses: BTreeMap<String, Response>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Parameter {
+    name: String,
+    #[serde(rename = "in")]
+    location: String,
+    #[serde(default)]
+    required: bool,
+    schema: Option<Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RequestBody {
+    #[serde(default)]
+    required: bool,
+    #[serde(default)]
+    content: BTreeMap<String, MediaType>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Response {
+    content: Option<BTreeMap<String, MediaType>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MediaType {
+    schema: Option<Value>,
+}
+
+/// Result of breaking change detection.
+#[derive(Debug, Default)]
+pub(crate) struct ChangeReport {
+    pub(crate) breaking: Vec<String>,
+    pub(crate) non_breaking: Vec<String>,
+}
+
+impl ChangeReport {
+    fn add(&mut self, breaking: bool, change: String) {
+        if breaking {
+            self.breaking.push(change);
+        } else {
+            self.non_breaking.push(change);
+        }
+    }
+}
+
+/// Which side of the exchange a schema describes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Direction {
+    Request,
+    Response,
+}
+
+/// Resolves `$ref`s within one spec.
+struct Resolver<'a> {
+    spec: &'a OpenApiSpec,
+    root: &'a Value,
+}
+
+impl<'a> Resolver<'a> {
+    fn resolve<'b>(&'b self, schema: &'b Value) -> &'b Value
+    where
+        'a: 'b,
+    {
+        let Some(reference) = schema.get("$ref").and_then(|r| r.as_str()) else {
+            return schema;
+        };
+
+        let target = if let Some(name) = reference.strip_prefix("#/components/schemas/") {
+            self.spec
+                .components
+                .as_ref()
+                .and_then(|c| c.schemas.get(name))
+        } else if let Some(name) = reference.strip_prefix("#/$defs/") {
+            self.root.get("$defs").and_then(|d| d.get(name))
+        } else if let Some(name) = reference.strip_prefix("#/definitions/") {
+            self.root.get("definitions").and_then(|d| d.get(name))
+        } else {
+            None
+        };
+
+        target.unwrap_or(schema)
+    }
+}
+
+/// Detect breaking changes between two OpenAPI specs.
+pub(crate) fn detect_breaking_changes(base: &OpenApiSpec, current: &OpenApiSpec) -> ChangeReport {
+    let mut report = ChangeReport::default();
+
+    for (path, base_item) in &base.paths {
+        match current.paths.get(path) {
+            None => report.breaking.push(format!("Removed endpoint: {}", path)),
+            Some(current_item) => {
+                for method in METHODS {
+                    let name = format!("{} {}", method.to_uppercase(), path);
+                    match (base_item.operation(method), current_item.operation(method)) {
+                        (Some(_), None) => {
+                            report.breaking.push(format!("Removed method: {}", name))
+                        }
+                        (None, Some(_)) => {
+                            report.non_breaking.push(format!("Added method: {}", name))
+                        }
+                        (Some(base_op), Some(current_op)) => {
+                            let mut diff = OperationDiff {
+                                name: &name,
+                                base: base_op,
+                                current: current_op,
+                                base_spec: base,
+                                current_spec: current,
+                                report: &mut report,
+                            };
+                            diff.compare();
+                        }
+                        (None, None) => {}
+                    }
+                }
+            }
+        }
+    }
+
+    for path in current.paths.keys() {
+        if !base.paths.contains_key(path) {
+            report
+                .non_breaking
+                .push(format!("Added endpoint: {}", path));
+        }
+    }
+
+    report
+}
+
+/// Compares two versions of the same operation.
+struct OperationDiff<'a> {
+    name: &'a str,
+    base: &'a Operation,
+    current: &'a Operation,
+    base_spec: &'a OpenApiSpec,
+    current_spec: &'a OpenApiSpec,
+    report: &'a mut ChangeReport,
+}
+
+impl OperationDiff<'_> {
+    fn compare(&mut self) {
+        self.compare_parameters();
+        self.compare_request_body();
+        self.compare_responses();
+    }
+
+    fn compare_parameters(&mut self) {
+        let name = self.name;
+
+        for param in &self.base.parameters {
+            let label = format!("parameter '{}' ({})", param.name, param.location);
+            match find_parameter(&self.current.parameters, param) {
+                None => self
+                    .report
+                    .non_breaking
+                    .push(format!("{}: removed {}", name, label)),
+                Some(current) => {
+                    if current.required && !param.required {
+                        self.report
+                            .breaking
+                            .push(format!("{}: {} is now required", name, label));
+                    } else if !current.required && param.required {
+                        self.report
+                            .non_breaking
+                            .push(format!("{}: {} is now optional", name, label));
+                    }
+
+                    if let (Some(base_schema), Some(current_schema)) =
+                        (&param.schema, &current.schema)
+                    {
+                        self.compare_schema(
+                            &format!("{} {}", name, label),
+                            Direction::Request,
+                            base_schema,
+                            current_schema,
+                        );
+                    }
+                }
+            }
+        }
+
+        for param in &self.current.parameters {
+            if find_parameter(&self.base.parameters, param).is_none() {
+                let kind = if param.required {
+                    "required"
+                } else {
+                    "optional"
+                };
+                self.report.add(
+                    param.required,
+                    format!(
+                        "{}: new {} parameter '{}' ({})",
+                        name, kind, param.name, param.location
+                    ),
+                );
+            }
+        }
+    }
+
+    fn compare_request_body(&mut self) {
+        let name = self.name;
+
+        match (&self.base.request_body, &self.current.request_body) {
+            (None, Some(current)) => self.report.add(
+                current.required,
+                format!(
+                    "{}: new {} request body",
+                    name,
+                    if current.required {
+                        "required"
+                    } else {
+                        "optional"
+                    }
+                ),
+            ),
+            (Some(_), None) => self
+                .report
+                .non_breaking
+                .push(format!("{}: removed request body", name)),
+            (Some(base), Some(current)) => {
+                if current.required && !base.required {
+                    self.report
+                        .breaking
+                        .push(format!("{}: request body is now required", name));
+                }
+
+                let context = format!("{} request body", name);
+                self.compare_content(
+                    &context,
+                    Direction::Request,
+                    Some(&base.content),
+                    Some(&current.content),
+                );
+            }
+            (None, None) => {}
+        }
+    }
+
+    fn compare_responses(&mut self) {
+        let name = self.name;
+
+        for (status, base) in &self.base.responses {
+            match self.current.responses.get(status) {
+                None => self
+                    .report
+                    .breaking
+                    .push(format!("{}: removed response {}", name, status)),
+                Some(current) => {
+                    let context = format!("{} response {}", name, status);
+                    self.compare_content(
+                        &context,
+                        Direction::Response,
+                        base.content.as_ref(),
+                        current.content.as_ref(),
+                    );
+                }
+            }
+        }
+
+        for status in self.current.responses.keys() {
+            if !self.base.responses.contains_key(status) {
+                self.report
+                    .non_breaking
+                    .push(format!("{}: added response {}", name, status));
+            }
+        }
+    }
+
+    fn compare_content(
+        &mut self,
+        context: &str,
+        direction: Direction,
+        base: Option<&BTreeMap<String, MediaType>>,
+        current: Option<&BTreeMap<String, MediaType>>,
+    ) {
+        let empty = BTreeMap::new();
+        let base = base.unwrap_or(&empty);
+        let current = current.unwrap_or(&empty);
+
+        for (content_type, base_media) in base {
+            match current.get(content_type) {
+                // Clients can no longer send it, or may no longer receive it
+                None => self.report.breaking.push(format!(
+                    "{}: removed content type {}",
+                    context, content_type
+                )),
+                Some(current_media) => {
+                    if let (Some(base_schema), Some(current_schema)) =
+                        (&base_media.schema, &current_media.schema)
+                    {
+                        self.compare_schema(context, direction, base_schema, current_schema);
+                    }
+                }
+            }
+        }
+
+        for content_type in current.keys() {
+            if !base.contains_key(content_type) {
+                self.report
+                    .non_breaking
+                    .push(format!("{}: added content type {}", context, content_type));
+            }
+        }
+    }
+
+    fn compare_schema(
+        &mut self,
+        context: &str,
+        direction: Direction,
+        base: &Value,
+        current: &Value,
+    ) {
+        let base_resolver = Resolver {
+            spec: self.base_spec,
+            root: base,
+        };
+        let current_resolver = Resolver {
+            spec: self.current_spec,
+            root: current,
+        };
+        let mut diff = SchemaDiff {
+            context,
+            direction,
+            base: &base_resolver,
+            current: &current_resolver,
+            report: self.report,
+        };
+        diff.compare("", base, current, 0);
+    }
+}
+
+/// Finds a parameter with the same name and location.
+fn find_parameter<'a>(params: &'a [Parameter], param: &Parameter) -> Option<&'a Parameter> {
+    params
+        .iter()
+        .find(|p| p.name == param.name && p.location == param.location)
+}
+
+/// Compares two schemas for one request or response.
+struct SchemaDiff<'a> {
+    context: &'a str,
+    direction: Direction,
+    base: &'a Resolver<'a>,
+    current: &'a Resolver<'a>,
+    report: &'a mut ChangeReport,
+}
+
+impl SchemaDiff<'_> {
+    fn compare(&mut self, field: &str, base: &Value, current: &Value, depth: usize) {
+        if depth > MAX_DEPTH {
+            return;
+        }
+        let base = self.base.resolve(base);
+        let current = self.current.resolve(current);
+
+        self.compare_types(field, base, current);
+        self.compare_enums(field, base, current);
+        self.compare_properties(field, base, current, depth);
+
+        if let (Some(base_items), Some(current_items)) = (base.get("items"), current.get("items")) {
+            self.compare(
+                &format!("{}[]", field),
+                base_items,
+                current_items,
+                depth + 1,
+            );
+        }
+    }
+
+    fn describe(&self, field: &str) -> String {
+        if field.is_empty() {
+            self.context.to_string()
+        } else {
+            format!("{} field '{}'", self.context, field)
+        }
+    }
+
+    fn compare_types(&mut self, field: &str, base: &Value, current: &Value) {
+        let (Some(base_types), Some(current_types)) = (schema_types(base), schema_types(current))
+        else {
+            return;
+        };
+
+        let narrowed = base_types.iter().any(|t| !covers(&current_types, t));
+        let widened = current_types.iter().any(|t| !covers(&base_types, t));
+        let change = format!(
+            "{}: type changed from {} to {}",
+            self.describe(field),
+            format_types(&base_types),
+            format_types(&current_types)
+        );
+
+        match (narrowed, widened) {
+            (true, true) => self.report.breaking.push(change),
+            (true, false) => self.report.add(
+                self.direction == Direction::Request,
+                format!("{} (narrowed)", change),
+            ),
+            (false, true) => self.report.add(
+                self.direction == Direction::Response,
+                format!("{} (widened)", change),
+            ),
+            (false, false) => {}
+        }
+    }
+
+    fn compare_enums(&mut self, field: &str, base: &Value, current: &Value) {
+        let (Some(base_values), Some(current_values)) = (
+            base.get("enum").and_then(|e| e.as_array()),
+            current.get("enum").and_then(|e| e.as_array()),
+        ) else {
+            return;
+        };
+
+        for value in base_values {
+            if !current_values.contains(value) {
+                self.report.add(
+                    self.direction == Direction::Request,
+                    format!("{}: removed enum value {}", self.describe(field), value),
+                );
+            }
+        }
+        for value in current_values {
+            if !base_values.contains(value) {
+                self.report.add(
+                    self.direction == Direction::Response,
+                    format!("{}: added enum value {}", self.describe(field), value),
+                );
+            }
+        }
+    }
+
+    fn compare_properties(&mut self, field: &str, base: &Value, current: &Value, depth: usize) {
+        let (Some(base_props), Some(current_props)) = (
+            base.get("properties").and_then(|p| p.as_object()),
+            current.get("properties").and_then(|p| p.as_object()),
+        ) else {
+            return;
+        };
+        let base_required = required_fields(base);
+        let current_required = required_fields(current);
+        let context = self.context;
+
+        let removed: Vec<&String> = base_props
+            .keys()
+            .filter(|k| !current_props.contains_key(*k))
+            .collect();
+        let mut added: Vec<&String> = current_props
+            .keys()
+            .filter(|k| !base_props.contains_key(*k))
+            .collect();
+
+        for name in removed {
+            // A removed field whose schema reappears under a new name is a rename
+            let renamed_to = added
+                .iter()
+                .position(|a| {
+                    self.base.resolve(&base_props[name]) == self.current.resolve(&current_props[*a])
+                })
+                .map(|i| added.remove(i));
+            let breaking = match self.direction {
+                // Clients may still send the old field, which is now ignored
+                Direction::Request => {
+                    renamed_to.is_some_and(|n| current_required.contains(n.as_str()))
+                }
+                Direction::Response => true,
+            };
+            let change = match renamed_to {
+                Some(new_name) => format!(
+                    "{}: renamed field '{}' to '{}'",
+                    context,
+                    join_field(field, name),
+                    join_field(field, new_name)
+                ),
+                None => format!("{}: removed field '{}'", context, join_field(field, name)),
+            };
+            self.report.add(breaking, change);
+        }
+
+        for name in added {
+            let required = current_required.contains(name.as_str());
+            let change = if required && self.direction == Direction::Request {
+                format!(
+                    "{}: new required field '{}'",
+                    context,
+                    join_field(field, name)
+                )
+            } else {
+                format!("{}: added field '{}'", context, join_field(field, name))
+            };
+            self.report
+                .add(required && self.direction == Direction::Request, change);
+        }
+
+        for (name, base_prop) in base_props {
+            let Some(current_prop) = current_props.get(name) else {
+                continue;
+            };
+            let path = join_field(field, name);
+            let was_required = base_required.contains(name.as_str());
+            let is_required = current_required.contains(name.as_str());
+
+            match (self.direction, was_required, is_required) {
+                (Direction::Request, false, true) => self
+                    .report
+                    .breaking
+                    .push(format!("{}: field '{}' is now required", context, path)),
+                (Direction::Response, true, false) => self
+                    .report
+                    .breaking
+                    .push(format!("{}: field '{}' is now optional", context, path)),
+                (_, false, true) => self
+                    .report
+                    .non_breaking
+                    .push(format!("{}: field '{}' is now required", context, path)),
+                (_, true, false) => self
+                    .report
+                    .non_breaking
+                    .push(format!("{}: field '{}' is now optional", context, path)),
+                _ => {}
+            }
+
+            self.compare(&path, base_prop, current_prop, depth + 1);
+        }
+    }
+}
+
+fn join_field(parent: &str, name: &str) -> String {
+    if parent.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", parent, name)
+    }
+}
+
+fn required_fields(schema: &Value) -> BTreeSet<&str> {
+    schema
+        .get("required")
+        .and_then(|r| r.as_array())
+        .map(|r| r.iter().filter_map(|v| v.as_str()).collect())
+        .unwrap_or_default()
+}
+
+/// Returns the set of JSON types a schema allows, if it declares any.
+fn schema_types(schema: &Value) -> Option<BTreeSet<&str>> {
+    let mut types: BTreeSet<&str> = match schema.get("type")? {
+        Value::String(t) => [t.as_str()].into(),
+        Value::Array(ts) => ts.iter().filter_map(|t| t.as_str()).collect(),
+        _ => return None,
+    };
+    if schema.get("nullable").and_then(|n| n.as_bool()) == Some(true) {
+        types.insert("null");
+    }
+    Some(types)
+}
+
+/// Whether `types` accepts every value of type `ty`.
+fn covers(types: &BTreeSet<&str>, ty: &str) -> bool {
+    types.contains(ty) || (ty == "integer" && types.contains("number"))
+}
+
+fn format_types(types: &BTreeSet<&str>) -> String {
+    types.iter().copied().collect::<Vec<_>>().join(" | ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn spec(value: Value) -> OpenApiSpec {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn diff(base: Value, current: Value) -> ChangeReport {
+        detect_breaking_changes(&spec(base), &spec(current))
+    }
+
+    fn with_request(schema: Value) -> Value {
+        json!({
+            "paths": {
+                "/users": {
+                    "post": {
+                        "requestBody": {
+                            "required": true,
+                            "content": { "application/json": { "schema": schema } }
+                        },
+                        "responses": { "200": { "description": "Success" } }
+                    }
+                }
+            }
+        })
+    }
+
+    fn with_response(schema: Value) -> Value {
+        json!({
+            "paths": {
+                "/users": {
+                    "get": {
+                        "responses": {
+                            "200": {
+                                "description": "Success",
+                                "content": { "application/json": { "schema": schema } }
+                            }
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    fn with_parameters(parameters: Value) -> Value {
+        json!({
+            "paths": {
+                "/users": {
+                    "get": {
+                        "parameters": parameters,
+                        "responses": { "200": { "description": "Success" } }
+                    }
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn test_detect_removed_endpoint() {
+        let report = diff(
+            json!({ "paths": { "/users": { "get": {} }, "/posts": { "get": {} } } }),
+            json!({ "paths": { "/users": { "get": {} } } }),
+        );
+        assert!(report.breaking.iter().any(|c| c.contains("/posts")));
+    }
+
+    #[test]
+    fn test_detect_added_endpoint() {
+        let report = diff(
+            json!({ "paths": { "/users": { "get": {} } } }),
+            json!({ "paths": { "/users": { "get": {} }, "/posts": { "get": {} } } }),
+        );
+        assert!(report.breaking.is_empty());
+        assert!(report.non_breaking.iter().any(|c| c.contains("/posts")));
+    }
+
+    #[test]
+    fn test_detect_removed_method() {
+        let report = diff(
+            json!({ "paths": { "/users": { "get": {}, "post": {} } } }),
+            json!({ "paths": { "/users": { "get": {} } } }),
+        );
+        assert!(report.breaking.iter().any(|c| c.contains("POST")));
+    }
+
+    #[test]
+    fn test_detect_added_method() {
+        let report = diff(
+            json!({ "paths": { "/users": { "get": {} } } }),
+            json!({ "paths": { "/users": { "get": {}, "patch": {} } } }),
+        );
+        assert!(report.breaking.is_empty());
+        assert_eq!(report.non_breaking, vec!["Added method: PATCH /users"]);
+    }
+
+    #[test]
+    fn test_no_changes() {
+        let spec = with_response(json!({
+            "type": "object",
+            "properties": { "id": { "type": "integer" } }
+        }));
+        let report = diff(spec.clone(), spec);
+        assert!(report.breaking.is_empty());
+        assert!(report.non_breaking.is_empty());
+    }
+
+    #[test]
+    fn test_removed_response_field_is_breaking() {
+        let report = diff(
+            with_response(json!({
+                "type": "object",
+                "properties": { "id": { "type": "integer" }, "email": { "type": "string" } }
+            })),
+            with_response(json!({
+                "type": "object",
+                "properties": { "id": { "type": "integer" } }
+            })),
+        );
+        assert_eq!(
+            report.breaking,
+            vec!["GET /users response 200: removed field 'email'"]
+        );
+    }
+
+    #[test]
+    fn test_added_response_field_is_not_breaking() {
+        let report = diff(
+            with_response(json!({
+                "type": "object",
+                "properties": { "id": { "type": "integer" } }
+            })),
+            with_response(json!({
+                "type": "object",
+                "properties": { "id": { "type": "integer" }, "email": { "type": "string" } }
+            })),
+        );
+        assert!(report.breaking.is_empty());
+        assert_eq!(
+            report.non_breaking,
+            vec!["GET /users response 200: added field 'email'"]
+        );
+    }
+
+    #[test]
+    fn test_renamed_response_field_is_breaking() {
+        let report = diff(
+            with_response(json!({
+                "type": "object",
+                "properties": { "mail": { "type": "string" } }
+            })),
+            with_response(json!({
+                "type": "object",
+                "properties": { "email": { "type": "string" } }
+            })),
+        );
+        assert_eq!(
+            report.breaking,
+            vec!["GET /users response 200: renamed field 'mail' to 'email'"]
+        );
+        assert!(report.non_breaking.is_empty());
+    }
+
+    #[test]
+    fn test_nested_field_removal_through_ref() {
+        let base = json!({
+            "paths": {
+                "/users": {
+                    "get": {
+                        "responses": {
+                            "200": {
+                                "description": "Success",
+                                "content": { "application/json": { "schema": {
+                                    "type": "object",
+                                    "properties": { "address": { "$ref": "#/$defs/Address" } },
+                                    "$defs": { "Address": {
+                                        "type": "object",
+                                        "properties": {
+                                            "city": { "type": "string" },
+                                            "zip": { "type": "string" }
+                                        }
+                                    } }
+                                } } }
+                            }
+                        }
+                    }
+                }
+            }
+        });
+        let mut current = base.clone();
+        current["paths"]["/users"]["get"]["responses"]["200"]["content"]["application/json"]
+            ["schema"]["$defs"]["Address"]["properties"]
+            .as_object_mut()
+            .unwrap()
+            .remove("zip");
+
+        let report = diff(base, current);
+        assert_eq!(
+            report.breaking,
+            vec!["GET /users response 200: removed field 'address.zip'"]
+        );
+    }
+
+    #[test]
+    fn test_request_type_narrowing_is_breaking() {
+        let report = diff(
+            with_request(json!({
+                "type": "object",
+                "properties": { "age": { "type": "number" } }
+            })),
+            with_request(json!({
+                "type": "object",
+                "properties": { "age": { "type": "integer" } }
+            })),
+        );
+        assert_eq!(
+            report.breaking,
+            vec![
+                "POST /users request body field 'age': type changed from number to integer (narrowed)"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_request_type_widening_is_not_breaking() {
+        let report = diff(
+            with_request(json!({
+                "type": "object",
+                "properties": { "nickname": { "type": "string" } }
+            })),
+            with_request(json!({
+                "type": "object",
+                "properties": { "nickname": { "type": ["string", "null"] } }
+            })),
+        );
+        assert!(report.breaking.is_empty());
+        assert_eq!(report.non_breaking.len(), 1);
+        assert!(report.non_breaking[0].contains("(widened)"));
+    }
+
+    #[test]
+    fn test_response_type_widening_is_breaking() {
+        let report = diff(
+            with_response(json!({
+                "type": "object",
+                "properties": { "name": { "type": "string" } }
+            })),
+            with_response(json!({
+                "type": "object",
+                "properties": { "name": { "type": ["string", "null"] } }
+            })),
+        );
+        assert_eq!(report.breaking.len(), 1);
+        assert!(report.breaking[0].contains("field 'name'"));
+    }
+
+    #[test]
+    fn test_incompatible_type_change_is_breaking() {
+        let report = diff(
+            with_response(json!({ "type": "string" })),
+            with_response(json!({ "type": "integer" })),
+        );
+        assert_eq!(
+            report.breaking,
+            vec!["GET /users response 200: type changed from string to integer"]
+        );
+    }
+
+    #[test]
+    fn test_removed_request_enum_value_is_breaking() {
+        let report = diff(
+            with_request(json!({ "type": "string", "enum": ["admin", "user"] })),
+            with_request(json!({ "type": "string", "enum": ["user"] })),
+        );
+        assert_eq!(
+            report.breaking,
+            vec!["POST /users request body: removed enum value \"admin\""]
+        );
+    }
+
+    #[test]
+    fn test_new_required_request_field_is_breaking() {
+        let report = diff(
+            with_request(json!({
+                "type": "object",
+                "required": ["name"],
+                "properties": { "name": { "type": "string" } }
+            })),
+            with_request(json!({
+                "type": "object",
+                "required": ["name", "email"],
+                "properties": { "name": { "type": "string" }, "email": { "type": "string" } }
+            })),
+        );
+        assert_eq!(
+            report.breaking,
+            vec!["POST /users request body: new required field 'email'"]
+        );
+    }
+
+    #[test]
+    fn test_new_optional_request_field_is_not_breaking() {
+        let report = diff(
+            with_request(json!({
+                "type": "object",
+                "properties": { "name": { "type": "string" } }
+            })),
+            with_request(json!({
+                "type": "object",
+                "properties": { "name": { "type": "string" }, "bio": { "type": "string" } }
+            })),
+        );
+        assert!(report.breaking.is_empty());
+        assert_eq!(
+            report.non_breaking,
+            vec!["POST /users request body: added field 'bio'"]
+        );
+    }
+
+    #[test]
+    fn test_request_field_becoming_required_is_breaking() {
+        let report = diff(
+            with_request(json!({
+                "type": "object",
+                "properties": { "name": { "type": "string" } }
+            })),
+            with_request(json!({
+                "type": "object",
+                "required": ["name"],
+                "properties": { "name": { "type": "string" } }
+            })),
+        );
+        assert_eq!(
+            report.breaking,
+            vec!["POST /users request body: field 'name' is now required"]
+        );
+    }
+
+    #[test]
+    fn test_removed_request_field_is_not_breaking() {
+        let report = diff(
+            with_request(json!({
+                "type": "object",
+                "properties": { "name": { "type": "string" }, "legacy": { "type": "boolean" } }
+            })),
+            with_request(json!({
+                "type": "object",
+                "properties": { "name": { "type": "string" } }
+            })),
+        );
+        assert!(report.breaking.is_empty());
+        assert_eq!(
+            report.non_breaking,
+            vec!["POST /users request body: removed field 'legacy'"]
+        );
+    }
+
+    #[test]
+    fn test_new_required_parameter_is_breaking() {
+        let report = diff(
+            with_parameters(json!([])),
+            with_parameters(json!([
+                { "name": "page", "in": "query", "required": true },
+                { "name": "search", "in": "query", "required": false }
+            ])),
+        );
+        assert_eq!(
+            report.breaking,
+            vec!["GET /users: new required parameter 'page' (query)"]
+        );
+        assert_eq!(
+            report.non_breaking,
+            vec!["GET /users: new optional parameter 'search' (query)"]
+        );
+    }
+
+    #[test]
+    fn test_new_required_request_body_is_breaking() {
+        let report = diff(
+            json!({ "paths": { "/users": { "post": { "responses": {} } } } }),
+            with_request(json!({ "type": "object" })),
+        );
+        assert_eq!(
+            report.breaking,
+            vec!["POST /users: new required request body"]
+        );
+    }
+
+    #[test]
+    fn test_removed_response_code_is_breaking() {
+        let base = json!({
+            "paths": { "/users": { "get": { "responses": {
+                "200": { "description": "Success" },
+                "404": { "description": "Not found" }
+            } } } }
+        });
+        let current = json!({
+            "paths": { "/users": { "get": { "responses": {
+                "200": { "description": "Success" }
+            } } } }
+        });
+
+        let report = diff(base, current);
+        assert_eq!(report.breaking, vec!["GET /users: removed response 404"]);
+    }
+
+    #[test]
+    fn test_added_response_code_is_not_breaking() {
+        let base = json!({
+            "paths": { "/users": { "get": { "responses": {
+                "200": { "description": "Success" }
+            } } } }
+        });
+        let current = json!({
+            "paths": { "/users": { "get": { "responses": {
+                "200": { "description": "Success" },
+                "409": { "description": "Conflict" }
+            } } } }
+        });
+
+        let report = diff(base, current);
+        assert!(report.breaking.is_empty());
+        assert_eq!(report.non_breaking, vec!["GET /users: added response 409"]);
+    }
+
+    #[test]
+    fn test_components_ref_resolution() {
+        let base = json!({
+            "paths": { "/users": { "get": { "responses": { "200": {
+                "description": "Success",
+                "content": { "application/json": { "schema": {
+                    "$ref": "#/components/schemas/User"
+                } } }
+            } } } } },
+            "components": { "schemas": { "User": {
+                "type": "object",
+                "properties": { "id": { "type": "integer" }, "name": { "type": "string" } }
+            } } }
+        });
+        let mut current = base.clone();
+        current["components"]["schemas"]["User"]["properties"]
+            .as_object_mut()
+            .unwrap()
+            .remove("name");
+
+        let report = diff(base, current);
+        assert_eq!(
+            report.breaking,
+            vec!["GET /users response 200: removed field 'name'"]
+        );
+    }
+}
@@ -11,6 +11,7 @@ struct RouteInfo {
     method: String,
     path: String,
     handler_name: String,
+    host: Option<String>,
 }
 
 /// List all registered routes from the running application.
@@ -42,10 +43,14 @@ pub fn execute() -> Result<(), String> {
             "DELETE" => route.method.red(),
             _ => route.method.normal(),
         };
+        let path = match &route.host {
+            Some(host) => format!("{}{}", host, route.path),
+            None => route.path.clone(),
+        };
         println!(
             "  {:<6}  {:<20}  {}",
             method_colored,
-            route.path.cyan(),
+            path.cyan(),
             route.handler_name
         );
     }
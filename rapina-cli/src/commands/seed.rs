@@ -0,0 +1,31 @@
+//! Implementation of the `rapina seed` command.
+
+use colored::Colorize;
+use std::path::Path;
+use std::process::Command;
+
+/// Run the seeders in `src/seeds` against the database in `DATABASE_URL`.
+pub fn execute() -> Result<(), String> {
+    if !Path::new("src/bin/seed.rs").exists() {
+        return Err(
+            "No seeders found. Generate one with: rapina add seeder <name> <field:type> ..."
+                .to_string(),
+        );
+    }
+
+    println!();
+    println!("  {} Seeding the database...", "→".cyan());
+    println!();
+
+    let status = Command::new("cargo")
+        .args(["run", "--quiet", "--bin", "seed"])
+        .status()
+        .map_err(|e| format!("Failed to run cargo: {}", e))?;
+
+    if !status.success() {
+        return Err("Seeding failed".to_string());
+    }
+
+    println!();
+    Ok(())
+}
@@ -21,6 +21,9 @@ enum Commands {
     New {
         /// Name of the project to create
         name: String,
+        /// Project template: minimal, api or fullstack
+        #[arg(short, long, default_value = "minimal")]
+        template: String,
     },
     /// Start development server with hot reload
     Dev {
@@ -33,6 +36,9 @@ enum Commands {
         /// Disable hot reload
         #[arg(long)]
         no_reload: bool,
+        /// Don't apply pending migrations before starting the server
+        #[arg(long)]
+        no_migrate: bool,
     },
     /// OpenAPI specification tools
     Openapi {
@@ -46,6 +52,8 @@ enum Commands {
         #[command(subcommand)]
         command: MigrateCommands,
     },
+    /// Insert development data with the seeders in src/seeds
+    Seed,
     /// Run health checks on your API
     Doctor,
     /// Add components to your Rapina project
@@ -83,7 +91,19 @@ enum AddCommands {
         name: String,
         /// Fields in name:type format (e.g., title:string active:bool)
         fields: Vec<String>,
+        /// Add a version column and reject updates from clients with a stale version (409)
+        #[arg(long)]
+        versioned: bool,
     },
+    /// Generate a seeder inserting sample rows for a resource
+    Seeder {
+        /// Name of the resource (lowercase, e.g., user, blog_post)
+        name: String,
+        /// Fields in name:type format (e.g., title:string active:bool)
+        fields: Vec<String>,
+    },
+    /// Generate src/bin/migrate.rs, which `rapina dev` runs to apply pending migrations
+    Migrator,
 }
 
 #[derive(Subcommand)]
@@ -118,8 +138,8 @@ fn main() {
         Some(Commands::Version) => {
             print_version();
         }
-        Some(Commands::New { name }) => {
-            if let Err(e) = commands::new::execute(&name) {
+        Some(Commands::New { name, template }) => {
+            if let Err(e) = commands::new::execute(&name, &template) {
                 eprintln!("{} {}", "Error:".red().bold(), e);
                 std::process::exit(1);
             }
@@ -128,11 +148,13 @@ fn main() {
             port,
             host,
             no_reload,
+            no_migrate,
         }) => {
             let config = commands::dev::DevConfig {
                 host,
                 port,
                 reload: !no_reload,
+                migrate: !no_migrate,
             };
             if let Err(e) = commands::dev::execute(config) {
                 eprintln!("{} {}", "Error:".red().bold(), e);
@@ -150,7 +172,13 @@ fn main() {
         }
         Some(Commands::Add { command }) => {
             let result = match command {
-                AddCommands::Resource { name, fields } => commands::add::resource(&name, &fields),
+                AddCommands::Resource {
+                    name,
+                    fields,
+                    versioned,
+                } => commands::add::resource(&name, &fields, versioned),
+                AddCommands::Seeder { name, fields } => commands::add::seeder(&name, &fields),
+                AddCommands::Migrator => commands::add::migrator(),
             };
             if let Err(e) = result {
                 eprintln!("{} {}", "Error:".red().bold(), e);
@@ -174,6 +202,12 @@ fn main() {
                 std::process::exit(1);
             }
         }
+        Some(Commands::Seed) => {
+            if let Err(e) = commands::seed::execute() {
+                eprintln!("{} {}", "Error:".red().bold(), e);
+                std::process::exit(1);
+            }
+        }
         Some(Commands::Doctor) => {
             if let Err(e) = commands::doctor::execute() {
                 eprintln!("{} {}", "Error:".red().bold(), e);
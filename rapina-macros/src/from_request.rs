@@ -0,0 +1,254 @@
+//! `#[derive(FromRequest)]`: one struct extracted from several parts of a request.
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::spanned::Spanned;
+use syn::{DeriveInput, Field, LitStr};
+
+use super::{ExtractorKind, classify_extractor};
+
+/// Where a field of the struct is extracted from.
+enum Source {
+    /// A path parameter, named after the field unless renamed.
+    Path(String),
+    /// A query string parameter, named after the field unless renamed.
+    Query(String),
+    /// The request body, as JSON unless the field is a body extractor.
+    Body,
+    /// A header, named after the field with `_` replaced by `-` unless renamed.
+    Header(String),
+    /// Any other parts extractor, such as `Context` or `CurrentUser`.
+    Extractor,
+}
+
+/// Reads the `#[from(...)]` attribute of a field, e.g. `#[from(query)]` or
+/// `#[from(header = "x-api-key")]`.
+fn field_source(field: &Field) -> syn::Result<Source> {
+    let name = field.ident.as_ref().unwrap().to_string();
+    let mut source = None;
+
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("from"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if source.is_some() {
+                return Err(meta.error("a field can only come from one part of the request"));
+            }
+            let rename = if meta.input.peek(syn::Token![=]) {
+                Some(meta.value()?.parse::<LitStr>()?.value())
+            } else {
+                None
+            };
+            source = Some(if meta.path.is_ident("path") {
+                Source::Path(rename.unwrap_or_else(|| name.clone()))
+            } else if meta.path.is_ident("query") {
+                Source::Query(rename.unwrap_or_else(|| name.clone()))
+            } else if meta.path.is_ident("header") {
+                Source::Header(rename.unwrap_or_else(|| name.replace('_', "-")))
+            } else if meta.path.is_ident("body") && rename.is_none() {
+                Source::Body
+            } else {
+                return Err(meta.error(
+                    "expected `path`, `query`, `header` or `body`, optionally with `= \"name\"`",
+                ));
+            });
+            Ok(())
+        })?;
+    }
+
+    Ok(source.unwrap_or(Source::Extractor))
+}
+
+/// Returns `T` when `ty` is `Option<T>`.
+fn option_inner(ty: &syn::Type) -> Option<&syn::Type> {
+    let syn::Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) => match args.args.first()? {
+            syn::GenericArgument::Type(inner) => Some(inner),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+pub(crate) fn derive_from_request_impl(input: TokenStream) -> TokenStream {
+    match expand(input) {
+        Ok(tokens) => tokens,
+        Err(e) => e.to_compile_error(),
+    }
+}
+
+fn expand(input: TokenStream) -> syn::Result<TokenStream> {
+    let input: DeriveInput = syn::parse2(input)?;
+    let name = &input.ident;
+
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new(
+            input.generics.span(),
+            "FromRequest derive does not support generic structs",
+        ));
+    }
+    let fields = match &input.data {
+        syn::Data::Struct(syn::DataStruct {
+            fields: syn::Fields::Named(fields),
+            ..
+        }) => &fields.named,
+        _ => {
+            return Err(syn::Error::new(
+                input.span(),
+                "FromRequest derive only supports structs with named fields",
+            ));
+        }
+    };
+
+    let path_struct = format_ident!("__{}Path", name);
+    let query_struct = format_ident!("__{}Query", name);
+    let mut path_fields = Vec::new();
+    let mut query_fields = Vec::new();
+    let mut parts_lets = Vec::new();
+    let mut body_let = None;
+    let mut field_names = Vec::new();
+
+    for field in fields {
+        let field_name = field.ident.as_ref().unwrap();
+        let ty = &field.ty;
+        field_names.push(field_name);
+
+        match field_source(field)? {
+            Source::Path(param) => {
+                path_fields.push(quote! { #[serde(rename = #param)] #field_name: #ty });
+                parts_lets.push(quote! { let #field_name = __path.#field_name; });
+            }
+            Source::Query(param) => {
+                query_fields.push(quote! { #[serde(rename = #param)] #field_name: #ty });
+                parts_lets.push(quote! { let #field_name = __query.#field_name; });
+            }
+            Source::Header(header) => {
+                let value = match option_inner(ty) {
+                    Some(inner) => quote! {
+                        rapina::extract::header_field::<#inner>(&parts.headers, #header)?
+                    },
+                    None => quote! {
+                        rapina::extract::required_header_field::<#ty>(&parts.headers, #header)?
+                    },
+                };
+                parts_lets.push(quote! { let #field_name = #value; });
+            }
+            Source::Extractor => {
+                parts_lets.push(quote! {
+                    let #field_name = <#ty as rapina::extract::FromRequestParts>::from_request_parts(
+                        &parts, params, state,
+                    )
+                    .await?;
+                });
+            }
+            Source::Body => {
+                if body_let.is_some() {
+                    return Err(syn::Error::new(
+                        field.span(),
+                        "only one field can come from the body",
+                    ));
+                }
+                let extractor = if classify_extractor(ty) == ExtractorKind::Body {
+                    quote! { <#ty as rapina::extract::FromRequest>::from_request(req, params, state).await? }
+                } else {
+                    quote! {
+                        <rapina::extract::Json<#ty> as rapina::extract::FromRequest>::from_request(
+                            req, params, state,
+                        )
+                        .await?
+                        .0
+                    }
+                };
+                body_let = Some(quote! { let #field_name = #extractor; });
+            }
+        }
+    }
+
+    let path_items = (!path_fields.is_empty()).then(|| {
+        quote! {
+            #[derive(rapina::serde::Deserialize)]
+            #[serde(crate = "rapina::serde")]
+            struct #path_struct { #(#path_fields),* }
+        }
+    });
+    let path_let = path_items.is_some().then(|| {
+        quote! {
+            let __path = <rapina::extract::Path<#path_struct> as rapina::extract::FromRequestParts>::from_request_parts(
+                &parts, params, state,
+            )
+            .await?
+            .0;
+        }
+    });
+    let query_items = (!query_fields.is_empty()).then(|| {
+        quote! {
+            #[derive(rapina::serde::Deserialize)]
+            #[serde(crate = "rapina::serde")]
+            struct #query_struct { #(#query_fields),* }
+        }
+    });
+    let query_let = query_items.is_some().then(|| {
+        quote! {
+            let __query = <rapina::extract::Query<#query_struct> as rapina::extract::FromRequestParts>::from_request_parts(
+                &parts, params, state,
+            )
+            .await?
+            .0;
+        }
+    });
+
+    let extraction = quote! {
+        #path_let
+        #query_let
+        #(#parts_lets)*
+    };
+
+    // With a body field the struct consumes the request, so it must be the
+    // last handler argument; without one it can go anywhere
+    let impl_block = match body_let {
+        Some(body_let) => quote! {
+            impl rapina::extract::FromRequest for #name {
+                async fn from_request(
+                    req: rapina::hyper::Request<rapina::hyper::body::Incoming>,
+                    params: &rapina::extract::PathParams,
+                    state: &std::sync::Arc<rapina::state::AppState>,
+                ) -> std::result::Result<Self, rapina::error::Error> {
+                    let (parts, body) = req.into_parts();
+                    #extraction
+                    let req = rapina::hyper::Request::from_parts(parts, body);
+                    #body_let
+                    Ok(Self { #(#field_names),* })
+                }
+            }
+        },
+        None => quote! {
+            impl rapina::extract::FromRequestParts for #name {
+                async fn from_request_parts(
+                    parts: &rapina::http::request::Parts,
+                    params: &rapina::extract::PathParams,
+                    state: &std::sync::Arc<rapina::state::AppState>,
+                ) -> std::result::Result<Self, rapina::error::Error> {
+                    #extraction
+                    Ok(Self { #(#field_names),* })
+                }
+            }
+        },
+    };
+
+    Ok(quote! {
+        const _: () = {
+            #path_items
+            #query_items
+            #impl_block
+        };
+    })
+}
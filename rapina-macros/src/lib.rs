@@ -1,7 +1,9 @@
 use proc_macro::TokenStream;
-use quote::quote;
+use quote::{ToTokens, quote, quote_spanned};
+use syn::spanned::Spanned;
 use syn::{FnArg, ItemFn, LitStr, Pat};
 
+mod from_request;
 mod schema;
 
 #[proc_macro_attribute]
@@ -61,8 +63,13 @@ fn route_macro_core(
     attr: proc_macro2::TokenStream,
     item: proc_macro2::TokenStream,
 ) -> proc_macro2::TokenStream {
-    let _path: LitStr = syn::parse2(attr).expect("expected path as string literal");
-    let mut func: ItemFn = syn::parse2(item).expect("expected function");
+    if let Err(e) = syn::parse2::<LitStr>(attr) {
+        return syn::Error::new(e.span(), "expected path as string literal").to_compile_error();
+    }
+    let mut func: ItemFn = match syn::parse2(item) {
+        Ok(func) => func,
+        Err(e) => return syn::Error::new(e.span(), "expected function").to_compile_error(),
+    };
 
     let func_name = &func.sig.ident;
     let func_name_str = func_name.to_string();
@@ -81,6 +88,76 @@ fn route_macro_core(
         quote! {}
     };
 
+    // Rust's #[deprecated] marks the operation as deprecated in the spec.
+    // It is consumed here so registering the route doesn't emit warnings.
+    let deprecated = extract_deprecated_attr(&mut func.attrs);
+    let deprecated_impl = if deprecated {
+        quote! { const DEPRECATED: bool = true; }
+    } else {
+        quote! {}
+    };
+
+    // #[openapi(hidden)] keeps the route out of the OpenAPI spec
+    let hidden_impl = match extract_openapi_attr(&mut func.attrs) {
+        Ok(true) => quote! { const HIDDEN: bool = true; },
+        Ok(false) => quote! {},
+        Err(e) => return e.to_compile_error(),
+    };
+
+    // Extract #[tag("name", ...)] attribute if present
+    let tags = match extract_tag_attr(&mut func.attrs) {
+        Ok(tags) => tags,
+        Err(e) => return e.to_compile_error(),
+    };
+    let tags_impl = if tags.is_empty() {
+        quote! {}
+    } else {
+        quote! { const TAGS: &'static [&'static str] = &[#(#tags),*]; }
+    };
+
+    // Extract #[responses((status, Type), ...)] attribute if present
+    let responses = match extract_responses_attr(&mut func.attrs) {
+        Ok(responses) => responses,
+        Err(e) => return e.to_compile_error(),
+    };
+
+    let responses_impl = if responses.is_empty() {
+        quote! {}
+    } else {
+        let entries = responses.iter().map(|r| {
+            let status = &r.status;
+            let ty = &r.ty;
+            let content_type = r
+                .content_type
+                .as_ref()
+                .map(|c| c.value())
+                .unwrap_or_else(|| "application/json".to_string());
+            quote! {
+                rapina::introspection::ResponseInfo::new(
+                    #status,
+                    #content_type,
+                    Some(serde_json::to_value(rapina::schemars::schema_for!(#ty)).unwrap()),
+                )
+            }
+        });
+        quote! {
+            fn responses() -> Vec<rapina::introspection::ResponseInfo> {
+                vec![#(#entries),*]
+            }
+        }
+    };
+
+    // Extract #[example(request = "...")] attribute if present
+    let request_example_impl = match extract_example_attr(&mut func.attrs) {
+        Ok(Some(example)) => quote! {
+            fn request_example() -> Option<serde_json::Value> {
+                Some(serde_json::from_str(#example).unwrap())
+            }
+        },
+        Ok(None) => quote! {},
+        Err(e) => return e.to_compile_error(),
+    };
+
     // Extract return type for schema generation
     let response_schema_impl = if let syn::ReturnType::Type(_, return_type) = &func.sig.output {
         if let Some(inner_type) = extract_json_inner_type(return_type) {
@@ -98,10 +175,33 @@ fn route_macro_core(
 
     let args: Vec<_> = func.sig.inputs.iter().collect();
 
-    // Extract return type for type annotation (helps with type inference in async blocks)
+    // Describe the request body, query string and path parameter for introspection
+    let mut request_schema_impl = quote! {};
+    let mut query_schema_impl = quote! {};
+    let mut path_schema_impl = quote! {};
+    for arg in &args {
+        if let FnArg::Typed(pat_type) = arg {
+            let mut arg_type = &*pat_type.ty;
+            if let Some(inner_type) = extract_extractor_inner_type(arg_type, "SchemaValidated") {
+                arg_type = inner_type;
+            }
+            if let Some(inner_type) = extract_body_inner_type(arg_type) {
+                request_schema_impl = schema_fn(quote!(request_schema), inner_type);
+            } else if let Some(inner_type) = extract_extractor_inner_type(arg_type, "Query") {
+                query_schema_impl = schema_fn(quote!(query_schema), inner_type);
+            } else if let Some(inner_type) = extract_extractor_inner_type(arg_type, "Path") {
+                path_schema_impl = schema_fn(quote!(path_schema), inner_type);
+            }
+        }
+    }
+
+    // Extract return type for type annotation (helps with type inference in async blocks).
+    // `impl Trait` isn't allowed in a binding, so those return types are left to inference.
     let return_type_annotation = match &func.sig.output {
-        syn::ReturnType::Type(_, ty) => quote! { : #ty },
-        syn::ReturnType::Default => quote! {},
+        syn::ReturnType::Type(_, ty) if !contains_impl_trait(ty.to_token_stream()) => {
+            quote! { : #ty }
+        }
+        _ => quote! {},
     };
 
     // Build the handler body
@@ -114,46 +214,79 @@ fn route_macro_core(
         }
     } else {
         let mut parts_extractions = Vec::new();
-        let mut body_extractors: Vec<(syn::Ident, Box<syn::Type>)> = Vec::new();
-
-        for arg in &args {
-            if let FnArg::Typed(pat_type) = arg
-                && let Pat::Ident(pat_ident) = &*pat_type.pat
-            {
-                let arg_name = &pat_ident.ident;
-                let arg_type = &pat_type.ty;
+        let mut body_extractors: Vec<(syn::Ident, Box<syn::Type>, &syn::PatType)> = Vec::new();
 
-                let type_str = quote!(#arg_type).to_string();
-                if is_parts_only_extractor(&type_str) {
-                    parts_extractions.push(quote! {
-                        let #arg_name = match <#arg_type as rapina::extract::FromRequestParts>::from_request_parts(&__rapina_parts, &__rapina_params, &__rapina_state).await {
+        let typed_args: Vec<_> = args
+            .iter()
+            .filter_map(|arg| match arg {
+                FnArg::Typed(pat_type) => match &*pat_type.pat {
+                    Pat::Ident(pat_ident) => Some((pat_ident, pat_type)),
+                    _ => None,
+                },
+                FnArg::Receiver(_) => None,
+            })
+            .collect();
+        let has_known_body = typed_args
+            .iter()
+            .any(|(_, pat_type)| classify_extractor(&pat_type.ty) == ExtractorKind::Body);
+
+        for (i, (pat_ident, pat_type)) in typed_args.iter().enumerate() {
+            let arg_name = &pat_ident.ident;
+            let arg_type = &pat_type.ty;
+
+            // Unrecognized types are read from the request parts, except for
+            // the last argument when no known body extractor is present. That
+            // one goes through FromRequest, which parts extractors implement
+            // too, so custom extractors of either kind work in that position.
+            let is_body = match classify_extractor(arg_type) {
+                ExtractorKind::Parts => false,
+                ExtractorKind::Body => true,
+                ExtractorKind::Unknown => !has_known_body && i == typed_args.len() - 1,
+            };
+
+            if !is_body {
+                // Span the trait so a type that isn't a parts extractor is
+                // reported at its argument
+                let parts_trait =
+                    quote_spanned!(arg_type.span()=> rapina::extract::FromRequestParts);
+                parts_extractions.push(quote! {
+                        let #arg_name = match <#arg_type as #parts_trait>::from_request_parts(&__rapina_parts, &__rapina_params, &__rapina_state).await {
                             Ok(v) => v,
-                            Err(e) => return rapina::response::IntoResponse::into_response(e),
+                            Err(e) => return rapina::extract::rejection_response(e, &__rapina_state),
                         };
                     });
-                } else {
-                    body_extractors.push((arg_name.clone(), arg_type.clone()));
-                }
+            } else {
+                body_extractors.push((arg_name.clone(), arg_type.clone(), pat_type));
             }
         }
 
         let body_extraction = if body_extractors.is_empty() {
             quote! {}
         } else if body_extractors.len() == 1 {
-            let (arg_name, arg_type) = &body_extractors[0];
+            let (arg_name, arg_type, _) = &body_extractors[0];
             quote! {
                 let __rapina_req = rapina::http::Request::from_parts(__rapina_parts, __rapina_body);
                 let #arg_name = match <#arg_type as rapina::extract::FromRequest>::from_request(__rapina_req, &__rapina_params, &__rapina_state).await {
                     Ok(v) => v,
-                    Err(e) => return rapina::response::IntoResponse::into_response(e),
+                    Err(e) => return rapina::extract::rejection_response(e, &__rapina_state),
                 };
             }
         } else {
-            let names: Vec<_> = body_extractors.iter().map(|(n, _)| n.to_string()).collect();
-            panic!(
+            // Point at every body extractor after the first
+            let names: Vec<_> = body_extractors
+                .iter()
+                .map(|(n, _, _)| n.to_string())
+                .collect();
+            let message = format!(
                 "Multiple body-consuming extractors are not supported: {}. Only one extractor can consume the request body.",
                 names.join(", ")
             );
+            let mut errors = body_extractors[1..]
+                .iter()
+                .map(|(_, _, arg)| syn::Error::new_spanned(arg, &message));
+            let mut error = errors.next().unwrap();
+            error.extend(errors);
+            return error.to_compile_error();
         };
 
         let inner_block = &func.block;
@@ -175,9 +308,17 @@ fn route_macro_core(
 
         impl rapina::handler::Handler for #func_name {
             const NAME: &'static str = #func_name_str;
+            #deprecated_impl
+            #hidden_impl
+            #tags_impl
 
             #response_schema_impl
             #error_responses_impl
+            #responses_impl
+            #request_schema_impl
+            #request_example_impl
+            #query_schema_impl
+            #path_schema_impl
 
             fn call(
                 &self,
@@ -193,15 +334,76 @@ fn route_macro_core(
     }
 }
 
-fn is_parts_only_extractor(type_str: &str) -> bool {
-    type_str.contains("Path")
-        || type_str.contains("Query")
-        || type_str.contains("Headers")
-        || type_str.contains("State")
-        || type_str.contains("Context")
-        || type_str.contains("CurrentUser")
-        || type_str.contains("Db")
-        || type_str.contains("Cookie")
+/// How a handler argument is extracted from the request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ExtractorKind {
+    /// A built-in extractor that only reads the request parts
+    Parts,
+    /// A built-in extractor that consumes the request body
+    Body,
+    /// Any other type, e.g. a custom extractor
+    Unknown,
+}
+
+/// Returns whether a type mentions `impl Trait` anywhere, e.g. `Wrapper<impl Stream>`.
+fn contains_impl_trait(tokens: proc_macro2::TokenStream) -> bool {
+    tokens.into_iter().any(|token| match token {
+        proc_macro2::TokenTree::Ident(ident) => ident == "impl",
+        proc_macro2::TokenTree::Group(group) => contains_impl_trait(group.stream()),
+        _ => false,
+    })
+}
+
+/// Classifies an extractor by the last segment of its type path, so that
+/// `Path<T>` and `rapina::extract::Path<T>` match but `PathTracker` does not.
+///
+/// `Option<T>` and `WithRejection<T, R>` take the kind of the extractor they wrap.
+fn classify_extractor(ty: &syn::Type) -> ExtractorKind {
+    const PARTS: &[&str] = &[
+        "Path",
+        "Query",
+        "Headers",
+        "TypedHeader",
+        "State",
+        "Context",
+        "Deadline",
+        "MatchedPath",
+        "RawQuery",
+        "Host",
+        "Pagination",
+        "CurrentUser",
+        "Db",
+        "Cookie",
+        "SignedCookies",
+        "PrivateCookies",
+    ];
+    const BODY: &[&str] = &["Json", "Form", "MsgPack", "Body", "ContentLengthLimit"];
+
+    let syn::Type::Path(type_path) = ty else {
+        return ExtractorKind::Unknown;
+    };
+    let Some(last_segment) = type_path.path.segments.last() else {
+        return ExtractorKind::Unknown;
+    };
+    let ident = last_segment.ident.to_string();
+
+    if ident == "Option"
+        || ident == "WithRejection"
+        || ident == "Validated"
+        || ident == "SchemaValidated"
+    {
+        return match extract_extractor_inner_type(ty, &ident) {
+            Some(inner_type) => classify_extractor(inner_type),
+            None => ExtractorKind::Unknown,
+        };
+    }
+    if PARTS.contains(&ident.as_str()) {
+        ExtractorKind::Parts
+    } else if BODY.contains(&ident.as_str()) {
+        ExtractorKind::Body
+    } else {
+        ExtractorKind::Unknown
+    }
 }
 
 /// Extracts the inner type from Json<T> wrapper for schema generation
@@ -228,6 +430,43 @@ fn extract_json_inner_type(return_type: &syn::Type) -> Option<proc_macro2::Token
     None
 }
 
+/// Extracts `T` from an extractor type like `Query<T>`
+fn extract_extractor_inner_type<'a>(ty: &'a syn::Type, name: &str) -> Option<&'a syn::Type> {
+    if let syn::Type::Path(type_path) = ty
+        && let Some(last_segment) = type_path.path.segments.last()
+        && last_segment.ident == name
+        && let syn::PathArguments::AngleBracketed(args) = &last_segment.arguments
+        && let Some(syn::GenericArgument::Type(inner_type)) = args.args.first()
+    {
+        return Some(inner_type);
+    }
+    None
+}
+
+/// Extracts `T` from a body extractor: `Json<T>`, `Form<T>`, or either wrapped
+/// in `Validated` or `ContentLengthLimit`
+fn extract_body_inner_type(ty: &syn::Type) -> Option<&syn::Type> {
+    if let Some(inner_type) = extract_extractor_inner_type(ty, "Validated")
+        .or_else(|| extract_extractor_inner_type(ty, "ContentLengthLimit"))
+    {
+        return extract_body_inner_type(inner_type);
+    }
+    extract_extractor_inner_type(ty, "Json").or_else(|| extract_extractor_inner_type(ty, "Form"))
+}
+
+/// Generates a Handler schema method for `ty`, returning `None` when the
+/// type does not implement `JsonSchema`
+fn schema_fn(name: proc_macro2::TokenStream, ty: &syn::Type) -> proc_macro2::TokenStream {
+    quote! {
+        #[allow(clippy::needless_borrow)]
+        fn #name() -> Option<serde_json::Value> {
+            #[allow(unused_imports)]
+            use rapina::introspection::{HasSchema as _, NoSchema as _};
+            (&rapina::introspection::SchemaProbe::<#ty>::new()).schema()
+        }
+    }
+}
+
 /// Extract #[errors(ErrorType)] attribute from function attributes, removing it if found.
 fn extract_errors_attr(attrs: &mut Vec<syn::Attribute>) -> Option<syn::Type> {
     let idx = attrs
@@ -238,6 +477,134 @@ fn extract_errors_attr(attrs: &mut Vec<syn::Attribute>) -> Option<syn::Type> {
     Some(err_type)
 }
 
+/// Extract #[deprecated] attribute from function attributes, removing it if found.
+fn extract_deprecated_attr(attrs: &mut Vec<syn::Attribute>) -> bool {
+    match attrs
+        .iter()
+        .position(|attr| attr.path().is_ident("deprecated"))
+    {
+        Some(idx) => {
+            attrs.remove(idx);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Extract #[openapi(hidden)] attribute from function attributes, removing it if found.
+///
+/// Returns whether the route is hidden from the OpenAPI spec.
+fn extract_openapi_attr(attrs: &mut Vec<syn::Attribute>) -> syn::Result<bool> {
+    let Some(idx) = attrs
+        .iter()
+        .position(|attr| attr.path().is_ident("openapi"))
+    else {
+        return Ok(false);
+    };
+    let attr = attrs.remove(idx);
+    let mut hidden = false;
+    attr.parse_nested_meta(|meta| {
+        if meta.path.is_ident("hidden") {
+            hidden = true;
+            Ok(())
+        } else {
+            Err(meta.error("expected `hidden`"))
+        }
+    })?;
+    Ok(hidden)
+}
+
+/// Extract #[tag("name", ...)] attribute from function attributes, removing it if found.
+fn extract_tag_attr(attrs: &mut Vec<syn::Attribute>) -> syn::Result<Vec<LitStr>> {
+    let Some(idx) = attrs.iter().position(|attr| attr.path().is_ident("tag")) else {
+        return Ok(Vec::new());
+    };
+    let attr = attrs.remove(idx);
+    let tags = attr
+        .parse_args_with(syn::punctuated::Punctuated::<LitStr, syn::Token![,]>::parse_terminated)
+        .map_err(|e| syn::Error::new(e.span(), "expected #[tag(\"name\", ...)]"))?;
+    Ok(tags.into_iter().collect())
+}
+
+/// A `(status, Type)` or `(status, Type, "content/type")` entry of `#[responses(...)]`.
+struct ResponseSpec {
+    status: syn::LitInt,
+    ty: syn::Type,
+    content_type: Option<LitStr>,
+}
+
+impl syn::parse::Parse for ResponseSpec {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let content;
+        syn::parenthesized!(content in input);
+        let status: syn::LitInt = content.parse()?;
+        content.parse::<syn::Token![,]>()?;
+        let ty: syn::Type = content.parse()?;
+        let content_type = if content.parse::<Option<syn::Token![,]>>()?.is_some() {
+            Some(content.parse()?)
+        } else {
+            None
+        };
+        Ok(Self {
+            status,
+            ty,
+            content_type,
+        })
+    }
+}
+
+/// Extract #[responses((status, Type), ...)] attribute from function attributes, removing it if found.
+fn extract_responses_attr(attrs: &mut Vec<syn::Attribute>) -> syn::Result<Vec<ResponseSpec>> {
+    let Some(idx) = attrs
+        .iter()
+        .position(|attr| attr.path().is_ident("responses"))
+    else {
+        return Ok(Vec::new());
+    };
+    let attr = attrs.remove(idx);
+    let specs = attr
+        .parse_args_with(
+            syn::punctuated::Punctuated::<ResponseSpec, syn::Token![,]>::parse_terminated,
+        )
+        .map_err(|e| syn::Error::new(e.span(), "expected #[responses((status, Type), ...)]"))?;
+    Ok(specs.into_iter().collect())
+}
+
+/// Extract #[example(request = "...")] attribute from function attributes, removing it if found.
+///
+/// The example must be valid JSON, so mistakes are reported at compile time.
+fn extract_example_attr(attrs: &mut Vec<syn::Attribute>) -> syn::Result<Option<LitStr>> {
+    let Some(idx) = attrs
+        .iter()
+        .position(|attr| attr.path().is_ident("example"))
+    else {
+        return Ok(None);
+    };
+    let attr = attrs.remove(idx);
+    let mut request = None;
+    attr.parse_nested_meta(|meta| {
+        if meta.path.is_ident("request") {
+            request = Some(meta.value()?.parse::<LitStr>()?);
+            Ok(())
+        } else {
+            Err(meta.error("expected `request = \"...\"`"))
+        }
+    })?;
+    let Some(example) = request else {
+        return Err(syn::Error::new_spanned(
+            attr,
+            "expected #[example(request = \"...\")]",
+        ));
+    };
+    if let Err(e) = serde_json::from_str::<serde_json::Value>(&example.value()) {
+        return Err(syn::Error::new(
+            example.span(),
+            format!("request example is not valid JSON: {}", e),
+        ));
+    }
+    Ok(Some(example))
+}
+
 fn route_macro(attr: TokenStream, item: TokenStream) -> TokenStream {
     route_macro_core(attr.into(), item.into()).into()
 }
@@ -250,6 +617,38 @@ pub fn derive_config(input: TokenStream) -> TokenStream {
     derive_config_impl(input.into()).into()
 }
 
+/// Derive macro for extracting one struct from several parts of a request.
+///
+/// Each field names where it comes from with `#[from(path)]`,
+/// `#[from(query)]`, `#[from(header)]` or `#[from(body)]`. Path and query
+/// fields are looked up by field name, and header fields by field name with
+/// `_` replaced by `-`; give another name with e.g.
+/// `#[from(header = "x-api-key")]`. A body field is read as JSON unless its
+/// type is already a body extractor like `Form<T>`, and only one field may
+/// come from the body. Fields without an attribute are extracted by their
+/// own type, e.g. `Context` or `CurrentUser`.
+///
+/// A struct with a body field consumes the request, so it must be the last
+/// handler argument.
+///
+/// ```ignore
+/// #[derive(FromRequest)]
+/// struct CreateComment {
+///     #[from(path)]
+///     post_id: u64,
+///     #[from(query)]
+///     notify: Option<bool>,
+///     #[from(header = "x-api-key")]
+///     api_key: String,
+///     #[from(body)]
+///     comment: NewComment,
+/// }
+/// ```
+#[proc_macro_derive(FromRequest, attributes(from))]
+pub fn derive_from_request(input: TokenStream) -> TokenStream {
+    from_request::derive_from_request_impl(input.into()).into()
+}
+
 /// Define database entities with Prisma-like syntax.
 ///
 /// This macro generates SeaORM entity definitions from a declarative syntax
@@ -313,6 +712,15 @@ pub fn schema(input: TokenStream) -> TokenStream {
     schema::schema_impl(input.into()).into()
 }
 
+/// Mirrors `rapina::config::parse_bool`, which the macro can't depend on.
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "true" | "1" | "yes" | "on" => Some(true),
+        "false" | "0" | "no" | "off" => Some(false),
+        _ => None,
+    }
+}
+
 fn derive_config_impl(input: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
     let input: syn::DeriveInput = syn::parse2(input).expect("expected struct");
     let name = &input.ident;
@@ -355,15 +763,40 @@ fn derive_config_impl(input: proc_macro2::TokenStream) -> proc_macro2::TokenStre
                 && let syn::Expr::Lit(expr_lit) = &nv.value
                 && let syn::Lit::Str(lit_str) = &expr_lit.lit
             {
-                return Some(lit_str.value());
+                return Some(lit_str.clone());
             }
             None
         });
 
         let env_var_lit = syn::LitStr::new(&env_var, proc_macro2::Span::call_site());
 
-        if let Some(default) = default_value {
-            let default_lit = syn::LitStr::new(&default, proc_macro2::Span::call_site());
+        // bool fields also accept 1/0, yes/no and on/off
+        let is_bool = matches!(field_type, syn::Type::Path(p) if p.path.is_ident("bool"));
+
+        if is_bool {
+            if let Some(default) = default_value {
+                // Checked here rather than at runtime, where a typo would panic
+                let Some(default) = parse_bool(&default.value()) else {
+                    return syn::Error::new(
+                        default.span(),
+                        "invalid bool default, expected one of true/false, 1/0, yes/no, on/off",
+                    )
+                    .to_compile_error();
+                };
+                field_inits.push(quote! {
+                    #field_name: rapina::config::get_env_bool_or(#env_var_lit, #default)
+                });
+            } else {
+                field_inits.push(quote! {
+                    #field_name: rapina::config::get_env_bool(#env_var_lit)?
+                });
+                missing_checks.push(quote! {
+                    if std::env::var(#env_var_lit).is_err() {
+                        missing.push(#env_var_lit);
+                    }
+                });
+            }
+        } else if let Some(default_lit) = default_value {
             field_inits.push(quote! {
                 #field_name: rapina::config::get_env_or(#env_var_lit, #default_lit).parse().unwrap_or_else(|_| #default_lit.parse().unwrap())
             });
@@ -401,7 +834,8 @@ fn derive_config_impl(input: proc_macro2::TokenStream) -> proc_macro2::TokenStre
 
 #[cfg(test)]
 mod tests {
-    use super::route_macro_core;
+    use super::{ExtractorKind, derive_config_impl, route_macro_core};
+    use crate::from_request::derive_from_request_impl;
     use quote::quote;
 
     #[test]
@@ -466,28 +900,134 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "Multiple body-consuming extractors are not supported")]
-    fn test_multiple_body_extractors_panics() {
+    fn test_multiple_body_extractors_is_compile_error() {
         let path = quote!("/users");
         let input = quote! {
             async fn handler(
                 body1: rapina::extract::Json<String>,
-                body2: rapina::extract::Json<String>
+                body2: rapina::extract::Json<String>,
+                body3: rapina::extract::Form<String>
             ) -> String {
                 "ok".to_string()
             }
         };
 
-        route_macro_core(path, input);
+        let output = route_macro_core(path, input).to_string();
+
+        assert!(output.contains("compile_error"));
+        assert!(output.contains("Multiple body-consuming extractors are not supported"));
+        assert!(output.contains("body1, body2, body3"));
+        // One error for each extra body extractor
+        assert_eq!(output.matches("compile_error").count(), 2);
+        assert!(!output.contains("impl rapina :: handler :: Handler"));
+    }
+
+    #[test]
+    fn test_classify_extractor_matches_last_segment() {
+        let classify = |ty: proc_macro2::TokenStream| {
+            super::classify_extractor(&syn::parse2::<syn::Type>(ty).unwrap())
+        };
+
+        assert_eq!(classify(quote!(Path<u64>)), ExtractorKind::Parts);
+        assert_eq!(
+            classify(quote!(rapina::extract::Path<u64>)),
+            ExtractorKind::Parts
+        );
+        assert_eq!(classify(quote!(MatchedPath)), ExtractorKind::Parts);
+        assert_eq!(classify(quote!(Json<User>)), ExtractorKind::Body);
+        assert_eq!(classify(quote!(MsgPack<User>)), ExtractorKind::Body);
+        assert_eq!(classify(quote!(Validated<Form<User>>)), ExtractorKind::Body);
+        assert_eq!(
+            classify(quote!(Validated<Query<Page>>)),
+            ExtractorKind::Parts
+        );
+        assert_eq!(
+            classify(quote!(SchemaValidated<Path<UserId>>)),
+            ExtractorKind::Parts
+        );
+        assert_eq!(classify(quote!(Option<CurrentUser>)), ExtractorKind::Parts);
+        assert_eq!(
+            classify(quote!(WithRejection<Json<User>, ApiError>)),
+            ExtractorKind::Body
+        );
+        assert_eq!(classify(quote!(PathTracker)), ExtractorKind::Unknown);
+        assert_eq!(classify(quote!(JsonLines<Event>)), ExtractorKind::Unknown);
+        assert_eq!(classify(quote!(StateMachine)), ExtractorKind::Unknown);
+    }
+
+    #[test]
+    fn test_unknown_extractor_named_like_path_is_not_path() {
+        let path = quote!("/track");
+        let input = quote! {
+            async fn track(tracker: PathTracker) -> String {
+                "ok".to_string()
+            }
+        };
+
+        let output = route_macro_core(path, input).to_string();
+
+        // The only argument goes through FromRequest, which also covers
+        // custom parts extractors
+        assert!(output.contains("< PathTracker as rapina :: extract :: FromRequest >"));
+        assert!(!output.contains("< PathTracker as rapina :: extract :: FromRequestParts >"));
+    }
+
+    #[test]
+    fn test_unknown_extractor_before_body_reads_parts() {
+        let path = quote!("/track");
+        let input = quote! {
+            async fn track(tracker: PathTracker, body: Json<Event>) -> String {
+                "ok".to_string()
+            }
+        };
+
+        let output = route_macro_core(path, input).to_string();
+
+        assert!(output.contains("< PathTracker as rapina :: extract :: FromRequestParts >"));
+        assert!(output.contains("< Json < Event > as rapina :: extract :: FromRequest >"));
+        assert!(!output.contains("compile_error"));
     }
 
     #[test]
-    #[should_panic(expected = "expected function")]
-    fn test_invalid_input_panics() {
+    fn test_unknown_extractors_are_not_multiple_bodies() {
+        let path = quote!("/track");
+        let input = quote! {
+            async fn track(tracker: PathTracker, tenant: Tenant) -> String {
+                "ok".to_string()
+            }
+        };
+
+        let output = route_macro_core(path, input).to_string();
+
+        assert!(output.contains("< PathTracker as rapina :: extract :: FromRequestParts >"));
+        assert!(output.contains("< Tenant as rapina :: extract :: FromRequest >"));
+        assert!(!output.contains("compile_error"));
+    }
+
+    #[test]
+    fn test_invalid_input_is_compile_error() {
         let path = quote!("/");
         let invalid_input = quote! { not_a_function };
 
-        route_macro_core(path, invalid_input);
+        let output = route_macro_core(path, invalid_input).to_string();
+
+        assert!(output.contains("compile_error"));
+        assert!(output.contains("expected function"));
+    }
+
+    #[test]
+    fn test_invalid_path_is_compile_error() {
+        let path = quote!(users);
+        let input = quote! {
+            async fn handler() -> &'static str {
+                "ok"
+            }
+        };
+
+        let output = route_macro_core(path, input).to_string();
+
+        assert!(output.contains("compile_error"));
+        assert!(output.contains("expected path as string literal"));
     }
 
     #[test]
@@ -525,6 +1065,55 @@ mod tests {
         assert!(output_str.contains("UserResponse"));
     }
 
+    #[test]
+    fn test_example_attr_generates_request_example() {
+        let path = quote!("/users");
+        let input = quote! {
+            #[example(request = r#"{"email": "a@b.com"}"#)]
+            async fn create_user(body: Json<CreateUser>) -> Json<UserResponse> {
+                Json(UserResponse { id: 1 })
+            }
+        };
+
+        let output_str = route_macro_core(path, input).to_string();
+
+        assert!(output_str.contains("fn request_example"));
+        assert!(output_str.contains("serde_json :: from_str"));
+        assert!(!output_str.contains("# [example"));
+    }
+
+    #[test]
+    fn test_example_attr_rejects_invalid_json() {
+        let path = quote!("/users");
+        let input = quote! {
+            #[example(request = "{email: }")]
+            async fn create_user(body: Json<CreateUser>) -> Json<UserResponse> {
+                Json(UserResponse { id: 1 })
+            }
+        };
+
+        let output_str = route_macro_core(path, input).to_string();
+
+        assert!(output_str.contains("compile_error"));
+        assert!(output_str.contains("request example is not valid JSON"));
+    }
+
+    #[test]
+    fn test_example_attr_rejects_unknown_key() {
+        let path = quote!("/users");
+        let input = quote! {
+            #[example(response = "{}")]
+            async fn create_user(body: Json<CreateUser>) -> Json<UserResponse> {
+                Json(UserResponse { id: 1 })
+            }
+        };
+
+        let output_str = route_macro_core(path, input).to_string();
+
+        assert!(output_str.contains("compile_error"));
+        assert!(output_str.contains("expected `request"));
+    }
+
     #[test]
     fn test_errors_attr_generates_error_responses() {
         let path = quote!("/users");
@@ -543,6 +1132,215 @@ mod tests {
         assert!(output_str.contains("UserError"));
     }
 
+    #[test]
+    fn test_json_body_generates_request_schema() {
+        let path = quote!("/users");
+        let input = quote! {
+            async fn create_user(body: Json<CreateUser>) -> Json<User> {
+                Json(User { id: 1 })
+            }
+        };
+
+        let output = route_macro_core(path, input);
+        let output_str = output.to_string();
+
+        assert!(output_str.contains("fn request_schema"));
+        assert!(output_str.contains("SchemaProbe :: < CreateUser >"));
+        assert!(!output_str.contains("fn query_schema"));
+        assert!(!output_str.contains("fn path_schema"));
+    }
+
+    #[test]
+    fn test_validated_body_generates_request_schema() {
+        let path = quote!("/users");
+        let input = quote! {
+            async fn create_user(body: Validated<Form<CreateUser>>) -> StatusCode {
+                StatusCode::CREATED
+            }
+        };
+
+        let output = route_macro_core(path, input);
+        let output_str = output.to_string();
+
+        assert!(output_str.contains("fn request_schema"));
+        assert!(output_str.contains("SchemaProbe :: < CreateUser >"));
+    }
+
+    #[test]
+    fn test_limited_body_generates_request_schema() {
+        let path = quote!("/login");
+        let input = quote! {
+            async fn login(body: ContentLengthLimit<Json<LoginRequest>, 1024>) -> String {
+                "ok".to_string()
+            }
+        };
+
+        let output = route_macro_core(path, input);
+        let output_str = output.to_string();
+
+        assert!(output_str.contains("fn request_schema"));
+        assert!(output_str.contains("SchemaProbe :: < LoginRequest >"));
+        assert!(output_str.contains("FromRequest > :: from_request"));
+    }
+
+    #[test]
+    fn test_query_and_path_generate_param_schemas() {
+        let path = quote!("/users/:id");
+        let input = quote! {
+            async fn get_user(id: Path<u64>, query: Query<Filters>) -> String {
+                "ok".to_string()
+            }
+        };
+
+        let output = route_macro_core(path, input);
+        let output_str = output.to_string();
+
+        assert!(output_str.contains("fn path_schema"));
+        assert!(output_str.contains("SchemaProbe :: < u64 >"));
+        assert!(output_str.contains("fn query_schema"));
+        assert!(output_str.contains("SchemaProbe :: < Filters >"));
+        assert!(!output_str.contains("fn request_schema"));
+    }
+
+    #[test]
+    fn test_responses_attr_generates_responses() {
+        let path = quote!("/users");
+        let input = quote! {
+            #[responses((201, CreatedUser), (200, User))]
+            async fn create_user(body: Json<CreateUser>) -> Response {
+                todo!()
+            }
+        };
+
+        let output = route_macro_core(path, input);
+        let output_str = output.to_string();
+
+        assert!(output_str.contains("fn responses"));
+        assert!(output_str.contains("ResponseInfo :: new (201 , \"application/json\""));
+        assert!(output_str.contains("schema_for ! (CreatedUser)"));
+        assert!(output_str.contains("ResponseInfo :: new (200 , \"application/json\""));
+        assert!(output_str.contains("schema_for ! (User)"));
+        // The attribute is consumed by the macro
+        assert!(!output_str.contains("# [responses"));
+    }
+
+    #[test]
+    fn test_responses_attr_with_content_type() {
+        let path = quote!("/report");
+        let input = quote! {
+            #[responses((200, String, "text/csv"))]
+            async fn report() -> String {
+                String::new()
+            }
+        };
+
+        let output = route_macro_core(path, input);
+        let output_str = output.to_string();
+
+        assert!(output_str.contains("ResponseInfo :: new (200 , \"text/csv\""));
+    }
+
+    #[test]
+    fn test_tag_attr_invalid() {
+        let path = quote!("/users");
+        let input = quote! {
+            #[tag(users)]
+            async fn list_users() -> String {
+                String::new()
+            }
+        };
+
+        let output = route_macro_core(path, input).to_string();
+
+        assert!(output.contains("compile_error"));
+        assert!(output.contains("expected #[tag(\\\"name\\\", ...)]"));
+    }
+
+    #[test]
+    fn test_responses_attr_invalid() {
+        let path = quote!("/users");
+        let input = quote! {
+            #[responses(User)]
+            async fn get_user() -> String {
+                String::new()
+            }
+        };
+
+        let output = route_macro_core(path, input).to_string();
+
+        assert!(output.contains("compile_error"));
+        assert!(output.contains("expected #[responses((status, Type), ...)]"));
+    }
+
+    #[test]
+    fn test_deprecated_attr_sets_deprecated() {
+        let path = quote!("/v1/users");
+        let input = quote! {
+            #[deprecated(note = "use /v2/users")]
+            async fn list_users_v1() -> &'static str {
+                "users"
+            }
+        };
+
+        let output = route_macro_core(path, input);
+        let output_str = output.to_string();
+
+        assert!(output_str.contains("const DEPRECATED : bool = true"));
+        assert!(!output_str.contains("# [deprecated"));
+    }
+
+    #[test]
+    fn test_openapi_hidden_attr_sets_hidden() {
+        let path = quote!("/admin/stats");
+        let input = quote! {
+            #[openapi(hidden)]
+            async fn admin_stats() -> &'static str {
+                "stats"
+            }
+        };
+
+        let output_str = route_macro_core(path, input).to_string();
+
+        assert!(output_str.contains("const HIDDEN : bool = true"));
+        assert!(!output_str.contains("# [openapi"));
+    }
+
+    #[test]
+    fn test_openapi_attr_rejects_unknown_option() {
+        let path = quote!("/admin/stats");
+        let input = quote! {
+            #[openapi(secret)]
+            async fn admin_stats() -> &'static str {
+                "stats"
+            }
+        };
+
+        let output_str = route_macro_core(path, input).to_string();
+
+        assert!(output_str.contains("compile_error"));
+        assert!(output_str.contains("expected `hidden`"));
+    }
+
+    #[test]
+    fn test_tag_attr_sets_tags() {
+        let path = quote!("/users");
+        let input = quote! {
+            #[tag("users", "admin")]
+            async fn list_users() -> &'static str {
+                "users"
+            }
+        };
+
+        let output = route_macro_core(path, input);
+        let output_str = output.to_string();
+
+        assert!(
+            output_str
+                .contains("const TAGS : & 'static [& 'static str] = & [\"users\" , \"admin\"]")
+        );
+        assert!(!output_str.contains("# [tag"));
+    }
+
     #[test]
     fn test_non_json_return_type_no_response_schema() {
         let path = quote!("/health");
@@ -600,4 +1398,120 @@ mod tests {
         assert!(output_str.contains("__rapina_result"));
         assert!(output_str.contains("Result < String , Error >"));
     }
+
+    #[test]
+    fn test_impl_trait_return_type_is_not_annotated() {
+        let path = quote!("/export");
+        let input = quote! {
+            async fn export() -> JsonStream<impl Stream<Item = u64> + Send> {
+                JsonStream::new(stream::iter(0..10))
+            }
+        };
+
+        let output_str = route_macro_core(path, input).to_string();
+
+        assert!(output_str.contains("let __rapina_result = (async"));
+        assert!(!output_str.contains("let __rapina_result : JsonStream"));
+    }
+
+    #[test]
+    fn test_config_bool_field_uses_lenient_parsing() {
+        let input = quote! {
+            struct AppConfig {
+                #[env = "FEATURE_X"]
+                feature_x: bool,
+                #[env = "DEBUG"]
+                #[default = "off"]
+                debug: bool,
+                port: u16,
+            }
+        };
+
+        let output = derive_config_impl(input).to_string();
+
+        assert!(output.contains("rapina :: config :: get_env_bool (\"FEATURE_X\")"));
+        assert!(output.contains("rapina :: config :: get_env_bool_or (\"DEBUG\" , false)"));
+        assert!(output.contains("get_env_parsed :: < u16 > (\"PORT\")"));
+    }
+
+    #[test]
+    fn test_config_invalid_bool_default() {
+        let input = quote! {
+            struct AppConfig {
+                #[default = "enabled"]
+                debug: bool,
+            }
+        };
+
+        let output = derive_config_impl(input).to_string();
+
+        assert!(output.contains("compile_error"));
+        assert!(output.contains("invalid bool default"));
+    }
+
+    #[test]
+    fn test_from_request_with_body_implements_from_request() {
+        let output = derive_from_request_impl(quote! {
+            struct CreateComment {
+                #[from(path)]
+                post_id: u64,
+                #[from(body)]
+                comment: NewComment,
+            }
+        })
+        .to_string();
+
+        assert!(output.contains("impl rapina :: extract :: FromRequest for CreateComment"));
+        assert!(output.contains("rename = \"post_id\""));
+        assert!(output.contains("rapina :: extract :: Json < NewComment >"));
+    }
+
+    #[test]
+    fn test_from_request_without_body_implements_from_request_parts() {
+        let output = derive_from_request_impl(quote! {
+            struct Listing {
+                #[from(query = "p")]
+                page: u32,
+                #[from(header)]
+                if_none_match: Option<String>,
+            }
+        })
+        .to_string();
+
+        assert!(output.contains("impl rapina :: extract :: FromRequestParts for Listing"));
+        assert!(output.contains("rename = \"p\""));
+        assert!(
+            output.contains("header_field :: < String > (& parts . headers , \"if-none-match\")")
+        );
+    }
+
+    #[test]
+    fn test_from_request_rejects_two_bodies() {
+        let output = derive_from_request_impl(quote! {
+            struct Upload {
+                #[from(body)]
+                meta: Meta,
+                #[from(body)]
+                data: Data,
+            }
+        })
+        .to_string();
+
+        assert!(output.contains("compile_error"));
+        assert!(output.contains("only one field can come from the body"));
+    }
+
+    #[test]
+    fn test_from_request_rejects_unknown_source() {
+        let output = derive_from_request_impl(quote! {
+            struct Lookup {
+                #[from(cookie)]
+                session: String,
+            }
+        })
+        .to_string();
+
+        assert!(output.contains("compile_error"));
+        assert!(output.contains("expected `path`, `query`, `header` or `body`"));
+    }
 }
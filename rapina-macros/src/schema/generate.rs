@@ -61,6 +61,12 @@ fn generate_entity_module(entity: &AnalyzedEntity, schema: &AnalyzedSchema) -> T
         quote! {}
     };
 
+    let version_field = if entity.attrs.has_version {
+        quote! { pub version: i32, }
+    } else {
+        quote! {}
+    };
+
     quote! {
         pub mod #mod_name {
             use rapina::sea_orm;
@@ -76,6 +82,7 @@ fn generate_entity_module(entity: &AnalyzedEntity, schema: &AnalyzedSchema) -> T
                 #model_fields
                 #created_at_field
                 #updated_at_field
+                #version_field
             }
 
             #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -520,6 +527,23 @@ mod tests {
         assert!(output.contains("updated_at"));
     }
 
+    #[test]
+    fn test_generate_versioned() {
+        let input = quote! {
+            #[versioned]
+            User {
+                email: String,
+            }
+        };
+
+        let parsed = parse_schema(input).unwrap();
+        let analyzed = analyze_schema(parsed).unwrap();
+        let generated = generate_schema(analyzed);
+        let output = generated.to_string();
+
+        assert!(output.contains("pub version : i32"));
+    }
+
     #[test]
     fn test_generate_indexed_field() {
         let input = quote! {
@@ -24,6 +24,8 @@ pub struct EntityAttrs {
     pub has_created_at: bool,
     /// Include updated_at timestamp (default: true)
     pub has_updated_at: bool,
+    /// Include a version column for optimistic concurrency, e.g., #[versioned]
+    pub has_version: bool,
 }
 
 impl Default for EntityAttrs {
@@ -32,6 +34,7 @@ impl Default for EntityAttrs {
             table_name: None,
             has_created_at: true,
             has_updated_at: true,
+            has_version: false,
         }
     }
 }
@@ -124,6 +127,12 @@ impl Parse for EntityDef {
                     ),
                 ));
             }
+            if attrs.has_version && field_name == "version" {
+                return Err(syn::Error::new(
+                    field.name.span(),
+                    "field 'version' is reserved on #[versioned] entities and automatically generated",
+                ));
+            }
         }
 
         // Check for duplicate field names
@@ -147,7 +156,8 @@ impl Parse for EntityDef {
     }
 }
 
-/// Parse entity-level attributes like #[table_name = "people"] or #[timestamps(created_at)]
+/// Parse entity-level attributes like #[table_name = "people"], #[timestamps(created_at)]
+/// or #[versioned]
 fn parse_entity_attrs(input: ParseStream) -> Result<EntityAttrs> {
     let mut attrs = EntityAttrs::default();
 
@@ -165,6 +175,9 @@ fn parse_entity_attrs(input: ParseStream) -> Result<EntityAttrs> {
                 let value: syn::LitStr = content.parse()?;
                 attrs.table_name = Some(value.value());
             }
+            "versioned" => {
+                attrs.has_version = true;
+            }
             "timestamps" => {
                 // Parse timestamps(created_at) or timestamps(updated_at) or timestamps(none)
                 let inner;
@@ -200,7 +213,7 @@ fn parse_entity_attrs(input: ParseStream) -> Result<EntityAttrs> {
                 return Err(syn::Error::new(
                     attr_name.span(),
                     format!(
-                        "unknown entity attribute '{}'. Supported: table_name, timestamps",
+                        "unknown entity attribute '{}'. Supported: table_name, timestamps, versioned",
                         attr_name_str
                     ),
                 ));
@@ -575,6 +588,31 @@ mod tests {
         assert!(!schema.entities[0].attrs.has_updated_at);
     }
 
+    #[test]
+    fn test_parse_versioned() {
+        let input = quote! {
+            #[versioned]
+            User {
+                name: String,
+            }
+        };
+
+        let schema = parse_schema(input).unwrap();
+        assert!(schema.entities[0].attrs.has_version);
+
+        let input = quote! {
+            #[versioned]
+            User {
+                version: i32,
+            }
+        };
+        let err = parse_schema(input).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("reserved on #[versioned] entities")
+        );
+    }
+
     #[test]
     fn test_parse_index_attr() {
         let input = quote! {
@@ -1,21 +1,39 @@
 //! The main application builder for Rapina.
 
 use std::net::SocketAddr;
+use std::sync::Arc;
 
 use crate::auth::{AuthConfig, AuthMiddleware, PublicRoutes};
-use crate::introspection::{RouteRegistry, list_routes};
+use crate::cookie::CookieKey;
+use crate::error::{Error, ValidationMessages};
+use crate::extract::RejectionMapper;
+use crate::handler::HandlerFn;
+use crate::introspection::{AppInfo, RouteRegistry, app_info, list_routes};
 #[cfg(feature = "metrics")]
 use crate::metrics::{MetricsMiddleware, MetricsRegistry, metrics_handler};
 use crate::middleware::{
-    CompressionConfig, CompressionMiddleware, CorsConfig, CorsMiddleware, Middleware,
-    MiddlewareStack, RateLimitConfig, RateLimitMiddleware,
+    CompressionConfig, CompressionMiddleware, CorsConfig, CorsMiddleware, IdempotencyConfig,
+    IdempotencyMiddleware, Middleware, MiddlewareStack, RateLimitConfig, RateLimitMiddleware,
+    ResponseObserver,
 };
+use crate::negotiate::{Serializer, Serializers};
 use crate::observability::TracingConfig;
-use crate::openapi::{OpenApiRegistry, build_openapi_spec, openapi_spec};
-use crate::router::Router;
-use crate::server::serve;
+use crate::openapi::{OpenApiRegistry, OpenApiSpec, build_openapi_spec, openapi_spec};
+use crate::pagination::PaginationConfig;
+use crate::response::JsonConfig;
+use crate::router::{BoxedHandler, Router, TrailingSlash, boxed_handler_fn};
+#[cfg(feature = "tls")]
+use crate::server::TlsListener;
+use crate::server::{ServerConfig, serve, serve_listener};
 use crate::state::AppState;
 
+/// Set by `rapina openapi export` to a path the app writes its spec to
+/// instead of serving.
+const OPENAPI_EXPORT_ENV: &str = "RAPINA_OPENAPI_EXPORT";
+
+/// A required state type's name and a check for its registration.
+type StateRequirement = (&'static str, fn(&AppState) -> bool);
+
 /// The main application type for building Rapina servers.
 ///
 /// Use the builder pattern to configure routing, state, middleware,
@@ -53,14 +71,32 @@ pub struct Rapina {
     pub(crate) introspection: bool,
     /// Whether metrics is enabled.
     pub(crate) metrics: bool,
+    /// Address of a separate listener serving only `/metrics`
+    #[cfg(feature = "metrics")]
+    pub(crate) metrics_bind: Option<SocketAddr>,
     /// Whether OpenAPI is enabled
     pub(crate) openapi: bool,
     pub(crate) openapi_title: String,
     pub(crate) openapi_version: String,
+    pub(crate) openapi_tags: Vec<(String, String)>,
+    pub(crate) openapi_servers: Vec<(String, Option<String>)>,
+    pub(crate) openapi_base_path: Option<String>,
     /// Authentication configuration (if enabled)
     pub(crate) auth_config: Option<AuthConfig>,
     /// Public routes registry
     pub(crate) public_routes: PublicRoutes,
+    /// State types that must be registered before serving
+    pub(crate) required_state: Vec<StateRequirement>,
+    /// Connection-level server settings
+    pub(crate) server_config: ServerConfig,
+    /// How request paths ending in `/` are matched
+    pub(crate) trailing_slash: TrailingSlash,
+    /// Handler for requests no route matches
+    pub(crate) fallback: Option<BoxedHandler>,
+    /// Handler for requests whose path only matches routes for other methods
+    pub(crate) method_not_allowed: Option<BoxedHandler>,
+    /// Routers for requests to other host names
+    pub(crate) hosts: Vec<(String, Router)>,
 }
 
 impl Rapina {
@@ -74,11 +110,22 @@ impl Rapina {
             middlewares: MiddlewareStack::new(),
             introspection: cfg!(debug_assertions),
             metrics: false,
+            #[cfg(feature = "metrics")]
+            metrics_bind: None,
             openapi: false,
             openapi_title: "API".to_string(),
             openapi_version: "1.0.0".to_string(),
+            openapi_tags: Vec::new(),
+            openapi_servers: Vec::new(),
+            openapi_base_path: None,
             auth_config: None,
             public_routes: PublicRoutes::new(),
+            required_state: Vec::new(),
+            server_config: ServerConfig::default(),
+            trailing_slash: TrailingSlash::default(),
+            fallback: None,
+            method_not_allowed: None,
+            hosts: Vec::new(),
         }
     }
 
@@ -88,18 +135,322 @@ impl Rapina {
         self
     }
 
+    /// Serves requests for another host name with a separate router.
+    ///
+    /// Requests are dispatched by their `Host` header, ignoring case and
+    /// port. Hosts without a router of their own, and requests without a
+    /// `Host`, use the router set with [`router`](Self::router). See
+    /// [`Router::host`].
+    ///
+    /// The host router gets the app's [`fallback`](Self::fallback),
+    /// [`method_not_allowed`](Self::method_not_allowed) handler and trailing
+    /// slash handling, and matches without regard to case if the main router
+    /// does. Its routes are listed at `/__rapina/routes` with their host, and
+    /// appear in the OpenAPI spec with the host as the operation's server.
+    ///
+    /// A few things stay with the main router:
+    ///
+    /// - the built-in endpoints, such as `/__rapina/openapi.json` and
+    ///   `/metrics`, are served only for hosts without a router of their own
+    /// - the OpenAPI spec keys operations by path and method alone, so a host
+    ///   route with the same method and path as an earlier route is left out
+    ///   of it
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// Rapina::new()
+    ///     .host("api.example.com", api_router)
+    ///     .host("admin.example.com", admin_router)
+    ///     .router(site_router)
+    ///     .listen("0.0.0.0:3000")
+    ///     .await
+    /// ```
+    pub fn host(mut self, host: &str, router: Router) -> Self {
+        self.hosts.push((host.to_string(), router));
+        self
+    }
+
     /// Adds shared state that can be accessed by handlers via [`State`](crate::extract::State).
     pub fn state<T: Send + Sync + 'static>(mut self, value: T) -> Self {
         self.state = self.state.with(value);
         self
     }
 
+    /// Adds shared state built by an async initializer on first use.
+    ///
+    /// The initializer runs once, when a handler first extracts
+    /// [`State<T>`](crate::extract::State), and every request after that
+    /// shares the result. Use it for expensive resources like connection
+    /// pools and HTTP clients, so they're built inside the running server
+    /// rather than in `main`.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// Rapina::new()
+    ///     .state_with(|| async { SearchClient::connect(&search_url).await })
+    ///     .router(router)
+    ///     .listen("127.0.0.1:3000")
+    ///     .await
+    /// ```
+    pub fn state_with<T, F, Fut>(mut self, init: F) -> Self
+    where
+        T: Send + Sync + 'static,
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = T> + Send + 'static,
+    {
+        self.state = self.state.with_lazy(init);
+        self
+    }
+
+    /// Requires state of type `T` to be registered before serving.
+    ///
+    /// The check runs when the app starts, so a forgotten [`state`](Self::state)
+    /// call fails at boot instead of as a 500 on the first request that
+    /// extracts [`State<T>`](crate::extract::State). The state itself may be
+    /// added before or after this call.
+    ///
+    /// # Panics
+    ///
+    /// [`listen`](Self::listen) and [`TestClient::new`](crate::testing::TestClient::new)
+    /// panic if any required state type is missing.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// Rapina::new()
+    ///     .require_state::<AppConfig>()
+    ///     .state(AppConfig::from_env()?)
+    ///     .router(router)
+    ///     .listen("127.0.0.1:3000")
+    ///     .await
+    /// ```
+    pub fn require_state<T: Send + Sync + 'static>(mut self) -> Self {
+        self.required_state
+            .push((std::any::type_name::<T>(), AppState::contains::<T>));
+        self
+    }
+
     /// Adds a middleware to the application.
     pub fn middleware<M: Middleware>(mut self, middleware: M) -> Self {
         self.middlewares.add(middleware);
         self
     }
 
+    /// Registers an observer called with every completed response.
+    ///
+    /// The observer sees the request context, method, path, matched route,
+    /// final status and elapsed time, which covers metrics and audit logging
+    /// without writing a [`Middleware`]. It runs after all middleware,
+    /// whatever order they were added in.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// Rapina::new()
+    ///     .on_response(|info: &CompletedResponse| {
+    ///         tracing::info!(
+    ///             route = info.route.unwrap_or("unmatched"),
+    ///             status = info.status.as_u16(),
+    ///             elapsed_ms = info.elapsed.as_millis() as u64,
+    ///         );
+    ///     })
+    ///     .router(router)
+    ///     .listen("127.0.0.1:3000")
+    ///     .await
+    /// ```
+    pub fn on_response<O: ResponseObserver>(mut self, observer: O) -> Self {
+        self.middlewares.observe(observer);
+        self
+    }
+
+    /// Registers a serializer for [`Negotiate`](crate::negotiate::Negotiate) responses.
+    ///
+    /// JSON is always available. Additional serializers are selected when the
+    /// request's `Accept` header prefers their media type.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// Rapina::new()
+    ///     .serializer(CsvSerializer)
+    ///     .router(router)
+    ///     .listen("127.0.0.1:3000")
+    ///     .await
+    /// ```
+    pub fn serializer<S: Serializer>(mut self, serializer: S) -> Self {
+        let serializers = self
+            .state
+            .get::<Serializers>()
+            .cloned()
+            .unwrap_or_default()
+            .with(serializer);
+        self.state = self.state.with(serializers);
+        self
+    }
+
+    /// Sets the messages used in 422 responses from the
+    /// [`Validated`](crate::extract::Validated) extractor.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// Rapina::new()
+    ///     .with_validation_messages(
+    ///         ValidationMessages::new()
+    ///             .code("email", "Please enter a valid email address")
+    ///             .field("password", "length", "Your password needs at least 8 characters"),
+    ///     )
+    ///     .router(router)
+    ///     .listen("127.0.0.1:3000")
+    ///     .await
+    /// ```
+    pub fn with_validation_messages(mut self, messages: ValidationMessages) -> Self {
+        self.state = self.state.with(messages);
+        self
+    }
+
+    /// Sets how [`Json`](crate::extract::Json) responses are serialized,
+    /// e.g. to send `camelCase` keys from `snake_case` structs.
+    ///
+    /// Serde attributes on a type still apply; see [`JsonConfig`] for how
+    /// the two combine.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// Rapina::new()
+    ///     .with_json_config(JsonConfig::new().camel_case(true).skip_nulls(true))
+    ///     .router(router)
+    ///     .listen("127.0.0.1:3000")
+    ///     .await
+    /// ```
+    pub fn with_json_config(mut self, config: JsonConfig) -> Self {
+        self.state = self.state.with(config);
+        self
+    }
+
+    /// Sets the default and maximum page sizes of the
+    /// [`Pagination`](crate::pagination::Pagination) extractor.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// Rapina::new()
+    ///     .with_pagination(PaginationConfig::new().default_per_page(50).max_per_page(500))
+    ///     .router(router)
+    ///     .listen("127.0.0.1:3000")
+    ///     .await
+    /// ```
+    pub fn with_pagination(mut self, config: PaginationConfig) -> Self {
+        self.state = self.state.with(config);
+        self
+    }
+
+    /// Maps every extractor rejection before it is sent to the client.
+    ///
+    /// Use it to return your own error format when a request can't be
+    /// extracted, e.g. malformed JSON or a missing header. The mapper also
+    /// sees rejections already converted by
+    /// [`WithRejection`](crate::extract::WithRejection), since they are
+    /// plain [`Error`](crate::error::Error)s again by then.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// Rapina::new()
+    ///     .map_rejections(|err| ApiError::from(err).into_api_error())
+    ///     .router(router)
+    ///     .listen("127.0.0.1:3000")
+    ///     .await
+    /// ```
+    pub fn map_rejections<F>(mut self, f: F) -> Self
+    where
+        F: Fn(Error) -> Error + Send + Sync + 'static,
+    {
+        self.state = self.state.with(RejectionMapper(Arc::new(f)));
+        self
+    }
+
+    /// Sets the handler for requests that no route matches.
+    ///
+    /// The handler takes extractors like a
+    /// [`Router::get_fn`](crate::router::Router::get_fn) handler, so it can
+    /// read the attempted [`Uri`](http::Uri) and the [`Context`](crate::extract::Context)
+    /// with the trace ID. Without a fallback, unmatched requests get an empty
+    /// `404 Not Found`.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// Rapina::new()
+    ///     .fallback(|uri: Uri, ctx: Context| async move {
+    ///         tracing::info!(path = uri.path(), "no route");
+    ///         Error::not_found(format!("no route for {}", uri.path()))
+    ///             .with_trace_id(ctx.trace_id())
+    ///     })
+    ///     .router(router)
+    ///     .listen("127.0.0.1:3000")
+    ///     .await
+    /// ```
+    pub fn fallback<H, Args>(mut self, handler: H) -> Self
+    where
+        H: HandlerFn<Args>,
+        Args: 'static,
+    {
+        self.fallback = Some(boxed_handler_fn(handler));
+        self
+    }
+
+    /// Sets the handler for requests whose path matches a route, but only
+    /// for other methods.
+    ///
+    /// An `Allow` header listing the route's methods is added to the
+    /// handler's response unless it sets one itself. Without this handler,
+    /// such requests are treated like any other unmatched request and go to
+    /// the [`fallback`](Self::fallback).
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// Rapina::new()
+    ///     .method_not_allowed(|method: Method, ctx: Context| async move {
+    ///         Error::new(405, "METHOD_NOT_ALLOWED", format!("{} is not supported here", method))
+    ///             .with_trace_id(ctx.trace_id())
+    ///     })
+    ///     .router(router)
+    ///     .listen("127.0.0.1:3000")
+    ///     .await
+    /// ```
+    pub fn method_not_allowed<H, Args>(mut self, handler: H) -> Self
+    where
+        H: HandlerFn<Args>,
+        Args: 'static,
+    {
+        self.method_not_allowed = Some(boxed_handler_fn(handler));
+        self
+    }
+
+    /// Sets how request paths ending in `/` are matched.
+    ///
+    /// Defaults to [`TrailingSlash::Strict`], where `/users` and `/users/`
+    /// are different routes.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// Rapina::new()
+    ///     .with_trailing_slash(TrailingSlash::RedirectToNonSlash)
+    ///     .router(router)
+    ///     .listen("127.0.0.1:3000")
+    ///     .await
+    /// ```
+    pub fn with_trailing_slash(mut self, policy: TrailingSlash) -> Self {
+        self.trailing_slash = policy;
+        self
+    }
+
     /// Enables CORS for the application.
     ///
     /// Use `CorsConfig::permisive()` for development (it allows all origins),
@@ -118,6 +469,25 @@ impl Rapina {
         self
     }
 
+    /// Sets connection-level server settings such as keep-alive and
+    /// connection limits.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use rapina::server::ServerConfig;
+    ///
+    /// Rapina::new()
+    ///     .with_server_config(ServerConfig::new().max_connections(10_000))
+    ///     .router(router)
+    ///     .listen("127.0.0.1:3000")
+    ///     .await
+    /// ```
+    pub fn with_server_config(mut self, config: ServerConfig) -> Self {
+        self.server_config = config;
+        self
+    }
+
     /// Enables rate limiting for the application.
     ///
     /// Uses a token bucket algorithm to limit requests per client.
@@ -137,7 +507,32 @@ impl Rapina {
         self
     }
 
+    /// Enables `Idempotency-Key` handling with an in-memory store.
+    ///
+    /// Use [`IdempotencyMiddleware::with_store`] with [`middleware`](Self::middleware)
+    /// to share keys between instances.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use rapina::middleware::IdempotencyConfig;
+    ///
+    /// Rapina::new()
+    ///     .with_idempotency(IdempotencyConfig::new())
+    ///     .router(router)
+    ///     .listen("127.0.0.1:3000")
+    ///     .await
+    /// ```
+    pub fn with_idempotency(mut self, config: IdempotencyConfig) -> Self {
+        self.middlewares.add(IdempotencyMiddleware::new(config));
+        self
+    }
+
     /// Enables response compression (gzip, deflate).
+    ///
+    /// Buffered bodies smaller than the configured minimum size are sent
+    /// as is. Streamed bodies are compressed chunk by chunk as they are
+    /// produced.
     pub fn with_compression(mut self, config: CompressionConfig) -> Self {
         self.middlewares.add(CompressionMiddleware::new(config));
         self
@@ -164,6 +559,28 @@ impl Rapina {
         self
     }
 
+    /// Sets the key for [`SignedCookies`](crate::cookie::SignedCookies) and
+    /// [`PrivateCookies`](crate::cookie::PrivateCookies).
+    ///
+    /// Without it, the key is derived from the [`with_auth`](Self::with_auth)
+    /// secret when authentication is enabled.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use rapina::cookie::CookieKey;
+    ///
+    /// Rapina::new()
+    ///     .with_cookie_key(CookieKey::new(get_env("COOKIE_SECRET")?))
+    ///     .router(router)
+    ///     .listen("127.0.0.1:3000")
+    ///     .await
+    /// ```
+    pub fn with_cookie_key(mut self, key: CookieKey) -> Self {
+        self.state = self.state.with(key);
+        self
+    }
+
     /// Registers a route as public (no authentication required).
     ///
     /// Use this for routes that should be accessible without a JWT token.
@@ -186,15 +603,26 @@ impl Rapina {
     }
 
     /// Configures tracing/logging for the application.
-    pub fn with_tracing(self, config: TracingConfig) -> Self {
+    ///
+    /// When OTLP export is configured (`otel` feature), this also adds
+    /// [`OtelMiddleware`](crate::observability::OtelMiddleware) so every
+    /// request gets a span.
+    #[cfg_attr(not(feature = "otel"), allow(unused_mut))]
+    pub fn with_tracing(mut self, config: TracingConfig) -> Self {
+        #[cfg(feature = "otel")]
+        if config.otlp_endpoint.is_some() {
+            self.middlewares
+                .add(crate::observability::OtelMiddleware::new());
+        }
         config.init();
         self
     }
 
     /// Enables or disables the introspection endpoint.
     ///
-    /// When enabled, a `GET /.__rapina/routes` endpoint is registered
-    /// that returns all routes as JSON.
+    /// When enabled, a `GET /__rapina/routes` endpoint is registered
+    /// that returns all routes as JSON, and a `GET /__rapina/info` endpoint
+    /// that reports the middleware stack, enabled features and versions.
     ///
     /// Introspection is enabled by default in debug builds.
     pub fn with_introspection(mut self, enabled: bool) -> Self {
@@ -213,6 +641,38 @@ impl Rapina {
         self
     }
 
+    /// Serves `/metrics` on its own listener instead of the app's.
+    ///
+    /// Enables metrics. The endpoint is removed from the main listener, so
+    /// it can be bound to an internal interface or port that isn't exposed
+    /// publicly, e.g. `127.0.0.1:9090`, while requests to the app are still
+    /// measured. The listener is started by [`listen`](Self::listen) and the
+    /// other `listen_*` methods; [`TestClient`](crate::testing::TestClient)
+    /// doesn't start it. If the listener later stops with an error, the
+    /// error is logged and the app keeps serving.
+    ///
+    /// Only available with the `metrics` feature.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the address cannot be parsed.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// Rapina::new()
+    ///     .with_metrics_bind("127.0.0.1:9090")
+    ///     .router(router)
+    ///     .listen("0.0.0.0:3000")
+    ///     .await
+    /// ```
+    #[cfg(feature = "metrics")]
+    pub fn with_metrics_bind(mut self, addr: &str) -> Self {
+        self.metrics = true;
+        self.metrics_bind = Some(addr.parse().expect("invalid address"));
+        self
+    }
+
     /// Enables or disables openapi endpoint
     ///
     /// When enabled, a get `/__rapina/openapi.json` endpoint is registered
@@ -225,6 +685,40 @@ impl Rapina {
         self
     }
 
+    /// Adds a description for an OpenAPI tag.
+    ///
+    /// Operations are tagged with `#[tag("name")]` on the handler. Swagger UI
+    /// and other tools group operations by tag and show this description.
+    pub fn openapi_tag(mut self, name: impl Into<String>, description: impl Into<String>) -> Self {
+        self.openapi_tags.push((name.into(), description.into()));
+        self
+    }
+
+    /// Adds a server URL to the OpenAPI spec.
+    ///
+    /// Servers are listed in the order they are added; tools like Swagger UI
+    /// send requests to the first one by default. An empty description is
+    /// left out of the spec.
+    pub fn openapi_server(
+        mut self,
+        url: impl Into<String>,
+        description: impl Into<String>,
+    ) -> Self {
+        let description = description.into();
+        let description = (!description.is_empty()).then_some(description);
+        self.openapi_servers.push((url.into(), description));
+        self
+    }
+
+    /// Prefixes every path in the OpenAPI spec with `base_path`.
+    ///
+    /// Use this when the app is served below a path prefix, e.g. behind a
+    /// reverse proxy mounting it at `/api`. Routing is unaffected.
+    pub fn openapi_base_path(mut self, base_path: impl Into<String>) -> Self {
+        self.openapi_base_path = Some(base_path.into());
+        self
+    }
+
     /// Configures database connection with the given configuration.
     ///
     /// This method connects to the database and registers the connection
@@ -304,13 +798,122 @@ impl Rapina {
         Ok(self)
     }
 
+    /// Builds the OpenAPI spec for the routes registered so far.
+    fn build_openapi(&self) -> OpenApiSpec {
+        let routes = self.router.routes();
+        let mut spec = build_openapi_spec(&self.openapi_title, &self.openapi_version, &routes);
+        for (name, description) in &self.openapi_tags {
+            spec.describe_tag(name, description.as_str());
+        }
+        for (url, description) in &self.openapi_servers {
+            spec.add_server(url, description.clone());
+        }
+        if let Some(base_path) = &self.openapi_base_path {
+            spec.apply_base_path(base_path);
+        }
+        spec
+    }
+
+    /// Renders the OpenAPI spec as pretty-printed JSON without starting a
+    /// server, e.g. to write it to a file at build time.
+    ///
+    /// The app is prepared as it would be for [`listen`](Self::listen), so
+    /// the output is the same document served at `/__rapina/openapi.json`,
+    /// routes added at startup such as `/metrics` included. It works whether
+    /// or not [`openapi`](Self::openapi) serving is enabled.
+    ///
+    /// `rapina openapi export` gets the spec the same way: it runs the app
+    /// with `RAPINA_OPENAPI_EXPORT` set to a file path, and `listen` writes
+    /// the spec there and returns instead of serving.
+    ///
+    /// # Panics
+    ///
+    /// Panics like `listen` if state marked with
+    /// [`require_state`](Self::require_state) isn't registered.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let app = Rapina::new()
+    ///     .openapi("My API", "1.0.0")
+    ///     .router(router);
+    /// std::fs::write("openapi.json", app.export_openapi())?;
+    /// ```
+    pub fn export_openapi(self) -> String {
+        self.prepare().openapi_json()
+    }
+
+    /// Renders the spec of a prepared app.
+    fn openapi_json(&self) -> String {
+        let built;
+        let registry = match self.state.get::<OpenApiRegistry>() {
+            Some(registry) => registry,
+            None => {
+                built = OpenApiRegistry::new(self.build_openapi());
+                &built
+            }
+        };
+        String::from_utf8_lossy(registry.json()).into_owned()
+    }
+
+    /// Writes the spec of a prepared app to the file named by
+    /// `RAPINA_OPENAPI_EXPORT`, returning whether it did.
+    fn export_openapi_to_env_path(&self) -> std::io::Result<bool> {
+        let Some(path) = std::env::var_os(OPENAPI_EXPORT_ENV) else {
+            return Ok(false);
+        };
+        std::fs::write(path, self.openapi_json())?;
+        Ok(true)
+    }
+
     /// Applies all deferred setup (auth middleware, introspection, metrics, openapi).
     ///
     /// Both [`listen`](Self::listen) and [`TestClient::new`](crate::testing::TestClient::new)
     /// call this so the app behaves identically in tests and production.
     pub(crate) fn prepare(mut self) -> Self {
+        let missing: Vec<&str> = self
+            .required_state
+            .iter()
+            .filter(|(_, is_registered)| !is_registered(&self.state))
+            .map(|(type_name, _)| *type_name)
+            .collect();
+        if !missing.is_empty() {
+            panic!(
+                "Required state not registered: {}. Add it with Rapina::state() before starting the app.",
+                missing.join(", ")
+            );
+        }
+
+        self.router.trailing_slash = self.trailing_slash;
+        if let Some(fallback) = self.fallback.take() {
+            self.router.fallback = Some(fallback);
+        }
+        if let Some(handler) = self.method_not_allowed.take() {
+            self.router.method_not_allowed = Some(handler);
+        }
+        for (host, router) in std::mem::take(&mut self.hosts) {
+            self.router = self.router.host(&host, router);
+        }
+        self.router.share_settings_with_hosts();
+
+        if let Err(shadowed) = self.router.validate() {
+            for route in shadowed {
+                tracing::warn!("{}", route);
+            }
+        }
+        for (host, router) in &self.router.hosts {
+            if let Err(shadowed) = router.validate() {
+                for route in shadowed {
+                    tracing::warn!(host = %host, "{}", route);
+                }
+            }
+        }
+
         // Add auth middleware if configured
         if let Some(auth_config) = self.auth_config.take() {
+            if !self.state.contains::<CookieKey>() {
+                self.state = self.state.with(CookieKey::new(auth_config.secret()));
+            }
             let auth_middleware =
                 AuthMiddleware::with_public_routes(auth_config, self.public_routes.clone());
             self.middlewares.add(auth_middleware);
@@ -321,7 +924,8 @@ impl Rapina {
             self.state = self.state.with(RouteRegistry::with_routes(routes));
             self.router = self
                 .router
-                .get_named("/__rapina/routes", "list_routes", list_routes);
+                .get_named("/__rapina/routes", "list_routes", list_routes)
+                .get_named("/__rapina/info", "app_info", app_info);
         }
 
         #[cfg(feature = "metrics")]
@@ -329,23 +933,65 @@ impl Rapina {
             let registry = MetricsRegistry::new();
             self.state = self.state.with(registry.clone());
             self.middlewares.add(MetricsMiddleware::new(registry));
-            self.router = self
-                .router
-                .get_named("/metrics", "metrics", metrics_handler);
+            if self.metrics_bind.is_none() {
+                self.router = self
+                    .router
+                    .get_named("/metrics", "metrics", metrics_handler);
+            }
         }
 
         if self.openapi {
-            let routes = self.router.routes();
-            let spec = build_openapi_spec(&self.openapi_title, &self.openapi_version, &routes);
+            let spec = self.build_openapi();
             self.state = self.state.with(OpenApiRegistry::new(spec));
             self.router =
                 self.router
                     .get_named("/__rapina/openapi.json", "openapi_spec", openapi_spec);
         }
 
+        if self.introspection {
+            let mut info = AppInfo::new(
+                &self.openapi_title,
+                &self.openapi_version,
+                self.middlewares.names(),
+            );
+            info.features.openapi = self.openapi;
+            self.state = self.state.with(info);
+        }
+
         self
     }
 
+    /// Starts the separate metrics listener set with `with_metrics_bind`, if
+    /// any.
+    ///
+    /// Failing to bind is returned so the app doesn't start without it; an
+    /// error once it's serving is logged.
+    async fn spawn_metrics_listener(&self) -> std::io::Result<()> {
+        #[cfg(feature = "metrics")]
+        if let Some(addr) = self.metrics_bind
+            && let Some(registry) = self.state.get::<MetricsRegistry>().cloned()
+        {
+            let listener = tokio::net::TcpListener::bind(addr).await?;
+            println!("Rapina metrics on http://{}/metrics", addr);
+
+            let router = Router::new().get_named("/metrics", "metrics", metrics_handler);
+            tokio::spawn(async move {
+                let served = serve_listener(
+                    listener,
+                    router,
+                    AppState::new().with(registry),
+                    MiddlewareStack::new(),
+                    ServerConfig::default(),
+                )
+                .await;
+                if let Err(e) = served {
+                    tracing::error!(addr = %addr, "metrics listener stopped: {}", e);
+                }
+            });
+        }
+        Ok(())
+    }
+
     /// Starts the HTTP server on the given address.
     ///
     /// # Panics
@@ -354,7 +1000,139 @@ impl Rapina {
     pub async fn listen(self, addr: &str) -> std::io::Result<()> {
         let addr: SocketAddr = addr.parse().expect("invalid address");
         let app = self.prepare();
-        serve(app.router, app.state, app.middlewares, addr).await
+        if app.export_openapi_to_env_path()? {
+            return Ok(());
+        }
+        app.spawn_metrics_listener().await?;
+        serve(
+            app.router,
+            app.state,
+            app.middlewares,
+            addr,
+            app.server_config,
+        )
+        .await
+    }
+
+    /// Starts the HTTP server on an already bound TCP listener.
+    ///
+    /// Useful for socket activation, where the listener is inherited from
+    /// the service manager, and for tests that bind to port 0 and need the
+    /// address before the server starts.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    /// let addr = listener.local_addr()?;
+    ///
+    /// tokio::spawn(Rapina::new().router(router).listen_on(listener));
+    /// ```
+    pub async fn listen_on(self, listener: tokio::net::TcpListener) -> std::io::Result<()> {
+        let app = self.prepare();
+        if app.export_openapi_to_env_path()? {
+            return Ok(());
+        }
+        app.spawn_metrics_listener().await?;
+
+        println!("Rapina listening on http://{}", listener.local_addr()?);
+
+        serve_listener(
+            listener,
+            app.router,
+            app.state,
+            app.middlewares,
+            app.server_config,
+        )
+        .await
+    }
+
+    /// Starts the HTTP server on a Unix domain socket at `path`.
+    ///
+    /// Fails if a file already exists at `path`.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// Rapina::new()
+    ///     .router(router)
+    ///     .listen_unix("/run/myapp.sock")
+    ///     .await
+    /// ```
+    #[cfg(unix)]
+    pub async fn listen_unix(self, path: impl AsRef<std::path::Path>) -> std::io::Result<()> {
+        let app = self.prepare();
+        if app.export_openapi_to_env_path()? {
+            return Ok(());
+        }
+        app.spawn_metrics_listener().await?;
+        let listener = tokio::net::UnixListener::bind(path.as_ref())?;
+
+        println!("Rapina listening on unix:{}", path.as_ref().display());
+
+        serve_listener(
+            listener,
+            app.router,
+            app.state,
+            app.middlewares,
+            app.server_config,
+        )
+        .await
+    }
+
+    /// Starts the HTTPS server on the given address.
+    ///
+    /// Loads the certificate chain and private key from PEM files and
+    /// terminates TLS for every connection. HTTP/2 is negotiated with ALPN
+    /// when enabled in the [`ServerConfig`].
+    ///
+    /// Requires the `tls` feature.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the address can't be bound, or if the PEM files
+    /// can't be read, contain no certificate or key, or the key doesn't
+    /// match the certificate.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// Rapina::new()
+    ///     .router(router)
+    ///     .listen_tls("0.0.0.0:443", "certs/cert.pem", "certs/key.pem")
+    ///     .await
+    /// ```
+    #[cfg(feature = "tls")]
+    pub async fn listen_tls(
+        self,
+        addr: &str,
+        cert_pem: impl AsRef<std::path::Path>,
+        key_pem: impl AsRef<std::path::Path>,
+    ) -> std::io::Result<()> {
+        let addr: SocketAddr = addr.parse().expect("invalid address");
+        let app = self.prepare();
+        if app.export_openapi_to_env_path()? {
+            return Ok(());
+        }
+        app.spawn_metrics_listener().await?;
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        let listener = TlsListener::new(
+            listener,
+            cert_pem.as_ref(),
+            key_pem.as_ref(),
+            app.server_config.protocol,
+        )?;
+
+        println!("Rapina listening on https://{}", addr);
+
+        serve_listener(
+            listener,
+            app.router,
+            app.state,
+            app.middlewares,
+            app.server_config,
+        )
+        .await
     }
 }
 
@@ -383,6 +1161,43 @@ mod tests {
         assert!(app.middlewares.is_empty());
     }
 
+    #[test]
+    fn test_require_state_registered() {
+        #[derive(Clone)]
+        struct AppConfig;
+
+        let app = Rapina::new()
+            .require_state::<AppConfig>()
+            .state(AppConfig)
+            .prepare();
+        assert!(app.state.contains::<AppConfig>());
+    }
+
+    #[test]
+    #[should_panic(expected = "Required state not registered")]
+    fn test_require_state_missing_panics() {
+        struct AppConfig;
+
+        Rapina::new().require_state::<AppConfig>().prepare();
+    }
+
+    #[test]
+    fn test_require_state_missing_names_types() {
+        struct AppConfig;
+        struct Mailer;
+
+        let result = std::panic::catch_unwind(|| {
+            Rapina::new()
+                .require_state::<AppConfig>()
+                .require_state::<Mailer>()
+                .state(1u32)
+                .prepare()
+        });
+        let message = *result.err().unwrap().downcast::<String>().unwrap();
+        assert!(message.contains(std::any::type_name::<AppConfig>()));
+        assert!(message.contains(std::any::type_name::<Mailer>()));
+    }
+
     #[test]
     fn test_rapina_with_router() {
         let router = Router::new().route(
@@ -14,6 +14,9 @@ use crate::response::{BoxBody, IntoResponse};
 /// Routes marked with `#[public]` or starting with `/__rapina` bypass authentication.
 /// All other routes require a valid `Authorization: Bearer <token>` header.
 ///
+/// On public routes a valid token is still decoded on a best-effort basis, so
+/// handlers can use `Option<CurrentUser>` to personalize responses.
+///
 /// # Example
 ///
 /// ```ignore
@@ -70,8 +73,17 @@ impl Middleware for AuthMiddleware {
             let method = req.method().as_str();
             let path = req.uri().path();
 
-            // Check if this route is public
+            // Public routes never reject, but still identify the user when a
+            // valid token is present so handlers can use `Option<CurrentUser>`
             if self.public_routes.is_public(method, path) {
+                let claims = Self::extract_bearer_token(&req)
+                    .and_then(|token| self.config.decode(token).ok());
+                if let Some(claims) = claims {
+                    req.extensions_mut().insert(CurrentUser {
+                        id: claims.sub.clone(),
+                        claims,
+                    });
+                }
                 return next.run(req).await;
             }
 
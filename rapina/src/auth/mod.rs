@@ -114,6 +114,9 @@ impl TokenResponse {
 /// This extractor is automatically populated by the auth middleware
 /// for protected routes. Use it to access the current user's information.
 ///
+/// On `#[public]` routes, use `Option<CurrentUser>` to get the user when a
+/// valid token was sent and `None` for anonymous requests.
+///
 /// # Example
 ///
 /// ```ignore
@@ -124,6 +127,15 @@ impl TokenResponse {
 ///         "claims": user.claims
 ///     }))
 /// }
+///
+/// #[public]
+/// #[get("/greeting")]
+/// async fn greeting(user: Option<CurrentUser>) -> String {
+///     match user {
+///         Some(user) => format!("Hello, {}!", user.id),
+///         None => "Hello, guest!".to_string(),
+///     }
+/// }
 /// ```
 #[derive(Debug, Clone)]
 pub struct CurrentUser {
@@ -145,6 +157,10 @@ impl FromRequestParts for CurrentUser {
             .cloned()
             .ok_or_else(|| Error::unauthorized("authentication required"))
     }
+
+    fn is_missing(parts: &http::request::Parts) -> bool {
+        parts.extensions.get::<CurrentUser>().is_none()
+    }
 }
 
 /// Configuration for JWT authentication.
@@ -187,6 +203,11 @@ impl AuthConfig {
         Ok(Self { secret, expiration })
     }
 
+    /// Returns the secret, used to derive the cookie key when none is set.
+    pub(crate) fn secret(&self) -> &str {
+        &self.secret
+    }
+
     /// Returns the configured expiration time in seconds.
     pub fn expiration(&self) -> u64 {
         self.expiration
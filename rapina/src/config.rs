@@ -26,11 +26,15 @@ pub fn get_env_or(key: &str, default: &str) -> String {
 }
 
 /// Get and parse an environment variable.
+///
+/// A value that fails to parse is reported as [`ConfigError::ParseError`],
+/// naming the variable, the value and the expected type.
 pub fn get_env_parsed<T: FromStr>(key: &str) -> Result<T, ConfigError> {
     let value = get_env(key)?;
-    value.parse().map_err(|_| ConfigError::Invalid {
-        key: key.to_string(),
+    value.parse().map_err(|_| ConfigError::ParseError {
+        var: key.to_string(),
         value,
+        expected_type: std::any::type_name::<T>(),
     })
 }
 
@@ -42,6 +46,36 @@ pub fn get_env_parsed_or<T: FromStr>(key: &str, default: T) -> T {
         .unwrap_or(default)
 }
 
+/// Parse a boolean the way operators usually write it.
+///
+/// Accepts `true`/`false`, `1`/`0`, `yes`/`no` and `on`/`off`, ignoring case
+/// and surrounding whitespace. Returns `None` for anything else.
+pub fn parse_bool(value: &str) -> Option<bool> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "true" | "1" | "yes" | "on" => Some(true),
+        "false" | "0" | "no" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+/// Get a required boolean environment variable, parsed with [`parse_bool`].
+pub fn get_env_bool(key: &str) -> Result<bool, ConfigError> {
+    let value = get_env(key)?;
+    parse_bool(&value).ok_or_else(|| ConfigError::ParseError {
+        var: key.to_string(),
+        value,
+        expected_type: "bool",
+    })
+}
+
+/// Get a boolean environment variable, parsed with [`parse_bool`], with a default.
+pub fn get_env_bool_or(key: &str, default: bool) -> bool {
+    env::var(key)
+        .ok()
+        .and_then(|v| parse_bool(&v))
+        .unwrap_or(default)
+}
+
 /// Configuration loading errors.
 #[derive(Debug)]
 pub enum ConfigError {
@@ -51,6 +85,15 @@ pub enum ConfigError {
     MissingMultiple(Vec<String>),
     /// Environment variable value is invalid.
     Invalid { key: String, value: String },
+    /// Environment variable value could not be parsed as the expected type.
+    ParseError {
+        /// Name of the variable.
+        var: String,
+        /// The value that failed to parse.
+        value: String,
+        /// Name of the type the value was parsed as.
+        expected_type: &'static str,
+    },
 }
 impl std::fmt::Display for ConfigError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -72,6 +115,17 @@ impl std::fmt::Display for ConfigError {
                     value, key
                 )
             }
+            ConfigError::ParseError {
+                var,
+                value,
+                expected_type,
+            } => {
+                write!(
+                    f,
+                    "Invalid value '{}' for environment variable '{}': expected {}",
+                    value, var, expected_type
+                )
+            }
         }
     }
 }
@@ -100,6 +154,54 @@ mod tests {
         assert_eq!(value, 3000);
     }
 
+    #[test]
+    fn test_parse_bool_accepted_spellings() {
+        for value in ["true", "TRUE", "1", "yes", "Yes", "on", "ON", " true "] {
+            assert_eq!(parse_bool(value), Some(true), "{:?}", value);
+        }
+        for value in ["false", "False", "0", "no", "NO", "off", "Off"] {
+            assert_eq!(parse_bool(value), Some(false), "{:?}", value);
+        }
+    }
+
+    #[test]
+    fn test_parse_bool_rejects_other_values() {
+        for value in ["", "2", "y", "n", "enabled", "truthy"] {
+            assert_eq!(parse_bool(value), None, "{:?}", value);
+        }
+    }
+
+    #[test]
+    fn test_get_env_bool_missing() {
+        let result = get_env_bool("RAPINA_TEST_MISSING_VAR_12345");
+        assert!(matches!(result, Err(ConfigError::Missing(_))));
+        assert!(get_env_bool_or("RAPINA_TEST_MISSING_VAR_12345", true));
+    }
+
+    #[test]
+    fn test_get_env_parsed_reports_parse_error() {
+        // Only this test uses the variable, so setting it doesn't race
+        unsafe { env::set_var("RAPINA_TEST_PARSE_ERROR_PORT", "abc") };
+
+        let err = get_env_parsed::<u16>("RAPINA_TEST_PARSE_ERROR_PORT").unwrap_err();
+        match &err {
+            ConfigError::ParseError {
+                var,
+                value,
+                expected_type,
+            } => {
+                assert_eq!(var, "RAPINA_TEST_PARSE_ERROR_PORT");
+                assert_eq!(value, "abc");
+                assert_eq!(*expected_type, "u16");
+            }
+            other => panic!("expected ParseError, got {:?}", other),
+        }
+        assert_eq!(
+            err.to_string(),
+            "Invalid value 'abc' for environment variable 'RAPINA_TEST_PARSE_ERROR_PORT': expected u16"
+        );
+    }
+
     #[test]
     fn test_config_error_display() {
         let err = ConfigError::Missing("DATABASE_URL".to_string());
@@ -1,29 +1,81 @@
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
 use std::time::Instant;
 
+type ValueMap = HashMap<TypeId, Arc<dyn Any + Send + Sync>>;
+
+/// Typed values attached to a single request.
+///
+/// Clones share the same map, so a value inserted by middleware through its
+/// `&RequestContext` is visible to the handler's copy.
+#[derive(Clone, Default)]
+struct Values(Arc<RwLock<ValueMap>>);
+
+impl std::fmt::Debug for Values {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let len = self.0.read().unwrap().len();
+        f.debug_struct("Values").field("len", &len).finish()
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct RequestContext {
     pub trace_id: String,
     pub start_time: Instant,
+    values: Values,
 }
 
 impl RequestContext {
     pub fn new() -> Self {
-        Self {
-            trace_id: uuid::Uuid::new_v4().to_string(),
-            start_time: Instant::now(),
-        }
+        Self::with_trace_id(crate::middleware::generate_uuid())
     }
 
     pub fn with_trace_id(trace_id: String) -> Self {
         Self {
             trace_id,
             start_time: Instant::now(),
+            values: Values::default(),
         }
     }
 
     pub fn elapsed(&self) -> std::time::Duration {
         self.start_time.elapsed()
     }
+
+    /// Stores a value for the rest of the request, replacing any previous
+    /// value of the same type.
+    ///
+    /// Middleware uses this to hand values such as the authenticated user or
+    /// tenant to handlers, which read them back with [`get`](Self::get) or
+    /// through the [`Context`](crate::extract::Context) extractor.
+    pub fn insert<T: Send + Sync + 'static>(&self, value: T) {
+        self.values
+            .0
+            .write()
+            .unwrap()
+            .insert(TypeId::of::<T>(), Arc::new(value));
+    }
+
+    /// Returns a copy of the value of type `T`, if one was inserted.
+    pub fn get<T: Clone + Send + Sync + 'static>(&self) -> Option<T> {
+        self.values
+            .0
+            .read()
+            .unwrap()
+            .get(&TypeId::of::<T>())
+            .and_then(|value| value.downcast_ref::<T>())
+            .cloned()
+    }
+
+    /// Returns `true` if a value of type `T` was inserted.
+    pub fn contains<T: Send + Sync + 'static>(&self) -> bool {
+        self.values
+            .0
+            .read()
+            .unwrap()
+            .contains_key(&TypeId::of::<T>())
+    }
 }
 
 impl Default for RequestContext {
@@ -82,6 +134,30 @@ mod tests {
         assert_eq!(ctx1.trace_id, ctx2.trace_id);
     }
 
+    #[test]
+    fn test_insert_and_get_value() {
+        #[derive(Debug, Clone, PartialEq)]
+        struct TenantId(u64);
+
+        let ctx = RequestContext::new();
+        assert!(ctx.get::<TenantId>().is_none());
+
+        ctx.insert(TenantId(7));
+        assert!(ctx.contains::<TenantId>());
+        assert_eq!(ctx.get::<TenantId>(), Some(TenantId(7)));
+
+        ctx.insert(TenantId(8));
+        assert_eq!(ctx.get::<TenantId>(), Some(TenantId(8)));
+    }
+
+    #[test]
+    fn test_clones_share_values() {
+        let ctx1 = RequestContext::new();
+        let ctx2 = ctx1.clone();
+        ctx1.insert("admin".to_string());
+        assert_eq!(ctx2.get::<String>().as_deref(), Some("admin"));
+    }
+
     #[test]
     fn test_debug() {
         let ctx = RequestContext::with_trace_id("test-id".to_string());
@@ -0,0 +1,728 @@
+//! Signed and private cookies, and `Set-Cookie` headers.
+//!
+//! [`SignedCookies`] lets the client read a cookie but detects any change to
+//! it. [`PrivateCookies`] also hides the value, by encrypting it. Both are
+//! keyed by a [`CookieKey`], registered with
+//! [`Rapina::with_cookie_key`](crate::app::Rapina::with_cookie_key) or
+//! derived from the [`AuthConfig`](crate::auth::AuthConfig) secret.
+//!
+//! # Examples
+//!
+//! ```ignore
+//! use rapina::cookie::{SetCookie, SignedCookies};
+//! use rapina::prelude::*;
+//!
+//! #[post("/login")]
+//! async fn login(cookies: SignedCookies) -> WithHeaders<&'static str> {
+//!     WithHeaders::new("welcome").cookie(cookies.sign(SetCookie::new("user_id", "42").http_only(true)))
+//! }
+//!
+//! #[get("/me")]
+//! async fn me(cookies: SignedCookies) -> Result<String> {
+//!     cookies
+//!         .get("user_id")
+//!         .ok_or_else(|| Error::unauthorized("not logged in"))
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use hmac::{Hmac, Mac};
+use http::{HeaderValue, Response, StatusCode};
+use sha2::Sha256;
+
+use crate::error::Error;
+use crate::extract::{FromRequestParts, PathParams};
+use crate::response::{BoxBody, IntoResponse};
+use crate::state::AppState;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Length of the AES-GCM nonce stored in front of each private cookie
+const NONCE_LEN: usize = 12;
+
+/// Secret used to sign and encrypt cookies.
+///
+/// Separate signing and encryption keys are derived from one secret, so the
+/// same secret can back both [`SignedCookies`] and [`PrivateCookies`]. Use at
+/// least 32 random bytes, and keep it stable across restarts and instances,
+/// or existing cookies stop verifying.
+#[derive(Clone)]
+pub struct CookieKey {
+    signing: [u8; 32],
+    encryption: [u8; 32],
+}
+
+impl fmt::Debug for CookieKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CookieKey").finish_non_exhaustive()
+    }
+}
+
+impl CookieKey {
+    /// Derives the cookie keys from a secret.
+    pub fn new(secret: impl AsRef<[u8]>) -> Self {
+        let secret = secret.as_ref();
+        Self {
+            signing: derive_key(secret, b"rapina signed cookies"),
+            encryption: derive_key(secret, b"rapina private cookies"),
+        }
+    }
+
+    /// Returns `value` with a signature for the cookie `name` appended.
+    pub fn sign(&self, name: &str, value: &str) -> String {
+        let tag = URL_SAFE_NO_PAD.encode(self.mac(name, value).finalize().into_bytes());
+        format!("{}.{}", value, tag)
+    }
+
+    /// Checks a value produced by [`sign`](Self::sign) and returns the
+    /// original value, or `None` if it was changed or signed for another name.
+    pub fn verify(&self, name: &str, signed: &str) -> Option<String> {
+        let (value, tag) = signed.rsplit_once('.')?;
+        let tag = URL_SAFE_NO_PAD.decode(tag).ok()?;
+        self.mac(name, value).verify_slice(&tag).ok()?;
+        Some(value.to_string())
+    }
+
+    /// Encrypts `value` for the cookie `name`.
+    pub fn encrypt(&self, name: &str, value: &str) -> String {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher()
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: value.as_bytes(),
+                    aad: name.as_bytes(),
+                },
+            )
+            .expect("AES-GCM encryption does not fail for cookie-sized values");
+
+        let mut data = nonce.to_vec();
+        data.extend_from_slice(&ciphertext);
+        URL_SAFE_NO_PAD.encode(data)
+    }
+
+    /// Decrypts a value produced by [`encrypt`](Self::encrypt), or returns
+    /// `None` if it was changed or encrypted for another name.
+    pub fn decrypt(&self, name: &str, encrypted: &str) -> Option<String> {
+        let data = URL_SAFE_NO_PAD.decode(encrypted).ok()?;
+        if data.len() < NONCE_LEN {
+            return None;
+        }
+        let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+        let nonce: [u8; NONCE_LEN] = nonce.try_into().ok()?;
+        let plaintext = self
+            .cipher()
+            .decrypt(
+                &Nonce::from(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad: name.as_bytes(),
+                },
+            )
+            .ok()?;
+        String::from_utf8(plaintext).ok()
+    }
+
+    fn mac(&self, name: &str, value: &str) -> HmacSha256 {
+        let mut mac =
+            <HmacSha256 as Mac>::new_from_slice(&self.signing).expect("HMAC accepts any key size");
+        // The name is bound to the signature so values can't be moved between cookies
+        mac.update(name.as_bytes());
+        mac.update(b"=");
+        mac.update(value.as_bytes());
+        mac
+    }
+
+    fn cipher(&self) -> Aes256Gcm {
+        Aes256Gcm::new(&self.encryption.into())
+    }
+}
+
+fn derive_key(secret: &[u8], purpose: &[u8]) -> [u8; 32] {
+    let mut mac = <HmacSha256 as Mac>::new_from_slice(secret).expect("HMAC accepts any key size");
+    mac.update(purpose);
+    mac.finalize().into_bytes().into()
+}
+
+/// The `SameSite` attribute of a cookie.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SameSite {
+    /// Sent only with requests from the same site.
+    Strict,
+    /// Also sent on top-level navigation from other sites.
+    Lax,
+    /// Sent with all requests; requires `Secure`.
+    None,
+}
+
+impl fmt::Display for SameSite {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SameSite::Strict => "Strict",
+            SameSite::Lax => "Lax",
+            SameSite::None => "None",
+        })
+    }
+}
+
+/// A cookie to send in a `Set-Cookie` response header.
+///
+/// Attach it to a response with [`CookieJar`] or
+/// [`WithHeaders::cookie`](crate::response::WithHeaders::cookie). The name,
+/// value and attributes are checked when the response is built, and an
+/// invalid cookie turns the response into a 500 error rather than sending a
+/// truncated or malformed header.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use rapina::cookie::{SameSite, SetCookie};
+///
+/// let cookie = SetCookie::new("theme", "dark")
+///     .path("/")
+///     .max_age(Duration::from_secs(3600))
+///     .http_only(true)
+///     .secure(true)
+///     .same_site(SameSite::Lax);
+///
+/// assert_eq!(
+///     cookie.to_string(),
+///     "theme=dark; Max-Age=3600; Path=/; HttpOnly; Secure; SameSite=Lax"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SetCookie {
+    name: String,
+    value: String,
+    expires: Option<SystemTime>,
+    max_age: Option<Duration>,
+    domain: Option<String>,
+    path: Option<String>,
+    http_only: bool,
+    secure: bool,
+    same_site: Option<SameSite>,
+}
+
+impl SetCookie {
+    /// Creates a session cookie with no attributes.
+    pub fn new(name: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            value: value.into(),
+            expires: None,
+            max_age: None,
+            domain: None,
+            path: None,
+            http_only: false,
+            secure: false,
+            same_site: None,
+        }
+    }
+
+    /// Returns the cookie name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the cookie value.
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// Sets when the browser discards the cookie.
+    ///
+    /// Prefer [`max_age`](Self::max_age), which takes precedence in browsers
+    /// that support both.
+    pub fn expires(mut self, expires: SystemTime) -> Self {
+        self.expires = Some(expires);
+        self
+    }
+
+    /// Sets how long the browser keeps the cookie.
+    pub fn max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// Sends the cookie to `domain` and its subdomains.
+    pub fn domain(mut self, domain: impl Into<String>) -> Self {
+        self.domain = Some(domain.into());
+        self
+    }
+
+    /// Limits the cookie to paths under `path`.
+    pub fn path(mut self, path: impl Into<String>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    /// Hides the cookie from JavaScript.
+    pub fn http_only(mut self, http_only: bool) -> Self {
+        self.http_only = http_only;
+        self
+    }
+
+    /// Only sends the cookie over HTTPS.
+    pub fn secure(mut self, secure: bool) -> Self {
+        self.secure = secure;
+        self
+    }
+
+    /// Sets the `SameSite` attribute.
+    pub fn same_site(mut self, same_site: SameSite) -> Self {
+        self.same_site = Some(same_site);
+        self
+    }
+
+    fn with_value(mut self, value: String) -> Self {
+        self.value = value;
+        self
+    }
+
+    /// Checks that the cookie can be sent as is.
+    ///
+    /// The name must be an HTTP token and the value may only contain the
+    /// characters allowed by RFC 6265, optionally in double quotes.
+    /// `Domain` and `Path` can't contain control characters or `;`.
+    pub fn validate(&self) -> Result<(), Error> {
+        let invalid = |reason: &str| {
+            Err(Error::internal(format!(
+                "Invalid cookie '{}': {}",
+                self.name, reason
+            )))
+        };
+
+        if self.name.is_empty() || !self.name.bytes().all(is_token_byte) {
+            return invalid("name must be a non-empty token");
+        }
+        let value = self
+            .value
+            .strip_prefix('"')
+            .and_then(|v| v.strip_suffix('"'))
+            .unwrap_or(&self.value);
+        if !value.bytes().all(is_cookie_octet) {
+            return invalid("value contains characters not allowed in cookies");
+        }
+        for (attribute, value) in [("Domain", &self.domain), ("Path", &self.path)] {
+            if let Some(value) = value
+                && !value
+                    .bytes()
+                    .all(|b| (0x20..0x7f).contains(&b) && b != b';')
+            {
+                return invalid(&format!("{} contains ';' or control characters", attribute));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Token characters from RFC 9110: visible ASCII except separators
+fn is_token_byte(b: u8) -> bool {
+    b.is_ascii_graphic() && !b"\"(),/:;<=>?@[\\]{}".contains(&b)
+}
+
+/// `cookie-octet` from RFC 6265
+fn is_cookie_octet(b: u8) -> bool {
+    matches!(b, 0x21 | 0x23..=0x2B | 0x2D..=0x3A | 0x3C..=0x5B | 0x5D..=0x7E)
+}
+
+impl fmt::Display for SetCookie {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}={}", self.name, self.value)?;
+        if let Some(expires) = self.expires {
+            write!(f, "; Expires={}", httpdate::fmt_http_date(expires))?;
+        }
+        if let Some(max_age) = self.max_age {
+            write!(f, "; Max-Age={}", max_age.as_secs())?;
+        }
+        if let Some(domain) = &self.domain {
+            write!(f, "; Domain={}", domain)?;
+        }
+        if let Some(path) = &self.path {
+            write!(f, "; Path={}", path)?;
+        }
+        if self.http_only {
+            f.write_str("; HttpOnly")?;
+        }
+        if self.secure {
+            f.write_str("; Secure")?;
+        }
+        if let Some(same_site) = self.same_site {
+            write!(f, "; SameSite={}", same_site)?;
+        }
+        Ok(())
+    }
+}
+
+/// A set of cookies to send with a response.
+///
+/// Return it on its own, or paired with a body as `(CookieJar, T)`. Each
+/// cookie becomes its own `Set-Cookie` header.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rapina::cookie::{CookieJar, SameSite, SetCookie};
+///
+/// #[post("/preferences")]
+/// async fn save_preferences() -> (CookieJar, &'static str) {
+///     let jar = CookieJar::new()
+///         .cookie(SetCookie::new("theme", "dark").path("/"))
+///         .cookie(SetCookie::new("lang", "en").same_site(SameSite::Lax))
+///         .remove("legacy_pref");
+///     (jar, "saved")
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CookieJar {
+    cookies: Vec<SetCookie>,
+}
+
+impl CookieJar {
+    /// Creates an empty jar.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a cookie to send.
+    pub fn cookie(mut self, cookie: SetCookie) -> Self {
+        self.cookies.push(cookie);
+        self
+    }
+
+    /// Tells the browser to delete the cookie `name`.
+    ///
+    /// The `Path` and `Domain` must match the ones the cookie was set with;
+    /// use [`cookie`](Self::cookie) with an expired cookie when they differ from
+    /// the defaults.
+    pub fn remove(self, name: impl Into<String>) -> Self {
+        self.cookie(
+            SetCookie::new(name, "")
+                .expires(SystemTime::UNIX_EPOCH)
+                .max_age(Duration::ZERO),
+        )
+    }
+
+    /// Returns the cookies in the jar.
+    pub fn cookies(&self) -> &[SetCookie] {
+        &self.cookies
+    }
+}
+
+/// Appends a `Set-Cookie` header for each cookie, or replaces the response
+/// with a 500 error if one of them is invalid.
+pub(crate) fn append_cookies(
+    mut response: Response<BoxBody>,
+    cookies: &[SetCookie],
+) -> Response<BoxBody> {
+    for cookie in cookies {
+        let value = cookie.validate().and_then(|()| {
+            HeaderValue::from_str(&cookie.to_string()).map_err(|_| {
+                Error::internal(format!(
+                    "Invalid cookie '{}': not a valid header",
+                    cookie.name
+                ))
+            })
+        });
+        match value {
+            Ok(value) => {
+                response
+                    .headers_mut()
+                    .append(http::header::SET_COOKIE, value);
+            }
+            Err(e) => return e.into_response(),
+        }
+    }
+    response
+}
+
+impl IntoResponse for CookieJar {
+    fn into_response(self) -> Response<BoxBody> {
+        append_cookies(StatusCode::OK.into_response(), &self.cookies)
+    }
+}
+
+impl<T: IntoResponse> IntoResponse for (CookieJar, T) {
+    fn into_response(self) -> Response<BoxBody> {
+        append_cookies(self.1.into_response(), &self.0.cookies)
+    }
+}
+
+/// Parses the `Cookie` request header into name/value pairs.
+pub(crate) fn parse_cookie_header(headers: &http::HeaderMap) -> HashMap<String, String> {
+    headers
+        .get_all(http::header::COOKIE)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|header| header.split(';'))
+        .filter_map(|pair| {
+            let (key, value) = pair.trim().split_once('=')?;
+            if key.is_empty() {
+                None
+            } else {
+                Some((key.to_string(), value.to_string()))
+            }
+        })
+        .collect()
+}
+
+async fn cookie_key(state: &AppState) -> Result<CookieKey, Error> {
+    state
+        .get_or_init::<CookieKey>()
+        .await
+        .cloned()
+        .ok_or_else(|| {
+            Error::internal(
+                "No cookie key configured. Call Rapina::with_cookie_key() or Rapina::with_auth().",
+            )
+        })
+}
+
+/// Reads and writes cookies protected by a signature.
+///
+/// The client can see the values but any change is detected: [`get`](Self::get)
+/// returns `None` for a cookie that was modified, just like a missing one.
+#[derive(Debug)]
+pub struct SignedCookies {
+    key: CookieKey,
+    cookies: HashMap<String, String>,
+}
+
+impl SignedCookies {
+    /// Returns the verified value of the cookie `name`.
+    pub fn get(&self, name: &str) -> Option<String> {
+        self.key.verify(name, self.cookies.get(name)?)
+    }
+
+    /// Signs a cookie before it is sent.
+    pub fn sign(&self, cookie: SetCookie) -> SetCookie {
+        let value = self.key.sign(cookie.name(), cookie.value());
+        cookie.with_value(value)
+    }
+}
+
+impl FromRequestParts for SignedCookies {
+    async fn from_request_parts(
+        parts: &http::request::Parts,
+        _params: &PathParams,
+        state: &Arc<AppState>,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            key: cookie_key(state).await?,
+            cookies: parse_cookie_header(&parts.headers),
+        })
+    }
+}
+
+/// Reads and writes encrypted cookies.
+///
+/// Values are encrypted with AES-256-GCM, so the client can neither read
+/// nor change them. [`get`](Self::get) returns `None` for a cookie that was
+/// modified, just like a missing one.
+#[derive(Debug)]
+pub struct PrivateCookies {
+    key: CookieKey,
+    cookies: HashMap<String, String>,
+}
+
+impl PrivateCookies {
+    /// Returns the decrypted value of the cookie `name`.
+    pub fn get(&self, name: &str) -> Option<String> {
+        self.key.decrypt(name, self.cookies.get(name)?)
+    }
+
+    /// Encrypts a cookie before it is sent.
+    pub fn encrypt(&self, cookie: SetCookie) -> SetCookie {
+        let value = self.key.encrypt(cookie.name(), cookie.value());
+        cookie.with_value(value)
+    }
+}
+
+impl FromRequestParts for PrivateCookies {
+    async fn from_request_parts(
+        parts: &http::request::Parts,
+        _params: &PathParams,
+        state: &Arc<AppState>,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            key: cookie_key(state).await?,
+            cookies: parse_cookie_header(&parts.headers),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key() -> CookieKey {
+        CookieKey::new("a-test-secret-that-is-long-enough-to-use")
+    }
+
+    #[test]
+    fn test_signed_round_trip() {
+        let key = key();
+        let signed = key.sign("user_id", "42");
+        assert!(signed.starts_with("42."));
+        assert_eq!(key.verify("user_id", &signed).as_deref(), Some("42"));
+    }
+
+    #[test]
+    fn test_signed_value_with_dots() {
+        let key = key();
+        let signed = key.sign("email", "a.b@example.com");
+        assert_eq!(
+            key.verify("email", &signed).as_deref(),
+            Some("a.b@example.com")
+        );
+    }
+
+    #[test]
+    fn test_signed_rejects_tampering() {
+        let key = key();
+        let signed = key.sign("user_id", "42");
+        let tampered = signed.replacen("42", "43", 1);
+
+        assert_eq!(key.verify("user_id", &tampered), None);
+        assert_eq!(key.verify("user_id", "42"), None);
+        // A valid signature for another cookie is rejected
+        assert_eq!(key.verify("admin", &signed), None);
+        // As is one made with another secret
+        assert_eq!(CookieKey::new("other").verify("user_id", &signed), None);
+    }
+
+    #[test]
+    fn test_private_round_trip() {
+        let key = key();
+        let encrypted = key.encrypt("session", "secret data");
+        assert!(!encrypted.contains("secret"));
+        assert_eq!(
+            key.decrypt("session", &encrypted).as_deref(),
+            Some("secret data")
+        );
+        // A fresh nonce is used each time
+        assert_ne!(encrypted, key.encrypt("session", "secret data"));
+    }
+
+    #[test]
+    fn test_private_rejects_tampering() {
+        let key = key();
+        let encrypted = key.encrypt("session", "secret data");
+
+        let mut bytes = URL_SAFE_NO_PAD.decode(&encrypted).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 1;
+        let tampered = URL_SAFE_NO_PAD.encode(bytes);
+
+        assert_eq!(key.decrypt("session", &tampered), None);
+        assert_eq!(key.decrypt("other", &encrypted), None);
+        assert_eq!(key.decrypt("session", "not base64!"), None);
+        assert_eq!(key.decrypt("session", "AAAA"), None);
+    }
+
+    #[test]
+    fn test_set_cookie_attributes() {
+        let cookie = SetCookie::new("id", "1")
+            .max_age(Duration::from_secs(60))
+            .path("/app")
+            .http_only(true)
+            .secure(true)
+            .same_site(SameSite::Strict);
+        assert_eq!(
+            cookie.to_string(),
+            "id=1; Max-Age=60; Path=/app; HttpOnly; Secure; SameSite=Strict"
+        );
+        assert_eq!(SetCookie::new("id", "1").to_string(), "id=1");
+    }
+
+    #[test]
+    fn test_set_cookie_domain_and_expires() {
+        let expires = SystemTime::UNIX_EPOCH + Duration::from_secs(784111777);
+        let cookie = SetCookie::new("id", "1")
+            .domain("example.com")
+            .path("/")
+            .expires(expires);
+        assert_eq!(
+            cookie.to_string(),
+            "id=1; Expires=Sun, 06 Nov 1994 08:49:37 GMT; Domain=example.com; Path=/"
+        );
+    }
+
+    #[test]
+    fn test_set_cookie_validation() {
+        assert!(SetCookie::new("id", "abc-123_!").validate().is_ok());
+        assert!(SetCookie::new("id", "\"quoted\"").validate().is_ok());
+        assert!(SetCookie::new("id", "").validate().is_ok());
+
+        let invalid = [
+            SetCookie::new("", "1"),
+            SetCookie::new("my id", "1"),
+            SetCookie::new("id;", "1"),
+            SetCookie::new("id", "a;b"),
+            SetCookie::new("id", "a b"),
+            SetCookie::new("id", "a,b"),
+            SetCookie::new("id", "é"),
+            SetCookie::new("id", "1").path("/; Secure"),
+            SetCookie::new("id", "1").domain("a\nb"),
+        ];
+        for cookie in invalid {
+            let err = cookie.validate().unwrap_err();
+            assert!(err.message.starts_with("Invalid cookie"), "{}", err.message);
+        }
+    }
+
+    #[test]
+    fn test_cookie_jar_sets_each_cookie() {
+        let jar = CookieJar::new()
+            .cookie(SetCookie::new("a", "1"))
+            .cookie(SetCookie::new("b", "2").http_only(true))
+            .remove("c");
+        let response = (jar, "ok").into_response();
+
+        let values: Vec<_> = response
+            .headers()
+            .get_all(http::header::SET_COOKIE)
+            .iter()
+            .map(|v| v.to_str().unwrap().to_string())
+            .collect();
+        assert_eq!(
+            values,
+            [
+                "a=1",
+                "b=2; HttpOnly",
+                "c=; Expires=Thu, 01 Jan 1970 00:00:00 GMT; Max-Age=0",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cookie_jar_invalid_cookie_is_error() {
+        let jar = CookieJar::new()
+            .cookie(SetCookie::new("a", "1"))
+            .cookie(SetCookie::new("b", "bad;value"));
+        let response = (jar, "ok").into_response();
+
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(response.headers().get(http::header::SET_COOKIE).is_none());
+    }
+
+    #[test]
+    fn test_parse_cookie_header() {
+        let mut headers = http::HeaderMap::new();
+        headers.insert(http::header::COOKIE, "a=1; b=x=y; =skip".parse().unwrap());
+        let cookies = parse_cookie_header(&headers);
+        assert_eq!(cookies.len(), 2);
+        assert_eq!(cookies["a"], "1");
+        assert_eq!(cookies["b"], "x=y");
+    }
+}
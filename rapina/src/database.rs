@@ -185,7 +185,18 @@ impl DatabaseConfig {
 /// Wrapper around SeaORM's `DbErr` for Rapina error integration.
 ///
 /// This type implements `IntoApiError`, allowing you to use `?` directly
-/// on database operations without manual error mapping.
+/// on database operations without manual error mapping. Errors the client
+/// caused get a 4xx status:
+///
+/// - a missing record is a 404 Not Found
+/// - a unique constraint violation is a 409 Conflict
+/// - a foreign key violation is a 422 Validation Error when the row being
+///   written references a missing record, and a 409 Conflict otherwise,
+///   e.g. when deleting a record other rows still reference
+///
+/// Everything else is a 500 whose details are logged, not sent. Constraint
+/// violations are recognized with the `postgres`, `mysql` and `sqlite`
+/// features; the database's message is never included in the response.
 #[derive(Debug)]
 pub struct DbError(pub sea_orm::DbErr);
 
@@ -205,6 +216,22 @@ impl IntoApiError for DbError {
     fn into_api_error(self) -> Error {
         use sea_orm::DbErr;
 
+        match self.0.sql_err() {
+            Some(sea_orm::SqlErr::UniqueConstraintViolation(msg)) => {
+                tracing::debug!(error = %msg, "unique constraint violated");
+                return Error::conflict("a record with the same unique value already exists");
+            }
+            Some(sea_orm::SqlErr::ForeignKeyConstraintViolation(msg)) => {
+                tracing::debug!(error = %msg, "foreign key constraint violated");
+                return if references_missing_record(&msg) {
+                    Error::validation("a referenced record does not exist")
+                } else {
+                    Error::conflict("the record is still referenced by other records")
+                };
+            }
+            _ => {}
+        }
+
         match &self.0 {
             DbErr::RecordNotFound(msg) => Error::not_found(msg.clone()),
             DbErr::RecordNotInserted => Error::internal("failed to insert record"),
@@ -230,12 +257,84 @@ impl IntoApiError for DbError {
     }
 }
 
+/// Returns whether a foreign key violation came from writing a row that
+/// references a missing record, rather than from removing a referenced one.
+///
+/// SQLite reports both cases the same way, so they are treated as conflicts.
+fn references_missing_record(message: &str) -> bool {
+    // PostgreSQL: "insert or update on table ... violates foreign key constraint"
+    // MySQL: "Cannot add or update a child row: a foreign key constraint fails"
+    message.starts_with("insert or update on table")
+        || message.contains("add or update a child row")
+}
+
 impl From<sea_orm::DbErr> for DbError {
     fn from(err: sea_orm::DbErr) -> Self {
         DbError(err)
     }
 }
 
+/// Saves `active` only if the row still has the version the client read,
+/// and increments the version.
+///
+/// This is optimistic concurrency for entities declared `#[versioned]` in
+/// [`schema!`](crate::prelude::schema): the update is a single
+/// `UPDATE ... WHERE id = ? AND version = ?`, so of two requests that read
+/// the same version only the first one is saved. When the version doesn't
+/// match, or the row is gone, the update returns a 409 Conflict and the
+/// client should fetch the record again before retrying.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use rapina::database::update_versioned;
+///
+/// #[put("/posts/:id")]
+/// async fn update_post(db: Db, id: Path<i32>, body: Json<UpdatePost>) -> Result<Json<Post>> {
+///     let update = body.into_inner();
+///     let item = Post::find_by_id(id.into_inner())
+///         .one(db.conn())
+///         .await?
+///         .ok_or_else(|| Error::not_found("post not found"))?;
+///
+///     let mut active: post::ActiveModel = item.into();
+///     if let Some(title) = update.title {
+///         active.title = Set(title);
+///     }
+///     let post = update_versioned(active, update.version, db.conn()).await?;
+///     Ok(Json(post))
+/// }
+/// ```
+pub async fn update_versioned<A, C>(
+    mut active: A,
+    version: i32,
+    conn: &C,
+) -> Result<<A::Entity as sea_orm::EntityTrait>::Model, Error>
+where
+    A: sea_orm::ActiveModelTrait,
+    <A::Entity as sea_orm::EntityTrait>::Model: sea_orm::IntoActiveModel<A>,
+    C: sea_orm::ConnectionTrait,
+{
+    use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+    use std::str::FromStr;
+
+    let column = <<A::Entity as EntityTrait>::Column as FromStr>::from_str("version")
+        .map_err(|_| Error::internal("entity has no version column"))?;
+    active.set(column, (version + 1).into());
+
+    <A::Entity as EntityTrait>::update(active)
+        .filter(column.eq(version))
+        .exec(conn)
+        .await
+        .map_err(|err| match err {
+            sea_orm::DbErr::RecordNotUpdated => {
+                Error::conflict("the record was modified by another request")
+                    .with_details(serde_json::json!({ "version": version }))
+            }
+            err => DbError(err).into_api_error(),
+        })
+}
+
 /// Database connection extractor for handlers.
 ///
 /// Use this to access the database connection pool in your handlers.
@@ -326,6 +425,20 @@ mod tests {
         assert_eq!(api_err.code, "NOT_FOUND");
     }
 
+    #[test]
+    fn test_references_missing_record() {
+        assert!(references_missing_record(
+            "insert or update on table \"posts\" violates foreign key constraint \"posts_user_id_fkey\""
+        ));
+        assert!(references_missing_record(
+            "Cannot add or update a child row: a foreign key constraint fails (`app`.`posts`)"
+        ));
+        assert!(!references_missing_record(
+            "update or delete on table \"users\" violates foreign key constraint \"posts_user_id_fkey\" on table \"posts\""
+        ));
+        assert!(!references_missing_record("FOREIGN KEY constraint failed"));
+    }
+
     #[test]
     fn test_db_error_custom() {
         let err = DbError(sea_orm::DbErr::Custom("something went wrong".to_string()));
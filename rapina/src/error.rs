@@ -26,11 +26,10 @@
 //! ```
 
 use serde::Serialize;
+use std::collections::HashMap;
 use std::fmt;
 
-use crate::response::{BoxBody, IntoResponse};
-use bytes::Bytes;
-use http_body_util::Full;
+use crate::response::{BoxBody, IntoResponse, full_response};
 
 /// The JSON structure returned for error responses.
 #[derive(Debug, Serialize)]
@@ -82,6 +81,16 @@ pub struct Error {
     pub details: Option<serde_json::Value>,
     /// Optional trace ID for this error.
     pub trace_id: Option<String>,
+    /// Data only some errors carry, boxed so `Error` stays small in `Result`s.
+    extra: Option<Box<ErrorExtra>>,
+}
+
+#[derive(Debug, Default)]
+struct ErrorExtra {
+    /// The underlying cause, logged but never sent to the client.
+    source: Option<Box<dyn std::error::Error + Send + Sync>>,
+    /// Headers added to the response, as `(name, value)` pairs.
+    headers: Vec<(String, String)>,
 }
 
 impl Error {
@@ -93,6 +102,7 @@ impl Error {
             message: message.into(),
             details: None,
             trace_id: None,
+            extra: None,
         }
     }
 
@@ -108,6 +118,111 @@ impl Error {
         self
     }
 
+    /// Attaches the underlying cause of the error.
+    ///
+    /// The source is logged when the error becomes a response, together
+    /// with the trace ID, but is never part of the response body.
+    pub fn with_source(mut self, source: impl std::error::Error + Send + Sync + 'static) -> Self {
+        self.extra.get_or_insert_default().source = Some(Box::new(source));
+        self
+    }
+
+    /// Appends a header to the error response, such as `WWW-Authenticate`
+    /// on a 401.
+    ///
+    /// Like [`WithHeaders`](crate::response::WithHeaders), repeating a name
+    /// produces multiple values, and an invalid header name or value turns
+    /// the response into a 500 error.
+    ///
+    /// ```
+    /// use rapina::error::Error;
+    ///
+    /// let err = Error::unauthorized("token expired")
+    ///     .with_header("www-authenticate", "Bearer error=\"invalid_token\"");
+    /// assert_eq!(err.header("WWW-Authenticate"), Some("Bearer error=\"invalid_token\""));
+    /// ```
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.extra
+            .get_or_insert_default()
+            .headers
+            .push((name.into(), value.into()));
+        self
+    }
+
+    /// Returns the first value of a header added with
+    /// [`with_header`](Self::with_header), matching the name without regard
+    /// to case.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.extra
+            .as_ref()?
+            .headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Suggests how long the client should wait before retrying, sent as a
+    /// `Retry-After` header.
+    ///
+    /// Meant for 503 and 429 responses. The delay is rounded up to whole
+    /// seconds, the header's resolution.
+    ///
+    /// ```
+    /// use rapina::error::Error;
+    /// use std::time::Duration;
+    ///
+    /// let err = Error::service_unavailable("down for maintenance")
+    ///     .with_retry_after(Duration::from_millis(1500));
+    /// assert_eq!(err.header("retry-after"), Some("2"));
+    /// ```
+    pub fn with_retry_after(self, delay: std::time::Duration) -> Self {
+        let seconds = delay.as_secs() + u64::from(delay.subsec_nanos() > 0);
+        self.with_header(http::header::RETRY_AFTER.as_str(), seconds.to_string())
+    }
+
+    /// Creates an error with any 4xx or 5xx status.
+    ///
+    /// The code is the status's reason phrase in upper snake case, such as
+    /// `GONE` for 410 or `UNAVAILABLE_FOR_LEGAL_REASONS` for 451, and
+    /// `HTTP_<status>` for statuses without one. Use [`new`](Self::new) to
+    /// choose the code.
+    ///
+    /// A `status` that isn't a client or server error status is a bug in
+    /// the caller: debug builds panic, and release builds fall back to a 500
+    /// [`internal`](Self::internal) error with the given message.
+    ///
+    /// ```
+    /// use rapina::error::Error;
+    ///
+    /// let err = Error::from_status(402, "upgrade your plan to export reports");
+    /// assert_eq!(err.code, "PAYMENT_REQUIRED");
+    /// ```
+    pub fn from_status(status: u16, message: impl Into<String>) -> Self {
+        let valid = http::StatusCode::from_u16(status)
+            .ok()
+            .filter(|s| s.is_client_error() || s.is_server_error());
+        debug_assert!(
+            valid.is_some(),
+            "invalid error status {}: expected a 4xx or 5xx code",
+            status
+        );
+        let Some(valid) = valid else {
+            return Self::internal(message);
+        };
+        let code = match valid.canonical_reason() {
+            Some(reason) => reason
+                .chars()
+                .filter(|c| c.is_ascii_alphanumeric() || *c == ' ' || *c == '-')
+                .map(|c| match c {
+                    ' ' | '-' => '_',
+                    c => c.to_ascii_uppercase(),
+                })
+                .collect(),
+            None => format!("HTTP_{}", status),
+        };
+        Self::new(status, code, message)
+    }
+
     /// Creates a 400 Bad Request error.
     pub fn bad_request(message: impl Into<String>) -> Self {
         Self::new(400, "BAD_REQUEST", message)
@@ -133,6 +248,33 @@ impl Error {
         Self::new(409, "CONFLICT", message)
     }
 
+    /// Creates a 410 Gone error, for resources that were removed for good.
+    pub fn gone(message: impl Into<String>) -> Self {
+        Self::new(410, "GONE", message)
+    }
+
+    /// Creates a 413 Payload Too Large error.
+    pub fn payload_too_large(message: impl Into<String>) -> Self {
+        Self::new(413, "PAYLOAD_TOO_LARGE", message)
+    }
+
+    /// Creates a 413 Payload Too Large error for a body over `max_bytes`.
+    ///
+    /// The limit is reported in `details.max_bytes` so clients can size
+    /// their uploads.
+    pub fn body_too_large(max_bytes: usize) -> Self {
+        Self::payload_too_large(format!(
+            "Request body exceeds the limit of {} bytes",
+            max_bytes
+        ))
+        .with_details(serde_json::json!({ "max_bytes": max_bytes }))
+    }
+
+    /// Creates a 415 Unsupported Media Type error.
+    pub fn unsupported_media_type(message: impl Into<String>) -> Self {
+        Self::new(415, "UNSUPPORTED_MEDIA_TYPE", message)
+    }
+
     /// Creates a 422 Validation Error.
     pub fn validation(message: impl Into<String>) -> Self {
         Self::new(422, "VALIDATION_ERROR", message)
@@ -148,6 +290,28 @@ impl Error {
         Self::new(500, "INTERNAL_ERROR", message)
     }
 
+    /// Creates a 500 Internal Server Error that keeps `source` out of the
+    /// response.
+    ///
+    /// The client only sees `message`; `source` is logged server-side with
+    /// the trace ID, so database or I/O errors don't leak internals.
+    ///
+    /// ```ignore
+    /// let user = db.find(id).await.map_err(|e| Error::internal_with_source("failed to load user", e))?;
+    /// ```
+    pub fn internal_with_source(
+        message: impl Into<String>,
+        source: impl std::error::Error + Send + Sync + 'static,
+    ) -> Self {
+        Self::internal(message).with_source(source)
+    }
+
+    /// Creates a 503 Service Unavailable error, e.g. during maintenance or
+    /// when a dependency is down.
+    pub fn service_unavailable(message: impl Into<String>) -> Self {
+        Self::new(503, "SERVICE_UNAVAILABLE", message)
+    }
+
     /// Converts this error to an ErrorResponse with the given trace ID.
     pub fn to_response(&self, trace_id: String) -> ErrorResponse {
         ErrorResponse {
@@ -167,7 +331,15 @@ impl fmt::Display for Error {
     }
 }
 
-impl std::error::Error for Error {}
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.extra
+            .as_ref()?
+            .source
+            .as_deref()
+            .map(|source| source as &(dyn std::error::Error + 'static))
+    }
+}
 
 /// Trait for converting domain errors into API errors.
 ///
@@ -217,6 +389,189 @@ impl<T: IntoApiError> From<T> for Error {
     }
 }
 
+/// Converts failed validation into a 422 `VALIDATION_ERROR`.
+///
+/// The details map each invalid field to the constraints it failed, in a
+/// flat object keyed by field path. Nested structs use dots and list items
+/// use their index, e.g. `address.city` or `items[0].name`:
+///
+/// ```json
+/// {
+///   "email": [{ "code": "email", "message": "must be a valid email address" }],
+///   "items[0].name": [{ "code": "length", "message": "must be at least 1 characters long" }]
+/// }
+/// ```
+///
+/// The message is the one set on the constraint (`message = "..."`), or a
+/// generated description of it otherwise. Use [`ValidationMessages`] to
+/// replace the generated descriptions.
+impl IntoApiError for validator::ValidationErrors {
+    fn into_api_error(self) -> Error {
+        ValidationMessages::new().error(&self)
+    }
+}
+
+/// Messages shown to clients for failed validation constraints.
+///
+/// Register it with [`Rapina::with_validation_messages`](crate::app::Rapina::with_validation_messages)
+/// and the [`Validated`](crate::extract::Validated) extractor uses it for its
+/// 422 responses. For each failed constraint the message is, in order:
+///
+/// 1. the message set on the constraint itself (`#[validate(email(message = "..."))]`)
+/// 2. the override for the field and constraint code, set with [`field`](Self::field)
+/// 3. the default for the constraint code, set with [`code`](Self::code)
+/// 4. a generated description of the constraint
+///
+/// # Example
+///
+/// ```
+/// use rapina::error::ValidationMessages;
+///
+/// let messages = ValidationMessages::new()
+///     .code("email", "Please enter a valid email address")
+///     .field("password", "length", "Your password needs at least 8 characters");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ValidationMessages {
+    codes: HashMap<String, String>,
+    fields: HashMap<(String, String), String>,
+}
+
+impl ValidationMessages {
+    /// Creates an empty message map.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the message for every failure of the constraint `code` (e.g. `"email"`).
+    pub fn code(mut self, code: impl Into<String>, message: impl Into<String>) -> Self {
+        self.codes.insert(code.into(), message.into());
+        self
+    }
+
+    /// Sets the message for failures of the constraint `code` on one field.
+    ///
+    /// `field` is the path used as the key in the error details, such as
+    /// `email` or `address.city`.
+    pub fn field(
+        mut self,
+        field: impl Into<String>,
+        code: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        self.fields
+            .insert((field.into(), code.into()), message.into());
+        self
+    }
+
+    /// Builds the 422 error for failed validation using these messages.
+    pub fn error(&self, errors: &validator::ValidationErrors) -> Error {
+        let mut fields = serde_json::Map::new();
+        self.flatten(errors, "", &mut fields);
+        Error::validation("validation failed").with_details(serde_json::Value::Object(fields))
+    }
+
+    /// Builds the 422 error for constraints that failed on the named fields.
+    pub(crate) fn fields_error(&self, failures: &[(String, validator::ValidationError)]) -> Error {
+        let mut fields = serde_json::Map::new();
+        for (field, error) in failures {
+            let entry = serde_json::json!({
+                "code": error.code,
+                "message": self.message(field, error),
+            });
+            if let serde_json::Value::Array(entries) = fields
+                .entry(field.clone())
+                .or_insert_with(|| serde_json::Value::Array(Vec::new()))
+            {
+                entries.push(entry);
+            }
+        }
+        Error::validation("validation failed").with_details(serde_json::Value::Object(fields))
+    }
+
+    fn flatten(
+        &self,
+        errors: &validator::ValidationErrors,
+        prefix: &str,
+        fields: &mut serde_json::Map<String, serde_json::Value>,
+    ) {
+        use validator::ValidationErrorsKind;
+
+        for (field, kind) in errors.errors() {
+            let path = if prefix.is_empty() {
+                field.to_string()
+            } else {
+                format!("{}.{}", prefix, field)
+            };
+            match kind {
+                ValidationErrorsKind::Field(errors) => {
+                    let entries = errors
+                        .iter()
+                        .map(|e| {
+                            serde_json::json!({
+                                "code": e.code,
+                                "message": self.message(&path, e),
+                            })
+                        })
+                        .collect();
+                    fields.insert(path, serde_json::Value::Array(entries));
+                }
+                ValidationErrorsKind::Struct(errors) => {
+                    self.flatten(errors, &path, fields);
+                }
+                ValidationErrorsKind::List(items) => {
+                    for (index, errors) in items {
+                        self.flatten(errors, &format!("{}[{}]", path, index), fields);
+                    }
+                }
+            }
+        }
+    }
+
+    fn message(&self, field: &str, error: &validator::ValidationError) -> String {
+        if let Some(message) = &error.message {
+            return message.to_string();
+        }
+        let code = error.code.to_string();
+        self.fields
+            .get(&(field.to_string(), code))
+            .or_else(|| self.codes.get(error.code.as_ref()))
+            .cloned()
+            .unwrap_or_else(|| generated_validation_message(error))
+    }
+}
+
+/// Describes a constraint from its code and params.
+fn generated_validation_message(error: &validator::ValidationError) -> String {
+    let param = |name: &str| error.params.get(name).map(|v| v.to_string());
+    match error.code.as_ref() {
+        "email" => "must be a valid email address".to_string(),
+        "url" => "must be a valid URL".to_string(),
+        "required" => "is required".to_string(),
+        "regex" => "has an invalid format".to_string(),
+        "must_match" => match param("other") {
+            Some(other) => format!("must match {}", other.trim_matches('"')),
+            None => "must match the other field".to_string(),
+        },
+        "length" => match (param("min"), param("max"), param("equal")) {
+            (_, _, Some(equal)) => format!("must be exactly {} characters long", equal),
+            (Some(min), Some(max), _) => {
+                format!("must be between {} and {} characters long", min, max)
+            }
+            (Some(min), None, _) => format!("must be at least {} characters long", min),
+            (None, Some(max), _) => format!("must be at most {} characters long", max),
+            _ => "has an invalid length".to_string(),
+        },
+        "range" => match (param("min"), param("max")) {
+            (Some(min), Some(max)) => format!("must be between {} and {}", min, max),
+            (Some(min), None) => format!("must be at least {}", min),
+            (None, Some(max)) => format!("must be at most {}", max),
+            _ => "is out of range".to_string(),
+        },
+        code => format!("failed the '{}' check", code),
+    }
+}
+
 /// Metadata about an error variant for OpenAPI documentation.
 #[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct ErrorVariant {
@@ -281,14 +636,43 @@ impl IntoResponse for Error {
             .trace_id
             .clone()
             .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+        if let Some(source) = self.extra.as_ref().and_then(|extra| extra.source.as_ref()) {
+            tracing::error!(
+                target: "rapina::error",
+                trace_id = %trace_id,
+                status = self.status,
+                code = %self.code,
+                source = %source,
+                "{}",
+                self.message
+            );
+        }
         let response = self.to_response(trace_id);
         let body = serde_json::to_vec(&response).unwrap_or_default();
+        let status = http::StatusCode::from_u16(self.status)
+            .unwrap_or(http::StatusCode::INTERNAL_SERVER_ERROR);
 
-        http::Response::builder()
-            .status(self.status)
-            .header("content-type", "application/json")
-            .body(Full::new(Bytes::from(body)))
-            .unwrap()
+        let mut response = full_response(status, "application/json", body);
+        let headers = self.extra.iter().flat_map(|extra| &extra.headers);
+        for (name, value) in headers {
+            let (Ok(name), Ok(value)) = (
+                http::HeaderName::from_bytes(name.as_bytes()),
+                http::HeaderValue::from_str(value),
+            ) else {
+                return Error::internal(format!("Invalid response header '{}'", name))
+                    .into_response();
+            };
+            response.headers_mut().append(name, value);
+        }
+        response
+    }
+}
+
+/// Domain errors are responses too, so handlers can return
+/// `Result<T, MyError>` and use `?` without converting to [`Error`] first.
+impl<E: IntoApiError> IntoResponse for E {
+    fn into_response(self) -> http::Response<BoxBody> {
+        self.into_api_error().into_response()
     }
 }
 
@@ -366,6 +750,16 @@ mod tests {
         assert_eq!(api_err.code, "NOT_FOUND");
     }
 
+    #[test]
+    fn test_result_with_domain_error_into_response() {
+        let result: std::result::Result<String, TestUserError> = Err(TestUserError::NotFound(1));
+        let response = result.into_response();
+        assert_eq!(response.status(), 404);
+
+        let result: std::result::Result<String, TestUserError> = Ok("found".to_string());
+        assert_eq!(result.into_response().status(), 200);
+    }
+
     #[test]
     fn test_documented_error_variants() {
         let variants = TestUserError::error_variants();
@@ -423,6 +817,95 @@ mod tests {
         assert_eq!(err.code, "CONFLICT");
     }
 
+    #[test]
+    fn test_error_body_too_large() {
+        let err = Error::body_too_large(1024);
+        assert_eq!(err.status, 413);
+        assert_eq!(err.code, "PAYLOAD_TOO_LARGE");
+        assert_eq!(err.message, "Request body exceeds the limit of 1024 bytes");
+        assert_eq!(err.details, Some(serde_json::json!({"max_bytes": 1024})));
+    }
+
+    #[test]
+    fn test_error_payload_too_large() {
+        let err = Error::payload_too_large("too big");
+        assert_eq!(err.status, 413);
+        assert_eq!(err.code, "PAYLOAD_TOO_LARGE");
+    }
+
+    #[test]
+    fn test_error_unsupported_media_type() {
+        let err = Error::unsupported_media_type("expected JSON");
+        assert_eq!(err.status, 415);
+        assert_eq!(err.code, "UNSUPPORTED_MEDIA_TYPE");
+    }
+
+    #[test]
+    fn test_validation_errors_flattened_per_field() {
+        let mut errors = validator::ValidationErrors::new();
+        errors.add("email", validator::ValidationError::new("email"));
+        errors.add(
+            "name",
+            validator::ValidationError::new("taken").with_message("name is taken".into()),
+        );
+
+        let err = Error::from(errors);
+        assert_eq!(err.status, 422);
+        assert_eq!(err.code, "VALIDATION_ERROR");
+        assert_eq!(
+            err.details.unwrap(),
+            serde_json::json!({
+                "email": [{ "code": "email", "message": "must be a valid email address" }],
+                "name": [{ "code": "taken", "message": "name is taken" }],
+            })
+        );
+    }
+
+    #[test]
+    fn test_validation_message_from_params() {
+        let mut error = validator::ValidationError::new("length");
+        error.add_param("min".into(), &2);
+        error.add_param("max".into(), &5);
+        assert_eq!(
+            generated_validation_message(&error),
+            "must be between 2 and 5 characters long"
+        );
+
+        let error = validator::ValidationError::new("custom_rule");
+        assert_eq!(
+            generated_validation_message(&error),
+            "failed the 'custom_rule' check"
+        );
+    }
+
+    #[test]
+    fn test_validation_messages_precedence() {
+        let messages = ValidationMessages::new()
+            .code("email", "Please enter a valid email address")
+            .field("backup_email", "email", "Check your backup email");
+
+        let mut errors = validator::ValidationErrors::new();
+        errors.add("email", validator::ValidationError::new("email"));
+        errors.add("backup_email", validator::ValidationError::new("email"));
+        errors.add(
+            "work_email",
+            validator::ValidationError::new("email").with_message("Use your work address".into()),
+        );
+        errors.add("name", validator::ValidationError::new("required"));
+
+        let details = messages.error(&errors).details.unwrap();
+        assert_eq!(
+            details["email"][0]["message"],
+            "Please enter a valid email address"
+        );
+        assert_eq!(
+            details["backup_email"][0]["message"],
+            "Check your backup email"
+        );
+        assert_eq!(details["work_email"][0]["message"], "Use your work address");
+        assert_eq!(details["name"][0]["message"], "is required");
+    }
+
     #[test]
     fn test_error_validation() {
         let err = Error::validation("invalid data");
@@ -444,6 +927,65 @@ mod tests {
         assert_eq!(err.code, "INTERNAL_ERROR");
     }
 
+    #[test]
+    fn test_error_gone_and_service_unavailable() {
+        let err = Error::gone("removed");
+        assert_eq!((err.status, err.code.as_str()), (410, "GONE"));
+        let err = Error::service_unavailable("maintenance");
+        assert_eq!(
+            (err.status, err.code.as_str()),
+            (503, "SERVICE_UNAVAILABLE")
+        );
+    }
+
+    #[test]
+    fn test_error_from_status_derives_code() {
+        let err = Error::from_status(451, "blocked");
+        assert_eq!(err.status, 451);
+        assert_eq!(err.code, "UNAVAILABLE_FOR_LEGAL_REASONS");
+        assert_eq!(Error::from_status(418, "tea").code, "IM_A_TEAPOT");
+        assert_eq!(Error::from_status(499, "closed").code, "HTTP_499");
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "invalid error status 302")]
+    fn test_error_from_status_rejects_non_error_status() {
+        let _ = Error::from_status(302, "moved");
+    }
+
+    #[tokio::test]
+    async fn test_error_retry_after_header() {
+        let response = Error::rate_limited("slow down")
+            .with_retry_after(std::time::Duration::from_millis(1500))
+            .into_response();
+        assert_eq!(response.status(), 429);
+        assert_eq!(response.headers()["retry-after"], "2");
+
+        let response = Error::internal("boom").into_response();
+        assert!(response.headers().get("retry-after").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_error_with_header() {
+        let response = Error::unauthorized("sign in")
+            .with_header("www-authenticate", "Basic realm=\"admin\"")
+            .with_header("www-authenticate", "Bearer")
+            .into_response();
+        assert_eq!(response.status(), 401);
+        let challenges: Vec<_> = response
+            .headers()
+            .get_all("www-authenticate")
+            .iter()
+            .collect();
+        assert_eq!(challenges, ["Basic realm=\"admin\"", "Bearer"]);
+
+        let response = Error::bad_request("oops")
+            .with_header("bad header", "x")
+            .into_response();
+        assert_eq!(response.status(), 500);
+    }
+
     #[test]
     fn test_error_with_details() {
         let details = serde_json::json!({"field": "email", "error": "invalid format"});
@@ -505,6 +1047,28 @@ mod tests {
         assert_eq!(trace_id.len(), 36); // UUID format
     }
 
+    #[tokio::test]
+    async fn test_internal_with_source_keeps_source_out_of_body() {
+        let cause = std::io::Error::other("connection refused on 10.0.0.5:5432");
+        let err = Error::internal_with_source("failed to load user", cause);
+        assert_eq!(err.status, 500);
+        assert_eq!(
+            std::error::Error::source(&err).unwrap().to_string(),
+            "connection refused on 10.0.0.5:5432"
+        );
+
+        let body = err
+            .into_response()
+            .into_body()
+            .collect()
+            .await
+            .unwrap()
+            .to_bytes();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        assert!(body.contains("failed to load user"), "{}", body);
+        assert!(!body.contains("10.0.0.5"), "{}", body);
+    }
+
     #[test]
     fn test_error_response_skips_none_details() {
         let err = Error::bad_request("test");
@@ -2,6 +2,19 @@
 //!
 //! Extractors are types that implement [`FromRequest`] or [`FromRequestParts`]
 //! and can be used as handler parameters to automatically parse request data.
+//!
+//! # Rejections
+//!
+//! The built-in extractors reject requests with consistent status codes:
+//!
+//! - `400 Bad Request` when the data can't be read or deserialized: malformed
+//!   JSON, a missing field, a value of the wrong type, or a missing header
+//! - `422 Unprocessable Entity` when the data deserializes but fails the
+//!   rules of a [`Validated`] or [`SchemaValidated`] extractor
+//! - `413 Payload Too Large` and `415 Unsupported Media Type` when the body
+//!   is over its limit or has the wrong `Content-Type`
+//! - `500 Internal Server Error` when the app is misconfigured, e.g. a
+//!   [`State`] that was never registered
 
 use bytes::Bytes;
 use http::Request;
@@ -9,14 +22,30 @@ use http_body_util::BodyExt;
 use hyper::body::Incoming;
 use serde::de::DeserializeOwned;
 use std::collections::HashMap;
-use std::str::FromStr;
 use std::sync::Arc;
 use validator::Validate;
 
 use crate::context::RequestContext;
-use crate::error::Error;
+use crate::error::{Error, IntoApiError, ValidationMessages};
+use crate::headers::{ContentType, Header};
+use crate::middleware::take_request_body;
 use crate::response::{BoxBody, IntoResponse};
+use crate::router::MatchedRoute;
 use crate::state::AppState;
+use field_error::Source;
+
+mod field_error;
+#[cfg(feature = "msgpack")]
+mod msgpack;
+mod path;
+mod pattern;
+mod schema;
+mod urlencoded;
+
+#[cfg(feature = "msgpack")]
+pub use msgpack::{Body, MSGPACK_CONTENT_TYPE, MsgPack};
+pub(crate) use pattern::{Constraint, parse_pattern};
+pub use rapina_macros::FromRequest;
 
 const JSON_CONTENT_TYPE: &str = "application/json";
 const FORM_CONTENT_TYPE: &str = "application/x-www-form-urlencoded";
@@ -46,10 +75,12 @@ const FORM_CONTENT_TYPE: &str = "application/x-www-form-urlencoded";
 #[derive(Debug)]
 pub struct Json<T>(pub T);
 
-/// Extracts a single path parameter from the URL.
+/// Extracts path parameters from the URL.
 ///
-/// Parses a path segment into the specified type `T`.
-/// Returns 400 Bad Request if parsing fails.
+/// With a struct, each `:name` segment of the route fills the field with the
+/// same name, so routes with several parameters are read reliably. Any
+/// other type reads the route's only parameter, and fails if it has more
+/// than one. Returns 400 Bad Request if parsing fails.
 ///
 /// # Examples
 ///
@@ -60,14 +91,29 @@ pub struct Json<T>(pub T);
 /// async fn get_user(id: Path<u64>) -> String {
 ///     format!("User ID: {}", id.into_inner())
 /// }
+///
+/// #[derive(Deserialize)]
+/// struct PostPath {
+///     user_id: u64,
+///     post_id: u64,
+/// }
+///
+/// #[get("/users/:user_id/posts/:post_id")]
+/// async fn get_post(path: Path<PostPath>) -> String {
+///     let path = path.into_inner();
+///     format!("Post {} by user {}", path.post_id, path.user_id)
+/// }
 /// ```
 #[derive(Debug)]
 pub struct Path<T>(pub T);
 
 /// Extracts and deserializes query string parameters.
 ///
-/// Parses the URL query string into a typed struct using `serde_urlencoded`.
-/// Returns 400 Bad Request if parsing fails.
+/// Parses the URL query string into a typed struct. A key repeated in the
+/// query (`?tag=a&tag=b`) fills a `Vec` field with every value, in order; a
+/// single occurrence gives a one-element `Vec`. Other fields take exactly one
+/// value, so repeating their key is an error. Bracketed keys nest the same
+/// way as in [`Form`]. Returns 400 Bad Request if parsing fails.
 ///
 /// # Examples
 ///
@@ -85,13 +131,23 @@ pub struct Path<T>(pub T);
 ///     let page = query.0.page.unwrap_or(1);
 ///     format!("Page: {}", page)
 /// }
+///
+/// #[derive(Deserialize)]
+/// struct Filter {
+///     // ?tag=rust&tag=web
+///     #[serde(default)]
+///     tag: Vec<String>,
+/// }
 /// ```
 #[derive(Debug)]
 pub struct Query<T>(pub T);
 
 /// Extracts and deserializes URL-encoded form data.
 ///
-/// Parses `application/x-www-form-urlencoded` request bodies.
+/// Parses `application/x-www-form-urlencoded` request bodies. Bracketed
+/// keys fill nested structs (`address[city]=London`) and sequences
+/// (`tags[]=a&tags[]=b`, or `items[0][name]=pen` for a `Vec` of structs), and
+/// a repeated key fills a `Vec` like it does for [`Query`].
 /// Returns 400 Bad Request if content-type is wrong or parsing fails.
 ///
 /// # Examples
@@ -132,6 +188,29 @@ pub struct Form<T>(pub T);
 #[derive(Debug)]
 pub struct Headers(pub http::HeaderMap);
 
+/// Extracts a single header parsed into a typed value.
+///
+/// Works with any type implementing [`Header`](crate::headers::Header),
+/// such as [`Authorization<Bearer>`](crate::headers::Authorization),
+/// [`ContentType`](crate::headers::ContentType),
+/// [`Accept`](crate::headers::Accept) and
+/// [`UserAgent`](crate::headers::UserAgent).
+/// Returns 400 Bad Request if the header is missing or malformed.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rapina::prelude::*;
+/// use rapina::headers::{Authorization, Bearer};
+///
+/// #[get("/token")]
+/// async fn token(auth: TypedHeader<Authorization<Bearer>>) -> String {
+///     auth.into_inner().0.token().to_string()
+/// }
+/// ```
+#[derive(Debug)]
+pub struct TypedHeader<H>(pub H);
+
 /// Extracts and deserializes cookies from the request.
 ///
 /// Parses the `Cookie` header into a typed struct. Each field in the struct
@@ -183,7 +262,8 @@ pub struct State<T>(pub T);
 
 /// Provides access to the request context.
 ///
-/// Contains the `trace_id` and request start time for logging and tracing.
+/// Contains the `trace_id` and request start time for logging and tracing,
+/// along with any values middleware stored for the request.
 ///
 /// # Examples
 ///
@@ -198,10 +278,112 @@ pub struct State<T>(pub T);
 #[derive(Debug)]
 pub struct Context(pub RequestContext);
 
+/// Extracts the route pattern that matched the request.
+///
+/// Returns the pattern as registered (e.g., `/users/:id`) rather than the
+/// concrete path, which keeps log fields and metric labels bounded.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rapina::prelude::*;
+///
+/// #[get("/users/:id")]
+/// async fn get_user(route: MatchedPath) -> String {
+///     format!("Matched {}", route.as_str())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct MatchedPath(pub String);
+
+/// Extracts the query string exactly as the client sent it.
+///
+/// Holds everything after the `?`, still percent-encoded and including
+/// parameters a [`Query`] struct doesn't declare, or `None` when the URI
+/// has no query. Useful alongside [`Query`] to build links that keep the
+/// client's other parameters. Never fails.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rapina::prelude::*;
+///
+/// #[get("/users")]
+/// async fn list_users(page: Query<Pagination>, raw: RawQuery) -> String {
+///     format!("page {} of ?{}", page.0.page, raw.0.unwrap_or_default())
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawQuery(pub Option<String>);
+
+/// Extracts the host name the request was sent to.
+///
+/// Read from the `Host` header, or from the URI authority for HTTP/2
+/// requests, with any port removed. Fails with 400 Bad Request when the
+/// request names no host.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rapina::prelude::*;
+///
+/// #[get("/")]
+/// async fn home(host: Host) -> String {
+///     format!("Welcome to {}", host.as_str())
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Host(pub String);
+
+/// Extracts the address of the connected client.
+///
+/// This is the peer of the TCP connection, so behind a reverse proxy it is
+/// the proxy's address; read `X-Forwarded-For` for the original client.
+/// Servers listening on a Unix socket have no client address, and the
+/// extractor fails with a 500 error there.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rapina::prelude::*;
+///
+/// #[get("/ip")]
+/// async fn ip(client: ConnectInfo) -> String {
+///     client.0.ip().to_string()
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConnectInfo(pub std::net::SocketAddr);
+
+/// Extracts the deadline set by [`TimeoutMiddleware`](crate::middleware::TimeoutMiddleware).
+///
+/// Handlers can pass [`remaining`](Self::remaining) to downstream timeouts so
+/// database queries and outgoing calls stop when the request would time out
+/// anyway. Without a timeout middleware the deadline is unbounded.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rapina::prelude::*;
+///
+/// #[get("/report")]
+/// async fn report(deadline: Deadline) -> Result<String> {
+///     let budget = deadline.remaining().unwrap_or(Duration::from_secs(30));
+///     let rows = tokio::time::timeout(budget, fetch_rows())
+///         .await
+///         .map_err(|_| Error::internal("report query timed out"))?;
+///     Ok(format!("{} rows", rows.len()))
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Deadline(pub Option<std::time::Instant>);
+
 /// Wraps an extractor and validates the extracted value.
 ///
 /// Uses the `validator` crate to run validation rules on the inner value.
-/// Returns 422 Validation Error if validation fails.
+/// Works with [`Json`], [`Form`], [`Query`] and [`Path`]. A request the inner
+/// extractor rejects, such as malformed JSON, still gets its 400; a value
+/// that deserializes but fails validation gets 422 Validation Error.
 ///
 /// # Examples
 ///
@@ -226,6 +408,99 @@ pub struct Context(pub RequestContext);
 #[derive(Debug)]
 pub struct Validated<T>(pub T);
 
+/// Wraps [`Query`] or [`Path`] and checks the raw values against the
+/// constraints of the type's JSON Schema.
+///
+/// An alternative to [`Validated`] for types that already derive
+/// `JsonSchema` for the OpenAPI spec: `minimum`, `maximum`, their exclusive
+/// forms, `minLength`, `maxLength` and `pattern` are enforced without
+/// deriving `Validate`. Values that don't deserialize still get a 400; values
+/// that break a constraint get 422 Validation Error, with the same details
+/// and [`ValidationMessages`] as [`Validated`].
+///
+/// Top-level fields and the items of list fields are checked; nested keys
+/// such as `address[city]` are not.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rapina::prelude::*;
+///
+/// #[derive(Deserialize, JsonSchema)]
+/// struct Pagination {
+///     #[schemars(range(min = 1, max = 100))]
+///     per_page: Option<u32>,
+/// }
+///
+/// #[get("/users")]
+/// async fn list_users(page: SchemaValidated<Query<Pagination>>) -> String {
+///     let page = page.into_inner().into_inner();
+///     format!("{} per page", page.per_page.unwrap_or(20))
+/// }
+/// ```
+#[derive(Debug)]
+pub struct SchemaValidated<T>(pub T);
+
+/// Wraps an extractor and converts its rejection into the error type `R`.
+///
+/// When the inner extractor fails, its [`Error`] is converted with
+/// `R::from` and turned back into a response with
+/// [`IntoApiError`](crate::error::IntoApiError), so the client gets your
+/// domain error format instead of the framework's. Works with every
+/// parts-only extractor and with [`Json`], [`Form`] and [`Validated`] bodies.
+///
+/// To map rejections for every handler, use
+/// [`Rapina::map_rejections`](crate::app::Rapina::map_rejections).
+///
+/// # Examples
+///
+/// ```ignore
+/// use rapina::prelude::*;
+///
+/// struct ApiError(Error);
+///
+/// impl From<Error> for ApiError {
+///     fn from(err: Error) -> Self {
+///         ApiError(err)
+///     }
+/// }
+///
+/// impl IntoApiError for ApiError {
+///     fn into_api_error(self) -> Error {
+///         Error::bad_request("the request body is not valid")
+///             .with_details(serde_json::json!({ "reason": self.0.message }))
+///     }
+/// }
+///
+/// #[post("/users")]
+/// async fn create_user(body: WithRejection<Json<CreateUser>, ApiError>) -> String {
+///     body.into_inner().into_inner().name
+/// }
+/// ```
+#[derive(Debug)]
+pub struct WithRejection<E, R>(pub E, std::marker::PhantomData<fn() -> R>);
+
+/// Limits the request body size for the wrapped body extractor.
+///
+/// Rejects bodies larger than `N` bytes with 413 Payload Too Large, whether
+/// the size is announced by `Content-Length` or only discovered while
+/// reading. Works with [`Json`] and [`Form`], and can be stricter than a
+/// global [`BodyLimitMiddleware`](crate::middleware::BodyLimitMiddleware).
+///
+/// # Examples
+///
+/// ```ignore
+/// use rapina::prelude::*;
+///
+/// #[post("/login")]
+/// async fn login(body: ContentLengthLimit<Json<LoginRequest>, 1024>) -> String {
+///     let data = body.into_inner().into_inner();
+///     format!("Welcome, {}", data.username)
+/// }
+/// ```
+#[derive(Debug)]
+pub struct ContentLengthLimit<T, const N: usize>(pub T);
+
 /// Type alias for path parameters extracted from the URL.
 pub type PathParams = HashMap<String, String>;
 
@@ -254,6 +529,15 @@ pub trait FromRequestParts: Sized + Send {
         params: &PathParams,
         state: &Arc<AppState>,
     ) -> impl std::future::Future<Output = Result<Self, Error>> + Send;
+
+    /// Returns whether the request lacks the input this extractor reads,
+    /// such as a header that wasn't sent.
+    ///
+    /// `Option<Self>` yields `None` when this returns `true`, and passes any
+    /// other extraction error on. Defaults to `false`.
+    fn is_missing(_parts: &http::request::Parts) -> bool {
+        false
+    }
 }
 
 impl<T> Json<T> {
@@ -263,6 +547,13 @@ impl<T> Json<T> {
     }
 }
 
+impl<T, const N: usize> ContentLengthLimit<T, N> {
+    /// Consumes the extractor and returns the inner extractor.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
 impl<T> Path<T> {
     /// Consumes the extractor and returns the inner value.
     pub fn into_inner(self) -> T {
@@ -296,6 +587,13 @@ impl Headers {
     }
 }
 
+impl<H> TypedHeader<H> {
+    /// Consumes the extractor and returns the inner header.
+    pub fn into_inner(self) -> H {
+        self.0
+    }
+}
+
 impl<T> Cookie<T> {
     /// Consumes the extractor and returns the inner value.
     pub fn into_inner(self) -> T {
@@ -325,6 +623,69 @@ impl Context {
     pub fn elapsed(&self) -> std::time::Duration {
         self.0.elapsed()
     }
+
+    /// Returns a copy of a value stored for this request, e.g. by middleware.
+    ///
+    /// See [`RequestContext::insert`].
+    pub fn get<T: Clone + Send + Sync + 'static>(&self) -> Option<T> {
+        self.0.get()
+    }
+
+    /// Stores a value for the rest of this request.
+    pub fn insert<T: Send + Sync + 'static>(&self, value: T) {
+        self.0.insert(value)
+    }
+}
+
+impl Deadline {
+    /// Returns the instant the request times out, or `None` if unbounded.
+    pub fn instant(&self) -> Option<std::time::Instant> {
+        self.0
+    }
+
+    /// Returns the time left before the request times out, or `None` if unbounded.
+    ///
+    /// Returns [`Duration::ZERO`](std::time::Duration::ZERO) once the deadline has passed.
+    pub fn remaining(&self) -> Option<std::time::Duration> {
+        self.0
+            .map(|deadline| deadline.saturating_duration_since(std::time::Instant::now()))
+    }
+
+    /// Returns `true` if the deadline has passed.
+    pub fn is_expired(&self) -> bool {
+        self.remaining().is_some_and(|d| d.is_zero())
+    }
+}
+
+impl MatchedPath {
+    /// Consumes the extractor and returns the route pattern.
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+
+    /// Returns the route pattern.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl RawQuery {
+    /// Consumes the extractor and returns the query string.
+    pub fn into_inner(self) -> Option<String> {
+        self.0
+    }
+}
+
+impl Host {
+    /// Consumes the extractor and returns the host name.
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+
+    /// Returns the host name.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
 }
 
 impl<T> Validated<T> {
@@ -334,34 +695,123 @@ impl<T> Validated<T> {
     }
 }
 
+impl<T> SchemaValidated<T> {
+    /// Consumes the extractor and returns the validated inner value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<E, R> WithRejection<E, R> {
+    /// Consumes the extractor and returns the inner extractor.
+    pub fn into_inner(self) -> E {
+        self.0
+    }
+}
+
+impl<E, R> std::ops::Deref for WithRejection<E, R> {
+    type Target = E;
+
+    fn deref(&self) -> &E {
+        &self.0
+    }
+}
+
+/// App-wide conversion of extractor rejections, set with
+/// [`Rapina::map_rejections`](crate::app::Rapina::map_rejections).
+#[derive(Clone)]
+pub(crate) struct RejectionMapper(pub(crate) Arc<dyn Fn(Error) -> Error + Send + Sync>);
+
+/// Turns an extractor rejection into the response sent to the client,
+/// applying the app's rejection mapper if one is set.
+#[doc(hidden)]
+pub fn rejection_response(error: Error, state: &AppState) -> http::Response<BoxBody> {
+    match state.get::<RejectionMapper>() {
+        Some(mapper) => (mapper.0)(error).into_response(),
+        None => error.into_response(),
+    }
+}
+
+/// Parses the header `name` for a `#[from(header)]` field of
+/// `#[derive(FromRequest)]`, returning `None` when it is missing.
+#[doc(hidden)]
+pub fn header_field<T>(headers: &http::HeaderMap, name: &str) -> Result<Option<T>, Error>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    let Some(value) = headers.get(name) else {
+        return Ok(None);
+    };
+    value
+        .to_str()
+        .map_err(|e| e.to_string())
+        .and_then(|value| value.parse::<T>().map_err(|e| e.to_string()))
+        .map(Some)
+        .map_err(|e| Source::Header.invalid(name, e))
+}
+
+/// Like [`header_field`], but rejects the request when the header is missing.
+#[doc(hidden)]
+pub fn required_header_field<T>(headers: &http::HeaderMap, name: &str) -> Result<T, Error>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    header_field(headers, name)?.ok_or_else(|| Source::Header.missing(name))
+}
+
+/// Rejects a request whose `Content-Type` is set but isn't JSON.
+///
+/// A missing or empty `Content-Type` is accepted, so clients that omit the
+/// header can still send JSON bodies.
+fn check_json_content_type(headers: &http::HeaderMap) -> Result<(), Error> {
+    let Some(value) = headers.get(http::header::CONTENT_TYPE) else {
+        return Ok(());
+    };
+    if value.as_bytes().iter().all(u8::is_ascii_whitespace) {
+        return Ok(());
+    }
+
+    match ContentType::decode(value) {
+        Ok(content_type) if content_type.is_json() => Ok(()),
+        _ => Err(Error::unsupported_media_type(format!(
+            "Expected Content-Type '{}', got '{}'",
+            JSON_CONTENT_TYPE,
+            value.to_str().unwrap_or("<invalid>")
+        ))),
+    }
+}
+
 impl<T: DeserializeOwned + Send> FromRequest for Json<T> {
     async fn from_request(
         req: Request<Incoming>,
         _params: &PathParams,
         _state: &Arc<AppState>,
     ) -> Result<Self, Error> {
-        let body = req.into_body();
+        check_json_content_type(req.headers())?;
+
+        let body = take_request_body(req);
         let bytes = body
             .collect()
             .await
             .map_err(|_| Error::bad_request("Failed to read request body"))?
             .to_bytes();
 
-        let value: T = serde_json::from_slice(&bytes)
-            .map_err(|e| Error::bad_request(format!("Invalid JSON in request body: {}", e)))?;
-
-        Ok(Json(value))
+        parse_json(&bytes)
     }
 }
 
+fn parse_json<T: DeserializeOwned>(bytes: &[u8]) -> Result<Json<T>, Error> {
+    let value: T = serde_json::from_slice(bytes)
+        .map_err(|e| Error::bad_request(format!("Invalid JSON in request body: {}", e)))?;
+    Ok(Json(value))
+}
+
 impl<T: serde::Serialize> IntoResponse for (http::StatusCode, Json<T>) {
     fn into_response(self) -> http::Response<BoxBody> {
-        let body = serde_json::to_vec(&(self.1).0).unwrap_or_default();
-        http::Response::builder()
-            .status(self.0)
-            .header("content-type", JSON_CONTENT_TYPE)
-            .body(http_body_util::Full::new(Bytes::from(body)))
-            .unwrap()
+        let body = crate::response::JsonConfig::to_vec(&(self.1).0).unwrap_or_default();
+        crate::response::full_response(self.0, JSON_CONTENT_TYPE, body)
     }
 }
 
@@ -371,39 +821,105 @@ impl<T: serde::Serialize> IntoResponse for Json<T> {
     }
 }
 
+/// Rejects a request whose `Content-Type` isn't URL-encoded form data.
+fn check_form_content_type(headers: &http::HeaderMap) -> Result<(), Error> {
+    let content_type = headers
+        .get(http::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok());
+
+    if !content_type
+        .map(|ct| ct.starts_with(FORM_CONTENT_TYPE))
+        .unwrap_or(false)
+    {
+        return Err(Error::bad_request(format!(
+            "Expected Content-Type '{}', got '{}'",
+            FORM_CONTENT_TYPE,
+            content_type.unwrap_or("none")
+        )));
+    }
+    Ok(())
+}
+
+fn parse_form<T: DeserializeOwned>(bytes: &[u8]) -> Result<Form<T>, Error> {
+    let value: T = urlencoded::from_bytes(bytes).map_err(|e| e.into_rejection(Source::Form))?;
+    Ok(Form(value))
+}
+
 impl<T: DeserializeOwned + Send> FromRequest for Form<T> {
     async fn from_request(
         req: Request<Incoming>,
         _params: &PathParams,
         _state: &Arc<AppState>,
     ) -> Result<Self, Error> {
-        let content_type = req
-            .headers()
-            .get(http::header::CONTENT_TYPE)
-            .and_then(|v| v.to_str().ok());
-
-        if !content_type
-            .map(|ct| ct.starts_with(FORM_CONTENT_TYPE))
-            .unwrap_or(false)
-        {
-            return Err(Error::bad_request(format!(
-                "Expected Content-Type '{}', got '{}'",
-                FORM_CONTENT_TYPE,
-                content_type.unwrap_or("none")
-            )));
-        }
+        check_form_content_type(req.headers())?;
 
-        let body = req.into_body();
+        let body = take_request_body(req);
         let bytes = body
             .collect()
             .await
             .map_err(|_| Error::bad_request("Failed to read form data from request body"))?
             .to_bytes();
 
-        let value: T = serde_urlencoded::from_bytes(&bytes)
-            .map_err(|e| Error::bad_request(format!("Invalid URL-encoded form data: {}", e)))?;
+        parse_form(&bytes)
+    }
+}
+
+/// Reads the request body, failing with 413 once it exceeds `limit` bytes.
+async fn read_body_limited(req: Request<Incoming>, limit: usize) -> Result<Bytes, Error> {
+    let too_large = || Error::body_too_large(limit);
+
+    let content_length = req
+        .headers()
+        .get(http::header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<usize>().ok());
+    if content_length.is_some_and(|len| len > limit) {
+        return Err(too_large());
+    }
+
+    // Bodies without Content-Length are capped while reading
+    http_body_util::Limited::new(take_request_body(req), limit)
+        .collect()
+        .await
+        .map(|collected| collected.to_bytes())
+        .map_err(|e| {
+            if e.is::<http_body_util::LengthLimitError>() {
+                too_large()
+            } else {
+                Error::bad_request("Failed to read request body")
+            }
+        })
+}
+
+impl<T: DeserializeOwned + Send, const N: usize> FromRequest for ContentLengthLimit<Json<T>, N> {
+    async fn from_request(
+        req: Request<Incoming>,
+        _params: &PathParams,
+        _state: &Arc<AppState>,
+    ) -> Result<Self, Error> {
+        check_json_content_type(req.headers())?;
+        let bytes = read_body_limited(req, N).await?;
+        parse_json(&bytes).map(ContentLengthLimit)
+    }
+}
+
+impl<T: DeserializeOwned + Send, const N: usize> FromRequest for ContentLengthLimit<Form<T>, N> {
+    async fn from_request(
+        req: Request<Incoming>,
+        _params: &PathParams,
+        _state: &Arc<AppState>,
+    ) -> Result<Self, Error> {
+        check_form_content_type(req.headers())?;
+        let bytes = read_body_limited(req, N).await?;
+        parse_form(&bytes).map(ContentLengthLimit)
+    }
+}
 
-        Ok(Form(value))
+/// Builds the 422 error using the app's [`ValidationMessages`], if registered.
+async fn validation_error(errors: &validator::ValidationErrors, state: &AppState) -> Error {
+    match state.get_or_init::<ValidationMessages>().await {
+        Some(messages) => messages.error(errors),
+        None => ValidationMessages::new().error(errors),
     }
 }
 
@@ -414,10 +930,9 @@ impl<T: DeserializeOwned + Validate + Send> FromRequest for Validated<Json<T>> {
         state: &Arc<AppState>,
     ) -> Result<Self, Error> {
         let json = Json::<T>::from_request(req, params, state).await?;
-        json.0.validate().map_err(|e| {
-            Error::validation("validation failed")
-                .with_details(serde_json::to_value(e).unwrap_or_default())
-        })?;
+        if let Err(e) = json.0.validate() {
+            return Err(validation_error(&e, state).await);
+        }
         Ok(Validated(json))
     }
 }
@@ -429,41 +944,140 @@ impl<T: DeserializeOwned + Validate + Send> FromRequest for Validated<Form<T>> {
         state: &Arc<AppState>,
     ) -> Result<Self, Error> {
         let form = Form::<T>::from_request(req, params, state).await?;
-        form.0.validate().map_err(|e| {
-            Error::validation("validation failed")
-                .with_details(serde_json::to_value(e).unwrap_or_default())
-        })?;
+        if let Err(e) = form.0.validate() {
+            return Err(validation_error(&e, state).await);
+        }
         Ok(Validated(form))
     }
 }
 
-impl<T: Clone + Send + Sync + 'static> FromRequestParts for State<T> {
+impl<T: DeserializeOwned + Validate + Send> FromRequestParts for Validated<Query<T>> {
     async fn from_request_parts(
-        _parts: &http::request::Parts,
-        _params: &PathParams,
+        parts: &http::request::Parts,
+        params: &PathParams,
         state: &Arc<AppState>,
     ) -> Result<Self, Error> {
-        let value = state.get::<T>().ok_or_else(|| {
-            Error::internal(format!(
-                "State not registered for type '{}'. Did you forget to call .state()?",
-                std::any::type_name::<T>()
-            ))
-        })?;
-        Ok(State(value.clone()))
+        let query = Query::<T>::from_request_parts(parts, params, state).await?;
+        if let Err(e) = query.0.validate() {
+            return Err(validation_error(&e, state).await);
+        }
+        Ok(Validated(query))
+    }
+
+    fn is_missing(parts: &http::request::Parts) -> bool {
+        Query::<T>::is_missing(parts)
     }
 }
 
-impl FromRequestParts for Context {
+impl<T: DeserializeOwned + Validate + Send> FromRequestParts for Validated<Path<T>> {
     async fn from_request_parts(
         parts: &http::request::Parts,
-        _params: &PathParams,
-        _state: &Arc<AppState>,
+        params: &PathParams,
+        state: &Arc<AppState>,
     ) -> Result<Self, Error> {
-        parts
-            .extensions
-            .get::<RequestContext>()
-            .cloned()
-            .map(Context)
+        let path = Path::<T>::from_request_parts(parts, params, state).await?;
+        if let Err(e) = path.0.validate() {
+            return Err(validation_error(&e, state).await);
+        }
+        Ok(Validated(path))
+    }
+}
+
+/// Builds the 422 error for failed schema constraints using the app's
+/// [`ValidationMessages`], if registered.
+async fn schema_validation_error(
+    failures: &[(String, validator::ValidationError)],
+    state: &AppState,
+) -> Error {
+    match state.get_or_init::<ValidationMessages>().await {
+        Some(messages) => messages.fields_error(failures),
+        None => ValidationMessages::new().fields_error(failures),
+    }
+}
+
+impl<T> FromRequestParts for SchemaValidated<Query<T>>
+where
+    T: DeserializeOwned + schemars::JsonSchema + Send + 'static,
+{
+    async fn from_request_parts(
+        parts: &http::request::Parts,
+        params: &PathParams,
+        state: &Arc<AppState>,
+    ) -> Result<Self, Error> {
+        let query = Query::<T>::from_request_parts(parts, params, state).await?;
+        let raw: Vec<(String, String)> =
+            form_urlencoded::parse(parts.uri.query().unwrap_or("").as_bytes())
+                .into_owned()
+                .collect();
+        let failures = schema::check_params(
+            &schema::schema_of::<T>(),
+            raw.iter().map(|(k, v)| (k.as_str(), v.as_str())),
+        );
+        if !failures.is_empty() {
+            return Err(schema_validation_error(&failures, state).await);
+        }
+        Ok(SchemaValidated(query))
+    }
+
+    fn is_missing(parts: &http::request::Parts) -> bool {
+        Query::<T>::is_missing(parts)
+    }
+}
+
+impl<T> FromRequestParts for SchemaValidated<Path<T>>
+where
+    T: DeserializeOwned + schemars::JsonSchema + Send + 'static,
+{
+    async fn from_request_parts(
+        parts: &http::request::Parts,
+        params: &PathParams,
+        state: &Arc<AppState>,
+    ) -> Result<Self, Error> {
+        let path = Path::<T>::from_request_parts(parts, params, state).await?;
+        let failures = schema::check_params(
+            &schema::schema_of::<T>(),
+            params.iter().map(|(k, v)| (k.as_str(), v.as_str())),
+        );
+        if !failures.is_empty() {
+            return Err(schema_validation_error(&failures, state).await);
+        }
+        Ok(SchemaValidated(path))
+    }
+}
+
+impl<T: Clone + Send + Sync + 'static> FromRequestParts for State<T> {
+    async fn from_request_parts(
+        _parts: &http::request::Parts,
+        _params: &PathParams,
+        state: &Arc<AppState>,
+    ) -> Result<Self, Error> {
+        let value = state.get_or_init::<T>().await.ok_or_else(|| {
+            let type_name = std::any::type_name::<T>();
+            tracing::warn!(
+                state_type = type_name,
+                "State<{}> requested but not registered; add it with Rapina::state()",
+                type_name
+            );
+            Error::internal(format!(
+                "State not registered for type '{}'. Did you forget to call .state()?",
+                type_name
+            ))
+        })?;
+        Ok(State(value.clone()))
+    }
+}
+
+impl FromRequestParts for Context {
+    async fn from_request_parts(
+        parts: &http::request::Parts,
+        _params: &PathParams,
+        _state: &Arc<AppState>,
+    ) -> Result<Self, Error> {
+        parts
+            .extensions
+            .get::<RequestContext>()
+            .cloned()
+            .map(Context)
             .ok_or_else(|| {
                 Error::internal(
                     "RequestContext missing from request extensions. \
@@ -473,6 +1087,129 @@ impl FromRequestParts for Context {
     }
 }
 
+impl FromRequestParts for Deadline {
+    async fn from_request_parts(
+        parts: &http::request::Parts,
+        _params: &PathParams,
+        _state: &Arc<AppState>,
+    ) -> Result<Self, Error> {
+        Ok(parts
+            .extensions
+            .get::<Deadline>()
+            .copied()
+            .unwrap_or(Deadline(None)))
+    }
+}
+
+impl FromRequestParts for ConnectInfo {
+    async fn from_request_parts(
+        parts: &http::request::Parts,
+        _params: &PathParams,
+        _state: &Arc<AppState>,
+    ) -> Result<Self, Error> {
+        parts
+            .extensions
+            .get::<ConnectInfo>()
+            .copied()
+            .ok_or_else(|| {
+                Error::internal(
+                    "ConnectInfo missing from request extensions. \
+                     The server is not listening on a TCP socket.",
+                )
+            })
+    }
+}
+
+/// Extracts the request method.
+impl FromRequestParts for http::Method {
+    async fn from_request_parts(
+        parts: &http::request::Parts,
+        _params: &PathParams,
+        _state: &Arc<AppState>,
+    ) -> Result<Self, Error> {
+        Ok(parts.method.clone())
+    }
+}
+
+/// Extracts the request URI, e.g. to log the path a fallback handler saw.
+impl FromRequestParts for http::Uri {
+    async fn from_request_parts(
+        parts: &http::request::Parts,
+        _params: &PathParams,
+        _state: &Arc<AppState>,
+    ) -> Result<Self, Error> {
+        Ok(parts.uri.clone())
+    }
+}
+
+impl FromRequestParts for RawQuery {
+    async fn from_request_parts(
+        parts: &http::request::Parts,
+        _params: &PathParams,
+        _state: &Arc<AppState>,
+    ) -> Result<Self, Error> {
+        Ok(RawQuery(parts.uri.query().map(str::to_string)))
+    }
+}
+
+/// Returns the host a request was sent to, without the port.
+///
+/// Prefers the `Host` header and falls back to the URI authority, which is
+/// where HTTP/2 clients put it.
+pub(crate) fn request_host<'a>(
+    headers: &'a http::HeaderMap,
+    uri: &'a http::Uri,
+) -> Option<&'a str> {
+    let authority = match headers.get(http::header::HOST) {
+        Some(value) => value.to_str().ok()?,
+        None => uri.authority()?.as_str().rsplit('@').next()?,
+    };
+    let host = if authority.starts_with('[') {
+        // IPv6 literal, e.g. `[::1]:8080`
+        authority
+            .find(']')
+            .map_or(authority, |end| &authority[..=end])
+    } else {
+        authority.split(':').next().unwrap_or(authority)
+    };
+    (!host.is_empty()).then_some(host)
+}
+
+impl FromRequestParts for Host {
+    async fn from_request_parts(
+        parts: &http::request::Parts,
+        _params: &PathParams,
+        _state: &Arc<AppState>,
+    ) -> Result<Self, Error> {
+        request_host(&parts.headers, &parts.uri)
+            .map(|host| Host(host.to_string()))
+            .ok_or_else(|| Error::bad_request("Missing Host header"))
+    }
+
+    fn is_missing(parts: &http::request::Parts) -> bool {
+        request_host(&parts.headers, &parts.uri).is_none()
+    }
+}
+
+impl FromRequestParts for MatchedPath {
+    async fn from_request_parts(
+        parts: &http::request::Parts,
+        _params: &PathParams,
+        _state: &Arc<AppState>,
+    ) -> Result<Self, Error> {
+        parts
+            .extensions
+            .get::<MatchedRoute>()
+            .map(|route| MatchedPath(route.as_str().to_string()))
+            .ok_or_else(|| {
+                Error::internal(
+                    "MatchedRoute missing from request extensions. \
+                     The request was not dispatched through the router.",
+                )
+            })
+    }
+}
+
 impl<T: DeserializeOwned + Send> FromRequestParts for Query<T> {
     async fn from_request_parts(
         parts: &http::request::Parts,
@@ -480,10 +1217,13 @@ impl<T: DeserializeOwned + Send> FromRequestParts for Query<T> {
         _state: &Arc<AppState>,
     ) -> Result<Self, Error> {
         let query = parts.uri.query().unwrap_or("");
-        let value: T = serde_urlencoded::from_str(query)
-            .map_err(|e| Error::bad_request(format!("Invalid query string parameters: {}", e)))?;
+        let value: T = urlencoded::from_str(query).map_err(|e| e.into_rejection(Source::Query))?;
         Ok(Query(value))
     }
+
+    fn is_missing(parts: &http::request::Parts) -> bool {
+        parts.uri.query().is_none_or(str::is_empty)
+    }
 }
 
 impl FromRequestParts for Headers {
@@ -496,32 +1236,34 @@ impl FromRequestParts for Headers {
     }
 }
 
-impl<T: DeserializeOwned + Send> FromRequestParts for Cookie<T> {
+impl<H: Header + Send> FromRequestParts for TypedHeader<H> {
     async fn from_request_parts(
         parts: &http::request::Parts,
         _params: &PathParams,
         _state: &Arc<AppState>,
     ) -> Result<Self, Error> {
-        let cookie_header = parts
+        let value = parts
             .headers
-            .get(http::header::COOKIE)
-            .and_then(|v| v.to_str().ok())
-            .unwrap_or("");
-
-        // Parse cookies into key=value pairs
-        let cookies: HashMap<String, String> = cookie_header
-            .split(';')
-            .filter_map(|pair| {
-                let mut parts = pair.trim().splitn(2, '=');
-                let key = parts.next()?.to_string();
-                let value = parts.next()?.to_string();
-                if key.is_empty() {
-                    None
-                } else {
-                    Some((key, value))
-                }
-            })
-            .collect();
+            .get(H::NAME)
+            .ok_or_else(|| Source::Header.missing(H::NAME.as_str()))?;
+
+        H::decode(value)
+            .map(TypedHeader)
+            .map_err(|e| Source::Header.invalid(H::NAME.as_str(), e))
+    }
+
+    fn is_missing(parts: &http::request::Parts) -> bool {
+        !parts.headers.contains_key(H::NAME)
+    }
+}
+
+impl<T: DeserializeOwned + Send> FromRequestParts for Cookie<T> {
+    async fn from_request_parts(
+        parts: &http::request::Parts,
+        _params: &PathParams,
+        _state: &Arc<AppState>,
+    ) -> Result<Self, Error> {
+        let cookies = crate::cookie::parse_cookie_header(&parts.headers);
 
         // Serialize to JSON then deserialize to target type
         let json = serde_json::to_string(&cookies)
@@ -532,37 +1274,89 @@ impl<T: DeserializeOwned + Send> FromRequestParts for Cookie<T> {
 
         Ok(Cookie(value))
     }
+
+    fn is_missing(parts: &http::request::Parts) -> bool {
+        !parts.headers.contains_key(http::header::COOKIE)
+    }
 }
 
-impl<T: FromStr + Send> FromRequestParts for Path<T>
-where
-    T::Err: std::fmt::Display,
-{
+impl<T: DeserializeOwned + Send> FromRequestParts for Path<T> {
     async fn from_request_parts(
         _parts: &http::request::Parts,
         params: &PathParams,
         _state: &Arc<AppState>,
     ) -> Result<Self, Error> {
-        let (param_name, value) = params.iter().next().ok_or_else(|| {
-            Error::bad_request(
-                "Missing path parameter. Ensure your route pattern includes a parameter like /:id",
-            )
-        })?;
+        let value: T = path::from_params(params).map_err(|e| e.into_rejection(Source::Path))?;
+        Ok(Path(value))
+    }
+}
 
-        let parsed = value.parse::<T>().map_err(|e| {
-            Error::bad_request(format!(
-                "Path parameter '{}' must be a valid {}, got '{}': {}",
-                param_name,
-                std::any::type_name::<T>(),
-                value,
-                e
-            ))
-        })?;
+/// Makes any parts-only extractor optional.
+///
+/// Yields `None` when the request lacks the input, e.g. a missing header or
+/// an empty query string. Input that is present but invalid is still
+/// rejected, as are errors such as missing state.
+impl<T: FromRequestParts> FromRequestParts for Option<T> {
+    async fn from_request_parts(
+        parts: &http::request::Parts,
+        params: &PathParams,
+        state: &Arc<AppState>,
+    ) -> Result<Self, Error> {
+        if T::is_missing(parts) {
+            return Ok(None);
+        }
+        T::from_request_parts(parts, params, state).await.map(Some)
+    }
+}
+
+impl<E, R> FromRequestParts for WithRejection<E, R>
+where
+    E: FromRequestParts,
+    R: From<Error> + IntoApiError,
+{
+    async fn from_request_parts(
+        parts: &http::request::Parts,
+        params: &PathParams,
+        state: &Arc<AppState>,
+    ) -> Result<Self, Error> {
+        E::from_request_parts(parts, params, state)
+            .await
+            .map(|value| WithRejection(value, std::marker::PhantomData))
+            .map_err(|e| R::from(e).into_api_error())
+    }
 
-        Ok(Path(parsed))
+    fn is_missing(parts: &http::request::Parts) -> bool {
+        E::is_missing(parts)
     }
 }
 
+macro_rules! impl_with_rejection_body {
+    ($($body:ty),*) => {
+        $(
+            impl<T, R> FromRequest for WithRejection<$body, R>
+            where
+                $body: FromRequest,
+                R: From<Error> + IntoApiError,
+            {
+                async fn from_request(
+                    req: Request<Incoming>,
+                    params: &PathParams,
+                    state: &Arc<AppState>,
+                ) -> Result<Self, Error> {
+                    <$body>::from_request(req, params, state)
+                        .await
+                        .map(|value| WithRejection(value, std::marker::PhantomData))
+                        .map_err(|e| R::from(e).into_api_error())
+                }
+            }
+        )*
+    };
+}
+
+impl_with_rejection_body!(Json<T>, Form<T>, Validated<Json<T>>, Validated<Form<T>>);
+#[cfg(feature = "msgpack")]
+impl_with_rejection_body!(MsgPack<T>, Body<T>);
+
 impl<T: FromRequestParts> FromRequest for T {
     async fn from_request(
         req: Request<Incoming>,
@@ -574,7 +1368,35 @@ impl<T: FromRequestParts> FromRequest for T {
     }
 }
 
-pub fn extract_path_params(pattern: &str, path: &str) -> Option<PathParams> {
+/// Matches `path` against a route `pattern`, capturing the `:param` segments.
+///
+/// Parameters may carry a constraint, `:id(\d+)` or `:id<u64>`; a segment
+/// that doesn't satisfy it doesn't match.
+///
+/// `pattern` is parsed on every call. The router parses each route once when
+/// it's added, so prefer it when matching many paths.
+///
+/// # Errors
+///
+/// Returns an error if a constraint in `pattern` is malformed or names an
+/// unknown type.
+pub fn extract_path_params(pattern: &str, path: &str) -> Result<Option<PathParams>, String> {
+    let (pattern, constraints) = parse_pattern(pattern)?;
+    Ok(match_path(&pattern, path, false, &constraints))
+}
+
+/// Matches `path` against a pattern without inline constraints, capturing
+/// the `:param` segments.
+///
+/// With `ignore_case`, static segments are compared without regard to ASCII
+/// case. Captured values are always kept as sent, and must satisfy the
+/// constraint registered for their parameter, if any.
+pub(crate) fn match_path(
+    pattern: &str,
+    path: &str,
+    ignore_case: bool,
+    constraints: &[(String, Constraint)],
+) -> Option<PathParams> {
     let pattern_parts: Vec<&str> = pattern.split('/').collect();
     let path_parts: Vec<&str> = path.split('/').collect();
 
@@ -586,8 +1408,16 @@ pub fn extract_path_params(pattern: &str, path: &str) -> Option<PathParams> {
 
     for (pattern_part, path_part) in pattern_parts.iter().zip(path_parts.iter()) {
         if let Some(param_name) = pattern_part.strip_prefix(':') {
+            let rejected = constraints
+                .iter()
+                .any(|(name, constraint)| name == param_name && !constraint.accepts(path_part));
+            if rejected {
+                return None;
+            }
             params.insert(param_name.to_string(), path_part.to_string());
-        } else if pattern_part != path_part {
+        } else if !(pattern_part == path_part
+            || ignore_case && pattern_part.eq_ignore_ascii_case(path_part))
+        {
             return None;
         }
     }
@@ -605,11 +1435,14 @@ impl FromRequestParts for crate::database::Db {
     ) -> Result<Self, Error> {
         use sea_orm::DatabaseConnection;
 
-        let conn = state.get::<DatabaseConnection>().ok_or_else(|| {
-            Error::internal(
-                "Database connection not configured. Did you forget to call .with_database()?",
-            )
-        })?;
+        let conn = state
+            .get_or_init::<DatabaseConnection>()
+            .await
+            .ok_or_else(|| {
+                Error::internal(
+                    "Database connection not configured. Did you forget to call .with_database()?",
+                )
+            })?;
         Ok(crate::database::Db::new(conn.clone()))
     }
 }
@@ -622,14 +1455,14 @@ mod tests {
     // Path params extraction tests
     #[test]
     fn test_extract_path_params_exact_match() {
-        let result = extract_path_params("/users", "/users");
+        let result = extract_path_params("/users", "/users").unwrap();
         assert!(result.is_some());
         assert!(result.unwrap().is_empty());
     }
 
     #[test]
     fn test_extract_path_params_single_param() {
-        let result = extract_path_params("/users/:id", "/users/123");
+        let result = extract_path_params("/users/:id", "/users/123").unwrap();
         assert!(result.is_some());
         let params = result.unwrap();
         assert_eq!(params.get("id"), Some(&"123".to_string()));
@@ -637,28 +1470,53 @@ mod tests {
 
     #[test]
     fn test_extract_path_params_multiple_params() {
-        let result = extract_path_params("/users/:user_id/posts/:post_id", "/users/1/posts/42");
+        let result =
+            extract_path_params("/users/:user_id/posts/:post_id", "/users/1/posts/42").unwrap();
         assert!(result.is_some());
         let params = result.unwrap();
         assert_eq!(params.get("user_id"), Some(&"1".to_string()));
         assert_eq!(params.get("post_id"), Some(&"42".to_string()));
     }
 
+    #[test]
+    fn test_match_path_ignore_case() {
+        let params = match_path("/users/:id", "/USERS/Ab", true, &[]).unwrap();
+        assert_eq!(params.get("id"), Some(&"Ab".to_string()));
+        assert!(match_path("/users/:id", "/USERS/Ab", false, &[]).is_none());
+    }
+
+    #[test]
+    fn test_extract_path_params_constraints() {
+        let result = extract_path_params(r"/users/:id(\d+)", "/users/42").unwrap();
+        assert_eq!(result.unwrap().get("id"), Some(&"42".to_string()));
+        assert!(
+            extract_path_params(r"/users/:id(\d+)", "/users/alice")
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            extract_path_params("/users/:id<u64>", "/users/alice")
+                .unwrap()
+                .is_none()
+        );
+        assert!(extract_path_params("/users/:id<number>", "/users/1").is_err());
+    }
+
     #[test]
     fn test_extract_path_params_no_match_different_length() {
-        let result = extract_path_params("/users/:id", "/users/123/extra");
+        let result = extract_path_params("/users/:id", "/users/123/extra").unwrap();
         assert!(result.is_none());
     }
 
     #[test]
     fn test_extract_path_params_no_match_different_static() {
-        let result = extract_path_params("/users/:id", "/posts/123");
+        let result = extract_path_params("/users/:id", "/posts/123").unwrap();
         assert!(result.is_none());
     }
 
     #[test]
     fn test_extract_path_params_root() {
-        let result = extract_path_params("/", "/");
+        let result = extract_path_params("/", "/").unwrap();
         assert!(result.is_some());
     }
 
@@ -732,6 +1590,20 @@ mod tests {
         assert_eq!(err.status, 400);
     }
 
+    #[tokio::test]
+    async fn test_query_extractor_rejects_deep_nesting() {
+        let uri = format!("/users?filter{}=1", "[x]".repeat(5000));
+        let (parts, _) = TestRequest::get(&uri).into_parts();
+        let result = Query::<std::collections::HashMap<String, String>>::from_request_parts(
+            &parts,
+            &empty_params(),
+            &empty_state(),
+        )
+        .await;
+
+        assert_eq!(result.unwrap_err().status, 400);
+    }
+
     // Headers extractor tests
     #[tokio::test]
     async fn test_headers_extractor() {
@@ -761,6 +1633,117 @@ mod tests {
         assert!(headers.get("x-nonexistent").is_none());
     }
 
+    // TypedHeader extractor tests
+    #[tokio::test]
+    async fn test_typed_header_bearer() {
+        use crate::headers::{Authorization, Bearer};
+
+        let (parts, _) = TestRequest::get("/")
+            .header("authorization", "Bearer abc123")
+            .into_parts();
+
+        let result = TypedHeader::<Authorization<Bearer>>::from_request_parts(
+            &parts,
+            &empty_params(),
+            &empty_state(),
+        )
+        .await;
+
+        let auth = result.unwrap().into_inner();
+        assert_eq!(auth.0.token(), "abc123");
+    }
+
+    #[tokio::test]
+    async fn test_typed_header_malformed_bearer() {
+        use crate::headers::{Authorization, Bearer};
+
+        let (parts, _) = TestRequest::get("/")
+            .header("authorization", "Basic dXNlcjpwYXNz")
+            .into_parts();
+
+        let result = TypedHeader::<Authorization<Bearer>>::from_request_parts(
+            &parts,
+            &empty_params(),
+            &empty_state(),
+        )
+        .await;
+
+        let err = result.unwrap_err();
+        assert_eq!(err.status, 400);
+        assert!(err.message.contains("authorization"));
+    }
+
+    #[tokio::test]
+    async fn test_typed_header_missing() {
+        use crate::headers::UserAgent;
+
+        let (parts, _) = TestRequest::get("/").into_parts();
+        let result =
+            TypedHeader::<UserAgent>::from_request_parts(&parts, &empty_params(), &empty_state())
+                .await;
+
+        let err = result.unwrap_err();
+        assert_eq!(err.status, 400);
+        assert!(err.message.contains("Missing"));
+    }
+
+    // Option extractor tests
+    #[tokio::test]
+    async fn test_option_extractor_some() {
+        let (parts, _) = TestRequest::get("/")
+            .header("user-agent", "curl/8.0")
+            .into_parts();
+        let result = Option::<TypedHeader<crate::headers::UserAgent>>::from_request_parts(
+            &parts,
+            &empty_params(),
+            &empty_state(),
+        )
+        .await;
+
+        assert_eq!(result.unwrap().unwrap().0.as_str(), "curl/8.0");
+    }
+
+    #[tokio::test]
+    async fn test_option_extractor_none_when_missing() {
+        let (parts, _) = TestRequest::get("/").into_parts();
+        let result = Option::<TypedHeader<crate::headers::UserAgent>>::from_request_parts(
+            &parts,
+            &empty_params(),
+            &empty_state(),
+        )
+        .await;
+
+        assert!(result.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_option_extractor_rejects_invalid_input() {
+        #[derive(Debug, serde::Deserialize)]
+        struct Filter {
+            #[allow(dead_code)]
+            page: u32,
+        }
+
+        let (parts, _) = TestRequest::get("/").into_parts();
+        let result =
+            Option::<Query<Filter>>::from_request_parts(&parts, &empty_params(), &empty_state())
+                .await;
+        assert!(result.unwrap().is_none());
+
+        let (parts, _) = TestRequest::get("/?page=abc").into_parts();
+        let err =
+            Option::<Query<Filter>>::from_request_parts(&parts, &empty_params(), &empty_state())
+                .await
+                .unwrap_err();
+        assert_eq!(err.status, 400);
+
+        let err =
+            Option::<State<String>>::from_request_parts(&parts, &empty_params(), &empty_state())
+                .await
+                .unwrap_err();
+        assert_eq!(err.status, 500);
+    }
+
     // Path extractor tests
     #[tokio::test]
     async fn test_path_extractor_u64() {
@@ -822,6 +1805,135 @@ mod tests {
         assert_eq!(result.unwrap().trace_id(), "custom-123");
     }
 
+    #[tokio::test]
+    async fn test_context_extractor_reads_inserted_values() {
+        let ctx = crate::context::RequestContext::new();
+        ctx.insert(42u64);
+        let (parts, _) = TestRequest::get("/").into_parts_with_context(ctx);
+
+        let context = Context::from_request_parts(&parts, &empty_params(), &empty_state())
+            .await
+            .unwrap();
+        assert_eq!(context.get::<u64>(), Some(42));
+        assert!(context.get::<String>().is_none());
+    }
+
+    // Deadline extractor tests
+    #[tokio::test]
+    async fn test_deadline_extractor_unbounded() {
+        let (parts, _) = TestRequest::get("/").into_parts();
+
+        let deadline = Deadline::from_request_parts(&parts, &empty_params(), &empty_state())
+            .await
+            .unwrap();
+        assert_eq!(deadline.instant(), None);
+        assert_eq!(deadline.remaining(), None);
+        assert!(!deadline.is_expired());
+    }
+
+    #[tokio::test]
+    async fn test_deadline_extractor_from_extensions() {
+        let (mut parts, _) = TestRequest::get("/").into_parts();
+        let instant = std::time::Instant::now() + std::time::Duration::from_secs(10);
+        parts.extensions.insert(Deadline(Some(instant)));
+
+        let deadline = Deadline::from_request_parts(&parts, &empty_params(), &empty_state())
+            .await
+            .unwrap();
+        let remaining = deadline.remaining().unwrap();
+        assert!(remaining > std::time::Duration::from_secs(9));
+        assert!(remaining <= std::time::Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_deadline_expired() {
+        let deadline = Deadline(Some(std::time::Instant::now()));
+        assert_eq!(deadline.remaining(), Some(std::time::Duration::ZERO));
+        assert!(deadline.is_expired());
+    }
+
+    // MatchedPath extractor tests
+    #[tokio::test]
+    async fn test_matched_path_extractor() {
+        let (mut parts, _) = TestRequest::get("/users/42").into_parts();
+        parts
+            .extensions
+            .insert(MatchedRoute("/users/:id".to_string()));
+
+        let result = MatchedPath::from_request_parts(&parts, &empty_params(), &empty_state()).await;
+        assert_eq!(result.unwrap().as_str(), "/users/:id");
+    }
+
+    #[tokio::test]
+    async fn test_matched_path_extractor_missing() {
+        let (parts, _) = TestRequest::get("/users/42").into_parts();
+
+        let result = MatchedPath::from_request_parts(&parts, &empty_params(), &empty_state()).await;
+        assert_eq!(result.unwrap_err().status, 500);
+    }
+
+    #[tokio::test]
+    async fn test_raw_query_extractor() {
+        let (parts, _) = TestRequest::get("/users?page=2&sort=-name&q=a%20b").into_parts();
+        let result = RawQuery::from_request_parts(&parts, &empty_params(), &empty_state()).await;
+        assert_eq!(
+            result.unwrap(),
+            RawQuery(Some("page=2&sort=-name&q=a%20b".to_string()))
+        );
+
+        let (parts, _) = TestRequest::get("/users").into_parts();
+        let result = RawQuery::from_request_parts(&parts, &empty_params(), &empty_state()).await;
+        assert_eq!(result.unwrap(), RawQuery(None));
+    }
+
+    #[tokio::test]
+    async fn test_host_extractor() {
+        let (parts, _) = TestRequest::get("/")
+            .header("host", "api.example.com:8080")
+            .into_parts();
+        let result = Host::from_request_parts(&parts, &empty_params(), &empty_state()).await;
+        assert_eq!(result.unwrap().as_str(), "api.example.com");
+
+        let (parts, _) = TestRequest::get("/")
+            .header("host", "[::1]:3000")
+            .into_parts();
+        let result = Host::from_request_parts(&parts, &empty_params(), &empty_state()).await;
+        assert_eq!(result.unwrap().as_str(), "[::1]");
+
+        let (parts, _) = TestRequest::get("https://www.example.com/").into_parts();
+        let result = Host::from_request_parts(&parts, &empty_params(), &empty_state()).await;
+        assert_eq!(result.unwrap().as_str(), "www.example.com");
+
+        let (parts, _) = TestRequest::get("/").into_parts();
+        let result = Host::from_request_parts(&parts, &empty_params(), &empty_state()).await;
+        assert_eq!(result.unwrap_err().status, 400);
+    }
+
+    #[tokio::test]
+    async fn test_method_and_uri_extractors() {
+        let (parts, _) = TestRequest::post("/users?page=2").into_parts();
+
+        let method =
+            http::Method::from_request_parts(&parts, &empty_params(), &empty_state()).await;
+        assert_eq!(method.unwrap(), http::Method::POST);
+        let uri = http::Uri::from_request_parts(&parts, &empty_params(), &empty_state()).await;
+        assert_eq!(uri.unwrap(), "/users?page=2");
+    }
+
+    #[tokio::test]
+    async fn test_connect_info_extractor() {
+        let (mut parts, _) = TestRequest::get("/").into_parts();
+        let addr = "203.0.113.7:51234".parse().unwrap();
+        parts.extensions.insert(ConnectInfo(addr));
+
+        let result = ConnectInfo::from_request_parts(&parts, &empty_params(), &empty_state()).await;
+        assert_eq!(result.unwrap(), ConnectInfo(addr));
+
+        let (parts, _) = TestRequest::get("/").into_parts();
+        let result = ConnectInfo::from_request_parts(&parts, &empty_params(), &empty_state()).await;
+        assert_eq!(result.unwrap_err().status, 500);
+    }
+
     // State extractor tests
     #[tokio::test]
     async fn test_state_extractor_success() {
@@ -854,6 +1966,63 @@ mod tests {
         assert_eq!(result.unwrap_err().status, 500);
     }
 
+    #[tokio::test]
+    async fn test_state_extractor_not_found_names_type() {
+        #[derive(Clone, Debug)]
+        struct ForgottenConfig;
+
+        let (parts, _) = TestRequest::get("/").into_parts();
+
+        let err =
+            State::<ForgottenConfig>::from_request_parts(&parts, &empty_params(), &empty_state())
+                .await
+                .unwrap_err();
+        assert_eq!(err.status, 500);
+        assert!(
+            err.message
+                .contains(std::any::type_name::<ForgottenConfig>())
+        );
+        assert!(err.message.contains(".state()"));
+    }
+
+    // Json content type tests
+    fn json_headers(content_type: Option<&'static str>) -> http::HeaderMap {
+        let mut headers = http::HeaderMap::new();
+        if let Some(ct) = content_type {
+            headers.insert(http::header::CONTENT_TYPE, ct.parse().unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn test_json_content_type_accepted() {
+        assert!(check_json_content_type(&json_headers(Some("application/json"))).is_ok());
+        assert!(
+            check_json_content_type(&json_headers(Some("application/json; charset=utf-8"))).is_ok()
+        );
+        assert!(check_json_content_type(&json_headers(Some("Application/JSON"))).is_ok());
+        assert!(
+            check_json_content_type(&json_headers(Some("application/merge-patch+json"))).is_ok()
+        );
+    }
+
+    #[test]
+    fn test_json_content_type_missing_is_lenient() {
+        assert!(check_json_content_type(&json_headers(None)).is_ok());
+        assert!(check_json_content_type(&json_headers(Some(""))).is_ok());
+    }
+
+    #[test]
+    fn test_json_content_type_rejected() {
+        let err = check_json_content_type(&json_headers(Some("application/x-www-form-urlencoded")))
+            .unwrap_err();
+        assert_eq!(err.status, 415);
+        assert!(err.message.contains("application/x-www-form-urlencoded"));
+
+        let err = check_json_content_type(&json_headers(Some("text/plain"))).unwrap_err();
+        assert_eq!(err.status, 415);
+    }
+
     // into_inner tests
     #[test]
     fn test_json_into_inner() {
@@ -0,0 +1,172 @@
+//! Errors from deserializing path parameters, query strings and forms that
+//! remember which key failed, so rejections can name it.
+
+use std::fmt;
+
+use serde::de;
+
+use crate::error::Error;
+
+/// A deserialization error and the key it happened at.
+#[derive(Debug)]
+pub(crate) struct FieldError {
+    /// The key, outermost first; `user[name]` is `["user", "name"]`.
+    path: Vec<String>,
+    message: String,
+    missing: bool,
+}
+
+impl FieldError {
+    /// Records that the error happened under `key`.
+    pub(crate) fn at(mut self, key: &str) -> Self {
+        self.path.insert(0, key.to_string());
+        self
+    }
+
+    /// Returns the failing key as written in the request, e.g. `user[name]`.
+    fn field(&self) -> Option<String> {
+        let (first, rest) = self.path.split_first()?;
+        Some(rest.iter().fold(first.clone(), |field, segment| {
+            format!("{}[{}]", field, segment)
+        }))
+    }
+
+    /// Turns the error into a 400 naming the source and key.
+    pub(crate) fn into_rejection(self, source: Source) -> Error {
+        match self.field() {
+            Some(field) if self.missing => source.missing(&field),
+            Some(field) => source.invalid(&field, &self.message),
+            None => Error::bad_request(format!("{}: {}", source.fallback(), self.message)),
+        }
+    }
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.field() {
+            Some(field) if self.missing => write!(f, "missing field `{}`", field),
+            Some(field) => write!(f, "{}: {}", field, self.message),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+impl std::error::Error for FieldError {}
+
+impl de::Error for FieldError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        FieldError {
+            path: Vec::new(),
+            message: msg.to_string(),
+            missing: false,
+        }
+    }
+
+    fn missing_field(field: &'static str) -> Self {
+        FieldError {
+            path: vec![field.to_string()],
+            message: "missing".to_string(),
+            missing: true,
+        }
+    }
+}
+
+/// The part of the request a value was read from.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Source {
+    Path,
+    Query,
+    Form,
+    Header,
+}
+
+impl Source {
+    fn name(self) -> &'static str {
+        match self {
+            Source::Path => "path",
+            Source::Query => "query",
+            Source::Form => "form",
+            Source::Header => "header",
+        }
+    }
+
+    fn noun(self) -> &'static str {
+        match self {
+            Source::Path => "path parameter",
+            Source::Query => "query parameter",
+            Source::Form => "form field",
+            Source::Header => "header",
+        }
+    }
+
+    /// The message prefix for errors that aren't tied to one key.
+    fn fallback(self) -> &'static str {
+        match self {
+            Source::Path => "Invalid path parameters",
+            Source::Query => "Invalid query string parameters",
+            Source::Form => "Invalid URL-encoded form data",
+            Source::Header => "Invalid headers",
+        }
+    }
+
+    fn details(self, field: &str) -> serde_json::Value {
+        serde_json::json!({ "source": self.name(), "field": field })
+    }
+
+    /// A 400 for a required value that wasn't sent.
+    pub(crate) fn missing(self, field: &str) -> Error {
+        let message = match self {
+            Source::Header => format!("Missing required header '{}'", field),
+            _ => format!("Missing {} '{}'", self.noun(), field),
+        };
+        Error::bad_request(message).with_details(self.details(field))
+    }
+
+    /// A 400 for a value that was sent but couldn't be parsed.
+    pub(crate) fn invalid(self, field: &str, reason: impl fmt::Display) -> Error {
+        let message = match self {
+            Source::Header => format!("Invalid '{}' header: {}", field, reason),
+            _ => format!("Invalid {} '{}': {}", self.noun(), field, reason),
+        };
+        Error::bad_request(message).with_details(self.details(field))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::de::Error as _;
+
+    #[test]
+    fn test_nested_field_is_written_with_brackets() {
+        let err = FieldError::custom("invalid digit").at("zip").at("address");
+        assert_eq!(err.to_string(), "address[zip]: invalid digit");
+
+        let err = err.into_rejection(Source::Form);
+        assert_eq!(
+            err.message,
+            "Invalid form field 'address[zip]': invalid digit"
+        );
+        assert_eq!(
+            err.details,
+            Some(serde_json::json!({ "source": "form", "field": "address[zip]" }))
+        );
+    }
+
+    #[test]
+    fn test_missing_field() {
+        let err = FieldError::missing_field("limit");
+        assert_eq!(err.to_string(), "missing field `limit`");
+        assert_eq!(
+            err.into_rejection(Source::Query).message,
+            "Missing query parameter 'limit'"
+        );
+    }
+
+    #[test]
+    fn test_error_without_field_keeps_generic_message() {
+        let err = FieldError::custom("expected a sequence").into_rejection(Source::Path);
+        assert_eq!(err.message, "Invalid path parameters: expected a sequence");
+        assert!(err.details.is_none());
+    }
+}
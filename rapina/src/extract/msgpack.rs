@@ -0,0 +1,246 @@
+//! MessagePack request and response bodies.
+
+use std::sync::Arc;
+
+use http::{Request, StatusCode};
+use http_body_util::BodyExt;
+use hyper::body::Incoming;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use super::{FromRequest, JSON_CONTENT_TYPE, Json, PathParams, parse_json};
+use crate::error::Error;
+use crate::headers::{ContentType, Header};
+use crate::middleware::take_request_body;
+use crate::negotiate::Negotiate;
+use crate::response::{BoxBody, IntoResponse, full_response};
+use crate::state::AppState;
+
+/// The media type of MessagePack bodies.
+pub const MSGPACK_CONTENT_TYPE: &str = "application/msgpack";
+
+/// Returns whether a media type is one of the names MessagePack goes by.
+pub(crate) fn is_msgpack(mime: &str) -> bool {
+    [
+        "application/msgpack",
+        "application/x-msgpack",
+        "application/vnd.msgpack",
+    ]
+    .iter()
+    .any(|name| mime.eq_ignore_ascii_case(name))
+}
+
+/// Extracts and deserializes MessagePack request bodies, and serializes
+/// MessagePack responses.
+///
+/// Works like [`Json`]: a request with another `Content-Type` is rejected
+/// with 415, one without a `Content-Type` is accepted, and a body that
+/// doesn't decode is a 400. Responses encode structs as maps keyed by field
+/// name, so clients don't depend on field order.
+///
+/// Requires the `msgpack` feature.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rapina::extract::MsgPack;
+/// use rapina::prelude::*;
+///
+/// #[post("/events")]
+/// async fn ingest(events: MsgPack<Vec<Event>>) -> MsgPack<Summary> {
+///     MsgPack(Summary { received: events.into_inner().len() })
+/// }
+/// ```
+#[derive(Debug)]
+pub struct MsgPack<T>(pub T);
+
+impl<T> MsgPack<T> {
+    /// Consumes the extractor and returns the inner value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+/// Rejects a request whose `Content-Type` is set but isn't MessagePack.
+fn check_msgpack_content_type(headers: &http::HeaderMap) -> Result<(), Error> {
+    let Some(value) = headers.get(http::header::CONTENT_TYPE) else {
+        return Ok(());
+    };
+    match ContentType::decode(value) {
+        Ok(content_type) if is_msgpack(content_type.mime()) => Ok(()),
+        _ => Err(Error::unsupported_media_type(format!(
+            "Expected Content-Type '{}', got '{}'",
+            MSGPACK_CONTENT_TYPE,
+            value.to_str().unwrap_or("<invalid>")
+        ))),
+    }
+}
+
+fn parse_msgpack<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, Error> {
+    rmp_serde::from_slice(bytes)
+        .map_err(|e| Error::bad_request(format!("Invalid MessagePack in request body: {}", e)))
+}
+
+async fn read_body(req: Request<Incoming>) -> Result<bytes::Bytes, Error> {
+    take_request_body(req)
+        .collect()
+        .await
+        .map(|collected| collected.to_bytes())
+        .map_err(|_| Error::bad_request("Failed to read request body"))
+}
+
+impl<T: DeserializeOwned + Send> FromRequest for MsgPack<T> {
+    async fn from_request(
+        req: Request<Incoming>,
+        _params: &PathParams,
+        _state: &Arc<AppState>,
+    ) -> Result<Self, Error> {
+        check_msgpack_content_type(req.headers())?;
+        let bytes = read_body(req).await?;
+        parse_msgpack(&bytes).map(MsgPack)
+    }
+}
+
+impl<T: Serialize> IntoResponse for (StatusCode, MsgPack<T>) {
+    fn into_response(self) -> http::Response<BoxBody> {
+        match rmp_serde::to_vec_named(&(self.1).0) {
+            Ok(body) => full_response(self.0, MSGPACK_CONTENT_TYPE, body),
+            Err(e) => Error::internal(format!("serialization failed: {}", e)).into_response(),
+        }
+    }
+}
+
+impl<T: Serialize> IntoResponse for MsgPack<T> {
+    fn into_response(self) -> http::Response<BoxBody> {
+        (StatusCode::OK, self).into_response()
+    }
+}
+
+/// A body read as JSON or MessagePack depending on the request's
+/// `Content-Type`, and written in the format the `Accept` header prefers.
+///
+/// A request without a `Content-Type` is read as JSON; any type other than
+/// JSON or MessagePack is rejected with 415. The response is rendered like
+/// [`Negotiate`], JSON by default, so a client asking only for a format
+/// that isn't registered gets 406.
+///
+/// Requires the `msgpack` feature.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rapina::extract::Body;
+/// use rapina::prelude::*;
+///
+/// #[post("/users")]
+/// async fn create_user(user: Body<CreateUser>) -> Body<User> {
+///     Body(save(user.into_inner()))
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Body<T>(pub T);
+
+impl<T> Body<T> {
+    /// Consumes the extractor and returns the inner value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T: DeserializeOwned + Send> FromRequest for Body<T> {
+    async fn from_request(
+        req: Request<Incoming>,
+        _params: &PathParams,
+        _state: &Arc<AppState>,
+    ) -> Result<Self, Error> {
+        let is_msgpack = match req.headers().get(http::header::CONTENT_TYPE) {
+            None => false,
+            Some(value) => match ContentType::decode(value) {
+                Ok(content_type) if content_type.is_json() => false,
+                Ok(content_type) if is_msgpack(content_type.mime()) => true,
+                _ => {
+                    return Err(Error::unsupported_media_type(format!(
+                        "Unsupported Content-Type '{}'",
+                        value.to_str().unwrap_or("<invalid>")
+                    ))
+                    .with_details(serde_json::json!({
+                        "supported": [JSON_CONTENT_TYPE, MSGPACK_CONTENT_TYPE]
+                    })));
+                }
+            },
+        };
+
+        let bytes = read_body(req).await?;
+        if is_msgpack {
+            parse_msgpack(&bytes).map(Body)
+        } else {
+            parse_json(&bytes).map(|Json(value)| Body(value))
+        }
+    }
+}
+
+impl<T: Serialize> IntoResponse for (StatusCode, Body<T>) {
+    fn into_response(self) -> http::Response<BoxBody> {
+        (self.0, Negotiate((self.1).0)).into_response()
+    }
+}
+
+impl<T: Serialize> IntoResponse for Body<T> {
+    fn into_response(self) -> http::Response<BoxBody> {
+        (StatusCode::OK, self).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http_body_util::BodyExt;
+    use serde::Deserialize;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Point {
+        x: i32,
+        label: String,
+    }
+
+    #[tokio::test]
+    async fn test_msgpack_response_round_trip() {
+        let point = Point {
+            x: 3,
+            label: "a".to_string(),
+        };
+        let response = MsgPack(point).into_response();
+        assert_eq!(response.headers()["content-type"], MSGPACK_CONTENT_TYPE);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let decoded: Point = parse_msgpack(&body).unwrap();
+        assert_eq!(
+            decoded,
+            Point {
+                x: 3,
+                label: "a".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_msgpack_content_type_check() {
+        let mut headers = http::HeaderMap::new();
+        assert!(check_msgpack_content_type(&headers).is_ok());
+
+        headers.insert("content-type", "application/x-msgpack".parse().unwrap());
+        assert!(check_msgpack_content_type(&headers).is_ok());
+
+        headers.insert("content-type", "application/json".parse().unwrap());
+        assert_eq!(
+            check_msgpack_content_type(&headers).unwrap_err().status,
+            415
+        );
+    }
+
+    #[test]
+    fn test_invalid_msgpack_is_bad_request() {
+        let err = parse_msgpack::<Point>(&[0xc1]).unwrap_err();
+        assert_eq!(err.status, 400);
+    }
+}
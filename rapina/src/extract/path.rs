@@ -0,0 +1,284 @@
+//! Path parameter deserialization.
+//!
+//! A struct is filled by matching each `:name` segment of the route to the
+//! field with the same name. Any other type reads the route's only
+//! parameter.
+
+use std::borrow::Cow;
+
+use serde::de::value::MapDeserializer;
+use serde::de::{self, DeserializeOwned, Deserializer, IntoDeserializer, Visitor};
+use serde::forward_to_deserialize_any;
+
+use super::PathParams;
+use super::field_error::FieldError as Error;
+use super::urlencoded::Part;
+
+/// Deserializes the path parameters captured for a route.
+pub(crate) fn from_params<T: DeserializeOwned>(params: &PathParams) -> Result<T, Error> {
+    T::deserialize(Params(params))
+}
+
+/// All parameters of a route, keyed by name.
+struct Params<'a>(&'a PathParams);
+
+impl<'de> Params<'de> {
+    /// Returns the route's only parameter.
+    fn single(self) -> Result<Param<'de>, Error> {
+        let mut params = self.0.iter();
+        match (params.next(), params.next()) {
+            (Some((name, value)), None) => Ok(Param { name, value }),
+            (None, _) => Err(de::Error::custom(
+                "missing path parameter, ensure the route pattern includes a parameter like /:id",
+            )),
+            (Some(_), Some(_)) => Err(de::Error::custom(format!(
+                "expected a single path parameter, found {}; \
+                 use a struct with fields named after the parameters",
+                self.0.len()
+            ))),
+        }
+    }
+
+    fn visit_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_map(MapDeserializer::new(self.0.iter().map(|(name, value)| {
+            (Part(Cow::Borrowed(name.as_str())), Param { name, value })
+        })))
+    }
+}
+
+macro_rules! deserialize_single {
+    ($($method:ident)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+                self.single()?.$method(visitor)
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for Params<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.single()?.deserialize_any(visitor)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.visit_map(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.visit_map(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.single()?.deserialize_enum(name, variants, visitor)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Error> {
+        Err(de::Error::custom(
+            "path parameters can't be read as a sequence, \
+             use a struct with fields named after the parameters",
+        ))
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_unit(visitor)
+    }
+
+    deserialize_single! {
+        deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64
+        deserialize_i128 deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64
+        deserialize_u128 deserialize_f32 deserialize_f64 deserialize_char deserialize_str
+        deserialize_string deserialize_bytes deserialize_byte_buf deserialize_option
+        deserialize_unit deserialize_identifier deserialize_ignored_any
+    }
+}
+
+/// One named parameter; errors are tagged with its name.
+struct Param<'a> {
+    name: &'a str,
+    value: &'a str,
+}
+
+impl Param<'_> {
+    fn error(&self, e: Error) -> Error {
+        e.at(self.name)
+    }
+}
+
+impl<'de> IntoDeserializer<'de, Error> for Param<'de> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+macro_rules! deserialize_part {
+    ($($method:ident)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+                Part(Cow::Borrowed(self.value))
+                    .$method(visitor)
+                    .map_err(|e| self.error(e))
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for Param<'de> {
+    type Error = Error;
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        Part(Cow::Borrowed(self.value))
+            .deserialize_enum(name, variants, visitor)
+            .map_err(|e| self.error(e))
+    }
+
+    deserialize_part! {
+        deserialize_any deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32
+        deserialize_i64 deserialize_i128 deserialize_u8 deserialize_u16 deserialize_u32
+        deserialize_u64 deserialize_u128 deserialize_f32 deserialize_f64 deserialize_char
+    }
+
+    forward_to_deserialize_any! {
+        str string bytes byte_buf unit unit_struct seq tuple tuple_struct map
+        struct identifier ignored_any
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    fn params(pairs: &[(&str, &str)]) -> PathParams {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct PostPath {
+        user_id: u64,
+        post_id: String,
+    }
+
+    #[test]
+    fn test_struct_fields_by_name() {
+        let path: PostPath = from_params(&params(&[("post_id", "abc"), ("user_id", "7")])).unwrap();
+        assert_eq!(
+            path,
+            PostPath {
+                user_id: 7,
+                post_id: "abc".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_struct_missing_field() {
+        let err = from_params::<PostPath>(&params(&[("user_id", "7")])).unwrap_err();
+        assert!(err.to_string().contains("post_id"), "{}", err);
+    }
+
+    #[test]
+    fn test_struct_invalid_field_names_parameter() {
+        let err =
+            from_params::<PostPath>(&params(&[("user_id", "x"), ("post_id", "1")])).unwrap_err();
+        assert!(err.to_string().starts_with("user_id:"), "{}", err);
+    }
+
+    #[test]
+    fn test_scalar_single_param() {
+        let id: u64 = from_params(&params(&[("id", "42")])).unwrap();
+        assert_eq!(id, 42);
+    }
+
+    #[test]
+    fn test_scalar_with_several_params_is_error() {
+        let err = from_params::<u64>(&params(&[("a", "1"), ("b", "2")])).unwrap_err();
+        assert!(err.to_string().contains("found 2"), "{}", err);
+    }
+
+    #[test]
+    fn test_scalar_missing_param() {
+        let err = from_params::<u64>(&PathParams::new()).unwrap_err();
+        assert!(
+            err.to_string().contains("missing path parameter"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_enum_param() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        #[serde(rename_all = "lowercase")]
+        enum Kind {
+            Posts,
+            Comments,
+        }
+
+        let kind: Kind = from_params(&params(&[("kind", "comments")])).unwrap();
+        assert_eq!(kind, Kind::Comments);
+    }
+}
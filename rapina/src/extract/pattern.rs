@@ -0,0 +1,181 @@
+//! Constraints on path parameters.
+//!
+//! A parameter can restrict the segments it matches with a regular
+//! expression, `/users/:id(\d+)`, or a type the segment must parse as,
+//! `/users/:id<u64>`. A segment that doesn't satisfy the constraint doesn't
+//! match the route, so the request falls through to later routes.
+
+use regex::Regex;
+
+/// A constraint a captured segment must satisfy.
+#[derive(Debug, Clone)]
+pub(crate) enum Constraint {
+    /// The whole segment must match the expression.
+    Regex(Regex),
+    /// The segment must parse as the named type.
+    Type(fn(&str) -> bool),
+}
+
+impl Constraint {
+    /// Returns whether `value` satisfies the constraint.
+    pub(crate) fn accepts(&self, value: &str) -> bool {
+        match self {
+            Constraint::Regex(regex) => regex.is_match(value),
+            Constraint::Type(parses) => parses(value),
+        }
+    }
+}
+
+macro_rules! parses_as {
+    ($name:expr, $($ty:ident)*) => {{
+        let check: fn(&str) -> bool = match $name {
+            $(stringify!($ty) => |v| v.parse::<$ty>().is_ok(),)*
+            "uuid" => |v| uuid::Uuid::parse_str(v).is_ok(),
+            _ => return None,
+        };
+        Some(check)
+    }};
+}
+
+/// Returns the check for a `:name<type>` constraint.
+fn type_check(name: &str) -> Option<fn(&str) -> bool> {
+    parses_as!(
+        name,
+        i8 i16 i32 i64 i128 isize u8 u16 u32 u64 u128 usize f32 f64 bool char
+    )
+}
+
+/// Splits a pattern into its `/`-separated segments.
+///
+/// A `/` inside a parameter's `(...)` constraint, such as `:name([^/]+)`,
+/// belongs to the expression rather than separating segments.
+fn segments(pattern: &str) -> Vec<&str> {
+    let mut segments = Vec::new();
+    let mut start = 0;
+    let mut depth = 0usize;
+    let mut in_class = false;
+    let mut chars = pattern.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' if depth > 0 => {
+                chars.next();
+            }
+            '[' if depth > 0 => in_class = true,
+            ']' if in_class => in_class = false,
+            '(' if !in_class && pattern[start..].starts_with(':') => depth += 1,
+            ')' if !in_class && depth > 0 => depth -= 1,
+            '/' if depth == 0 => {
+                segments.push(&pattern[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    segments.push(&pattern[start..]);
+    segments
+}
+
+/// Splits the constraints out of a route pattern.
+///
+/// Returns the pattern with the constraints removed, as shown in
+/// introspection and [`MatchedPath`](super::MatchedPath), along with the
+/// compiled constraint of each constrained parameter.
+pub(crate) fn parse_pattern(pattern: &str) -> Result<(String, Vec<(String, Constraint)>), String> {
+    let mut constraints = Vec::new();
+    let segments: Vec<String> = segments(pattern)
+        .into_iter()
+        .map(|segment| {
+            let Some(param) = segment.strip_prefix(':') else {
+                return Ok(segment.to_string());
+            };
+            let Some(start) = param.find(['(', '<']) else {
+                return Ok(segment.to_string());
+            };
+            let (name, spec) = param.split_at(start);
+            let constraint =
+                if let Some(regex) = spec.strip_prefix('(').and_then(|s| s.strip_suffix(')')) {
+                    Regex::new(&format!("^(?:{})$", regex))
+                        .map(Constraint::Regex)
+                        .map_err(|e| format!("invalid constraint on :{}: {}", name, e))?
+                } else if let Some(ty) = spec.strip_prefix('<').and_then(|s| s.strip_suffix('>')) {
+                    type_check(ty).map(Constraint::Type).ok_or_else(|| {
+                        format!("unknown type `{}` in constraint on :{}", ty, name)
+                    })?
+                } else {
+                    return Err(format!("unterminated constraint on :{}", name));
+                };
+            constraints.push((name.to_string(), constraint));
+            Ok(format!(":{}", name))
+        })
+        .collect::<Result<_, String>>()?;
+
+    Ok((segments.join("/"), constraints))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn accepts(pattern: &str, value: &str) -> bool {
+        let (_, constraints) = parse_pattern(pattern).unwrap();
+        constraints[0].1.accepts(value)
+    }
+
+    #[test]
+    fn test_pattern_without_constraints() {
+        let (plain, constraints) = parse_pattern("/users/:id/posts").unwrap();
+        assert_eq!(plain, "/users/:id/posts");
+        assert!(constraints.is_empty());
+    }
+
+    #[test]
+    fn test_constraints_are_stripped() {
+        let (plain, constraints) = parse_pattern(r"/orgs/:org<uuid>/users/:id(\d+)").unwrap();
+        assert_eq!(plain, "/orgs/:org/users/:id");
+        let names: Vec<_> = constraints.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["org", "id"]);
+    }
+
+    #[test]
+    fn test_regex_constraint_matches_whole_segment() {
+        assert!(accepts(r"/:id(\d+)", "42"));
+        assert!(!accepts(r"/:id(\d+)", "42a"));
+        assert!(!accepts(r"/:id(\d+)", "abc"));
+        assert!(accepts("/:lang(en|fr)", "fr"));
+        assert!(!accepts("/:lang(en|fr)", "french"));
+    }
+
+    #[test]
+    fn test_regex_constraint_containing_slash() {
+        let (plain, constraints) = parse_pattern(r"/files/:name([^/(]+\.txt)/raw").unwrap();
+        assert_eq!(plain, "/files/:name/raw");
+        assert!(constraints[0].1.accepts("notes.txt"));
+        assert!(!constraints[0].1.accepts("notes.md"));
+
+        assert!(accepts("/:path(a/b|c)", "c"));
+        assert!(accepts(r"/:id(\(\d+\))", "(42)"));
+    }
+
+    #[test]
+    fn test_type_constraint() {
+        assert!(accepts("/:id<u64>", "42"));
+        assert!(!accepts("/:id<u64>", "-1"));
+        assert!(accepts("/:id<i32>", "-1"));
+        assert!(accepts("/:flag<bool>", "true"));
+        assert!(accepts(
+            "/:id<uuid>",
+            "67e55044-10b1-426f-9247-bb680e5fe0c8"
+        ));
+        assert!(!accepts("/:id<uuid>", "42"));
+    }
+
+    #[test]
+    fn test_invalid_constraints() {
+        let err = parse_pattern("/:id<number>").unwrap_err();
+        assert_eq!(err, "unknown type `number` in constraint on :id");
+        let err = parse_pattern("/:id(").unwrap_err();
+        assert_eq!(err, "unterminated constraint on :id");
+        let err = parse_pattern(r"/:id([)").unwrap_err();
+        assert!(err.starts_with("invalid constraint on :id"), "{}", err);
+    }
+}
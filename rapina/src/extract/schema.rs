@@ -0,0 +1,350 @@
+//! Checks query string and path parameter values against the constraints
+//! of a type's JSON Schema, for the [`SchemaValidated`](super::SchemaValidated)
+//! extractor.
+
+use std::any::TypeId;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, RwLock};
+
+use regex::Regex;
+use schemars::JsonSchema;
+use serde_json::Value;
+use validator::ValidationError;
+
+static SCHEMAS: LazyLock<RwLock<HashMap<TypeId, Arc<ParamSchema>>>> =
+    LazyLock::new(Default::default);
+
+/// A type's JSON Schema with its `pattern`s compiled.
+pub(crate) struct ParamSchema {
+    schema: Value,
+    patterns: HashMap<String, Regex>,
+}
+
+impl ParamSchema {
+    fn new(schema: Value) -> (Self, Vec<(String, regex::Error)>) {
+        let mut patterns = HashMap::new();
+        let mut invalid = Vec::new();
+        compile_patterns(&schema, &mut patterns, &mut invalid);
+        (Self { schema, patterns }, invalid)
+    }
+}
+
+/// Compiles every `pattern` in `schema`, collecting the ones that aren't
+/// valid regexes in `invalid`.
+fn compile_patterns(
+    schema: &Value,
+    patterns: &mut HashMap<String, Regex>,
+    invalid: &mut Vec<(String, regex::Error)>,
+) {
+    match schema {
+        Value::Object(fields) => {
+            if let Some(pattern) = fields.get("pattern").and_then(Value::as_str)
+                && !patterns.contains_key(pattern)
+            {
+                match Regex::new(pattern) {
+                    Ok(regex) => {
+                        patterns.insert(pattern.to_string(), regex);
+                    }
+                    Err(e) => invalid.push((pattern.to_string(), e)),
+                }
+            }
+            for value in fields.values() {
+                compile_patterns(value, patterns, invalid);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                compile_patterns(item, patterns, invalid);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Returns the JSON Schema of `T`, generating it on first use.
+///
+/// A `pattern` that isn't a valid regex is logged then, and values aren't
+/// checked against it.
+pub(crate) fn schema_of<T: JsonSchema + 'static>() -> Arc<ParamSchema> {
+    if let Some(schema) = SCHEMAS.read().unwrap().get(&TypeId::of::<T>()) {
+        return schema.clone();
+    }
+    let (schema, invalid) = ParamSchema::new(schemars::schema_for!(T).to_value());
+    for (pattern, e) in invalid {
+        tracing::error!(
+            schema = std::any::type_name::<T>(),
+            pattern = %pattern,
+            "invalid pattern in JSON Schema, values won't be checked against it: {}",
+            e
+        );
+    }
+    SCHEMAS
+        .write()
+        .unwrap()
+        .entry(TypeId::of::<T>())
+        .or_insert(Arc::new(schema))
+        .clone()
+}
+
+/// Checks raw `(name, value)` pairs against `schema` and returns every
+/// failed constraint with the name it failed on.
+///
+/// Each value is checked against the schema of the property with its name,
+/// with `tags[]` and `tags[0]` checked against the items of `tags`. A schema
+/// without properties, such as that of `Path<u32>`, applies to every value.
+pub(crate) fn check_params<'a>(
+    param_schema: &ParamSchema,
+    params: impl IntoIterator<Item = (&'a str, &'a str)>,
+) -> Vec<(String, ValidationError)> {
+    let schema = &param_schema.schema;
+    let root = resolve(schema, schema);
+    let properties = root.get("properties").and_then(Value::as_object);
+
+    let mut failures = Vec::new();
+    for (name, value) in params {
+        let (base, indexed) = match name.split_once('[') {
+            Some((base, rest)) if rest.ends_with(']') && !rest[..rest.len() - 1].contains('[') => {
+                let index = &rest[..rest.len() - 1];
+                if !index.is_empty() && !index.bytes().all(|b| b.is_ascii_digit()) {
+                    // Nested keys like `address[city]` aren't checked.
+                    continue;
+                }
+                (base, true)
+            }
+            _ => (name, false),
+        };
+
+        let property = match properties {
+            Some(properties) => match properties.get(base) {
+                Some(property) => resolve(schema, property),
+                None => continue,
+            },
+            None => root,
+        };
+        let property = match (indexed, property.get("items")) {
+            (true, Some(items)) => resolve(schema, items),
+            (true, None) => continue,
+            (false, _) => property,
+        };
+
+        for error in check_value(param_schema, property, value) {
+            failures.push((base.to_string(), error));
+        }
+    }
+    failures
+}
+
+/// Follows a local `$ref` such as `#/$defs/Page` to the schema it names.
+fn resolve<'a>(root: &'a Value, schema: &'a Value) -> &'a Value {
+    match schema.get("$ref").and_then(Value::as_str) {
+        Some(reference) => reference
+            .strip_prefix('#')
+            .and_then(|pointer| root.pointer(pointer))
+            .map_or(schema, |target| resolve(root, target)),
+        None => schema,
+    }
+}
+
+/// Returns whether a schema only allows `null`.
+fn is_null(schema: &Value) -> bool {
+    schema.get("type").and_then(Value::as_str) == Some("null")
+}
+
+/// Checks one raw value against the range, length and pattern constraints
+/// of `schema`.
+fn check_value(param_schema: &ParamSchema, schema: &Value, value: &str) -> Vec<ValidationError> {
+    let root = &param_schema.schema;
+    let schema = resolve(root, schema);
+
+    // `Option<T>` is `anyOf: [T, null]`; the value passes if any branch does
+    if let Some(variants) = schema
+        .get("anyOf")
+        .or_else(|| schema.get("oneOf"))
+        .and_then(Value::as_array)
+    {
+        let mut first_failure = None;
+        for variant in variants.iter().filter(|v| !is_null(resolve(root, v))) {
+            let errors = check_value(param_schema, variant, value);
+            if errors.is_empty() {
+                return errors;
+            }
+            first_failure.get_or_insert(errors);
+        }
+        return first_failure.unwrap_or_default();
+    }
+
+    let mut errors: Vec<ValidationError> = schema
+        .get("allOf")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .flat_map(|part| check_value(param_schema, part, value))
+        .collect();
+
+    if let Some(error) = check_range(schema, value) {
+        errors.push(error);
+    }
+    if let Some(error) = check_length(schema, value) {
+        errors.push(error);
+    }
+    if let Some(pattern) = schema.get("pattern").and_then(Value::as_str)
+        && let Some(regex) = param_schema.patterns.get(pattern)
+        && !regex.is_match(value)
+    {
+        errors.push(ValidationError::new("regex"));
+    }
+    errors
+}
+
+fn check_range(schema: &Value, value: &str) -> Option<ValidationError> {
+    let bound = |name: &str| schema.get(name).and_then(Value::as_f64);
+    let (min, max) = (bound("minimum"), bound("maximum"));
+    let (exclusive_min, exclusive_max) = (bound("exclusiveMinimum"), bound("exclusiveMaximum"));
+    if min.is_none() && max.is_none() && exclusive_min.is_none() && exclusive_max.is_none() {
+        return None;
+    }
+    // A value that isn't a number was already rejected when deserializing
+    let number: f64 = value.parse().ok()?;
+
+    let in_range = min.is_none_or(|min| number >= min)
+        && max.is_none_or(|max| number <= max)
+        && exclusive_min.is_none_or(|min| number > min)
+        && exclusive_max.is_none_or(|max| number < max);
+    if in_range {
+        return None;
+    }
+
+    let mut error = ValidationError::new("range");
+    for (param, name) in [
+        ("min", "minimum"),
+        ("max", "maximum"),
+        ("exclusive_min", "exclusiveMinimum"),
+        ("exclusive_max", "exclusiveMaximum"),
+    ] {
+        if let Some(bound) = schema.get(name) {
+            error.add_param(Cow::Borrowed(param), bound);
+        }
+    }
+    Some(error)
+}
+
+fn check_length(schema: &Value, value: &str) -> Option<ValidationError> {
+    let min = schema.get("minLength").and_then(Value::as_u64);
+    let max = schema.get("maxLength").and_then(Value::as_u64);
+    let length = value.chars().count() as u64;
+    if min.is_none_or(|min| length >= min) && max.is_none_or(|max| length <= max) {
+        return None;
+    }
+
+    let mut error = ValidationError::new("length");
+    if let Some(min) = min {
+        error.add_param(Cow::Borrowed("min"), &min);
+    }
+    if let Some(max) = max {
+        error.add_param(Cow::Borrowed("max"), &max);
+    }
+    Some(error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn compiled(schema: Value) -> ParamSchema {
+        let (schema, invalid) = ParamSchema::new(schema);
+        assert!(invalid.is_empty());
+        schema
+    }
+
+    fn codes(failures: &[(String, ValidationError)]) -> Vec<(&str, &str)> {
+        failures
+            .iter()
+            .map(|(field, error)| (field.as_str(), error.code.as_ref()))
+            .collect()
+    }
+
+    #[test]
+    fn test_check_params_range_length_and_pattern() {
+        let schema = compiled(json!({
+            "type": "object",
+            "properties": {
+                "page": { "type": ["integer", "null"], "minimum": 1, "maximum": 100 },
+                "name": { "type": "string", "minLength": 2, "pattern": "^[a-z]+$" },
+                "tags": { "type": "array", "items": { "type": "string", "maxLength": 3 } }
+            }
+        }));
+
+        let ok = check_params(&schema, [("page", "5"), ("name", "ab"), ("tags[]", "abc")]);
+        assert!(ok.is_empty());
+
+        let failures = check_params(
+            &schema,
+            [
+                ("page", "0"),
+                ("name", "A"),
+                ("tags[1]", "abcd"),
+                ("other", "x"),
+            ],
+        );
+        assert_eq!(
+            codes(&failures),
+            [
+                ("page", "range"),
+                ("name", "length"),
+                ("name", "regex"),
+                ("tags", "length")
+            ]
+        );
+        assert_eq!(failures[0].1.params["min"], json!(1));
+        assert_eq!(failures[0].1.params["max"], json!(100));
+    }
+
+    #[test]
+    fn test_check_params_follows_refs_and_options() {
+        let schema = compiled(json!({
+            "type": "object",
+            "properties": {
+                "size": { "anyOf": [{ "$ref": "#/$defs/Size" }, { "type": "null" }] }
+            },
+            "$defs": { "Size": { "type": "integer", "exclusiveMaximum": 10 } }
+        }));
+
+        assert!(check_params(&schema, [("size", "9")]).is_empty());
+        assert_eq!(
+            codes(&check_params(&schema, [("size", "10")])),
+            [("size", "range")]
+        );
+    }
+
+    #[test]
+    fn test_check_params_without_properties() {
+        let schema = compiled(json!({ "type": "integer", "minimum": 1 }));
+        assert_eq!(
+            codes(&check_params(&schema, [("id", "0")])),
+            [("id", "range")]
+        );
+    }
+
+    #[test]
+    fn test_invalid_pattern_is_reported_when_compiled() {
+        let (schema, invalid) = ParamSchema::new(json!({
+            "type": "object",
+            "properties": {
+                "code": { "type": "string", "pattern": "^[a-z" },
+                "slug": { "$ref": "#/$defs/Slug" }
+            },
+            "$defs": { "Slug": { "type": "string", "pattern": "^[a-z-]+$" } }
+        }));
+        assert_eq!(invalid.len(), 1);
+        assert_eq!(invalid[0].0, "^[a-z");
+        assert!(schema.patterns.contains_key("^[a-z-]+$"));
+
+        assert!(check_params(&schema, [("code", "ABC"), ("slug", "a-b")]).is_empty());
+        assert_eq!(
+            codes(&check_params(&schema, [("slug", "A B")])),
+            [("slug", "regex")]
+        );
+    }
+}
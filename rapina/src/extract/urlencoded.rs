@@ -0,0 +1,749 @@
+//! URL-encoded data deserialization for query strings and form bodies.
+//!
+//! `serde_urlencoded` rejects a key that appears more than once and has no
+//! notion of nesting, so `tag=a&tag=b` can't fill a `Vec<String>` and
+//! `user[name]=x` can't fill a nested struct. This parser builds a tree of
+//! the keys first:
+//!
+//! - a repeated key, or one ending in `[]`, collects its values in order
+//! - `user[name]` nests `name` under `user`, up to [`MAX_DEPTH`] levels
+//! - `tags[0]`, `tags[1]` give a sequence ordered by index
+//!
+//! Sequence fields take every value of a key and other fields take its
+//! single value.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use serde::de::value::{CowStrDeserializer, MapDeserializer, SeqDeserializer};
+use serde::de::{self, DeserializeOwned, Deserializer, IntoDeserializer, Visitor};
+use serde::forward_to_deserialize_any;
+
+use super::field_error::FieldError as Error;
+
+/// How many levels of brackets a key may nest, e.g. `a[b][c][d][e][f]`.
+///
+/// Deeper keys are rejected so a crafted key can't make building and
+/// deserializing the tree recurse without bound.
+const MAX_DEPTH: usize = 5;
+
+/// Deserializes URL-encoded data, collecting repeated keys into sequences
+/// and bracketed keys into nested values.
+pub(crate) fn from_bytes<T: DeserializeOwned>(input: &[u8]) -> Result<T, Error> {
+    let mut root = Entries::default();
+    for (key, value) in form_urlencoded::parse(input) {
+        let segments = key_segments(&key)?;
+        root.insert(&segments, value)?;
+    }
+    T::deserialize(Node::Map(root))
+}
+
+/// Deserializes a query string. See [`from_bytes`].
+pub(crate) fn from_str<T: DeserializeOwned>(query: &str) -> Result<T, Error> {
+    from_bytes(query.as_bytes())
+}
+
+/// Splits `user[address][city]` into `["user", "address", "city"]`.
+///
+/// A trailing `[]` is dropped, since appending is what a repeated key does
+/// anyway. A key whose brackets don't close is used as is, and one nested
+/// deeper than [`MAX_DEPTH`] is an error.
+fn key_segments(key: &str) -> Result<Vec<String>, Error> {
+    let key = key.strip_suffix("[]").unwrap_or(key);
+    let Some(open) = key.find('[').filter(|&i| i > 0 && key.ends_with(']')) else {
+        return Ok(vec![key.to_string()]);
+    };
+
+    let mut segments = vec![key[..open].to_string()];
+    for segment in key[open + 1..key.len() - 1].split("][") {
+        if segment.contains(['[', ']']) {
+            return Ok(vec![key.to_string()]);
+        }
+        if segments.len() > MAX_DEPTH {
+            return Err(de::Error::custom(format!(
+                "key '{}' is nested more than {} levels deep",
+                &key[..open],
+                MAX_DEPTH
+            )));
+        }
+        segments.push(segment.to_string());
+    }
+    Ok(segments)
+}
+
+/// A key's value in the tree: its raw values, or the nested keys under it.
+enum Node<'a> {
+    Values(Vec<Cow<'a, str>>),
+    Map(Entries<'a>),
+}
+
+/// The keys under a node in the order they first appeared, indexed by name
+/// so many sibling keys stay cheap to look up.
+#[derive(Default)]
+struct Entries<'a> {
+    list: Vec<(String, Node<'a>)>,
+    positions: HashMap<String, usize>,
+}
+
+impl<'a> Entries<'a> {
+    fn insert(&mut self, segments: &[String], value: Cow<'a, str>) -> Result<(), Error> {
+        let (first, rest) = segments
+            .split_first()
+            .expect("a key has at least one segment");
+        let index = match self.positions.get(first) {
+            Some(&index) => index,
+            None => {
+                let node = if rest.is_empty() {
+                    Node::Values(Vec::new())
+                } else {
+                    Node::Map(Entries::default())
+                };
+                self.list.push((first.clone(), node));
+                self.positions.insert(first.clone(), self.list.len() - 1);
+                self.list.len() - 1
+            }
+        };
+
+        match (&mut self.list[index].1, rest.is_empty()) {
+            (Node::Values(values), true) => {
+                values.push(value);
+                Ok(())
+            }
+            (Node::Map(children), false) => children.insert(rest, value),
+            _ => Err(de::Error::custom(format!(
+                "key '{}' is used both as a value and with nested fields",
+                first
+            ))),
+        }
+    }
+}
+
+impl<'de> IntoDeserializer<'de, Error> for Node<'de> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+macro_rules! deserialize_node {
+    ($($method:ident)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+                match self {
+                    Node::Values(values) => Values(values).$method(visitor),
+                    Node::Map(_) => self.deserialize_any(visitor),
+                }
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for Node<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self {
+            Node::Values(values) => Values(values).deserialize_any(visitor),
+            Node::Map(entries) => visitor.visit_map(MapDeserializer::new(
+                entries
+                    .list
+                    .into_iter()
+                    .map(|(key, node)| (Part(Cow::Owned(key.clone())), Keyed { key, node })),
+            )),
+        }
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self {
+            Node::Values(values) => Values(values).deserialize_seq(visitor),
+            Node::Map(entries) => {
+                // `tags[1]=b&tags[0]=a` is a sequence ordered by index
+                let mut items = entries
+                    .list
+                    .into_iter()
+                    .map(|(key, node)| match key.parse::<usize>() {
+                        Ok(index) => Ok((index, node)),
+                        Err(_) => Err(de::Error::custom(format!(
+                            "expected a sequence index, found key '{}'",
+                            key
+                        ))),
+                    })
+                    .collect::<Result<Vec<_>, Error>>()?;
+                items.sort_by_key(|(index, _)| *index);
+
+                let mut seq = SeqDeserializer::new(items.into_iter().map(|(_, node)| node));
+                let value = visitor.visit_seq(&mut seq)?;
+                seq.end()?;
+                Ok(value)
+            }
+        }
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        match self {
+            Node::Values(values) => Values(values).deserialize_enum(name, variants, visitor),
+            Node::Map(_) => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        match self {
+            Node::Values(values) => Values(values).deserialize_unit_struct(name, visitor),
+            Node::Map(_) => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        match self {
+            Node::Values(values) => Values(values).deserialize_struct(name, fields, visitor),
+            Node::Map(_) => self.deserialize_any(visitor),
+        }
+    }
+
+    deserialize_node! {
+        deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64
+        deserialize_i128 deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64
+        deserialize_u128 deserialize_f32 deserialize_f64 deserialize_char deserialize_str
+        deserialize_string deserialize_bytes deserialize_byte_buf deserialize_unit
+        deserialize_map deserialize_identifier
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+}
+
+/// The value under one key; errors while reading it are tagged with the key.
+struct Keyed<'a> {
+    key: String,
+    node: Node<'a>,
+}
+
+impl<'de> IntoDeserializer<'de, Error> for Keyed<'de> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+macro_rules! deserialize_keyed {
+    ($($method:ident)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+                let key = self.key;
+                self.node.$method(visitor).map_err(|e| e.at(&key))
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for Keyed<'de> {
+    type Error = Error;
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, Error> {
+        let key = self.key;
+        self.node
+            .deserialize_tuple(len, visitor)
+            .map_err(|e| e.at(&key))
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        let key = self.key;
+        self.node
+            .deserialize_tuple_struct(name, len, visitor)
+            .map_err(|e| e.at(&key))
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        let key = self.key;
+        self.node
+            .deserialize_newtype_struct(name, visitor)
+            .map_err(|e| e.at(&key))
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        let key = self.key;
+        self.node
+            .deserialize_enum(name, variants, visitor)
+            .map_err(|e| e.at(&key))
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        let key = self.key;
+        self.node
+            .deserialize_unit_struct(name, visitor)
+            .map_err(|e| e.at(&key))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        let key = self.key;
+        self.node
+            .deserialize_struct(name, fields, visitor)
+            .map_err(|e| e.at(&key))
+    }
+
+    deserialize_keyed! {
+        deserialize_any deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32
+        deserialize_i64 deserialize_i128 deserialize_u8 deserialize_u16 deserialize_u32
+        deserialize_u64 deserialize_u128 deserialize_f32 deserialize_f64 deserialize_char
+        deserialize_str deserialize_string deserialize_bytes deserialize_byte_buf
+        deserialize_option deserialize_unit deserialize_seq deserialize_map
+        deserialize_identifier deserialize_ignored_any
+    }
+}
+
+/// A single key or value from URL-encoded data.
+///
+/// Also used for path parameters, which are parsed the same way.
+pub(super) struct Part<'a>(pub(super) Cow<'a, str>);
+
+impl<'de> IntoDeserializer<'de, Error> for Part<'de> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+macro_rules! deserialize_parsed {
+    ($($method:ident => $visit:ident,)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+                match self.0.parse() {
+                    Ok(value) => visitor.$visit(value),
+                    Err(_) => self.deserialize_any(visitor),
+                }
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for Part<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.0 {
+            Cow::Borrowed(value) => visitor.visit_borrowed_str(value),
+            Cow::Owned(value) => visitor.visit_string(value),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        CowStrDeserializer::new(self.0).deserialize_enum(name, variants, visitor)
+    }
+
+    deserialize_parsed! {
+        deserialize_bool => visit_bool,
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_u128 => visit_u128,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+        deserialize_char => visit_char,
+    }
+
+    forward_to_deserialize_any! {
+        str string bytes byte_buf unit unit_struct seq tuple tuple_struct map
+        struct identifier ignored_any
+    }
+}
+
+/// Every value given for one key, in order.
+struct Values<'a>(Vec<Cow<'a, str>>);
+
+impl<'de> Values<'de> {
+    /// Returns the only value, failing if the key was repeated.
+    fn single(self) -> Result<Part<'de>, Error> {
+        let mut values = self.0.into_iter();
+        match (values.next(), values.next()) {
+            (Some(value), None) => Ok(Part(value)),
+            _ => Err(de::Error::custom(
+                "expected a single value, found a repeated key",
+            )),
+        }
+    }
+}
+
+impl<'de> IntoDeserializer<'de, Error> for Values<'de> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+macro_rules! deserialize_single {
+    ($($method:ident)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+                self.single()?.$method(visitor)
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for Values<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        if self.0.len() == 1 {
+            self.single()?.deserialize_any(visitor)
+        } else {
+            self.deserialize_seq(visitor)
+        }
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let mut seq = SeqDeserializer::new(self.0.into_iter().map(Part));
+        let value = visitor.visit_seq(&mut seq)?;
+        seq.end()?;
+        Ok(value)
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.single()?.deserialize_enum(name, variants, visitor)
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.single()?.deserialize_unit_struct(name, visitor)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.single()?.deserialize_struct(name, fields, visitor)
+    }
+
+    deserialize_single! {
+        deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64
+        deserialize_i128 deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64
+        deserialize_u128 deserialize_f32 deserialize_f64 deserialize_char deserialize_str
+        deserialize_string deserialize_bytes deserialize_byte_buf deserialize_unit
+        deserialize_map deserialize_identifier
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[serde(rename_all = "lowercase")]
+    enum Order {
+        Asc,
+        Desc,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Params {
+        #[serde(default)]
+        tag: Vec<String>,
+        page: Option<u32>,
+        order: Option<Order>,
+    }
+
+    #[test]
+    fn test_repeated_keys_into_vec() {
+        let params: Params = from_str("tag=a&tag=b&tag=c").unwrap();
+        assert_eq!(params.tag, vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_single_value_into_vec() {
+        let params: Params = from_str("tag=a").unwrap();
+        assert_eq!(params.tag, vec!["a"]);
+    }
+
+    #[test]
+    fn test_missing_vec_uses_default() {
+        let params: Params = from_str("page=2").unwrap();
+        assert!(params.tag.is_empty());
+        assert_eq!(params.page, Some(2));
+    }
+
+    #[test]
+    fn test_mixed_single_and_repeated_keys() {
+        let params: Params = from_str("tag=a&page=3&tag=b&order=desc").unwrap();
+        assert_eq!(
+            params,
+            Params {
+                tag: vec!["a".to_string(), "b".to_string()],
+                page: Some(3),
+                order: Some(Order::Desc),
+            }
+        );
+    }
+
+    #[test]
+    fn test_percent_decoding() {
+        let params: Params = from_str("tag=hello%20world&tag=a+b").unwrap();
+        assert_eq!(params.tag, vec!["hello world", "a b"]);
+    }
+
+    #[test]
+    fn test_vec_of_numbers() {
+        #[derive(Deserialize)]
+        struct Ids {
+            id: Vec<u64>,
+        }
+
+        let ids: Ids = from_str("id=1&id=2").unwrap();
+        assert_eq!(ids.id, vec![1, 2]);
+        assert!(from_str::<Ids>("id=1&id=x").is_err());
+    }
+
+    #[test]
+    fn test_repeated_key_for_single_field_is_error() {
+        let err = from_str::<Params>("page=1&page=2").unwrap_err();
+        assert!(err.to_string().contains("repeated key"), "{}", err);
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Address {
+        city: String,
+        zip: Option<String>,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Signup {
+        name: String,
+        address: Address,
+        #[serde(default)]
+        tags: Vec<String>,
+    }
+
+    #[test]
+    fn test_key_segments() {
+        let key_segments = |key| key_segments(key).unwrap();
+        assert_eq!(key_segments("name"), vec!["name"]);
+        assert_eq!(key_segments("tags[]"), vec!["tags"]);
+        assert_eq!(
+            key_segments("user[address][city]"),
+            vec!["user", "address", "city"]
+        );
+        assert_eq!(key_segments("items[0][name]"), vec!["items", "0", "name"]);
+        assert_eq!(key_segments("odd[key"), vec!["odd[key"]);
+        assert_eq!(key_segments("[name]"), vec!["[name]"]);
+    }
+
+    #[test]
+    fn test_nested_struct() {
+        let signup: Signup =
+            from_str("name=Ada&address[city]=London&address[zip]=N1&tags[]=a&tags[]=b").unwrap();
+        assert_eq!(
+            signup,
+            Signup {
+                name: "Ada".to_string(),
+                address: Address {
+                    city: "London".to_string(),
+                    zip: Some("N1".to_string()),
+                },
+                tags: vec!["a".to_string(), "b".to_string()],
+            }
+        );
+    }
+
+    #[test]
+    fn test_indexed_sequence_of_structs() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Item {
+            name: String,
+            qty: u32,
+        }
+
+        #[derive(Debug, Deserialize)]
+        struct Order {
+            items: Vec<Item>,
+        }
+
+        let order: Order =
+            from_str("items[1][name]=pen&items[0][name]=book&items[0][qty]=2&items[1][qty]=5")
+                .unwrap();
+        assert_eq!(
+            order.items,
+            vec![
+                Item {
+                    name: "book".to_string(),
+                    qty: 2
+                },
+                Item {
+                    name: "pen".to_string(),
+                    qty: 5
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_percent_encoded_brackets() {
+        let signup: Signup = from_str("name=Ada&address%5Bcity%5D=Paris").unwrap();
+        assert_eq!(signup.address.city, "Paris");
+    }
+
+    #[test]
+    fn test_value_and_nested_key_conflict() {
+        let err = from_str::<Signup>("address=x&address[city]=y").unwrap_err();
+        assert!(err.to_string().contains("'address'"), "{}", err);
+    }
+
+    #[test]
+    fn test_nesting_depth_is_limited() {
+        let map: std::collections::HashMap<String, serde_json::Value> =
+            from_str("a[b][c][d][e][f]=1").unwrap();
+        assert_eq!(map["a"]["b"]["c"]["d"]["e"]["f"], "1");
+
+        let err = from_str::<std::collections::HashMap<String, serde_json::Value>>(
+            "a[b][c][d][e][f][g]=1",
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("more than 5 levels"), "{}", err);
+
+        let deep = format!("a{}=1", "[x]".repeat(5000));
+        assert!(from_str::<std::collections::HashMap<String, String>>(&deep).is_err());
+    }
+
+    #[test]
+    fn test_map_of_strings() {
+        let map: std::collections::HashMap<String, String> = from_str("a=1&b=2").unwrap();
+        assert_eq!(map["a"], "1");
+        assert_eq!(map["b"], "2");
+    }
+}
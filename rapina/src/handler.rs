@@ -8,8 +8,9 @@ use http::Request;
 use hyper::body::Incoming;
 
 use crate::error::ErrorVariant;
-use crate::extract::PathParams;
-use crate::response::BoxBody;
+use crate::extract::{FromRequest, FromRequestParts, PathParams, rejection_response};
+use crate::introspection::ResponseInfo;
+use crate::response::{BoxBody, IntoResponse};
 use crate::state::AppState;
 
 type BoxFuture = Pin<Box<dyn Future<Output = hyper::Response<BoxBody>> + Send>>;
@@ -32,6 +33,131 @@ pub trait Handler: Clone + Send + Sync + 'static {
         Vec::new()
     }
 
+    /// Whether the handler is marked `#[deprecated]`.
+    const DEPRECATED: bool = false;
+
+    /// Whether the handler is marked `#[openapi(hidden)]`.
+    const HIDDEN: bool = false;
+
+    /// Tags declared with `#[tag(...)]`.
+    const TAGS: &'static [&'static str] = &[];
+
+    /// Success responses declared with `#[responses(...)]`.
+    fn responses() -> Vec<ResponseInfo> {
+        Vec::new()
+    }
+
+    /// JSON Schema for the request body (if available).
+    fn request_schema() -> Option<serde_json::Value> {
+        None
+    }
+
+    /// Example request body declared with `#[example(request = "...")]`.
+    fn request_example() -> Option<serde_json::Value> {
+        None
+    }
+
+    /// JSON Schema for the query string (if available).
+    fn query_schema() -> Option<serde_json::Value> {
+        None
+    }
+
+    /// JSON Schema for the path parameter (if available).
+    fn path_schema() -> Option<serde_json::Value> {
+        None
+    }
+
     /// Handle the request.
     fn call(&self, req: Request<Incoming>, params: PathParams, state: Arc<AppState>) -> BoxFuture;
 }
+
+/// A function or closure that takes extractors as arguments.
+///
+/// Implemented for async functions and closures with up to six extractor
+/// arguments, so routes can be registered inline without the route macros
+/// (see [`Router::get_fn`](crate::router::Router::get_fn)). As with the
+/// macros, every argument but the last must implement [`FromRequestParts`];
+/// the last may consume the body via [`FromRequest`]. Extraction failures are
+/// returned as error responses without calling the function.
+///
+/// The `Args` parameter is the tuple of argument types and only exists to
+/// keep the implementations for different arities apart.
+///
+/// # Examples
+///
+/// ```
+/// use rapina::prelude::*;
+///
+/// let router = Router::new()
+///     .get_fn("/", || async { "Hello!" })
+///     .get_fn("/users/:id", |id: Path<u64>| async move {
+///         format!("User {}", id.into_inner())
+///     });
+/// ```
+pub trait HandlerFn<Args>: Clone + Send + Sync + 'static {
+    /// Extracts the arguments from the request and calls the function.
+    fn call(&self, req: Request<Incoming>, params: PathParams, state: Arc<AppState>) -> BoxFuture;
+}
+
+impl<F, Fut, Out> HandlerFn<()> for F
+where
+    F: Fn() -> Fut + Clone + Send + Sync + 'static,
+    Fut: Future<Output = Out> + Send + 'static,
+    Out: IntoResponse,
+{
+    fn call(
+        &self,
+        _req: Request<Incoming>,
+        _params: PathParams,
+        _state: Arc<AppState>,
+    ) -> BoxFuture {
+        let f = self.clone();
+        Box::pin(async move { f().await.into_response() })
+    }
+}
+
+macro_rules! impl_handler_fn {
+    ($($parts:ident),* ; $last:ident) => {
+        #[allow(non_snake_case)]
+        impl<F, Fut, Out, $($parts,)* $last> HandlerFn<($($parts,)* $last,)> for F
+        where
+            F: Fn($($parts,)* $last) -> Fut + Clone + Send + Sync + 'static,
+            Fut: Future<Output = Out> + Send + 'static,
+            Out: IntoResponse,
+            $($parts: FromRequestParts + 'static,)*
+            $last: FromRequest + Send + 'static,
+        {
+            fn call(
+                &self,
+                req: Request<Incoming>,
+                params: PathParams,
+                state: Arc<AppState>,
+            ) -> BoxFuture {
+                let f = self.clone();
+                Box::pin(async move {
+                    #[allow(unused_variables, unused_mut)]
+                    let (mut parts, body) = req.into_parts();
+                    $(
+                        let $parts = match $parts::from_request_parts(&parts, &params, &state).await {
+                            Ok(v) => v,
+                            Err(e) => return rejection_response(e, &state),
+                        };
+                    )*
+                    let req = Request::from_parts(parts, body);
+                    let $last = match $last::from_request(req, &params, &state).await {
+                        Ok(v) => v,
+                        Err(e) => return rejection_response(e, &state),
+                    };
+                    f($($parts,)* $last).await.into_response()
+                })
+            }
+        }
+    };
+}
+
+impl_handler_fn!(; T1);
+impl_handler_fn!(T1; T2);
+impl_handler_fn!(T1, T2; T3);
+impl_handler_fn!(T1, T2, T3; T4);
+impl_handler_fn!(T1, T2, T3, T4; T5);
+impl_handler_fn!(T1, T2, T3, T4, T5; T6);
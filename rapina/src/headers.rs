@@ -0,0 +1,303 @@
+//! Typed representations of common HTTP headers.
+//!
+//! These types are used with the [`TypedHeader`](crate::extract::TypedHeader)
+//! extractor to parse headers into structured values instead of
+//! string-matching on raw [`HeaderValue`]s.
+//!
+//! # Examples
+//!
+//! ```ignore
+//! use rapina::prelude::*;
+//! use rapina::headers::{Authorization, Bearer};
+//!
+//! #[get("/me")]
+//! async fn me(auth: TypedHeader<Authorization<Bearer>>) -> String {
+//!     format!("token: {}", auth.0.token())
+//! }
+//! ```
+
+use http::{HeaderName, HeaderValue, header};
+
+/// A header that can be decoded from a [`HeaderValue`].
+///
+/// Implement this trait to use your own header types with
+/// [`TypedHeader`](crate::extract::TypedHeader).
+pub trait Header: Sized {
+    /// The name of the header.
+    const NAME: HeaderName;
+
+    /// Decodes the header from its raw value.
+    ///
+    /// Returns a human-readable reason if the value is malformed.
+    fn decode(value: &HeaderValue) -> Result<Self, String>;
+}
+
+fn value_str(value: &HeaderValue) -> Result<&str, String> {
+    value
+        .to_str()
+        .map_err(|_| "header value is not valid ASCII".to_string())
+}
+
+/// An authentication scheme for the [`Authorization`] header.
+pub trait Credentials: Sized {
+    /// The scheme name (e.g., "Bearer").
+    const SCHEME: &'static str;
+
+    /// Decodes the credentials that follow the scheme name.
+    fn decode(credentials: &str) -> Result<Self, String>;
+}
+
+/// The `Authorization` header, parameterized by its scheme.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Authorization<C>(pub C);
+
+impl<C> Authorization<C> {
+    /// Consumes the header and returns the credentials.
+    pub fn into_inner(self) -> C {
+        self.0
+    }
+}
+
+impl<C: Credentials> Header for Authorization<C> {
+    const NAME: HeaderName = header::AUTHORIZATION;
+
+    fn decode(value: &HeaderValue) -> Result<Self, String> {
+        let value = value_str(value)?;
+        let (scheme, credentials) = value
+            .split_once(' ')
+            .ok_or_else(|| format!("expected '{} <credentials>'", C::SCHEME))?;
+
+        if !scheme.eq_ignore_ascii_case(C::SCHEME) {
+            return Err(format!("expected '{}' scheme, got '{}'", C::SCHEME, scheme));
+        }
+
+        C::decode(credentials.trim()).map(Authorization)
+    }
+}
+
+/// Bearer token credentials (`Authorization: Bearer <token>`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bearer {
+    token: String,
+}
+
+impl Bearer {
+    /// Returns the bearer token.
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+}
+
+impl Credentials for Bearer {
+    const SCHEME: &'static str = "Bearer";
+
+    fn decode(credentials: &str) -> Result<Self, String> {
+        if credentials.is_empty() || credentials.contains(' ') {
+            return Err("bearer token must be a single non-empty value".to_string());
+        }
+        Ok(Bearer {
+            token: credentials.to_string(),
+        })
+    }
+}
+
+/// The `Content-Type` header.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContentType(pub String);
+
+impl ContentType {
+    /// Returns the media type without parameters (e.g., "application/json").
+    pub fn mime(&self) -> &str {
+        self.0.split(';').next().unwrap_or("").trim()
+    }
+
+    /// Returns true if the media type is JSON (`application/json` or `+json`).
+    pub fn is_json(&self) -> bool {
+        let mime = self.mime();
+        mime.eq_ignore_ascii_case("application/json") || mime.ends_with("+json")
+    }
+}
+
+impl Header for ContentType {
+    const NAME: HeaderName = header::CONTENT_TYPE;
+
+    fn decode(value: &HeaderValue) -> Result<Self, String> {
+        let value = value_str(value)?;
+        let mime = value.split(';').next().unwrap_or("").trim();
+        match mime.split_once('/') {
+            Some((ty, subty)) if !ty.is_empty() && !subty.is_empty() => {
+                Ok(ContentType(value.to_string()))
+            }
+            _ => Err(format!("'{}' is not a valid media type", value)),
+        }
+    }
+}
+
+/// A single media range from an `Accept` header.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MediaRange {
+    /// The media type, e.g. "application/json" or "text/*".
+    pub mime: String,
+    /// The quality value in the range 0.0..=1.0.
+    pub quality: f32,
+}
+
+impl MediaRange {
+    /// Returns true if this range matches the given media type.
+    pub fn matches(&self, mime: &str) -> bool {
+        if self.mime == "*/*" {
+            return true;
+        }
+        match self.mime.strip_suffix("/*") {
+            Some(ty) => mime
+                .split_once('/')
+                .is_some_and(|(other, _)| other.eq_ignore_ascii_case(ty)),
+            None => self.mime.eq_ignore_ascii_case(mime),
+        }
+    }
+}
+
+/// The `Accept` header, with media ranges ordered by preference.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Accept(pub Vec<MediaRange>);
+
+impl Accept {
+    /// Returns the media ranges, most preferred first.
+    pub fn media_ranges(&self) -> &[MediaRange] {
+        &self.0
+    }
+
+    /// Returns true if the given media type is acceptable.
+    pub fn accepts(&self, mime: &str) -> bool {
+        self.0.iter().any(|r| r.quality > 0.0 && r.matches(mime))
+    }
+}
+
+impl Header for Accept {
+    const NAME: HeaderName = header::ACCEPT;
+
+    fn decode(value: &HeaderValue) -> Result<Self, String> {
+        let value = value_str(value)?;
+        let mut ranges = Vec::new();
+
+        for item in value.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            let mut params = item.split(';').map(str::trim);
+            let mime = params.next().unwrap_or("");
+            if !mime.contains('/') {
+                return Err(format!("'{}' is not a valid media range", mime));
+            }
+
+            let mut quality = 1.0;
+            for param in params {
+                if let Some(q) = param.strip_prefix("q=") {
+                    quality = q
+                        .parse::<f32>()
+                        .ok()
+                        .filter(|q| (0.0..=1.0).contains(q))
+                        .ok_or_else(|| format!("invalid quality value '{}'", q))?;
+                }
+            }
+
+            ranges.push(MediaRange {
+                mime: mime.to_string(),
+                quality,
+            });
+        }
+
+        // Stable sort keeps the client's order among equal qualities
+        ranges.sort_by(|a, b| b.quality.total_cmp(&a.quality));
+        Ok(Accept(ranges))
+    }
+}
+
+/// The `User-Agent` header.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UserAgent(pub String);
+
+impl UserAgent {
+    /// Returns the user agent string.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Header for UserAgent {
+    const NAME: HeaderName = header::USER_AGENT;
+
+    fn decode(value: &HeaderValue) -> Result<Self, String> {
+        value_str(value).map(|v| UserAgent(v.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bearer_decode() {
+        let value = HeaderValue::from_static("Bearer abc.def.ghi");
+        let auth = Authorization::<Bearer>::decode(&value).unwrap();
+        assert_eq!(auth.0.token(), "abc.def.ghi");
+    }
+
+    #[test]
+    fn test_bearer_scheme_case_insensitive() {
+        let value = HeaderValue::from_static("bearer token123");
+        let auth = Authorization::<Bearer>::decode(&value).unwrap();
+        assert_eq!(auth.0.token(), "token123");
+    }
+
+    #[test]
+    fn test_bearer_wrong_scheme() {
+        let value = HeaderValue::from_static("Basic dXNlcjpwYXNz");
+        assert!(Authorization::<Bearer>::decode(&value).is_err());
+    }
+
+    #[test]
+    fn test_bearer_missing_token() {
+        let value = HeaderValue::from_static("Bearer");
+        assert!(Authorization::<Bearer>::decode(&value).is_err());
+    }
+
+    #[test]
+    fn test_content_type_mime() {
+        let value = HeaderValue::from_static("application/json; charset=utf-8");
+        let ct = ContentType::decode(&value).unwrap();
+        assert_eq!(ct.mime(), "application/json");
+        assert!(ct.is_json());
+    }
+
+    #[test]
+    fn test_content_type_invalid() {
+        let value = HeaderValue::from_static("json");
+        assert!(ContentType::decode(&value).is_err());
+    }
+
+    #[test]
+    fn test_accept_sorted_by_quality() {
+        let value = HeaderValue::from_static("text/html;q=0.5, application/json, */*;q=0.1");
+        let accept = Accept::decode(&value).unwrap();
+        let mimes: Vec<_> = accept.media_ranges().iter().map(|r| &r.mime).collect();
+        assert_eq!(mimes, ["application/json", "text/html", "*/*"]);
+    }
+
+    #[test]
+    fn test_accept_wildcards() {
+        let value = HeaderValue::from_static("text/*");
+        let accept = Accept::decode(&value).unwrap();
+        assert!(accept.accepts("text/plain"));
+        assert!(!accept.accepts("application/json"));
+    }
+
+    #[test]
+    fn test_accept_invalid_quality() {
+        let value = HeaderValue::from_static("application/json;q=2");
+        assert!(Accept::decode(&value).is_err());
+    }
+
+    #[test]
+    fn test_user_agent() {
+        let value = HeaderValue::from_static("curl/8.0");
+        assert_eq!(UserAgent::decode(&value).unwrap().as_str(), "curl/8.0");
+    }
+}
@@ -4,6 +4,7 @@ use std::sync::Arc;
 
 use http::{Request, Response, StatusCode};
 use hyper::body::Incoming;
+use serde::Serialize;
 
 use crate::extract::PathParams;
 use crate::introspection::RouteInfo;
@@ -36,6 +37,87 @@ impl RouteRegistry {
     }
 }
 
+/// Application metadata served by `GET /__rapina/info`.
+///
+/// Collected when the app starts, after all middleware has been added.
+#[derive(Debug, Clone, Serialize)]
+pub struct AppInfo {
+    /// Version of the rapina crate the app was built with.
+    pub rapina_version: &'static str,
+    /// Title of the application, as configured with [`Rapina::openapi`](crate::app::Rapina::openapi).
+    pub title: String,
+    /// Version of the application, as configured with [`Rapina::openapi`](crate::app::Rapina::openapi).
+    pub version: String,
+    /// Names of the active middleware, in the order they run.
+    pub middleware: Vec<String>,
+    /// Which built-in features are enabled.
+    pub features: AppFeatures,
+}
+
+/// Built-in features reported by [`AppInfo`].
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AppFeatures {
+    /// JWT authentication is configured.
+    pub auth: bool,
+    /// CORS headers are added.
+    pub cors: bool,
+    /// Responses are compressed.
+    pub compression: bool,
+    /// Requests are rate limited.
+    pub rate_limit: bool,
+    /// The Prometheus `/metrics` endpoint is served.
+    pub metrics: bool,
+    /// The OpenAPI spec is served.
+    pub openapi: bool,
+}
+
+impl AppInfo {
+    /// Creates the info for an app running the given middleware.
+    pub fn new(
+        title: impl Into<String>,
+        version: impl Into<String>,
+        middleware: Vec<String>,
+    ) -> Self {
+        let has = |name: &str| middleware.iter().any(|m| m == name);
+        let features = AppFeatures {
+            auth: has("AuthMiddleware"),
+            cors: has("CorsMiddleware"),
+            compression: has("CompressionMiddleware"),
+            rate_limit: has("RateLimitMiddleware"),
+            metrics: has("MetricsMiddleware"),
+            openapi: false,
+        };
+        Self {
+            rapina_version: env!("CARGO_PKG_VERSION"),
+            title: title.into(),
+            version: version.into(),
+            middleware,
+            features,
+        }
+    }
+}
+
+/// Handler for the app info endpoint.
+///
+/// Returns the [`AppInfo`] collected at startup as JSON.
+pub async fn app_info(
+    _req: Request<Incoming>,
+    _params: PathParams,
+    state: Arc<AppState>,
+) -> Response<BoxBody> {
+    match state.get::<AppInfo>() {
+        Some(info) => {
+            let json = serde_json::to_vec(info).unwrap_or_default();
+            Response::builder()
+                .status(StatusCode::OK)
+                .header("content-type", "application/json")
+                .body(BoxBody::from(json))
+                .unwrap()
+        }
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
 /// Handler for the introspection endpoint.
 ///
 /// Returns all registered routes as JSON.
@@ -52,7 +134,7 @@ pub async fn list_routes(
             Response::builder()
                 .status(StatusCode::OK)
                 .header("content-type", "application/json")
-                .body(http_body_util::Full::new(bytes::Bytes::from(json)))
+                .body(BoxBody::from(json))
                 .unwrap()
         }
         None => StatusCode::NOT_FOUND.into_response(),
@@ -107,6 +189,24 @@ mod tests {
         assert_eq!(registry.routes()[1].method, "POST");
     }
 
+    #[test]
+    fn test_app_info_features_from_middleware() {
+        let info = AppInfo::new(
+            "API",
+            "1.0.0",
+            vec![
+                "TraceIdMiddleware".to_string(),
+                "CorsMiddleware".to_string(),
+                "AuthMiddleware".to_string(),
+            ],
+        );
+        assert!(info.features.cors);
+        assert!(info.features.auth);
+        assert!(!info.features.compression);
+        assert!(!info.features.rate_limit);
+        assert_eq!(info.rapina_version, env!("CARGO_PKG_VERSION"));
+    }
+
     #[test]
     fn test_route_registry_debug() {
         let registry = RouteRegistry::new();
@@ -5,6 +5,9 @@
 
 mod endpoint;
 mod route_info;
+mod schema;
 
-pub use endpoint::{RouteRegistry, list_routes};
-pub use route_info::RouteInfo;
+pub use endpoint::{AppFeatures, AppInfo, RouteRegistry, app_info, list_routes};
+pub use route_info::{ParamInfo, ResponseInfo, RouteInfo};
+#[doc(hidden)]
+pub use schema::{HasSchema, NoSchema, SchemaProbe};
@@ -4,10 +4,61 @@ use serde::Serialize;
 
 use crate::error::ErrorVariant;
 
+/// Metadata about a path or query parameter.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ParamInfo {
+    /// The parameter name.
+    pub name: String,
+    /// Whether the parameter must be present.
+    pub required: bool,
+    /// JSON Schema for the parameter value, if known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema: Option<serde_json::Value>,
+}
+
+impl ParamInfo {
+    /// Creates a new ParamInfo with the given metadata.
+    pub fn new(name: impl Into<String>, required: bool, schema: Option<serde_json::Value>) -> Self {
+        Self {
+            name: name.into(),
+            required,
+            schema,
+        }
+    }
+}
+
+/// Metadata about a documented success response.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ResponseInfo {
+    /// The HTTP status code.
+    pub status: u16,
+    /// The response content type.
+    pub content_type: String,
+    /// JSON Schema for the response body, if known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema: Option<serde_json::Value>,
+}
+
+impl ResponseInfo {
+    /// Creates a new ResponseInfo with the given metadata.
+    pub fn new(
+        status: u16,
+        content_type: impl Into<String>,
+        schema: Option<serde_json::Value>,
+    ) -> Self {
+        Self {
+            status,
+            content_type: content_type.into(),
+            schema,
+        }
+    }
+}
+
 /// Metadata about a registered route.
 ///
 /// Contains information about a route's HTTP method, path pattern,
-/// and handler name for introspection and documentation generation.
+/// handler name, parameters and request/response schemas for
+/// introspection and documentation generation.
 ///
 /// # Examples
 ///
@@ -32,6 +83,33 @@ pub struct RouteInfo {
     /// Error variants for OpenAPI documentation.
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub error_responses: Vec<ErrorVariant>,
+    /// Parameters captured from the path pattern.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub path_params: Vec<ParamInfo>,
+    /// Parameters read from the query string.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub query_params: Vec<ParamInfo>,
+    /// JSON Schema for the request body.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_schema: Option<serde_json::Value>,
+    /// Example request body.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_example: Option<serde_json::Value>,
+    /// Documented success responses, replacing the single `response_schema`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub responses: Vec<ResponseInfo>,
+    /// Whether the route is deprecated.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub deprecated: bool,
+    /// Whether the route is left out of the OpenAPI spec.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub hidden: bool,
+    /// Tags grouping the route in documentation.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// The host the route is served for, if it belongs to a host router.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
 }
 
 impl RouteInfo {
@@ -49,8 +127,71 @@ impl RouteInfo {
             handler_name: handler_name.into(),
             response_schema,
             error_responses,
+            path_params: Vec::new(),
+            query_params: Vec::new(),
+            request_schema: None,
+            request_example: None,
+            responses: Vec::new(),
+            deprecated: false,
+            hidden: false,
+            tags: Vec::new(),
+            host: None,
         }
     }
+
+    /// Sets the path parameters.
+    pub fn with_path_params(mut self, params: Vec<ParamInfo>) -> Self {
+        self.path_params = params;
+        self
+    }
+
+    /// Sets the query parameters.
+    pub fn with_query_params(mut self, params: Vec<ParamInfo>) -> Self {
+        self.query_params = params;
+        self
+    }
+
+    /// Sets the request body schema.
+    pub fn with_request_schema(mut self, schema: Option<serde_json::Value>) -> Self {
+        self.request_schema = schema;
+        self
+    }
+
+    /// Sets the example request body.
+    pub fn with_request_example(mut self, example: Option<serde_json::Value>) -> Self {
+        self.request_example = example;
+        self
+    }
+
+    /// Sets the documented success responses.
+    pub fn with_responses(mut self, responses: Vec<ResponseInfo>) -> Self {
+        self.responses = responses;
+        self
+    }
+
+    /// Marks the route as deprecated.
+    pub fn with_deprecated(mut self, deprecated: bool) -> Self {
+        self.deprecated = deprecated;
+        self
+    }
+
+    /// Hides the route from the OpenAPI spec.
+    pub fn with_hidden(mut self, hidden: bool) -> Self {
+        self.hidden = hidden;
+        self
+    }
+
+    /// Sets the documentation tags.
+    pub fn with_tags(mut self, tags: Vec<String>) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    /// Sets the host the route is served for.
+    pub fn with_host(mut self, host: impl Into<String>) -> Self {
+        self.host = Some(host.into());
+        self
+    }
 }
 
 #[cfg(test)]
@@ -95,6 +236,67 @@ mod tests {
         assert!(debug.contains("/users/:id"));
     }
 
+    #[test]
+    fn test_route_info_with_params_and_body() {
+        let info = RouteInfo::new("PUT", "/users/:id", "update_user", None, Vec::new())
+            .with_path_params(vec![ParamInfo::new(
+                "id",
+                true,
+                Some(serde_json::json!({"type": "integer"})),
+            )])
+            .with_query_params(vec![ParamInfo::new("notify", false, None)])
+            .with_request_schema(Some(serde_json::json!({"type": "object"})));
+
+        let json = serde_json::to_value(&info).unwrap();
+        assert_eq!(json["path_params"][0]["name"], "id");
+        assert_eq!(json["path_params"][0]["schema"]["type"], "integer");
+        assert_eq!(json["query_params"][0]["required"], false);
+        assert_eq!(json["request_schema"]["type"], "object");
+    }
+
+    #[test]
+    fn test_route_info_with_responses() {
+        let info = RouteInfo::new("POST", "/users", "create_user", None, Vec::new())
+            .with_responses(vec![
+                ResponseInfo::new(201, "application/json", None),
+                ResponseInfo::new(200, "text/plain", None),
+            ]);
+
+        let json = serde_json::to_value(&info).unwrap();
+        assert_eq!(json["responses"][0]["status"], 201);
+        assert_eq!(json["responses"][1]["content_type"], "text/plain");
+    }
+
+    #[test]
+    fn test_route_info_omits_empty_params() {
+        let info = RouteInfo::new("GET", "/health", "health_check", None, Vec::new());
+        let json = serde_json::to_value(&info).unwrap();
+        assert!(json.get("path_params").is_none());
+        assert!(json.get("query_params").is_none());
+        assert!(json.get("request_schema").is_none());
+        assert!(json.get("responses").is_none());
+        assert!(json.get("deprecated").is_none());
+        assert!(json.get("tags").is_none());
+    }
+
+    #[test]
+    fn test_route_info_deprecated() {
+        let info = RouteInfo::new("GET", "/v1/users", "list_users_v1", None, Vec::new())
+            .with_deprecated(true);
+        let json = serde_json::to_value(&info).unwrap();
+        assert_eq!(json["deprecated"], true);
+    }
+
+    #[test]
+    fn test_route_info_hidden() {
+        let info = RouteInfo::new("GET", "/admin", "admin", None, Vec::new());
+        let json = serde_json::to_value(&info).unwrap();
+        assert!(json.get("hidden").is_none());
+
+        let json = serde_json::to_value(info.with_hidden(true)).unwrap();
+        assert_eq!(json["hidden"], true);
+    }
+
     #[test]
     fn test_route_info_with_error_responses() {
         let errors = vec![ErrorVariant {
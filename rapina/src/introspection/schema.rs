@@ -0,0 +1,76 @@
+//! Optional schema lookup for handler extractor types.
+//!
+//! Route macros describe request bodies, query strings and path parameters
+//! when their types implement [`JsonSchema`], without requiring it. The
+//! lookup uses autoref specialization: the method on [`SchemaProbe`] is
+//! preferred when the type has a schema, and the fallback on
+//! `&SchemaProbe` returns `None` otherwise.
+
+use std::marker::PhantomData;
+
+use schemars::JsonSchema;
+
+#[doc(hidden)]
+pub struct SchemaProbe<T: ?Sized>(PhantomData<T>);
+
+impl<T: ?Sized> SchemaProbe<T> {
+    #[doc(hidden)]
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T: ?Sized> Default for SchemaProbe<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[doc(hidden)]
+pub trait HasSchema {
+    fn schema(&self) -> Option<serde_json::Value>;
+}
+
+impl<T: JsonSchema + ?Sized> HasSchema for SchemaProbe<T> {
+    fn schema(&self) -> Option<serde_json::Value> {
+        serde_json::to_value(schemars::schema_for!(T)).ok()
+    }
+}
+
+#[doc(hidden)]
+pub trait NoSchema {
+    fn schema(&self) -> Option<serde_json::Value>;
+}
+
+impl<T: ?Sized> NoSchema for &SchemaProbe<T> {
+    fn schema(&self) -> Option<serde_json::Value> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct WithSchema {
+        name: String,
+    }
+
+    #[allow(dead_code)]
+    struct WithoutSchema;
+
+    #[test]
+    // The borrow selects the specialized method
+    #[allow(clippy::needless_borrow)]
+    fn test_probe_type_with_schema() {
+        let schema = (&SchemaProbe::<WithSchema>::new()).schema().unwrap();
+        assert_eq!(schema["properties"]["name"]["type"], "string");
+    }
+
+    #[test]
+    fn test_probe_type_without_schema() {
+        assert!((&SchemaProbe::<WithoutSchema>::new()).schema().is_none());
+    }
+}
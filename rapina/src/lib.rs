@@ -52,6 +52,7 @@
 //! - [`Query`](extract::Query) - Parse query string parameters
 //! - [`Form`](extract::Form) - Parse URL-encoded form data
 //! - [`Headers`](extract::Headers) - Access request headers
+//! - [`TypedHeader`](extract::TypedHeader) - Parse a single header into a typed value
 //! - [`Cookie`](extract::Cookie) - Extract and deserialize cookies
 //! - [`State`](extract::State) - Access application state
 //! - [`Context`](extract::Context) - Access request context with trace_id
@@ -64,7 +65,8 @@
 //! - [`TimeoutMiddleware`](middleware::TimeoutMiddleware) - Request timeout handling
 //! - [`BodyLimitMiddleware`](middleware::BodyLimitMiddleware) - Limit request body size
 //! - [`TraceIdMiddleware`](middleware::TraceIdMiddleware) - Add trace IDs to requests
-//! - [`RequestLogMiddleware`](middleware::RequestLogMiddleware) - Structured request logging
+//! - [`RequestLogMiddleware`](middleware::RequestLogMiddleware) - Structured or Combined Log Format request logging
+//! - [`BodyLogMiddleware`](middleware::BodyLogMiddleware) - Log request and response bodies with redaction
 //! - [`RateLimitMiddleware`](middleware::RateLimitMiddleware) - Token bucket rate limiting
 //!
 //! ## Introspection
@@ -83,19 +85,23 @@ pub mod app;
 pub mod auth;
 pub mod config;
 pub mod context;
+pub mod cookie;
 #[cfg(feature = "database")]
 pub mod database;
 pub mod error;
 pub mod extract;
 pub mod handler;
+pub mod headers;
 pub mod introspection;
 #[cfg(feature = "metrics")]
 pub mod metrics;
 pub mod middleware;
 #[cfg(feature = "database")]
 pub mod migration;
+pub mod negotiate;
 pub mod observability;
 pub mod openapi;
+pub mod pagination;
 pub mod response;
 pub mod router;
 pub mod server;
@@ -115,30 +121,45 @@ pub mod prelude {
     pub use crate::app::Rapina;
     pub use crate::auth::{AuthConfig, CurrentUser, TokenResponse};
     pub use crate::config::{
-        ConfigError, get_env, get_env_or, get_env_parsed, get_env_parsed_or, load_dotenv,
+        ConfigError, get_env, get_env_bool, get_env_bool_or, get_env_or, get_env_parsed,
+        get_env_parsed_or, load_dotenv,
     };
     pub use crate::context::RequestContext;
-    pub use crate::error::{DocumentedError, Error, ErrorVariant, IntoApiError, Result};
-    pub use crate::extract::{Context, Cookie, Form, Headers, Json, Path, Query, State, Validated};
+    pub use crate::cookie::{CookieJar, PrivateCookies, SetCookie, SignedCookies};
+    pub use crate::error::{
+        DocumentedError, Error, ErrorVariant, IntoApiError, Result, ValidationMessages,
+    };
+    pub use crate::extract::{
+        ConnectInfo, ContentLengthLimit, Context, Cookie, Deadline, Form, Headers, Host, Json,
+        MatchedPath, Path, Query, RawQuery, SchemaValidated, State, TypedHeader, Validated,
+        WithRejection,
+    };
     pub use crate::introspection::RouteInfo;
-    pub use crate::middleware::{KeyExtractor, Middleware, Next, RateLimitConfig};
+    pub use crate::middleware::{
+        CompletedResponse, KeyExtractor, Middleware, Next, RateLimitConfig,
+    };
+    pub use crate::negotiate::Negotiate;
     pub use crate::observability::TracingConfig;
-    pub use crate::response::IntoResponse;
-    pub use crate::router::Router;
+    pub use crate::pagination::{Page, Pagination};
+    pub use crate::response::{
+        Created, Download, IntoResponse, JsonConfig, JsonStream, NoContent, WithHeaders,
+    };
+    pub use crate::router::{Router, TrailingSlash};
 
-    pub use http::{Method, StatusCode};
+    pub use http::{Method, StatusCode, Uri};
     pub use schemars::JsonSchema;
     pub use serde::{Deserialize, Serialize};
     pub use tracing;
     pub use validator::Validate;
 
-    pub use rapina_macros::{Config, delete, get, post, public, put, schema};
+    pub use rapina_macros::{Config, FromRequest, delete, get, post, public, put, schema};
 }
 
 // Re-export dependencies so users don't need to add them to their Cargo.toml
 pub use http;
 pub use hyper;
 pub use schemars;
+pub use serde;
 
 // Re-export sea-orm when database feature is enabled
 #[cfg(feature = "database")]
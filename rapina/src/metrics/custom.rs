@@ -0,0 +1,97 @@
+//! Handles for application-defined metrics.
+//!
+//! Created through [`MetricsRegistry::counter`], [`MetricsRegistry::gauge`]
+//! and [`MetricsRegistry::histogram`]. Label values are passed on each call,
+//! in the same order as the label names given at registration.
+//!
+//! [`MetricsRegistry::counter`]: super::MetricsRegistry::counter
+//! [`MetricsRegistry::gauge`]: super::MetricsRegistry::gauge
+//! [`MetricsRegistry::histogram`]: super::MetricsRegistry::histogram
+
+use prometheus::{CounterVec, GaugeVec, HistogramVec};
+
+/// A monotonically increasing counter.
+#[derive(Clone)]
+pub struct Counter(pub(crate) CounterVec);
+
+impl Counter {
+    /// Increments the counter by one.
+    pub fn inc(&self, labels: &[&str]) {
+        self.0.with_label_values(labels).inc();
+    }
+
+    /// Increments the counter by the given amount.
+    pub fn inc_by(&self, labels: &[&str], value: f64) {
+        self.0.with_label_values(labels).inc_by(value);
+    }
+
+    /// Returns the current value for the given labels.
+    pub fn get(&self, labels: &[&str]) -> f64 {
+        self.0.with_label_values(labels).get()
+    }
+}
+
+/// A value that can go up and down.
+#[derive(Clone)]
+pub struct Gauge(pub(crate) GaugeVec);
+
+impl Gauge {
+    /// Sets the gauge to the given value.
+    pub fn set(&self, labels: &[&str], value: f64) {
+        self.0.with_label_values(labels).set(value);
+    }
+
+    /// Increments the gauge by one.
+    pub fn inc(&self, labels: &[&str]) {
+        self.0.with_label_values(labels).inc();
+    }
+
+    /// Decrements the gauge by one.
+    pub fn dec(&self, labels: &[&str]) {
+        self.0.with_label_values(labels).dec();
+    }
+
+    /// Adds the given amount to the gauge.
+    pub fn add(&self, labels: &[&str], value: f64) {
+        self.0.with_label_values(labels).add(value);
+    }
+
+    /// Returns the current value for the given labels.
+    pub fn get(&self, labels: &[&str]) -> f64 {
+        self.0.with_label_values(labels).get()
+    }
+}
+
+/// A distribution of observed values bucketed for percentile queries.
+#[derive(Clone)]
+pub struct Histogram(pub(crate) HistogramVec);
+
+impl Histogram {
+    /// Records an observation.
+    pub fn observe(&self, labels: &[&str], value: f64) {
+        self.0.with_label_values(labels).observe(value);
+    }
+
+    /// Returns the number of observations for the given labels.
+    pub fn count(&self, labels: &[&str]) -> u64 {
+        self.0.with_label_values(labels).get_sample_count()
+    }
+}
+
+/// A registered custom metric, kept so repeated lookups return the same handle.
+#[derive(Clone)]
+pub(crate) enum CustomMetric {
+    Counter(Counter),
+    Gauge(Gauge),
+    Histogram(Histogram),
+}
+
+impl CustomMetric {
+    pub(crate) fn kind(&self) -> &'static str {
+        match self {
+            CustomMetric::Counter(_) => "counter",
+            CustomMetric::Gauge(_) => "gauge",
+            CustomMetric::Histogram(_) => "histogram",
+        }
+    }
+}
@@ -2,13 +2,26 @@ use std::time::Instant;
 
 use hyper::body::Incoming;
 use hyper::{Request, Response};
+use prometheus::IntGauge;
 
 use crate::context::RequestContext;
 use crate::middleware::{BoxFuture, Middleware, Next};
 use crate::response::BoxBody;
+use crate::router::MatchedRoute;
 
 use super::prometheus::MetricsRegistry;
 
+/// Records request count, duration, error and in-flight metrics.
+///
+/// The in-flight gauge is decremented when the request finishes, including
+/// when the handler panics or the client disconnects first. Responses with
+/// a 4xx or 5xx status are also counted in `http_request_errors_total`,
+/// labeled by status class.
+///
+/// Requests are labeled with the matched route pattern (e.g., `/users/:id`)
+/// rather than the raw path, so label cardinality stays bounded. Responses
+/// produced before routing fall back to the path with numeric segments
+/// replaced by `:id`.
 pub struct MetricsMiddleware {
     registry: MetricsRegistry,
 }
@@ -19,6 +32,32 @@ impl MetricsMiddleware {
     }
 }
 
+/// Counts a request as in flight until dropped, so the gauge is decremented
+/// even if the request future never completes.
+struct InFlight(IntGauge);
+
+impl InFlight {
+    fn start(gauge: &IntGauge) -> Self {
+        gauge.inc();
+        Self(gauge.clone())
+    }
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        self.0.dec();
+    }
+}
+
+/// Returns the status class counted as an error, e.g. `5xx`.
+fn error_class(status: u16) -> Option<&'static str> {
+    match status {
+        400..=499 => Some("4xx"),
+        500..=599 => Some("5xx"),
+        _ => None,
+    }
+}
+
 /// Replaces pure-numeric path segments with `:id` to avoid label cardinality explosion.
 /// e.g `/users/123/posts` -> `/users/:id/posts`
 fn normalize_path(path: &str) -> String {
@@ -42,16 +81,26 @@ impl Middleware for MetricsMiddleware {
         next: Next<'a>,
     ) -> BoxFuture<'a, Response<BoxBody>> {
         let method = req.method().to_string();
-        let path = normalize_path(req.uri().path());
+        let raw_path = req.uri().path().to_string();
         let registry = self.registry.clone();
 
         Box::pin(async move {
-            registry.http_requests_in_flight.inc();
+            let in_flight = InFlight::start(&registry.http_requests_in_flight);
             let start = Instant::now();
             let response = next.run(req).await;
             let duration = start.elapsed().as_secs_f64();
-            registry.http_requests_in_flight.dec();
-
+            drop(in_flight);
+
+            let path = match response.extensions().get::<MatchedRoute>() {
+                Some(route) => route.as_str().to_string(),
+                None => normalize_path(&raw_path),
+            };
+            if let Some(class) = error_class(response.status().as_u16()) {
+                registry
+                    .http_request_errors_total
+                    .with_label_values(&[&method, &path, class])
+                    .inc();
+            }
             let status = response.status().as_u16().to_string();
             registry
                 .http_requests_total
@@ -59,7 +108,7 @@ impl Middleware for MetricsMiddleware {
                 .inc();
             registry
                 .http_request_duration_seconds
-                .with_label_values(&[&method, &path])
+                .with_label_values(&[&method, &path, &status])
                 .observe(duration);
 
             response
@@ -108,6 +157,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_error_class() {
+        assert_eq!(error_class(200), None);
+        assert_eq!(error_class(304), None);
+        assert_eq!(error_class(404), Some("4xx"));
+        assert_eq!(error_class(503), Some("5xx"));
+    }
+
+    #[test]
+    fn test_in_flight_decremented_on_panic() {
+        let registry = MetricsRegistry::new();
+        let gauge = registry.http_requests_in_flight.clone();
+
+        let result = std::panic::catch_unwind(|| {
+            let _in_flight = InFlight::start(&gauge);
+            assert_eq!(gauge.get(), 1);
+            panic!("handler panicked");
+        });
+        assert!(result.is_err());
+        assert_eq!(registry.http_requests_in_flight.get(), 0);
+    }
+
     #[test]
     fn test_metrics_middleware_new() {
         let registry = MetricsRegistry::new();
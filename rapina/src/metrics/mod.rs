@@ -1,9 +1,15 @@
 //! Metrics utilities for Rapina applications.
 //!
 //! This module provides tools for metrics.
+//!
+//! Besides the built-in HTTP metrics, application code can record its own
+//! counters, gauges and histograms through [`MetricsRegistry`]. They are
+//! exported alongside the built-in metrics at `/metrics`.
 
+mod custom;
 pub mod middleware;
 mod prometheus;
 
+pub use self::custom::{Counter, Gauge, Histogram};
 pub use self::middleware::MetricsMiddleware;
 pub use self::prometheus::{MetricsRegistry, metrics_handler};
@@ -1,23 +1,48 @@
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 
-use bytes::Bytes;
 use http::{Request, Response, StatusCode};
-use http_body_util::Full;
 use hyper::body::Incoming;
+use prometheus::core::Collector;
 use prometheus::{
-    CounterVec, Encoder, HistogramOpts, HistogramVec, IntGauge, Opts, Registry, TextEncoder,
+    CounterVec, Encoder, GaugeVec, HistogramOpts, HistogramVec, IntGauge, Opts, Registry,
+    TextEncoder,
 };
 
 use crate::extract::PathParams;
 use crate::response::BoxBody;
 use crate::state::AppState;
 
+use super::custom::{Counter, CustomMetric, Gauge, Histogram};
+
+/// Prometheus metrics collected by the application.
+///
+/// Holds the built-in HTTP metrics recorded by [`MetricsMiddleware`](super::MetricsMiddleware)
+/// and any custom metrics registered by application code. When metrics are
+/// enabled the registry is available to handlers via `State<MetricsRegistry>`.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rapina::prelude::*;
+/// use rapina::metrics::MetricsRegistry;
+///
+/// #[post("/orders")]
+/// async fn create_order(metrics: State<MetricsRegistry>) -> StatusCode {
+///     metrics
+///         .counter("orders_created_total", "Orders created", &["plan"])
+///         .inc(&["pro"]);
+///     StatusCode::CREATED
+/// }
+/// ```
 #[derive(Clone)]
 pub struct MetricsRegistry {
     pub(crate) registry: Arc<Registry>,
     pub(crate) http_requests_total: CounterVec,
     pub(crate) http_request_duration_seconds: HistogramVec,
     pub(crate) http_requests_in_flight: IntGauge,
+    pub(crate) http_request_errors_total: CounterVec,
+    custom: Arc<Mutex<HashMap<String, CustomMetric>>>,
 }
 
 impl MetricsRegistry {
@@ -39,7 +64,7 @@ impl MetricsRegistry {
                 "http_request_duration_seconds",
                 "HTTP request duration in seconds",
             ),
-            &["method", "path"],
+            &["method", "path", "status"],
         )
         .expect("failed to create http_request_duration_seconds metric");
 
@@ -57,14 +82,120 @@ impl MetricsRegistry {
             .register(Box::new(http_requests_in_flight.clone()))
             .expect("failed to register http_requests_in_flight");
 
+        let http_request_errors_total = CounterVec::new(
+            Opts::new(
+                "http_request_errors_total",
+                "Total number of HTTP requests answered with a 4xx or 5xx status",
+            ),
+            &["method", "path", "class"],
+        )
+        .expect("failed to create http_request_errors_total metric");
+
+        registry
+            .register(Box::new(http_request_errors_total.clone()))
+            .expect("failed to register http_request_errors_total");
+
         Self {
             registry: Arc::new(registry),
             http_requests_total,
             http_request_duration_seconds,
             http_requests_in_flight,
+            http_request_errors_total,
+            custom: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
+    /// Returns a counter, registering it on first use.
+    ///
+    /// Calling this again with the same name returns the existing counter,
+    /// so it is safe to call from handlers on every request.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the name or labels are invalid, or if the name is already
+    /// used by a metric of a different type.
+    pub fn counter(&self, name: &str, help: &str, labels: &[&str]) -> Counter {
+        let metric = self.get_or_register(name, || {
+            let vec = CounterVec::new(Opts::new(name, help), labels)?;
+            Ok(CustomMetric::Counter(Counter(vec)))
+        });
+        match metric {
+            CustomMetric::Counter(counter) => counter,
+            other => panic!(
+                "metric '{}' is already registered as a {}",
+                name,
+                other.kind()
+            ),
+        }
+    }
+
+    /// Returns a gauge, registering it on first use.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the name or labels are invalid, or if the name is already
+    /// used by a metric of a different type.
+    pub fn gauge(&self, name: &str, help: &str, labels: &[&str]) -> Gauge {
+        let metric = self.get_or_register(name, || {
+            let vec = GaugeVec::new(Opts::new(name, help), labels)?;
+            Ok(CustomMetric::Gauge(Gauge(vec)))
+        });
+        match metric {
+            CustomMetric::Gauge(gauge) => gauge,
+            other => panic!(
+                "metric '{}' is already registered as a {}",
+                name,
+                other.kind()
+            ),
+        }
+    }
+
+    /// Returns a histogram with the default buckets, registering it on first use.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the name or labels are invalid, or if the name is already
+    /// used by a metric of a different type.
+    pub fn histogram(&self, name: &str, help: &str, labels: &[&str]) -> Histogram {
+        let metric = self.get_or_register(name, || {
+            let vec = HistogramVec::new(HistogramOpts::new(name, help), labels)?;
+            Ok(CustomMetric::Histogram(Histogram(vec)))
+        });
+        match metric {
+            CustomMetric::Histogram(histogram) => histogram,
+            other => panic!(
+                "metric '{}' is already registered as a {}",
+                name,
+                other.kind()
+            ),
+        }
+    }
+
+    fn get_or_register(
+        &self,
+        name: &str,
+        create: impl FnOnce() -> prometheus::Result<CustomMetric>,
+    ) -> CustomMetric {
+        let mut custom = self.custom.lock().unwrap();
+        if let Some(metric) = custom.get(name) {
+            return metric.clone();
+        }
+
+        let metric =
+            create().unwrap_or_else(|e| panic!("failed to create metric '{}': {}", name, e));
+        let collector: Box<dyn Collector> = match &metric {
+            CustomMetric::Counter(c) => Box::new(c.0.clone()),
+            CustomMetric::Gauge(g) => Box::new(g.0.clone()),
+            CustomMetric::Histogram(h) => Box::new(h.0.clone()),
+        };
+        self.registry
+            .register(collector)
+            .unwrap_or_else(|e| panic!("failed to register metric '{}': {}", name, e));
+
+        custom.insert(name.to_string(), metric.clone());
+        metric
+    }
+
     /// Encodes all metrics in the Prometheus text exposition format.
     pub fn encode(&self) -> String {
         let encoder = TextEncoder::new();
@@ -97,12 +228,12 @@ pub async fn metrics_handler(
             Response::builder()
                 .status(StatusCode::OK)
                 .header("content-type", "text/plain; version=0.0.4; charset=utf-8")
-                .body(Full::new(Bytes::from(body)))
+                .body(BoxBody::from(body))
                 .unwrap()
         }
         None => Response::builder()
             .status(StatusCode::SERVICE_UNAVAILABLE)
-            .body(Full::new(Bytes::new()))
+            .body(BoxBody::empty())
             .unwrap(),
     }
 }
@@ -172,7 +303,7 @@ mod tests {
         let registry = MetricsRegistry::new();
         registry
             .http_request_duration_seconds
-            .with_label_values(&["POST", "/users"])
+            .with_label_values(&["POST", "/users", "201"])
             .observe(0.042);
 
         let output = registry.encode();
@@ -194,4 +325,54 @@ mod tests {
         let output = clone.encode();
         assert!(output.contains("} 1"));
     }
+
+    #[test]
+    fn test_custom_counter() {
+        let registry = MetricsRegistry::new();
+        let counter = registry.counter("orders_total", "Orders placed", &["plan"]);
+        counter.inc(&["pro"]);
+        counter.inc_by(&["pro"], 2.0);
+
+        assert_eq!(counter.get(&["pro"]), 3.0);
+        let output = registry.encode();
+        assert!(output.contains(r#"orders_total{plan="pro"} 3"#));
+    }
+
+    #[test]
+    fn test_custom_counter_same_name_returns_same_metric() {
+        let registry = MetricsRegistry::new();
+        registry.counter("jobs_total", "Jobs", &[]).inc(&[]);
+        registry.counter("jobs_total", "Jobs", &[]).inc(&[]);
+
+        assert_eq!(registry.counter("jobs_total", "Jobs", &[]).get(&[]), 2.0);
+    }
+
+    #[test]
+    fn test_custom_gauge() {
+        let registry = MetricsRegistry::new();
+        let gauge = registry.gauge("queue_depth", "Queued jobs", &[]);
+        gauge.set(&[], 5.0);
+        gauge.dec(&[]);
+
+        assert_eq!(gauge.get(&[]), 4.0);
+        assert!(registry.encode().contains("queue_depth 4"));
+    }
+
+    #[test]
+    fn test_custom_histogram() {
+        let registry = MetricsRegistry::new();
+        let histogram = registry.histogram("payload_bytes", "Payload size", &["kind"]);
+        histogram.observe(&["upload"], 512.0);
+
+        assert_eq!(histogram.count(&["upload"]), 1);
+        assert!(registry.encode().contains("payload_bytes_bucket"));
+    }
+
+    #[test]
+    #[should_panic(expected = "already registered as a counter")]
+    fn test_custom_metric_type_mismatch_panics() {
+        let registry = MetricsRegistry::new();
+        registry.counter("things", "Things", &[]);
+        registry.gauge("things", "Things", &[]);
+    }
 }
@@ -41,7 +41,7 @@ impl Middleware for BodyLimitMiddleware {
                 .and_then(|v| v.parse::<usize>().ok());
 
             if content_length.is_some_and(|len| len > self.max_size) {
-                return Error::bad_request("body too large").into_response();
+                return Error::body_too_large(self.max_size).into_response();
             }
 
             next.run(req).await
@@ -0,0 +1,365 @@
+use http::HeaderMap;
+use http::header::{CONTENT_LENGTH, CONTENT_TYPE};
+use http_body::Body;
+use hyper::body::Incoming;
+use hyper::{Request, Response};
+use tracing::{Level, debug};
+
+use crate::context::RequestContext;
+use crate::response::{BoxBody, IntoResponse};
+
+use super::{BoxFuture, Middleware, Next, buffer_body, buffer_response};
+
+/// Replaces the value of a redacted header or field.
+const REDACTED: &str = "[REDACTED]";
+
+/// Largest body read into memory for logging unless configured otherwise.
+const DEFAULT_BUFFER_LIMIT: usize = 64 * 1024; // 64KB
+
+/// Logs request bodies, and optionally response bodies, for debugging.
+///
+/// Each body is logged at `DEBUG` level with target `rapina::body`, along
+/// with the headers, cut off after `max_bytes`. Headers and JSON or form
+/// fields whose name is in the redaction list are replaced with
+/// `[REDACTED]`, at any depth of a JSON body. By default the list holds
+/// `authorization`, `cookie`, `set-cookie`, `password` and `token`; names
+/// match case-insensitively. A JSON body that doesn't parse is not logged,
+/// since it can't be redacted.
+///
+/// Nothing is read unless `DEBUG` is enabled for `rapina::body`, so the
+/// middleware can stay in place in production. When it is, a body is read
+/// into memory before the handler runs and handed on unchanged, as long as
+/// its declared size is within the [buffer limit](Self::buffer_limit).
+/// Larger bodies, and request bodies without a `Content-Length` or
+/// responses of unknown size such as streams, are passed through unread and
+/// logged as a size summary.
+///
+/// # Examples
+///
+/// ```
+/// use rapina::middleware::BodyLogMiddleware;
+///
+/// let middleware = BodyLogMiddleware::new(4096)
+///     .redact("api_key")
+///     .log_responses(true);
+/// ```
+#[derive(Debug, Clone)]
+pub struct BodyLogMiddleware {
+    max_bytes: usize,
+    buffer_limit: usize,
+    log_responses: bool,
+    redact: Vec<String>,
+}
+
+impl BodyLogMiddleware {
+    /// Logs up to `max_bytes` of each request body.
+    pub fn new(max_bytes: usize) -> Self {
+        Self {
+            max_bytes,
+            buffer_limit: DEFAULT_BUFFER_LIMIT.max(max_bytes),
+            log_responses: false,
+            redact: ["authorization", "cookie", "set-cookie", "password", "token"]
+                .map(String::from)
+                .to_vec(),
+        }
+    }
+
+    /// Adds a header or field name whose value is never logged.
+    pub fn redact(mut self, name: impl Into<String>) -> Self {
+        self.redact.push(name.into().to_ascii_lowercase());
+        self
+    }
+
+    /// Sets the largest body read into memory for logging, 64KB by default.
+    ///
+    /// Bodies declaring a larger size are passed through without being
+    /// read, and only their size is logged.
+    pub fn buffer_limit(mut self, bytes: usize) -> Self {
+        self.buffer_limit = bytes;
+        self
+    }
+
+    /// Sets whether response bodies are logged too.
+    pub fn log_responses(mut self, enabled: bool) -> Self {
+        self.log_responses = enabled;
+        self
+    }
+
+    fn is_redacted(&self, name: &str) -> bool {
+        self.redact.iter().any(|r| r.eq_ignore_ascii_case(name))
+    }
+
+    /// Formats headers as `name: value` pairs, redacting listed names.
+    fn render_headers(&self, headers: &HeaderMap) -> String {
+        headers
+            .iter()
+            .map(|(name, value)| {
+                let value = if self.is_redacted(name.as_str()) {
+                    REDACTED
+                } else {
+                    value.to_str().unwrap_or("[binary]")
+                };
+                format!("{}: {}", name, value)
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Formats a body for the log: redacted, then cut to `max_bytes`.
+    fn render_body(&self, headers: &HeaderMap, body: &[u8]) -> String {
+        if body.is_empty() {
+            return String::new();
+        }
+        let content_type = headers
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("");
+
+        let text = if content_type.contains("json") {
+            match serde_json::from_slice(body) {
+                Ok(mut value) => {
+                    self.redact_json(&mut value);
+                    value.to_string()
+                }
+                Err(_) => return format!("[invalid JSON, {} bytes]", body.len()),
+            }
+        } else if content_type.starts_with("application/x-www-form-urlencoded") {
+            let mut form = form_urlencoded::Serializer::new(String::new());
+            for (key, value) in form_urlencoded::parse(body) {
+                let value = if self.is_redacted(&key) {
+                    REDACTED.into()
+                } else {
+                    value
+                };
+                form.append_pair(&key, &value);
+            }
+            form.finish()
+        } else {
+            match std::str::from_utf8(body) {
+                Ok(text) => text.to_string(),
+                Err(_) => return format!("[binary, {} bytes]", body.len()),
+            }
+        };
+
+        if text.len() <= self.max_bytes {
+            return text;
+        }
+        let mut end = self.max_bytes;
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        format!("{}... ({} bytes total)", &text[..end], body.len())
+    }
+
+    /// Whether a body of the given declared size is read for logging.
+    fn fits(&self, size: Option<u64>) -> bool {
+        size.is_some_and(|size| size <= self.buffer_limit as u64)
+    }
+
+    fn redact_json(&self, value: &mut serde_json::Value) {
+        match value {
+            serde_json::Value::Object(fields) => {
+                for (key, value) in fields.iter_mut() {
+                    if self.is_redacted(key) {
+                        *value = serde_json::Value::from(REDACTED);
+                    } else {
+                        self.redact_json(value);
+                    }
+                }
+            }
+            serde_json::Value::Array(items) => {
+                items.iter_mut().for_each(|item| self.redact_json(item));
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Middleware for BodyLogMiddleware {
+    fn handle<'a>(
+        &'a self,
+        req: Request<Incoming>,
+        ctx: &'a RequestContext,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, Response<BoxBody>> {
+        Box::pin(async move {
+            if !tracing::enabled!(target: "rapina::body", Level::DEBUG) {
+                return next.run(req).await;
+            }
+
+            let content_length = req
+                .headers()
+                .get(CONTENT_LENGTH)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.parse::<u64>().ok());
+            let req = if self.fits(content_length) {
+                let request = match buffer_body(req, self.buffer_limit).await {
+                    Ok(request) => request,
+                    Err(e) => return e.into_response(),
+                };
+                debug!(
+                    target: "rapina::body",
+                    trace_id = %ctx.trace_id,
+                    method = %request.parts.method,
+                    path = %request.parts.uri.path(),
+                    headers = %self.render_headers(&request.parts.headers),
+                    body = %self.render_body(&request.parts.headers, &request.body),
+                    "request body"
+                );
+                request.into_request()
+            } else {
+                debug!(
+                    target: "rapina::body",
+                    trace_id = %ctx.trace_id,
+                    method = %req.method(),
+                    path = %req.uri().path(),
+                    headers = %self.render_headers(req.headers()),
+                    body = %unread(content_length),
+                    "request body"
+                );
+                req
+            };
+
+            let response = next.run(req).await;
+            if !self.log_responses {
+                return response;
+            }
+
+            let size = response.body().size_hint().upper();
+            if !self.fits(size) {
+                debug!(
+                    target: "rapina::body",
+                    trace_id = %ctx.trace_id,
+                    status = response.status().as_u16(),
+                    headers = %self.render_headers(response.headers()),
+                    body = %unread(size),
+                    "response body"
+                );
+                return response;
+            }
+
+            let response = match buffer_response(response).await {
+                Ok(response) => response,
+                Err(e) => return e.into_response(),
+            };
+            debug!(
+                target: "rapina::body",
+                trace_id = %ctx.trace_id,
+                status = response.parts.status.as_u16(),
+                headers = %self.render_headers(&response.parts.headers),
+                body = %self.render_body(&response.parts.headers, &response.body),
+                "response body"
+            );
+            response.into_response()
+        })
+    }
+}
+
+/// Summarizes a body that was too large, or of unknown size, to read.
+fn unread(size: Option<u64>) -> String {
+    match size {
+        Some(size) => format!("[{} bytes, not logged]", size),
+        None => "[streamed, not logged]".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http::HeaderValue;
+
+    fn headers(content_type: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_str(content_type).unwrap());
+        headers
+    }
+
+    #[test]
+    fn test_body_is_truncated_at_cap() {
+        let middleware = BodyLogMiddleware::new(5);
+        let body = middleware.render_body(&headers("text/plain"), b"hello world");
+        assert_eq!(body, "hello... (11 bytes total)");
+
+        let body = middleware.render_body(&headers("text/plain"), b"hello");
+        assert_eq!(body, "hello");
+    }
+
+    #[test]
+    fn test_truncation_keeps_whole_characters() {
+        let middleware = BodyLogMiddleware::new(2);
+        let body = middleware.render_body(&headers("text/plain"), "héllo".as_bytes());
+        assert_eq!(body, "h... (6 bytes total)");
+    }
+
+    #[test]
+    fn test_json_fields_are_redacted() {
+        let middleware = BodyLogMiddleware::new(1024).redact("API_KEY");
+        let body = middleware.render_body(
+            &headers("application/json"),
+            br#"{"user":"ada","Password":"hunter2","nested":[{"api_key":"k","ok":1}]}"#,
+        );
+        assert_eq!(
+            body,
+            r#"{"Password":"[REDACTED]","nested":[{"api_key":"[REDACTED]","ok":1}],"user":"ada"}"#
+        );
+    }
+
+    #[test]
+    fn test_redaction_happens_before_truncation() {
+        let middleware = BodyLogMiddleware::new(20);
+        let body = middleware.render_body(
+            &headers("application/json"),
+            br#"{"token":"0123456789abcdef0123456789"}"#,
+        );
+        assert!(!body.contains("0123"), "{}", body);
+        assert!(body.starts_with(r#"{"token":"[REDACTED]"#), "{}", body);
+    }
+
+    #[test]
+    fn test_invalid_json_is_not_logged() {
+        let middleware = BodyLogMiddleware::new(1024);
+        let body = middleware.render_body(&headers("application/json"), br#"{"password":"x"#);
+        assert_eq!(body, "[invalid JSON, 14 bytes]");
+    }
+
+    #[test]
+    fn test_form_fields_are_redacted() {
+        let middleware = BodyLogMiddleware::new(1024);
+        let body = middleware.render_body(
+            &headers("application/x-www-form-urlencoded"),
+            b"user=ada&password=hunter2",
+        );
+        assert_eq!(body, "user=ada&password=%5BREDACTED%5D");
+    }
+
+    #[test]
+    fn test_headers_are_redacted() {
+        let middleware = BodyLogMiddleware::new(1024).redact("x-api-key");
+        let mut headers = headers("text/plain");
+        headers.insert("authorization", HeaderValue::from_static("Bearer abc"));
+        headers.insert("x-api-key", HeaderValue::from_static("secret"));
+
+        let rendered = middleware.render_headers(&headers);
+        assert_eq!(
+            rendered,
+            "content-type: text/plain, authorization: [REDACTED], x-api-key: [REDACTED]"
+        );
+    }
+
+    #[test]
+    fn test_only_bodies_within_buffer_limit_are_read() {
+        let middleware = BodyLogMiddleware::new(16).buffer_limit(1024);
+        assert!(middleware.fits(Some(1024)));
+        assert!(!middleware.fits(Some(1025)));
+        assert!(!middleware.fits(None));
+        assert_eq!(unread(Some(1025)), "[1025 bytes, not logged]");
+        assert_eq!(unread(None), "[streamed, not logged]");
+    }
+
+    #[test]
+    fn test_binary_body_is_summarized() {
+        let middleware = BodyLogMiddleware::new(1024);
+        let body = middleware.render_body(&headers("application/octet-stream"), &[0xff, 0xfe]);
+        assert_eq!(body, "[binary, 2 bytes]");
+    }
+}
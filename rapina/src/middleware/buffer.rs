@@ -0,0 +1,256 @@
+//! Reading request and response bodies in middleware without taking them
+//! from the rest of the chain.
+
+use bytes::Bytes;
+use http::header::{CONTENT_LENGTH, HeaderValue};
+use http::{request, response};
+use http_body_util::{BodyExt, Either, Full, LengthLimitError, Limited};
+use hyper::body::Incoming;
+use hyper::{Request, Response};
+
+use crate::error::Error;
+use crate::response::{BoxBody, IntoResponse};
+
+/// A request body read ahead of the handler, kept for the extractors.
+#[derive(Debug, Clone)]
+struct BufferedBody(Bytes);
+
+/// Reads the whole request body so middleware can inspect it, keeping it
+/// for the extractors downstream.
+///
+/// After this call, [`Json`](crate::extract::Json), [`Form`](crate::extract::Form)
+/// and the other body extractors read the buffered bytes as if the body had
+/// never been touched, so middleware can verify a signature or validate the
+/// payload before deciding whether to call [`Next::run`](super::Next::run).
+/// Calling it again returns the same bytes.
+///
+/// Buffering holds the entire body in memory and delays the handler until
+/// the last byte has arrived, so large uploads lose streaming. At most
+/// `limit` bytes are read, whether or not the request declares a
+/// `Content-Length`. Handlers that read `Request<Incoming>` directly instead
+/// of through an extractor see an empty body.
+///
+/// # Errors
+///
+/// Returns a 413 error if the body is larger than `limit`, and a 400 error
+/// if it can't be read.
+///
+/// # Examples
+///
+/// ```ignore
+/// impl Middleware for SignatureMiddleware {
+///     fn handle<'a>(
+///         &'a self,
+///         mut req: Request<Incoming>,
+///         _ctx: &'a RequestContext,
+///         next: Next<'a>,
+///     ) -> BoxFuture<'a, Response<BoxBody>> {
+///         Box::pin(async move {
+///             let body = match buffer_request_body(&mut req, 64 * 1024).await {
+///                 Ok(body) => body,
+///                 Err(e) => return e.into_response(),
+///             };
+///             if !self.verify(req.headers(), &body) {
+///                 return Error::unauthorized("invalid signature").into_response();
+///             }
+///             next.run(req).await
+///         })
+///     }
+/// }
+/// ```
+pub async fn buffer_request_body(
+    req: &mut Request<Incoming>,
+    limit: usize,
+) -> Result<Bytes, Error> {
+    let too_large = || Error::body_too_large(limit);
+    if let Some(BufferedBody(bytes)) = req.extensions().get::<BufferedBody>() {
+        if bytes.len() > limit {
+            return Err(too_large());
+        }
+        return Ok(bytes.clone());
+    }
+
+    let content_length = req
+        .headers()
+        .get(CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<usize>().ok());
+    if content_length.is_some_and(|len| len > limit) {
+        return Err(too_large());
+    }
+
+    // Chunked bodies have no Content-Length, so they're capped while reading
+    let bytes = Limited::new(req.body_mut(), limit)
+        .collect()
+        .await
+        .map_err(|e| {
+            if e.is::<LengthLimitError>() {
+                too_large()
+            } else {
+                Error::bad_request("Failed to read request body")
+            }
+        })?
+        .to_bytes();
+    req.extensions_mut().insert(BufferedBody(bytes.clone()));
+    Ok(bytes)
+}
+
+/// A request whose body has been read into memory.
+///
+/// Returned by [`buffer_body`]. The parts and body can be inspected or
+/// changed before [`into_request`](Self::into_request) puts the request
+/// back together for the next middleware or handler.
+#[derive(Debug)]
+pub struct BufferedRequest {
+    /// The method, URI, headers and extensions of the request.
+    pub parts: request::Parts,
+    /// The complete request body.
+    pub body: Bytes,
+    /// The drained connection body, kept so the request can be rebuilt.
+    incoming: Incoming,
+}
+
+impl BufferedRequest {
+    /// Rebuilds the request; the body extractors downstream read `body`.
+    ///
+    /// If `body` was replaced, update `Content-Length` to match.
+    pub fn into_request(self) -> Request<Incoming> {
+        let mut req = Request::from_parts(self.parts, self.incoming);
+        req.extensions_mut().insert(BufferedBody(self.body));
+        req
+    }
+}
+
+/// Reads the whole request body, splitting the request into its parts and
+/// bytes.
+///
+/// Has the same cost and `limit` as [`buffer_request_body`], which suits
+/// middleware that only reads the body; this variant is for middleware that
+/// also rewrites the request, e.g. to decrypt the payload.
+///
+/// # Errors
+///
+/// Returns a 413 error if the body is larger than `limit`, and a 400 error
+/// if it can't be read.
+///
+/// # Examples
+///
+/// ```ignore
+/// let mut buffered = buffer_body(req, 64 * 1024).await?;
+/// buffered.body = decrypt(&buffered.body)?;
+/// buffered.parts.headers.remove(http::header::CONTENT_LENGTH);
+/// next.run(buffered.into_request()).await
+/// ```
+pub async fn buffer_body(
+    mut req: Request<Incoming>,
+    limit: usize,
+) -> Result<BufferedRequest, Error> {
+    let body = buffer_request_body(&mut req, limit).await?;
+    let (mut parts, incoming) = req.into_parts();
+    parts.extensions.remove::<BufferedBody>();
+    Ok(BufferedRequest {
+        parts,
+        body,
+        incoming,
+    })
+}
+
+/// A response whose body has been read into memory.
+///
+/// Returned by [`buffer_response`]. If `body` is replaced, a
+/// `Content-Length` header is updated to match when the response is
+/// rebuilt.
+#[derive(Debug)]
+pub struct BufferedResponse {
+    /// The status, headers and extensions of the response.
+    pub parts: response::Parts,
+    /// The complete response body.
+    pub body: Bytes,
+}
+
+impl IntoResponse for BufferedResponse {
+    fn into_response(mut self) -> Response<BoxBody> {
+        // Keep a declared length in step with a body that may have changed
+        if self.parts.headers.contains_key(CONTENT_LENGTH) {
+            self.parts
+                .headers
+                .insert(CONTENT_LENGTH, HeaderValue::from(self.body.len()));
+        }
+        Response::from_parts(self.parts, BoxBody::from(self.body))
+    }
+}
+
+/// Reads the whole response body, splitting the response into its parts
+/// and bytes.
+///
+/// A streamed body is collected completely first, so the client receives
+/// nothing until the stream ends. Turn the result back into a response with
+/// [`IntoResponse::into_response`].
+///
+/// # Errors
+///
+/// Returns a 500 error if producing the body fails.
+pub async fn buffer_response(response: Response<BoxBody>) -> Result<BufferedResponse, Error> {
+    let (parts, body) = response.into_parts();
+    let body = body
+        .collect()
+        .await
+        .map_err(|e| Error::internal(format!("failed to read response body: {}", e)))?
+        .to_bytes();
+    Ok(BufferedResponse { parts, body })
+}
+
+/// Takes the body of `req`, preferring bytes buffered by middleware.
+pub(crate) fn take_request_body(mut req: Request<Incoming>) -> Either<Full<Bytes>, Incoming> {
+    match req.extensions_mut().remove::<BufferedBody>() {
+        Some(BufferedBody(bytes)) => Either::Left(Full::new(bytes)),
+        None => Either::Right(req.into_body()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::stream;
+    use http::StatusCode;
+
+    #[tokio::test]
+    async fn test_buffer_response_round_trip() {
+        let response = Response::builder()
+            .status(StatusCode::CREATED)
+            .header("x-custom", "1")
+            .body(BoxBody::from("created"))
+            .unwrap();
+
+        let buffered = buffer_response(response).await.unwrap();
+        assert_eq!(buffered.parts.status, StatusCode::CREATED);
+        assert_eq!(&buffered.body[..], b"created");
+
+        let response = buffered.into_response();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert_eq!(response.headers()["x-custom"], "1");
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        assert_eq!(&body[..], b"created");
+    }
+
+    #[tokio::test]
+    async fn test_buffer_response_collects_stream() {
+        let chunks = stream::iter(["a", "b", "c"].map(|c| Ok::<_, Error>(Bytes::from(c))));
+        let response = Response::new(BoxBody::from_stream(chunks));
+
+        let buffered = buffer_response(response).await.unwrap();
+        assert_eq!(&buffered.body[..], b"abc");
+    }
+
+    #[tokio::test]
+    async fn test_buffer_response_stream_error() {
+        let chunks = stream::iter([
+            Ok(Bytes::from("a")),
+            Err(std::io::Error::other("disk gone")),
+        ]);
+        let response = Response::new(BoxBody::from_stream(chunks));
+
+        let err = buffer_response(response).await.unwrap_err();
+        assert_eq!(err.status, 500);
+    }
+}
@@ -3,13 +3,15 @@ use std::io::Write;
 use bytes::Bytes;
 use flate2::Compression;
 use flate2::write::{DeflateEncoder, GzEncoder};
+use futures_util::{StreamExt, stream};
 use http::{HeaderValue, Response, header};
-use http_body_util::{BodyExt, Full};
+use http_body::Body;
+use http_body_util::BodyExt;
 use hyper::Request;
 use hyper::body::Incoming;
 
 use crate::context::RequestContext;
-use crate::response::BoxBody;
+use crate::response::{BoxBody, BoxError};
 
 use super::{BoxFuture, Middleware, Next};
 
@@ -55,6 +57,66 @@ impl Algorithm {
     }
 }
 
+/// Compresses a body chunk by chunk, flushing after each one so the client
+/// receives data as soon as the handler produces it.
+enum StreamEncoder {
+    Gzip(GzEncoder<Vec<u8>>),
+    Deflate(DeflateEncoder<Vec<u8>>),
+}
+
+impl StreamEncoder {
+    fn new(algorithm: Algorithm, level: Compression) -> Self {
+        match algorithm {
+            Algorithm::Gzip => StreamEncoder::Gzip(GzEncoder::new(Vec::new(), level)),
+            Algorithm::Deflate => StreamEncoder::Deflate(DeflateEncoder::new(Vec::new(), level)),
+        }
+    }
+
+    /// Compresses `chunk` and returns everything encoded so far.
+    fn write(&mut self, chunk: &[u8]) -> std::io::Result<Bytes> {
+        let output = match self {
+            StreamEncoder::Gzip(encoder) => {
+                encoder.write_all(chunk)?;
+                encoder.flush()?;
+                encoder.get_mut()
+            }
+            StreamEncoder::Deflate(encoder) => {
+                encoder.write_all(chunk)?;
+                encoder.flush()?;
+                encoder.get_mut()
+            }
+        };
+        Ok(Bytes::from(std::mem::take(output)))
+    }
+
+    /// Ends the compressed stream, returning the remaining bytes.
+    fn finish(self) -> std::io::Result<Bytes> {
+        match self {
+            StreamEncoder::Gzip(encoder) => encoder.finish(),
+            StreamEncoder::Deflate(encoder) => encoder.finish(),
+        }
+        .map(Bytes::from)
+    }
+}
+
+/// Compresses a streamed body as its chunks arrive.
+fn compress_stream(body: BoxBody, algorithm: Algorithm, level: Compression) -> BoxBody {
+    let encoder = StreamEncoder::new(algorithm, level);
+    let chunks = stream::unfold(
+        (body.into_data_stream(), Some(encoder)),
+        |(mut data, encoder)| async move {
+            let mut encoder = encoder?;
+            let chunk = match data.next().await {
+                Some(Ok(chunk)) => encoder.write(&chunk).map_err(BoxError::from),
+                Some(Err(e)) => return Some((Err(e), (data, None))),
+                None => return Some((encoder.finish().map_err(BoxError::from), (data, None))),
+            };
+            Some((chunk, (data, Some(encoder))))
+        },
+    );
+    BoxBody::from_stream(chunks)
+}
+
 #[derive(Debug, Clone)]
 pub struct CompressionConfig {
     pub min_size: usize,
@@ -104,8 +166,13 @@ impl CompressionMiddleware {
             || ct_str.contains("+xml")
     }
 
+    /// A handler that set `Content-Encoding` itself (e.g. serving a
+    /// pre-gzipped asset) owns the encoding, unless it is `identity`.
     fn is_already_encoded(response: &Response<BoxBody>) -> bool {
-        response.headers().contains_key(header::CONTENT_ENCODING)
+        response
+            .headers()
+            .get(header::CONTENT_ENCODING)
+            .is_some_and(|v| !v.as_bytes().eq_ignore_ascii_case(b"identity"))
     }
 }
 
@@ -145,28 +212,35 @@ impl Middleware for CompressionMiddleware {
                 _ => return response,
             };
 
+            let level = Compression::new(self.config.level);
             let (parts, body) = response.into_parts();
-            let body_bytes = match body.collect().await {
-                Ok(collected) => collected.to_bytes(),
-                Err(_) => return Response::from_parts(parts, Full::new(Bytes::new())),
-            };
 
-            if body_bytes.len() < self.config.min_size {
-                return Response::from_parts(parts, Full::new(body_bytes));
-            }
+            // A streamed body has no known size; compress it on the fly
+            let compressed = if body.size_hint().exact().is_none() {
+                compress_stream(body, algorithm, level)
+            } else {
+                let body_bytes = match body.collect().await {
+                    Ok(collected) => collected.to_bytes(),
+                    Err(_) => return Response::from_parts(parts, BoxBody::empty()),
+                };
+
+                if body_bytes.len() < self.config.min_size {
+                    return Response::from_parts(parts, BoxBody::from(body_bytes));
+                }
 
-            let level = Compression::new(self.config.level);
-            let compressed = match algorithm.compress(&body_bytes, level) {
-                Ok(data) => data,
-                Err(_) => return Response::from_parts(parts, Full::new(body_bytes)),
-            };
+                let compressed = match algorithm.compress(&body_bytes, level) {
+                    Ok(data) => data,
+                    Err(_) => return Response::from_parts(parts, BoxBody::from(body_bytes)),
+                };
 
-            // not worth it
-            if compressed.len() >= body_bytes.len() {
-                return Response::from_parts(parts, Full::new(body_bytes));
-            }
+                // not worth it
+                if compressed.len() >= body_bytes.len() {
+                    return Response::from_parts(parts, BoxBody::from(body_bytes));
+                }
+                BoxBody::from(compressed)
+            };
 
-            let mut response = Response::from_parts(parts, Full::new(Bytes::from(compressed)));
+            let mut response = Response::from_parts(parts, compressed);
             response.headers_mut().insert(
                 header::CONTENT_ENCODING,
                 HeaderValue::from_static(algorithm.content_encoding()),
@@ -229,6 +303,26 @@ mod tests {
         assert!(compressed.len() < data.len());
     }
 
+    #[test]
+    fn test_stream_encoder_flushes_each_chunk() {
+        use std::io::Read;
+
+        let mut encoder = StreamEncoder::new(Algorithm::Gzip, Compression::default());
+        let first = encoder.write(b"hello ").unwrap();
+        let second = encoder.write(b"world").unwrap();
+        assert!(!first.is_empty());
+        assert!(!second.is_empty());
+
+        let mut compressed = [first, second].concat();
+        compressed.extend_from_slice(&encoder.finish().unwrap());
+
+        let mut decoded = String::new();
+        flate2::read::GzDecoder::new(compressed.as_slice())
+            .read_to_string(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, "hello world");
+    }
+
     #[test]
     fn test_is_compressible_content_type() {
         assert!(CompressionMiddleware::is_compressible_content_type(Some(
@@ -242,4 +336,22 @@ mod tests {
         )));
         assert!(CompressionMiddleware::is_compressible_content_type(None));
     }
+
+    #[test]
+    fn test_is_already_encoded() {
+        let encoded = |value: &'static str| {
+            let mut response = Response::new(BoxBody::empty());
+            response
+                .headers_mut()
+                .insert(header::CONTENT_ENCODING, HeaderValue::from_static(value));
+            CompressionMiddleware::is_already_encoded(&response)
+        };
+
+        assert!(encoded("gzip"));
+        assert!(encoded("br"));
+        assert!(!encoded("identity"));
+        assert!(!CompressionMiddleware::is_already_encoded(&Response::new(
+            BoxBody::empty()
+        )));
+    }
 }
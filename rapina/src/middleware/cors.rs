@@ -3,7 +3,9 @@
 //! Provides configurable CORS support for Rapina applications,
 //! handling preflight OPTIONS requests and adding appropriate headers.
 
-use http::{HeaderValue, Method, Request, Response, StatusCode, header};
+use std::time::Duration;
+
+use http::{HeaderMap, HeaderValue, Method, Request, Response, StatusCode, header};
 use hyper::body::Incoming;
 
 use crate::context::RequestContext;
@@ -13,7 +15,23 @@ use super::{BoxFuture, Middleware, Next};
 
 /// Configuration for CORS middleware.
 ///
-/// Use `permissive()` for development or `with_origins()` for production.
+/// Use `permissive()` for development or `with_origins()` for production,
+/// then refine it with the builder methods.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use rapina::http::header;
+/// use rapina::middleware::CorsConfig;
+///
+/// let cors = CorsConfig::with_origins(vec!["https://app.example.com".to_string()])
+///     .allow_credentials(true)
+///     .allow_headers(vec![header::AUTHORIZATION, header::CONTENT_TYPE])
+///     .expose_headers(vec![header::HeaderName::from_static("x-total-count")])
+///     .max_age(Duration::from_secs(600));
+/// ```
 #[derive(Debug, Clone)]
 pub struct CorsConfig {
     /// Allowed origins for CORS requests.
@@ -22,6 +40,15 @@ pub struct CorsConfig {
     pub allowed_methods: AllowedMethods,
     /// Allowed request headers.
     pub allowed_headers: AllowedHeaders,
+    /// Whether to send `Access-Control-Allow-Credentials: true`.
+    ///
+    /// Browsers reject a wildcard origin on credentialed requests, so with
+    /// [`AllowedOrigins::Any`] the request origin is reflected instead.
+    pub allow_credentials: bool,
+    /// Response headers exposed to the browser via `Access-Control-Expose-Headers`.
+    pub exposed_headers: Vec<header::HeaderName>,
+    /// How long browsers may cache preflight results (`Access-Control-Max-Age`).
+    pub max_age: Option<Duration>,
 }
 
 impl CorsConfig {
@@ -33,6 +60,9 @@ impl CorsConfig {
             allowed_origins: AllowedOrigins::Any,
             allowed_methods: AllowedMethods::Any,
             allowed_headers: AllowedHeaders::Any,
+            allow_credentials: false,
+            exposed_headers: Vec::new(),
+            max_age: None,
         }
     }
 
@@ -52,8 +82,35 @@ impl CorsConfig {
             ]),
             allowed_origins: AllowedOrigins::Exact(origins),
             allowed_headers: AllowedHeaders::List(vec![header::ACCEPT, header::AUTHORIZATION]),
+            allow_credentials: false,
+            exposed_headers: Vec::new(),
+            max_age: None,
         }
     }
+
+    /// Sets whether credentialed requests (cookies, `Authorization`) are allowed.
+    pub fn allow_credentials(mut self, allow: bool) -> Self {
+        self.allow_credentials = allow;
+        self
+    }
+
+    /// Sets the request headers allowed in CORS requests.
+    pub fn allow_headers(mut self, headers: Vec<header::HeaderName>) -> Self {
+        self.allowed_headers = AllowedHeaders::List(headers);
+        self
+    }
+
+    /// Sets the response headers exposed to the browser.
+    pub fn expose_headers(mut self, headers: Vec<header::HeaderName>) -> Self {
+        self.exposed_headers = headers;
+        self
+    }
+
+    /// Sets how long browsers may cache preflight results.
+    pub fn max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
 }
 
 /// Specifies which headers are allowed in CORS requests.
@@ -95,69 +152,143 @@ impl CorsMiddleware {
         Self { config }
     }
 
-    fn preflight_response(&self, origin: &Option<HeaderValue>) -> Response<BoxBody> {
+    /// Returns the `Access-Control-Allow-Origin` value for the request origin,
+    /// or `None` if the origin is not allowed.
+    fn allow_origin(&self, origin: &Option<HeaderValue>) -> Option<HeaderValue> {
+        match &self.config.allowed_origins {
+            // Credentialed requests can't use a wildcard, so echo the origin back
+            AllowedOrigins::Any if self.config.allow_credentials => origin.clone(),
+            AllowedOrigins::Any => Some(HeaderValue::from_static("*")),
+            AllowedOrigins::Exact(origins) => origin
+                .as_ref()
+                .filter(|o| {
+                    origins
+                        .iter()
+                        .any(|allowed| o.as_bytes() == allowed.as_bytes())
+                })
+                .cloned(),
+        }
+    }
+
+    /// Builds the response to a preflight request.
+    ///
+    /// The allow headers are only sent when the method requested through
+    /// `Access-Control-Request-Method` (and every header listed in
+    /// `Access-Control-Request-Headers`) is allowed, so the browser blocks
+    /// the actual request otherwise.
+    fn preflight_response(&self, req_headers: &HeaderMap) -> Response<BoxBody> {
         let mut builder = Response::builder().status(StatusCode::NO_CONTENT);
+        let origin = req_headers.get(header::ORIGIN).cloned();
+        let requested_method = req_headers.get(header::ACCESS_CONTROL_REQUEST_METHOD);
+        let requested_headers = req_headers.get(header::ACCESS_CONTROL_REQUEST_HEADERS);
 
         // Set Access-Control-Allow-Origin
-        match &self.config.allowed_origins {
-            AllowedOrigins::Any => {
-                builder = builder.header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*");
-            }
-            AllowedOrigins::Exact(origins) => {
-                if let Some(req_origin) = origin {
-                    let origin_str = req_origin.to_str().unwrap_or("");
-                    if origins.iter().any(|o| o == origin_str) {
-                        builder =
-                            builder.header(header::ACCESS_CONTROL_ALLOW_ORIGIN, req_origin.clone());
-                    }
-                }
+        if let Some(allow_origin) = self.allow_origin(&origin) {
+            builder = builder.header(header::ACCESS_CONTROL_ALLOW_ORIGIN, allow_origin);
+            if self.config.allow_credentials {
+                builder = builder.header(header::ACCESS_CONTROL_ALLOW_CREDENTIALS, "true");
             }
         }
 
-        // Set Access-Control-Allow-Methods
-        let methods_value = match &self.config.allowed_methods {
-            AllowedMethods::Any => "*".to_string(),
-            AllowedMethods::List(methods) => methods
-                .iter()
-                .map(|m| m.as_str())
-                .collect::<Vec<_>>()
-                .join(", "),
-        };
-        builder = builder.header(header::ACCESS_CONTROL_ALLOW_METHODS, methods_value);
-
-        // Set Access-Control-Allow-Headers
-        let headers_value = match &self.config.allowed_headers {
-            AllowedHeaders::Any => "*".to_string(),
-            AllowedHeaders::List(headers) => headers
-                .iter()
-                .map(|h| h.as_str())
-                .collect::<Vec<_>>()
-                .join(", "),
-        };
-        builder = builder.header(header::ACCESS_CONTROL_ALLOW_HEADERS, headers_value);
+        if let Some(methods_value) = self.allow_methods_value(requested_method) {
+            builder = builder.header(header::ACCESS_CONTROL_ALLOW_METHODS, methods_value);
+
+            if let Some(headers_value) = self.allow_headers_value(requested_headers) {
+                builder = builder.header(header::ACCESS_CONTROL_ALLOW_HEADERS, headers_value);
+            }
+
+            if let Some(max_age) = self.config.max_age {
+                builder = builder.header(header::ACCESS_CONTROL_MAX_AGE, max_age.as_secs());
+            }
+        }
 
         builder = builder.header(header::VARY, "Origin");
 
         builder.body(BoxBody::default()).unwrap()
     }
 
+    /// Returns the `Access-Control-Allow-Methods` value, or `None` if the
+    /// requested method is not allowed.
+    fn allow_methods_value(&self, requested: Option<&HeaderValue>) -> Option<HeaderValue> {
+        let requested = match requested {
+            Some(value) => Some(Method::from_bytes(value.as_bytes()).ok()?),
+            None => None,
+        };
+
+        match (&self.config.allowed_methods, requested) {
+            // Echo the method back, since `*` is taken literally on credentialed requests
+            (AllowedMethods::Any, Some(method)) => HeaderValue::from_str(method.as_str()).ok(),
+            (AllowedMethods::Any, None) => Some(HeaderValue::from_static("*")),
+            (AllowedMethods::List(methods), requested) => {
+                if requested.is_some_and(|m| !methods.contains(&m)) {
+                    return None;
+                }
+                let value = methods
+                    .iter()
+                    .map(|m| m.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                HeaderValue::from_str(&value).ok()
+            }
+        }
+    }
+
+    /// Returns the `Access-Control-Allow-Headers` value, or `None` if any
+    /// requested header is not allowed.
+    fn allow_headers_value(&self, requested: Option<&HeaderValue>) -> Option<HeaderValue> {
+        match &self.config.allowed_headers {
+            AllowedHeaders::Any => match requested {
+                Some(value) => Some(value.clone()),
+                None => Some(HeaderValue::from_static("*")),
+            },
+            AllowedHeaders::List(headers) => {
+                if let Some(requested) = requested {
+                    let requested = requested.to_str().ok()?;
+                    let all_allowed = requested
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|name| !name.is_empty())
+                        .all(|name| {
+                            headers
+                                .iter()
+                                .any(|h| h.as_str().eq_ignore_ascii_case(name))
+                        });
+                    if !all_allowed {
+                        return None;
+                    }
+                }
+                let value = headers
+                    .iter()
+                    .map(|h| h.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                HeaderValue::from_str(&value).ok()
+            }
+        }
+    }
+
     fn add_cors_headers(&self, response: &mut Response<BoxBody>, origin: &Option<HeaderValue>) {
         let headers = response.headers_mut();
 
         // Set Access-Control-Allow-Origin
-        match &self.config.allowed_origins {
-            AllowedOrigins::Any => {
+        if let Some(allow_origin) = self.allow_origin(origin) {
+            headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, allow_origin);
+            if self.config.allow_credentials {
                 headers.insert(
-                    header::ACCESS_CONTROL_ALLOW_ORIGIN,
-                    HeaderValue::from_static("*"),
+                    header::ACCESS_CONTROL_ALLOW_CREDENTIALS,
+                    HeaderValue::from_static("true"),
                 );
             }
-            AllowedOrigins::Exact(origins) => {
-                if let Some(req_origin) = origin {
-                    let origin_str = req_origin.to_str().unwrap_or("");
-                    if origins.iter().any(|o| o == origin_str) {
-                        headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, req_origin.clone());
-                    }
+            if !self.config.exposed_headers.is_empty() {
+                let exposed = self
+                    .config
+                    .exposed_headers
+                    .iter()
+                    .map(|h| h.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                if let Ok(value) = HeaderValue::from_str(&exposed) {
+                    headers.insert(header::ACCESS_CONTROL_EXPOSE_HEADERS, value);
                 }
             }
         }
@@ -179,7 +310,7 @@ impl Middleware for CorsMiddleware {
 
             // if it's OPTIONS (preflight), return early with 204 + CORS headers
             if req.method() == Method::OPTIONS {
-                return self.preflight_response(&origin);
+                return self.preflight_response(req.headers());
             }
 
             let mut response = next.run(req).await;
@@ -0,0 +1,225 @@
+use std::io::Read;
+
+use flate2::read::{DeflateDecoder, GzDecoder, ZlibDecoder};
+use http::header::{CONTENT_ENCODING, CONTENT_LENGTH, HeaderValue};
+use hyper::body::Incoming;
+use hyper::{Request, Response};
+
+use crate::context::RequestContext;
+use crate::error::Error;
+use crate::response::{BoxBody, IntoResponse};
+
+use super::{BoxFuture, Middleware, Next, buffer_body};
+
+const DEFAULT_MAX_SIZE: usize = 10 * 1024 * 1024; // 10MB
+
+const SUPPORTED_ENCODINGS: [&str; 2] = ["gzip", "deflate"];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Encoding {
+    Gzip,
+    Deflate,
+}
+
+impl Encoding {
+    fn parse(name: &str) -> Option<Self> {
+        if name.eq_ignore_ascii_case("gzip") || name.eq_ignore_ascii_case("x-gzip") {
+            Some(Encoding::Gzip)
+        } else if name.eq_ignore_ascii_case("deflate") {
+            Some(Encoding::Deflate)
+        } else {
+            None
+        }
+    }
+
+    /// Decodes `data`, failing with a 413 once the output passes `max_size`.
+    fn decode(&self, data: &[u8], max_size: usize) -> Result<Vec<u8>, Error> {
+        match self {
+            Encoding::Gzip => read_limited(GzDecoder::new(data), max_size, "gzip"),
+            // `deflate` is meant to be zlib-wrapped, but some clients send raw deflate
+            Encoding::Deflate => {
+                read_limited(ZlibDecoder::new(data), max_size, "deflate").or_else(|err| {
+                    match err.status {
+                        400 => read_limited(DeflateDecoder::new(data), max_size, "deflate"),
+                        _ => Err(err),
+                    }
+                })
+            }
+        }
+    }
+}
+
+/// Reads at most `max_size` bytes from `decoder`, so a small compressed body
+/// can't expand into an unbounded allocation.
+fn read_limited(decoder: impl Read, max_size: usize, name: &str) -> Result<Vec<u8>, Error> {
+    let mut output = Vec::new();
+    decoder
+        .take(max_size as u64 + 1)
+        .read_to_end(&mut output)
+        .map_err(|_| Error::bad_request(format!("Invalid {} request body", name)))?;
+    if output.len() > max_size {
+        return Err(Error::body_too_large(max_size));
+    }
+    Ok(output)
+}
+
+/// Decompresses gzip and deflate request bodies before the extractors read
+/// them.
+///
+/// The body is decoded according to the request's `Content-Encoding`, and
+/// the header is removed so [`Json`](crate::extract::Json) and the other
+/// body extractors see plain bytes. Requests without a `Content-Encoding`, or
+/// with `identity`, pass through untouched.
+///
+/// An encoding other than gzip or deflate is rejected with 415, and a body
+/// that doesn't decode with 400. The limit (10MB by default) applies to both
+/// the compressed body as it's read and the decompressed output, so neither
+/// a large upload nor a small one that expands can grow without bound; going
+/// over it is rejected with 413. Decoding runs on the blocking thread pool,
+/// so it doesn't stall other requests.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rapina::middleware::RequestDecompressionMiddleware;
+///
+/// Rapina::new()
+///     .middleware(RequestDecompressionMiddleware::new(2 * 1024 * 1024))
+///     .router(router)
+/// ```
+#[derive(Debug, Clone)]
+pub struct RequestDecompressionMiddleware {
+    max_size: usize,
+}
+
+impl RequestDecompressionMiddleware {
+    /// Creates the middleware, limiting decompressed bodies to `max_size`
+    /// bytes.
+    pub fn new(max_size: usize) -> Self {
+        Self { max_size }
+    }
+
+    /// Parses a `Content-Encoding` header into the encodings applied, in the
+    /// order they were applied.
+    fn encodings(value: &HeaderValue) -> Result<Vec<Encoding>, Error> {
+        let value = value
+            .to_str()
+            .map_err(|_| unsupported_encoding("<invalid>"))?;
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty() && !name.eq_ignore_ascii_case("identity"))
+            .map(|name| Encoding::parse(name).ok_or_else(|| unsupported_encoding(name)))
+            .collect()
+    }
+
+    async fn decompress(&self, req: Request<Incoming>) -> Result<Request<Incoming>, Error> {
+        let Some(value) = req.headers().get(CONTENT_ENCODING) else {
+            return Ok(req);
+        };
+        let encodings = Self::encodings(value)?;
+        if encodings.is_empty() {
+            return Ok(req);
+        }
+
+        let mut buffered = buffer_body(req, self.max_size).await?;
+        let (body, max_size) = (buffered.body, self.max_size);
+        buffered.body = tokio::task::spawn_blocking(move || {
+            // Encodings are listed in the order they were applied, so undo the last first
+            encodings.iter().rev().try_fold(body, |body, encoding| {
+                encoding.decode(&body, max_size).map(Into::into)
+            })
+        })
+        .await
+        .map_err(|_| Error::internal("Request body decompression failed"))??;
+        buffered.parts.headers.remove(CONTENT_ENCODING);
+        buffered
+            .parts
+            .headers
+            .insert(CONTENT_LENGTH, HeaderValue::from(buffered.body.len()));
+        Ok(buffered.into_request())
+    }
+}
+
+impl Default for RequestDecompressionMiddleware {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_SIZE)
+    }
+}
+
+fn unsupported_encoding(name: &str) -> Error {
+    Error::unsupported_media_type(format!("Unsupported Content-Encoding '{}'", name))
+        .with_details(serde_json::json!({ "supported": SUPPORTED_ENCODINGS }))
+}
+
+impl Middleware for RequestDecompressionMiddleware {
+    fn handle<'a>(
+        &'a self,
+        req: Request<Incoming>,
+        _ctx: &'a RequestContext,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, Response<BoxBody>> {
+        Box::pin(async move {
+            match self.decompress(req).await {
+                Ok(req) => next.run(req).await,
+                Err(err) => err.into_response(),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::Compression;
+    use flate2::write::{DeflateEncoder, GzEncoder, ZlibEncoder};
+    use std::io::Write;
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn test_decode_gzip_and_deflate() {
+        let data = b"{\"name\":\"rapina\"}";
+        assert_eq!(Encoding::Gzip.decode(&gzip(data), 1024).unwrap(), data);
+
+        let mut zlib = ZlibEncoder::new(Vec::new(), Compression::default());
+        zlib.write_all(data).unwrap();
+        let zlib = zlib.finish().unwrap();
+        assert_eq!(Encoding::Deflate.decode(&zlib, 1024).unwrap(), data);
+
+        let mut raw = DeflateEncoder::new(Vec::new(), Compression::default());
+        raw.write_all(data).unwrap();
+        let raw = raw.finish().unwrap();
+        assert_eq!(Encoding::Deflate.decode(&raw, 1024).unwrap(), data);
+    }
+
+    #[test]
+    fn test_decode_enforces_limit() {
+        let bomb = gzip(&vec![0; 64 * 1024]);
+        assert!(bomb.len() < 1024);
+
+        let err = Encoding::Gzip.decode(&bomb, 1024).unwrap_err();
+        assert_eq!(err.status, 413);
+        assert_eq!(
+            Encoding::Gzip.decode(b"not gzip", 1024).unwrap_err().status,
+            400
+        );
+    }
+
+    #[test]
+    fn test_encodings() {
+        let encodings = |value: &'static str| {
+            RequestDecompressionMiddleware::encodings(&HeaderValue::from_static(value))
+        };
+        assert_eq!(encodings("identity").unwrap(), []);
+        assert_eq!(
+            encodings("deflate, X-Gzip").unwrap(),
+            [Encoding::Deflate, Encoding::Gzip]
+        );
+        assert_eq!(encodings("br").unwrap_err().status, 415);
+    }
+}
@@ -0,0 +1,466 @@
+//! Idempotency-Key support for safely retrying non-idempotent requests.
+//!
+//! Clients send a unique `Idempotency-Key` header with a POST or PUT request.
+//! The first response for a key is stored and replayed for any retry with the
+//! same key and path, so a retried payment or order is only processed once.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use bytes::Bytes;
+use dashmap::DashMap;
+use dashmap::mapref::entry::Entry;
+use http::{HeaderMap, HeaderValue, Method, StatusCode};
+use hyper::body::Incoming;
+use hyper::{Request, Response};
+
+use crate::context::RequestContext;
+use crate::error::Error;
+use crate::response::{BoxBody, IntoResponse};
+
+use super::{BoxFuture, Middleware, Next, buffer_response};
+
+/// Header carrying the client-generated idempotency key.
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+/// Header added to responses replayed from the store.
+pub const IDEMPOTENT_REPLAYED_HEADER: &str = "idempotent-replayed";
+
+/// How often the in-memory store prunes expired entries (every N requests)
+const CLEANUP_INTERVAL: u64 = 1000;
+
+/// A response stored for replay.
+#[derive(Debug, Clone)]
+pub struct CachedResponse {
+    /// The response status.
+    pub status: StatusCode,
+    /// The response headers.
+    pub headers: HeaderMap,
+    /// The response body.
+    pub body: Bytes,
+}
+
+impl IntoResponse for CachedResponse {
+    fn into_response(self) -> Response<BoxBody> {
+        let mut response = Response::new(BoxBody::from(self.body));
+        *response.status_mut() = self.status;
+        *response.headers_mut() = self.headers;
+        response
+    }
+}
+
+/// The state of an idempotency key when a request claims it.
+#[derive(Debug, Clone)]
+pub enum IdempotencyStatus {
+    /// The key was unused and is now reserved for this request.
+    New,
+    /// Another request with the same key is still being processed.
+    InFlight,
+    /// A response was already stored for the key.
+    Completed(CachedResponse),
+}
+
+/// Storage backend for idempotency keys.
+///
+/// [`InMemoryIdempotencyStore`] is used by default. Implement this trait to
+/// share keys between instances, e.g. through Redis.
+pub trait IdempotencyStore: Send + Sync + 'static {
+    /// Reserves `key` for a new request, or reports its current state.
+    ///
+    /// Reserving must be atomic, so that only one of several concurrent
+    /// requests with the same key gets [`IdempotencyStatus::New`]. The
+    /// reservation should expire after `ttl` in case it is never completed.
+    fn begin<'a>(&'a self, key: &'a str, ttl: Duration) -> BoxFuture<'a, IdempotencyStatus>;
+
+    /// Stores the response for a reserved key for `ttl`.
+    fn complete<'a>(
+        &'a self,
+        key: &'a str,
+        response: CachedResponse,
+        ttl: Duration,
+    ) -> BoxFuture<'a, ()>;
+
+    /// Releases a reserved key without storing a response, so it can be retried.
+    fn release<'a>(&'a self, key: &'a str) -> BoxFuture<'a, ()>;
+}
+
+#[derive(Debug)]
+enum StoredState {
+    InFlight,
+    Completed(CachedResponse),
+}
+
+#[derive(Debug)]
+struct StoredEntry {
+    state: StoredState,
+    expires_at: Instant,
+}
+
+/// An [`IdempotencyStore`] that keeps keys in process memory.
+#[derive(Debug, Default)]
+pub struct InMemoryIdempotencyStore {
+    entries: DashMap<String, StoredEntry>,
+    request_count: AtomicU64,
+}
+
+impl InMemoryIdempotencyStore {
+    /// Creates an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Remove entries past their expiry
+    fn cleanup_expired(&self) {
+        let now = Instant::now();
+        self.entries.retain(|_, entry| entry.expires_at > now);
+    }
+}
+
+impl IdempotencyStore for InMemoryIdempotencyStore {
+    fn begin<'a>(&'a self, key: &'a str, ttl: Duration) -> BoxFuture<'a, IdempotencyStatus> {
+        Box::pin(async move {
+            let count = self.request_count.fetch_add(1, Ordering::Relaxed);
+            if count > 0 && count % CLEANUP_INTERVAL == 0 {
+                self.cleanup_expired();
+            }
+
+            let now = Instant::now();
+            let reserved = StoredEntry {
+                state: StoredState::InFlight,
+                expires_at: now + ttl,
+            };
+
+            match self.entries.entry(key.to_string()) {
+                Entry::Occupied(mut entry) if entry.get().expires_at <= now => {
+                    entry.insert(reserved);
+                    IdempotencyStatus::New
+                }
+                Entry::Occupied(entry) => match &entry.get().state {
+                    StoredState::InFlight => IdempotencyStatus::InFlight,
+                    StoredState::Completed(response) => {
+                        IdempotencyStatus::Completed(response.clone())
+                    }
+                },
+                Entry::Vacant(entry) => {
+                    entry.insert(reserved);
+                    IdempotencyStatus::New
+                }
+            }
+        })
+    }
+
+    fn complete<'a>(
+        &'a self,
+        key: &'a str,
+        response: CachedResponse,
+        ttl: Duration,
+    ) -> BoxFuture<'a, ()> {
+        Box::pin(async move {
+            self.entries.insert(
+                key.to_string(),
+                StoredEntry {
+                    state: StoredState::Completed(response),
+                    expires_at: Instant::now() + ttl,
+                },
+            );
+        })
+    }
+
+    fn release<'a>(&'a self, key: &'a str) -> BoxFuture<'a, ()> {
+        Box::pin(async move {
+            self.entries.remove(key);
+        })
+    }
+}
+
+/// A key reserved by [`IdempotencyStore::begin`], released when dropped
+/// unless the response was stored.
+///
+/// A handler that panics, or a request future dropped by a client
+/// disconnect or a timeout, would otherwise leave the key in flight until
+/// the reservation expires and turn every retry into a 409.
+struct Reservation {
+    store: Arc<dyn IdempotencyStore>,
+    key: Option<String>,
+}
+
+impl Reservation {
+    fn new(store: Arc<dyn IdempotencyStore>, key: String) -> Self {
+        Self {
+            store,
+            key: Some(key),
+        }
+    }
+
+    /// Releases the key now.
+    async fn release(mut self) {
+        if let Some(key) = self.key.take() {
+            self.store.release(&key).await;
+        }
+    }
+
+    /// Stores the response for the key, which then stays reserved.
+    async fn complete(mut self, response: CachedResponse, ttl: Duration) {
+        if let Some(key) = &self.key {
+            self.store.complete(key, response, ttl).await;
+        }
+        self.key = None;
+    }
+}
+
+impl Drop for Reservation {
+    fn drop(&mut self) {
+        if let Some(key) = self.key.take()
+            && let Ok(runtime) = tokio::runtime::Handle::try_current()
+        {
+            let store = self.store.clone();
+            runtime.spawn(async move { store.release(&key).await });
+        }
+    }
+}
+
+/// Configuration for idempotency handling.
+#[derive(Debug, Clone)]
+pub struct IdempotencyConfig {
+    /// Methods the middleware applies to.
+    pub methods: Vec<Method>,
+    /// How long stored responses are replayed.
+    pub ttl: Duration,
+}
+
+impl IdempotencyConfig {
+    /// Creates a config for POST and PUT requests with a 24 hour TTL.
+    pub fn new() -> Self {
+        Self {
+            methods: vec![Method::POST, Method::PUT],
+            ttl: Duration::from_secs(24 * 60 * 60),
+        }
+    }
+
+    /// Sets the methods the middleware applies to.
+    pub fn methods(mut self, methods: Vec<Method>) -> Self {
+        self.methods = methods;
+        self
+    }
+
+    /// Sets how long stored responses are replayed.
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+}
+
+impl Default for IdempotencyConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Middleware that replays the stored response for a repeated `Idempotency-Key`.
+///
+/// Responses are stored per key and path. A retry that arrives while the
+/// first request is still running gets `409 Conflict`. Server errors (5xx)
+/// are not stored, so the client can retry them with the same key. Requests
+/// without the header, or with a method not in the config, pass through.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rapina::middleware::{IdempotencyConfig, IdempotencyMiddleware};
+///
+/// Rapina::new()
+///     .middleware(IdempotencyMiddleware::new(IdempotencyConfig::new()))
+///     .router(router)
+///     .listen("127.0.0.1:3000")
+///     .await
+/// ```
+#[derive(Clone)]
+pub struct IdempotencyMiddleware {
+    config: IdempotencyConfig,
+    store: Arc<dyn IdempotencyStore>,
+}
+
+impl std::fmt::Debug for IdempotencyMiddleware {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IdempotencyMiddleware")
+            .field("config", &self.config)
+            .finish_non_exhaustive()
+    }
+}
+
+impl IdempotencyMiddleware {
+    /// Creates the middleware with an in-memory store.
+    pub fn new(config: IdempotencyConfig) -> Self {
+        Self::with_store(config, InMemoryIdempotencyStore::new())
+    }
+
+    /// Creates the middleware with a custom store.
+    pub fn with_store<S: IdempotencyStore>(config: IdempotencyConfig, store: S) -> Self {
+        Self {
+            config,
+            store: Arc::new(store),
+        }
+    }
+}
+
+impl Middleware for IdempotencyMiddleware {
+    fn handle<'a>(
+        &'a self,
+        req: Request<Incoming>,
+        ctx: &'a RequestContext,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, Response<BoxBody>> {
+        Box::pin(async move {
+            if !self.config.methods.contains(req.method()) {
+                return next.run(req).await;
+            }
+            let Some(key) = req
+                .headers()
+                .get(IDEMPOTENCY_KEY_HEADER)
+                .and_then(|v| v.to_str().ok())
+                .filter(|v| !v.is_empty())
+            else {
+                return next.run(req).await;
+            };
+
+            let store_key = format!("{} {}", key, req.uri().path());
+
+            let reservation = match self.store.begin(&store_key, self.config.ttl).await {
+                IdempotencyStatus::New => Reservation::new(self.store.clone(), store_key),
+                IdempotencyStatus::InFlight => {
+                    return Error::conflict(
+                        "A request with this Idempotency-Key is already being processed",
+                    )
+                    .with_trace_id(&ctx.trace_id)
+                    .into_response();
+                }
+                IdempotencyStatus::Completed(cached) => {
+                    let mut response = cached.into_response();
+                    response
+                        .headers_mut()
+                        .insert(IDEMPOTENT_REPLAYED_HEADER, HeaderValue::from_static("true"));
+                    return response;
+                }
+            };
+
+            let response = next.run(req).await;
+
+            if response.status().is_server_error() {
+                reservation.release().await;
+                return response;
+            }
+
+            let buffered = match buffer_response(response).await {
+                Ok(buffered) => buffered,
+                Err(e) => {
+                    reservation.release().await;
+                    return e.into_response();
+                }
+            };
+            let cached = CachedResponse {
+                status: buffered.parts.status,
+                headers: buffered.parts.headers.clone(),
+                body: buffered.body.clone(),
+            };
+            reservation.complete(cached, self.config.ttl).await;
+
+            buffered.into_response()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cached(body: &'static str) -> CachedResponse {
+        CachedResponse {
+            status: StatusCode::CREATED,
+            headers: HeaderMap::new(),
+            body: Bytes::from_static(body.as_bytes()),
+        }
+    }
+
+    #[test]
+    fn test_config_defaults() {
+        let config = IdempotencyConfig::new();
+        assert_eq!(config.methods, vec![Method::POST, Method::PUT]);
+        assert_eq!(config.ttl, Duration::from_secs(86400));
+    }
+
+    #[tokio::test]
+    async fn test_store_reserves_new_key() {
+        let store = InMemoryIdempotencyStore::new();
+        let ttl = Duration::from_secs(60);
+
+        assert!(matches!(
+            store.begin("key", ttl).await,
+            IdempotencyStatus::New
+        ));
+        assert!(matches!(
+            store.begin("key", ttl).await,
+            IdempotencyStatus::InFlight
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_store_returns_completed_response() {
+        let store = InMemoryIdempotencyStore::new();
+        let ttl = Duration::from_secs(60);
+
+        store.begin("key", ttl).await;
+        store.complete("key", cached("done"), ttl).await;
+
+        match store.begin("key", ttl).await {
+            IdempotencyStatus::Completed(response) => {
+                assert_eq!(response.status, StatusCode::CREATED);
+                assert_eq!(&response.body[..], b"done");
+            }
+            other => panic!("expected completed response, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_store_release_frees_key() {
+        let store = InMemoryIdempotencyStore::new();
+        let ttl = Duration::from_secs(60);
+
+        store.begin("key", ttl).await;
+        store.release("key").await;
+
+        assert!(matches!(
+            store.begin("key", ttl).await,
+            IdempotencyStatus::New
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_dropped_reservation_releases_key() {
+        let store: Arc<dyn IdempotencyStore> = Arc::new(InMemoryIdempotencyStore::new());
+        let ttl = Duration::from_secs(60);
+
+        store.begin("key", ttl).await;
+        let reservation = Reservation::new(store.clone(), "key".to_string());
+        drop(reservation);
+        tokio::task::yield_now().await;
+
+        assert!(matches!(
+            store.begin("key", ttl).await,
+            IdempotencyStatus::New
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_store_expired_entry_is_reused() {
+        let store = InMemoryIdempotencyStore::new();
+
+        store.begin("key", Duration::ZERO).await;
+        store.complete("key", cached("old"), Duration::ZERO).await;
+
+        assert!(matches!(
+            store.begin("key", Duration::from_secs(60)).await,
+            IdempotencyStatus::New
+        ));
+    }
+}
@@ -8,23 +8,53 @@
 //! - [`TimeoutMiddleware`] - Request timeout handling
 //! - [`BodyLimitMiddleware`] - Limit request body size
 //! - [`TraceIdMiddleware`] - Add trace IDs to requests/responses
-//! - [`RequestLogMiddleware`] - Structured request logging
+//! - [`RequestLogMiddleware`] - Structured or Combined Log Format request logging
+//! - [`IdempotencyMiddleware`] - Replay responses for repeated `Idempotency-Key`s
+//! - [`BodyLogMiddleware`] - Log request and response bodies with redaction
+//! - [`RequestDecompressionMiddleware`] - Decompress gzip and deflate request bodies
+//!
+//! Middleware that needs to inspect the request body can read it with
+//! [`buffer_request_body`] and still hand it on to the handler.
+//! [`buffer_body`] and [`buffer_response`] split a request or response into
+//! its parts and bytes so both can be rewritten.
+//!
+//! For observing responses without changing them, a [`ResponseObserver`]
+//! is lighter than a full middleware.
 
 mod body_limit;
+mod body_log;
+mod buffer;
 mod compression;
 mod cors;
+mod decompression;
+mod idempotency;
+mod observer;
 mod rate_limit;
 mod request_log;
 mod timeout;
 mod trace_id;
 
 pub use body_limit::BodyLimitMiddleware;
+pub use body_log::BodyLogMiddleware;
+pub(crate) use buffer::take_request_body;
+pub use buffer::{
+    BufferedRequest, BufferedResponse, buffer_body, buffer_request_body, buffer_response,
+};
 pub use compression::{CompressionConfig, CompressionMiddleware};
 pub use cors::{AllowedHeaders, AllowedMethods, AllowedOrigins, CorsConfig, CorsMiddleware};
-pub use rate_limit::{KeyExtractor, RateLimitConfig, RateLimitMiddleware};
-pub use request_log::RequestLogMiddleware;
+pub use decompression::RequestDecompressionMiddleware;
+pub use idempotency::{
+    CachedResponse, IDEMPOTENCY_KEY_HEADER, IDEMPOTENT_REPLAYED_HEADER, IdempotencyConfig,
+    IdempotencyMiddleware, IdempotencyStatus, IdempotencyStore, InMemoryIdempotencyStore,
+};
+pub use observer::{CompletedResponse, ResponseObserver};
+pub use rate_limit::{
+    KeyExtractor, RATE_LIMIT_LIMIT_HEADER, RATE_LIMIT_REMAINING_HEADER, RATE_LIMIT_RESET_HEADER,
+    RateLimitConfig, RateLimitMiddleware,
+};
+pub use request_log::{LogFormat, RequestLogMiddleware};
 pub use timeout::TimeoutMiddleware;
-pub use trace_id::{TRACE_ID_HEADER, TraceIdMiddleware};
+pub use trace_id::{TRACE_ID_HEADER, TraceIdMiddleware, generate_ulid, generate_uuid};
 
 use std::future::Future;
 use std::pin::Pin;
@@ -32,10 +62,11 @@ use std::sync::Arc;
 
 use hyper::body::Incoming;
 use hyper::{Request, Response};
+use tracing::{Instrument, Span, info_span};
 
 use crate::context::RequestContext;
 use crate::response::BoxBody;
-use crate::router::Router;
+use crate::router::{MatchedRoute, Router};
 use crate::state::AppState;
 
 /// A boxed future type used by middleware.
@@ -75,6 +106,37 @@ pub trait Middleware: Send + Sync + 'static {
         ctx: &'a RequestContext,
         next: Next<'a>,
     ) -> BoxFuture<'a, Response<BoxBody>>;
+
+    /// Name shown for this middleware by the `/__rapina/info` endpoint.
+    ///
+    /// Defaults to the type name without its module path.
+    fn name(&self) -> &str {
+        short_type_name(std::any::type_name::<Self>())
+    }
+
+    /// Supplies the request's trace ID, e.g. one propagated in a header.
+    ///
+    /// Called for each middleware in order before the request span opens
+    /// and the chain runs; the first ID returned replaces the generated one,
+    /// so the span, every middleware and the handler see the same ID.
+    /// Defaults to `None`.
+    fn trace_id(&self, _req: &Request<Incoming>) -> Option<String> {
+        None
+    }
+
+    /// Whether this middleware opens its own `request` span, in which case
+    /// the stack doesn't open one. Defaults to `false`.
+    fn opens_request_span(&self) -> bool {
+        false
+    }
+}
+
+/// Strips module paths from a type name, e.g. `rapina::middleware::CorsMiddleware`
+/// becomes `CorsMiddleware`. Generic arguments are kept as they are.
+fn short_type_name(full: &str) -> &str {
+    let end = full.find('<').unwrap_or(full.len());
+    let start = full[..end].rfind("::").map(|i| i + 2).unwrap_or(0);
+    &full[start..]
 }
 
 /// Represents the next middleware or handler in the chain.
@@ -117,26 +179,76 @@ impl<'a> Next<'a> {
 }
 
 /// A stack of middleware to be executed in order.
+///
+/// Each request runs inside a `request` span carrying its trace ID, method
+/// and path, so every `tracing` event emitted by middleware and handlers
+/// can be correlated with the request.
 pub struct MiddlewareStack {
     middlewares: Vec<Arc<dyn Middleware>>,
+    observers: Vec<Arc<dyn ResponseObserver>>,
+    request_span: bool,
 }
 
 impl MiddlewareStack {
     pub fn new() -> Self {
         Self {
             middlewares: Vec::new(),
+            observers: Vec::new(),
+            request_span: true,
         }
     }
 
     pub fn add<M: Middleware>(&mut self, middleware: M) {
-        self.middlewares.push(Arc::new(middleware));
+        self.push(Arc::new(middleware));
     }
 
     pub fn push(&mut self, middleware: Arc<dyn Middleware>) {
+        if middleware.opens_request_span() {
+            self.request_span = false;
+        }
         self.middlewares.push(middleware);
     }
 
+    /// Adds an observer that sees every response after the whole chain has run.
+    pub fn observe<O: ResponseObserver>(&mut self, observer: O) {
+        self.observers.push(Arc::new(observer));
+    }
+
     pub async fn execute(
+        &self,
+        mut req: Request<Incoming>,
+        router: &Router,
+        state: &Arc<AppState>,
+        ctx: &RequestContext,
+    ) -> Response<BoxBody> {
+        let resolved;
+        let ctx = match self.middlewares.iter().find_map(|m| m.trace_id(&req)) {
+            Some(trace_id) => {
+                // Keeps the start time and the values shared with the request's copy
+                let mut with_id = ctx.clone();
+                with_id.trace_id = trace_id;
+                req.extensions_mut().insert(with_id.clone());
+                resolved = with_id;
+                &resolved
+            }
+            None => ctx,
+        };
+
+        let span = if self.request_span {
+            info_span!(
+                target: "rapina::request",
+                "request",
+                method = %req.method(),
+                path = %req.uri().path(),
+                trace_id = %ctx.trace_id,
+            )
+        } else {
+            Span::none()
+        };
+        self.run(req, router, state, ctx).instrument(span).await
+    }
+
+    async fn run(
         &self,
         req: Request<Incoming>,
         router: &Router,
@@ -144,12 +256,42 @@ impl MiddlewareStack {
         ctx: &RequestContext,
     ) -> Response<BoxBody> {
         let next = Next::new(&self.middlewares, router, state, ctx);
-        next.run(req).await
+        if self.observers.is_empty() {
+            return next.run(req).await;
+        }
+
+        let method = req.method().clone();
+        let path = req.uri().path().to_string();
+        let response = next.run(req).await;
+
+        let info = CompletedResponse {
+            ctx,
+            method: &method,
+            path: &path,
+            route: response
+                .extensions()
+                .get::<MatchedRoute>()
+                .map(MatchedRoute::as_str),
+            status: response.status(),
+            elapsed: ctx.elapsed(),
+        };
+        for observer in &self.observers {
+            observer.on_response(&info);
+        }
+        response
     }
 
     pub fn is_empty(&self) -> bool {
         self.middlewares.is_empty()
     }
+
+    /// Returns the middleware names in the order they run.
+    pub fn names(&self) -> Vec<String> {
+        self.middlewares
+            .iter()
+            .map(|m| m.name().to_string())
+            .collect()
+    }
 }
 
 impl Default for MiddlewareStack {
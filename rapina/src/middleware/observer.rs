@@ -0,0 +1,51 @@
+//! Observing completed responses without writing a middleware.
+
+use std::time::Duration;
+
+use http::{Method, StatusCode};
+
+use crate::context::RequestContext;
+
+/// A completed request, as seen by a [`ResponseObserver`].
+#[derive(Debug)]
+pub struct CompletedResponse<'a> {
+    /// The context of the request.
+    pub ctx: &'a RequestContext,
+    /// The request method.
+    pub method: &'a Method,
+    /// The request path, without the query string.
+    pub path: &'a str,
+    /// The route pattern that matched, e.g. `/users/:id`, or `None` when no
+    /// route matched.
+    pub route: Option<&'a str>,
+    /// The final response status.
+    pub status: StatusCode,
+    /// Time from the start of the request until the response was ready.
+    ///
+    /// A streamed body is still being sent at this point, so its transfer
+    /// time isn't included.
+    pub elapsed: Duration,
+}
+
+/// Observes every response once the middleware chain has finished.
+///
+/// Register observers with [`Rapina::on_response`](crate::app::Rapina::on_response).
+/// They run after all middleware, so they see the status actually sent to
+/// the client, including rejections from auth, rate limiting and timeouts.
+/// Observers run inline before the response is sent, so keep them fast and
+/// hand slow work, like writing an audit log, to a background task.
+///
+/// Closures taking a `&CompletedResponse` implement this trait.
+pub trait ResponseObserver: Send + Sync + 'static {
+    /// Called with each completed response.
+    fn on_response(&self, info: &CompletedResponse<'_>);
+}
+
+impl<F> ResponseObserver for F
+where
+    F: Fn(&CompletedResponse<'_>) + Send + Sync + 'static,
+{
+    fn on_response(&self, info: &CompletedResponse<'_>) {
+        self(info)
+    }
+}
@@ -3,12 +3,15 @@ use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{Duration, Instant};
 
 use dashmap::DashMap;
+use http::{HeaderMap, HeaderValue};
 use hyper::body::Incoming;
 use hyper::{Request, Response};
 
 use crate::context::RequestContext;
 use crate::error::Error;
+use crate::extract::{Constraint, parse_pattern};
 use crate::response::{BoxBody, IntoResponse};
+use crate::router::Router;
 
 use super::{BoxFuture, Middleware, Next};
 
@@ -21,6 +24,15 @@ const CLEANUP_INTERVAL: u64 = 1000;
 /// Remove buckets not seen in this duration
 const STALE_AFTER: Duration = Duration::from_secs(600); // 10 minutes
 
+/// Header with the number of requests the client may burst
+pub const RATE_LIMIT_LIMIT_HEADER: &str = "x-ratelimit-limit";
+
+/// Header with the number of requests left before the client is limited
+pub const RATE_LIMIT_REMAINING_HEADER: &str = "x-ratelimit-remaining";
+
+/// Header with the seconds until the client's quota is fully restored
+pub const RATE_LIMIT_RESET_HEADER: &str = "x-ratelimit-reset";
+
 /// Internal state for each rate-limited key
 #[derive(Debug)]
 struct TokenBucket {
@@ -77,6 +89,30 @@ impl KeyExtractor {
     }
 }
 
+/// Outcome of checking a request against a bucket
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct LimitStatus {
+    /// Bucket capacity
+    limit: u32,
+    /// Whole tokens left after this request
+    remaining: u32,
+    /// Seconds until the bucket is full again
+    reset: u64,
+    /// Seconds until a request is allowed, set when this one was rejected
+    retry_after: Option<u64>,
+}
+
+impl LimitStatus {
+    fn write_headers(&self, headers: &mut HeaderMap) {
+        headers.insert(RATE_LIMIT_LIMIT_HEADER, HeaderValue::from(self.limit));
+        headers.insert(
+            RATE_LIMIT_REMAINING_HEADER,
+            HeaderValue::from(self.remaining),
+        );
+        headers.insert(RATE_LIMIT_RESET_HEADER, HeaderValue::from(self.reset));
+    }
+}
+
 /// Configuration for rate limiting
 #[derive(Debug, Clone)]
 pub struct RateLimitConfig {
@@ -110,10 +146,67 @@ impl RateLimitConfig {
     }
 }
 
+/// A limit that applies to paths matching a route pattern
+#[derive(Debug, Clone)]
+struct RouteLimit {
+    /// The pattern as given, naming the limit's buckets.
+    name: String,
+    /// The parsed pattern, without a trailing `/*`.
+    pattern: String,
+    constraints: Vec<(String, Constraint)>,
+    /// Whether the pattern ended in `/*` and covers every path below it.
+    prefix: bool,
+    config: RateLimitConfig,
+}
+
+impl RouteLimit {
+    /// Matches a path against the pattern, like `router` matches routes.
+    ///
+    /// A prefix pattern matches when the path, or the path up to any of its
+    /// `/`s, does, so `/api/*` covers a whole route group.
+    fn matches(&self, router: &Router, path: &str) -> bool {
+        let matches = |path: &str| {
+            router
+                .match_pattern(&self.pattern, &self.constraints, path)
+                .is_some()
+        };
+        if !self.prefix {
+            return matches(path);
+        }
+        if self.pattern.is_empty() {
+            return true;
+        }
+        path.match_indices('/')
+            .map(|(i, _)| &path[..i])
+            .chain([path])
+            .any(matches)
+    }
+}
+
 /// Rate limiting middleware using token bucket algorithm
+///
+/// Requests are limited by the config given to [`new`](Self::new) unless
+/// their path matches a pattern added with [`route`](Self::route). Each
+/// pattern keeps its own buckets, so a client that exhausts the login
+/// limit can still use the rest of the API.
+///
+/// Every response carries `X-RateLimit-Limit`, `X-RateLimit-Remaining` and
+/// `X-RateLimit-Reset` (seconds until the quota is full again), and rejected
+/// requests also get `Retry-After`.
+///
+/// # Example
+///
+/// ```ignore
+/// let rate_limit = RateLimitMiddleware::new(RateLimitConfig::per_minute(100))
+///     .route("/login", RateLimitConfig::per_minute(5))
+///     .route("/admin/*", RateLimitConfig::per_minute(20));
+///
+/// Rapina::new().middleware(rate_limit).router(router)
+/// ```
 #[derive(Debug)]
 pub struct RateLimitMiddleware {
     config: RateLimitConfig,
+    routes: Vec<RouteLimit>,
     buckets: Arc<DashMap<String, TokenBucket>>,
     request_count: Arc<AtomicU64>,
 }
@@ -122,6 +215,7 @@ impl Clone for RateLimitMiddleware {
     fn clone(&self) -> Self {
         Self {
             config: self.config.clone(),
+            routes: self.routes.clone(),
             buckets: Arc::clone(&self.buckets),
             request_count: Arc::clone(&self.request_count),
         }
@@ -129,14 +223,45 @@ impl Clone for RateLimitMiddleware {
 }
 
 impl RateLimitMiddleware {
+    /// Creates the middleware with the limit used for unmatched routes.
     pub fn new(config: RateLimitConfig) -> Self {
         Self {
             config,
+            routes: Vec::new(),
             buckets: Arc::new(DashMap::new()),
             request_count: Arc::new(AtomicU64::new(0)),
         }
     }
 
+    /// Uses a separate limit for paths matching `pattern`.
+    ///
+    /// Patterns use the router's `:param` syntax, and may end in `/*` to
+    /// match every path below a prefix. They're matched with the router's
+    /// trailing slash and case settings, so `/Login/` can't get around a
+    /// `/login` limit the router would route there anyway. When several
+    /// patterns match, the one added first wins.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the pattern has an invalid parameter constraint.
+    pub fn route(mut self, pattern: impl Into<String>, config: RateLimitConfig) -> Self {
+        let name = pattern.into();
+        let (pattern, prefix) = match name.strip_suffix("/*") {
+            Some(pattern) => (pattern, true),
+            None => (name.as_str(), false),
+        };
+        let (pattern, constraints) = parse_pattern(pattern)
+            .unwrap_or_else(|e| panic!("invalid route pattern '{}': {}", name, e));
+        self.routes.push(RouteLimit {
+            name,
+            pattern,
+            constraints,
+            prefix,
+            config,
+        });
+        self
+    }
+
     /// Remove buckets that haven't been accessed recently
     fn cleanup_stale_buckets(&self) {
         let now = Instant::now();
@@ -144,8 +269,13 @@ impl RateLimitMiddleware {
             .retain(|_, bucket| now.duration_since(bucket.last_refill) < STALE_AFTER);
     }
 
-    /// Check if request is allowed, returns Some(retry_after_secs) if rate limited
-    fn check_rate_limit(&self, key: &str) -> Option<u64> {
+    /// Check if request is allowed under the default limit
+    fn check_rate_limit(&self, key: &str) -> LimitStatus {
+        self.check_limit(&self.config, key)
+    }
+
+    /// Check a request against `config`, using the bucket stored under `key`
+    fn check_limit(&self, config: &RateLimitConfig, key: &str) -> LimitStatus {
         // Periodic cleanup: every CLEANUP_INTERVAL requests, prune stale buckets
         let count = self.request_count.fetch_add(1, Ordering::Relaxed);
         if count > 0 && count % CLEANUP_INTERVAL == 0 {
@@ -157,25 +287,33 @@ impl RateLimitMiddleware {
             .buckets
             .entry(key.to_string())
             .or_insert_with(|| TokenBucket {
-                tokens: self.config.burst as f64,
+                tokens: config.burst as f64,
                 last_refill: now,
             });
 
         // Refill tokens based on elapsed time
         let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
-        let refill = elapsed * self.config.requests_per_second;
-        bucket.tokens = (bucket.tokens + refill).min(self.config.burst as f64);
+        let refill = elapsed * config.requests_per_second;
+        bucket.tokens = (bucket.tokens + refill).min(config.burst as f64);
         bucket.last_refill = now;
 
         // Try to consume one token
-        if bucket.tokens >= 1.0 {
+        let retry_after = if bucket.tokens >= 1.0 {
             bucket.tokens -= 1.0;
             None // Request allowed
         } else {
             // Calculate when bucket will have 1 token
             let tokens_needed = 1.0 - bucket.tokens;
-            let seconds_until_ready = tokens_needed / self.config.requests_per_second;
+            let seconds_until_ready = tokens_needed / config.requests_per_second;
             Some(seconds_until_ready.ceil() as u64)
+        };
+
+        let seconds_until_full = (config.burst as f64 - bucket.tokens) / config.requests_per_second;
+        LimitStatus {
+            limit: config.burst,
+            remaining: bucket.tokens.floor() as u32,
+            reset: seconds_until_full.ceil() as u64,
+            retry_after,
         }
     }
 }
@@ -188,21 +326,30 @@ impl Middleware for RateLimitMiddleware {
         next: Next<'a>,
     ) -> BoxFuture<'a, Response<BoxBody>> {
         Box::pin(async move {
-            let key = self.config.key_extractor.extract(&req);
-
-            if let Some(retry_after) = self.check_rate_limit(&key) {
+            let path = req.uri().path();
+            let route = self.routes.iter().find(|r| r.matches(next.router, path));
+            let status = match route {
+                Some(route) => {
+                    let client = route.config.key_extractor.extract(&req);
+                    // Prefix the pattern so each route limit has its own buckets
+                    let key = format!("{} {}", route.name, client);
+                    self.check_limit(&route.config, &key)
+                }
+                None => self.check_rate_limit(&self.config.key_extractor.extract(&req)),
+            };
+
+            if let Some(retry_after) = status.retry_after {
                 let mut response = Error::rate_limited("too many requests")
                     .with_trace_id(&ctx.trace_id)
+                    .with_retry_after(Duration::from_secs(retry_after))
                     .into_response();
-
-                response
-                    .headers_mut()
-                    .insert("retry-after", retry_after.to_string().parse().unwrap());
-
+                status.write_headers(response.headers_mut());
                 return response;
             }
 
-            next.run(req).await
+            let mut response = next.run(req).await;
+            status.write_headers(response.headers_mut());
+            response
         })
     }
 }
@@ -246,11 +393,21 @@ mod tests {
 
         // Should allow 5 requests (burst capacity)
         for _ in 0..5 {
-            assert!(middleware.check_rate_limit("test-key").is_none());
+            assert!(
+                middleware
+                    .check_rate_limit("test-key")
+                    .retry_after
+                    .is_none()
+            );
         }
 
         // 6th request should be rate limited
-        assert!(middleware.check_rate_limit("test-key").is_some());
+        assert!(
+            middleware
+                .check_rate_limit("test-key")
+                .retry_after
+                .is_some()
+        );
     }
 
     #[test]
@@ -259,10 +416,15 @@ mod tests {
         let middleware = RateLimitMiddleware::new(config);
 
         // First request allowed
-        assert!(middleware.check_rate_limit("test-key").is_none());
+        assert!(
+            middleware
+                .check_rate_limit("test-key")
+                .retry_after
+                .is_none()
+        );
 
         // Second request blocked with retry_after
-        let retry_after = middleware.check_rate_limit("test-key");
+        let retry_after = middleware.check_rate_limit("test-key").retry_after;
         assert!(retry_after.is_some());
         assert_eq!(retry_after.unwrap(), 1); // Should wait ~1 second
     }
@@ -273,12 +435,12 @@ mod tests {
         let middleware = RateLimitMiddleware::new(config);
 
         // Each key gets its own bucket
-        assert!(middleware.check_rate_limit("user-1").is_none());
-        assert!(middleware.check_rate_limit("user-2").is_none());
-        assert!(middleware.check_rate_limit("user-3").is_none());
+        assert!(middleware.check_rate_limit("user-1").retry_after.is_none());
+        assert!(middleware.check_rate_limit("user-2").retry_after.is_none());
+        assert!(middleware.check_rate_limit("user-3").retry_after.is_none());
 
         // But same key is limited
-        assert!(middleware.check_rate_limit("user-1").is_some());
+        assert!(middleware.check_rate_limit("user-1").retry_after.is_some());
     }
 
     #[test]
@@ -288,14 +450,96 @@ mod tests {
         let middleware2 = middleware1.clone();
 
         // Use one token via middleware1
-        assert!(middleware1.check_rate_limit("shared-key").is_none());
+        assert!(
+            middleware1
+                .check_rate_limit("shared-key")
+                .retry_after
+                .is_none()
+        );
 
         // Use second token via middleware2 (same shared bucket)
-        assert!(middleware2.check_rate_limit("shared-key").is_none());
+        assert!(
+            middleware2
+                .check_rate_limit("shared-key")
+                .retry_after
+                .is_none()
+        );
 
         // Both should now see the bucket as empty
-        assert!(middleware1.check_rate_limit("shared-key").is_some());
-        assert!(middleware2.check_rate_limit("shared-key").is_some());
+        assert!(
+            middleware1
+                .check_rate_limit("shared-key")
+                .retry_after
+                .is_some()
+        );
+        assert!(
+            middleware2
+                .check_rate_limit("shared-key")
+                .retry_after
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn test_status_counts_down_remaining() {
+        let middleware = RateLimitMiddleware::new(RateLimitConfig::new(1.0, 3));
+
+        let first = middleware.check_rate_limit("key");
+        assert_eq!((first.limit, first.remaining), (3, 2));
+        assert_eq!(middleware.check_rate_limit("key").remaining, 1);
+        assert_eq!(middleware.check_rate_limit("key").remaining, 0);
+
+        let rejected = middleware.check_rate_limit("key");
+        assert_eq!(rejected.remaining, 0);
+        assert_eq!(rejected.reset, 3);
+        assert!(rejected.retry_after.is_some());
+    }
+
+    #[test]
+    fn test_route_pattern_matching() {
+        let router = Router::new();
+        let matches = |pattern: &str, path: &str| {
+            RateLimitMiddleware::new(RateLimitConfig::per_minute(5))
+                .route(pattern, RateLimitConfig::per_minute(5))
+                .routes[0]
+                .matches(&router, path)
+        };
+
+        assert!(matches("/login", "/login"));
+        assert!(!matches("/login", "/login/extra"));
+        assert!(!matches("/login", "/Login"));
+        assert!(matches("/users/:id", "/users/42"));
+        assert!(!matches("/users/:id", "/users"));
+        assert!(!matches("/users/:id(\\d+)", "/users/abc"));
+        assert!(matches("/admin/*", "/admin/users/1"));
+        assert!(matches("/admin/*", "/admin/"));
+        assert!(matches("/admin/*", "/admin"));
+        assert!(!matches("/admin/*", "/administrators"));
+        assert!(!matches("/admin/*", "/items"));
+        assert!(matches("/*", "/items"));
+    }
+
+    #[test]
+    fn test_route_limits_use_separate_buckets() {
+        let middleware = RateLimitMiddleware::new(RateLimitConfig::new(1.0, 3))
+            .route("/login", RateLimitConfig::new(1.0, 1));
+        let login = &middleware.routes[0].config;
+
+        assert!(
+            middleware
+                .check_limit(login, "/login client")
+                .retry_after
+                .is_none()
+        );
+        assert!(
+            middleware
+                .check_limit(login, "/login client")
+                .retry_after
+                .is_some()
+        );
+
+        // The default bucket for the same client is untouched
+        assert!(middleware.check_rate_limit("client").retry_after.is_none());
     }
 
     #[test]
@@ -1,18 +1,60 @@
+use std::fmt;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use http::header::{REFERER, USER_AGENT};
+use http_body::Body;
 use hyper::body::Incoming;
 use hyper::{Request, Response};
-use tracing::{Instrument, info, info_span};
+use tracing::info;
 
 use crate::context::RequestContext;
+use crate::extract::ConnectInfo;
 use crate::response::BoxBody;
 
 use super::{BoxFuture, Middleware, Next};
 
+/// The shape of the lines written by [`RequestLogMiddleware`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogFormat {
+    /// A `request completed` event with status and duration fields, inside a
+    /// `request` span carrying the method, path and trace ID. Rendered as
+    /// text or JSON depending on the [`TracingConfig`](crate::observability::TracingConfig).
+    #[default]
+    Structured,
+    /// One Apache Combined Log Format line per request, followed by the
+    /// latency in microseconds like Apache's `%D`:
+    ///
+    /// ```text
+    /// 203.0.113.7 - - [16/Oct/2026:09:30:00 +0000] "GET /users?page=2 HTTP/1.1" 200 512 "-" "curl/8.5.0" 1834
+    /// ```
+    ///
+    /// The size is `-` when the body is empty or streamed.
+    Combined,
+}
+
 #[derive(Debug, Clone, Copy)]
-pub struct RequestLogMiddleware;
+pub struct RequestLogMiddleware {
+    format: LogFormat,
+}
 
 impl RequestLogMiddleware {
     pub fn new() -> Self {
-        Self
+        Self {
+            format: LogFormat::Structured,
+        }
+    }
+
+    /// Logs each request as an Apache Combined Log Format line.
+    pub fn combined() -> Self {
+        Self::new().with_format(LogFormat::Combined)
+    }
+
+    /// Sets the format of the log lines.
+    pub fn with_format(mut self, format: LogFormat) -> Self {
+        self.format = format;
+        self
     }
 }
 
@@ -29,36 +71,113 @@ impl Middleware for RequestLogMiddleware {
         ctx: &'a RequestContext,
         next: Next<'a>,
     ) -> BoxFuture<'a, Response<BoxBody>> {
-        let method = req.method().clone();
-        let path = req.uri().path().to_string();
-        let trace_id = ctx.trace_id.clone();
-
-        let span = info_span!(
-            "request",
-            method = %method,
-            path = %path,
-            trace_id = %trace_id,
-        );
+        if self.format == LogFormat::Combined {
+            return Box::pin(log_combined(req, ctx, next));
+        }
+
+        // The event inherits the method, path and trace ID from the request
+        // span the middleware stack opens around every request
+        Box::pin(async move {
+            let response = next.run(req).await;
+            let duration = ctx.elapsed();
+            let status = response.status().as_u16();
+
+            info!(
+                status = status,
+                duration_ms = duration.as_millis() as u64,
+                "request completed"
+            );
+
+            response
+        })
+    }
+}
+
+async fn log_combined(
+    req: Request<Incoming>,
+    ctx: &RequestContext,
+    next: Next<'_>,
+) -> Response<BoxBody> {
+    let header = |name| {
+        req.headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string)
+    };
+    let client = req.extensions().get::<ConnectInfo>().map(|info| info.0);
+    let time = Utc::now();
+    let request_line = format!(
+        "{} {} {:?}",
+        req.method(),
+        req.uri()
+            .path_and_query()
+            .map_or_else(|| req.uri().path(), |pq| pq.as_str()),
+        req.version()
+    );
+    let referer = header(REFERER);
+    let user_agent = header(USER_AGENT);
+
+    let response = next.run(req).await;
+
+    let line = CombinedLine {
+        client,
+        time,
+        request_line: &request_line,
+        status: response.status().as_u16(),
+        bytes: response.body().size_hint().exact(),
+        referer: referer.as_deref(),
+        user_agent: user_agent.as_deref(),
+        elapsed: ctx.elapsed(),
+    };
+    info!(target: "rapina::access", "{}", line);
+
+    response
+}
+
+/// A request formatted as an Apache Combined Log Format line.
+struct CombinedLine<'a> {
+    client: Option<SocketAddr>,
+    time: DateTime<Utc>,
+    request_line: &'a str,
+    status: u16,
+    bytes: Option<u64>,
+    referer: Option<&'a str>,
+    user_agent: Option<&'a str>,
+    elapsed: Duration,
+}
 
-        Box::pin(
-            async move {
-                let response = next.run(req).await;
-                let duration = ctx.elapsed();
-                let status = response.status().as_u16();
-
-                info!(
-                    status = status,
-                    duration_ms = duration.as_millis() as u64,
-                    "request completed"
-                );
-
-                response
-            }
-            .instrument(span),
+impl fmt::Display for CombinedLine<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.client {
+            Some(addr) => write!(f, "{}", addr.ip())?,
+            None => f.write_str("-")?,
+        }
+        write!(
+            f,
+            " - - [{}] \"{}\" {} ",
+            self.time.format("%d/%b/%Y:%H:%M:%S %z"),
+            escape(self.request_line),
+            self.status
+        )?;
+        match self.bytes {
+            Some(bytes) if bytes > 0 => write!(f, "{}", bytes)?,
+            _ => f.write_str("-")?,
+        }
+        write!(
+            f,
+            " \"{}\" \"{}\" {}",
+            escape(self.referer.unwrap_or("-")),
+            escape(self.user_agent.unwrap_or("-")),
+            self.elapsed.as_micros()
         )
     }
 }
 
+/// Escapes quotes and backslashes so a quoted field can't end early.
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -72,4 +191,68 @@ mod tests {
     fn test_request_log_middleware_default() {
         let _mw: RequestLogMiddleware = Default::default();
     }
+
+    #[test]
+    fn test_request_log_middleware_format() {
+        assert_eq!(RequestLogMiddleware::new().format, LogFormat::Structured);
+        assert_eq!(RequestLogMiddleware::combined().format, LogFormat::Combined);
+    }
+
+    fn entry() -> CombinedLine<'static> {
+        CombinedLine {
+            client: Some("203.0.113.7:51234".parse().unwrap()),
+            time: DateTime::from_timestamp(1_792_143_000, 0).unwrap(),
+            request_line: "GET /users?page=2 HTTP/1.1",
+            status: 200,
+            bytes: Some(512),
+            referer: None,
+            user_agent: Some("curl/8.5.0"),
+            elapsed: Duration::from_micros(1834),
+        }
+    }
+
+    #[test]
+    fn test_combined_line_shape() {
+        let line = entry().to_string();
+        assert_eq!(
+            line,
+            "203.0.113.7 - - [16/Oct/2026:09:30:00 +0000] \"GET /users?page=2 HTTP/1.1\" 200 512 \"-\" \"curl/8.5.0\" 1834"
+        );
+
+        let clf = regex::Regex::new(
+            r#"^\S+ \S+ \S+ \[\d{2}/\w{3}/\d{4}:\d{2}:\d{2}:\d{2} [+-]\d{4}\] "[^"]*" \d{3} (\d+|-) "[^"]*" "[^"]*" \d+$"#,
+        )
+        .unwrap();
+        assert!(clf.is_match(&line), "{}", line);
+    }
+
+    #[test]
+    fn test_combined_line_placeholders() {
+        let line = CombinedLine {
+            client: None,
+            bytes: Some(0),
+            user_agent: None,
+            ..entry()
+        }
+        .to_string();
+        assert!(line.starts_with("- - - ["), "{}", line);
+        assert!(line.contains(" 200 - \"-\" \"-\" "), "{}", line);
+
+        let line = CombinedLine {
+            bytes: None,
+            ..entry()
+        }
+        .to_string();
+        assert!(line.contains(" 200 - "), "{}", line);
+    }
+
+    #[test]
+    fn test_combined_line_escapes_quotes() {
+        let line = CombinedLine {
+            user_agent: Some("evil\" agent"),
+            ..entry()
+        }
+        .to_string();
+        assert!(line.contains(" \"evil\\\" agent\" "), "{}", line);
+    }
 }
@@ -1,22 +1,87 @@
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
+use http::Method;
 use hyper::body::Incoming;
 use hyper::{Request, Response};
 
 use crate::context::RequestContext;
 use crate::error::Error;
+use crate::extract::{Constraint, Deadline, parse_pattern};
 use crate::response::{BoxBody, IntoResponse};
+use crate::router::Router;
 
 use super::{BoxFuture, Middleware, Next};
 
+/// A timeout that replaces the default for one route.
+#[derive(Debug, Clone)]
+struct RouteTimeout {
+    method: Method,
+    pattern: String,
+    constraints: Vec<(String, Constraint)>,
+    duration: Duration,
+}
+
 #[derive(Debug, Clone)]
 pub struct TimeoutMiddleware {
     pub(crate) duration: Duration,
+    routes: Vec<RouteTimeout>,
 }
 
 impl TimeoutMiddleware {
     pub fn new(duration: Duration) -> Self {
-        Self { duration }
+        Self {
+            duration,
+            routes: Vec::new(),
+        }
+    }
+
+    /// Uses `duration` instead of the default for requests matching
+    /// `method` and `pattern`.
+    ///
+    /// The pattern uses the same syntax as the router, e.g.
+    /// `/reports/:id`, and is matched with the router's trailing slash and
+    /// case settings. When several overrides match, the first one added
+    /// wins.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the pattern has an invalid parameter constraint.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// Rapina::new()
+    ///     .middleware(
+    ///         TimeoutMiddleware::new(Duration::from_secs(30))
+    ///             .route(Method::POST, "/reports", Duration::from_secs(300)),
+    ///     )
+    ///     .router(router)
+    ///     .listen("127.0.0.1:3000")
+    ///     .await
+    /// ```
+    pub fn route(mut self, method: Method, pattern: &str, duration: Duration) -> Self {
+        let (pattern, constraints) = parse_pattern(pattern)
+            .unwrap_or_else(|e| panic!("invalid route pattern '{}': {}", pattern, e));
+        self.routes.push(RouteTimeout {
+            method,
+            pattern,
+            constraints,
+            duration,
+        });
+        self
+    }
+
+    /// Returns the timeout for a request, matching paths like `router` does.
+    fn duration_for(&self, router: &Router, method: &Method, path: &str) -> Duration {
+        self.routes
+            .iter()
+            .find(|route| {
+                route.method == method
+                    && router
+                        .match_pattern(&route.pattern, &route.constraints, path)
+                        .is_some()
+            })
+            .map_or(self.duration, |route| route.duration)
     }
 }
 
@@ -34,10 +99,66 @@ impl Middleware for TimeoutMiddleware {
         next: Next<'a>,
     ) -> BoxFuture<'a, Response<BoxBody>> {
         Box::pin(async move {
-            match tokio::time::timeout(self.duration, next.run(req)).await {
+            // Keep the earliest deadline when timeouts are nested
+            let mut req = req;
+            let duration = self.duration_for(next.router, req.method(), req.uri().path());
+            let deadline = Instant::now() + duration;
+            let current = req.extensions().get::<Deadline>().and_then(|d| d.0);
+            if current.is_none_or(|current| deadline < current) {
+                req.extensions_mut().insert(Deadline(Some(deadline)));
+            }
+
+            match tokio::time::timeout(duration, next.run(req)).await {
                 Ok(response) => response,
                 Err(_) => Error::internal("request timeout").into_response(),
             }
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::router::TrailingSlash;
+
+    #[test]
+    fn test_route_timeout_overrides_default() {
+        let timeout = TimeoutMiddleware::new(Duration::from_secs(30))
+            .route(Method::GET, "/reports/:id<u64>", Duration::from_secs(300))
+            .route(Method::GET, "/reports/:id", Duration::from_secs(60));
+        let router = Router::new();
+
+        let duration = timeout.duration_for(&router, &Method::GET, "/reports/7");
+        assert_eq!(duration, Duration::from_secs(300));
+        let duration = timeout.duration_for(&router, &Method::GET, "/reports/latest");
+        assert_eq!(duration, Duration::from_secs(60));
+        let duration = timeout.duration_for(&router, &Method::DELETE, "/reports/7");
+        assert_eq!(duration, Duration::from_secs(30));
+        let duration = timeout.duration_for(&router, &Method::GET, "/users");
+        assert_eq!(duration, Duration::from_secs(30));
+    }
+
+    #[test]
+    fn test_route_timeout_follows_router_matching() {
+        let timeout = TimeoutMiddleware::new(Duration::from_secs(30)).route(
+            Method::POST,
+            "/reports",
+            Duration::from_secs(300),
+        );
+
+        let router = Router::new();
+        let duration = timeout.duration_for(&router, &Method::POST, "/Reports/");
+        assert_eq!(duration, Duration::from_secs(30));
+
+        let mut router = Router::new().case_insensitive(true);
+        router.trailing_slash = TrailingSlash::Ignore;
+        let duration = timeout.duration_for(&router, &Method::POST, "/Reports/");
+        assert_eq!(duration, Duration::from_secs(300));
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid route pattern '/reports/:id<number>'")]
+    fn test_route_timeout_invalid_pattern() {
+        TimeoutMiddleware::default().route(Method::GET, "/reports/:id<number>", Duration::ZERO);
+    }
+}
@@ -9,12 +9,68 @@ use super::{BoxFuture, Middleware, Next};
 
 pub const TRACE_ID_HEADER: &str = "x-trace-id";
 
+/// Crockford's base32 alphabet, used by ULIDs.
+const CROCKFORD_BASE32: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+/// Generates a random UUID v4 trace ID, e.g.
+/// `67e55044-10b1-426f-9247-bb680e5fe0c8`.
+///
+/// This is the format trace IDs have by default.
+pub fn generate_uuid() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+/// Generates a ULID trace ID, e.g. `01ARZ3NDEKTSV4RRFFQ69G5FAV`.
+///
+/// ULIDs start with the millisecond timestamp, so IDs sort by the time the
+/// request arrived. The remaining 80 bits are random.
+pub fn generate_ulid() -> String {
+    let millis = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis());
+    // A v4 UUID's version and variant bits are fixed, but its low 62 bits
+    // are all random, so take 40 of them from each of two UUIDs
+    let random_40 = || uuid::Uuid::new_v4().as_u128() & ((1 << 40) - 1);
+    let random = random_40() << 40 | random_40();
+    let value = (millis & ((1 << 48) - 1)) << 80 | random;
+
+    (0..26)
+        .rev()
+        .map(|i| CROCKFORD_BASE32[((value >> (i * 5)) & 0x1f) as usize] as char)
+        .collect()
+}
+
+/// Returns each response's trace ID in the `x-trace-id` header.
+///
+/// A trace ID sent by the client in `x-trace-id` is kept, so IDs propagate
+/// across services. Otherwise the request keeps the UUID it was given, or
+/// gets one from the generator set with [`with_generator`](Self::with_generator).
 #[derive(Debug, Clone, Copy)]
-pub struct TraceIdMiddleware;
+pub struct TraceIdMiddleware {
+    generator: Option<fn() -> String>,
+}
 
 impl TraceIdMiddleware {
     pub fn new() -> Self {
-        Self
+        Self { generator: None }
+    }
+
+    /// Creates the middleware with a custom trace ID format.
+    ///
+    /// [`generate_uuid`] and [`generate_ulid`] are built in; any function
+    /// returning a string that is valid in a header value works.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rapina::middleware::{TraceIdMiddleware, generate_ulid};
+    ///
+    /// let middleware = TraceIdMiddleware::with_generator(generate_ulid);
+    /// ```
+    pub fn with_generator(generator: fn() -> String) -> Self {
+        Self {
+            generator: Some(generator),
+        }
     }
 }
 
@@ -27,37 +83,32 @@ impl Default for TraceIdMiddleware {
 impl Middleware for TraceIdMiddleware {
     fn handle<'a>(
         &'a self,
-        mut req: Request<Incoming>,
+        req: Request<Incoming>,
         ctx: &'a RequestContext,
         next: Next<'a>,
     ) -> BoxFuture<'a, Response<BoxBody>> {
         Box::pin(async move {
-            // Check for incoming x-trace-id header for distributed tracing
-            let incoming_trace_id = req
-                .headers()
-                .get(TRACE_ID_HEADER)
-                .and_then(|v| v.to_str().ok())
-                .map(String::from);
-
-            let trace_id = if let Some(id) = incoming_trace_id {
-                // Use the provided trace_id and update context in extensions
-                let new_ctx = RequestContext::with_trace_id(id.clone());
-                req.extensions_mut().insert(new_ctx);
-                id
-            } else {
-                ctx.trace_id.clone()
-            };
-
             let mut response = next.run(req).await;
 
             // Add x-trace-id to response headers
-            if let Ok(header_value) = HeaderValue::from_str(&trace_id) {
+            if let Ok(header_value) = HeaderValue::from_str(&ctx.trace_id) {
                 response.headers_mut().insert(TRACE_ID_HEADER, header_value);
             }
 
             response
         })
     }
+
+    // Resolved before the chain runs, so the request span, later middleware
+    // and response observers all see the same ID as the handler
+    fn trace_id(&self, req: &Request<Incoming>) -> Option<String> {
+        // Check for incoming x-trace-id header for distributed tracing
+        req.headers()
+            .get(TRACE_ID_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .map(String::from)
+            .or_else(|| self.generator.map(|generate| generate()))
+    }
 }
 
 #[cfg(test)]
@@ -73,4 +124,39 @@ mod tests {
     fn test_trace_id_middleware_default() {
         let _mw: TraceIdMiddleware = Default::default();
     }
+
+    #[test]
+    fn test_generate_ulid_format() {
+        let id = generate_ulid();
+        assert_eq!(id.len(), 26);
+        assert!(id.bytes().all(|b| CROCKFORD_BASE32.contains(&b)));
+        assert_ne!(id, generate_ulid());
+    }
+
+    #[test]
+    fn test_generate_ulid_random_bits_all_vary() {
+        let random_bits = |id: String| {
+            id[10..].bytes().fold(0u128, |value, b| {
+                let digit = CROCKFORD_BASE32.iter().position(|&c| c == b).unwrap();
+                value << 5 | digit as u128
+            })
+        };
+        let (mut any_set, mut all_set) = (0, (1u128 << 80) - 1);
+        for _ in 0..64 {
+            let bits = random_bits(generate_ulid());
+            any_set |= bits;
+            all_set &= bits;
+        }
+        // No bit of the 80 is fixed, as UUID version and variant bits would be
+        assert_eq!(any_set, (1 << 80) - 1);
+        assert_eq!(all_set, 0);
+    }
+
+    #[test]
+    fn test_generate_ulid_sorts_by_time() {
+        let earlier = generate_ulid();
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        let later = generate_ulid();
+        assert!(earlier[..10] < later[..10]);
+    }
 }
@@ -0,0 +1,293 @@
+//! Content negotiation based on the `Accept` header.
+//!
+//! Handlers return [`Negotiate<T>`] and the router picks a serializer
+//! matching the client's `Accept` header. JSON is always available and
+//! is used when the header is absent. Additional formats can be
+//! registered with [`Rapina::serializer`](crate::app::Rapina::serializer).
+//!
+//! # Examples
+//!
+//! ```ignore
+//! use rapina::prelude::*;
+//!
+//! #[get("/users/:id")]
+//! async fn get_user(id: Path<u64>) -> Negotiate<User> {
+//!     Negotiate(User { id: id.into_inner(), name: "Alice".into() })
+//! }
+//! ```
+
+use std::sync::Arc;
+
+use http::{HeaderValue, Response, StatusCode};
+use serde::Serialize;
+
+use crate::error::Error;
+use crate::headers::{Accept, Header};
+use crate::response::{BoxBody, IntoResponse, full_response};
+
+/// Serializes response values into a specific media type.
+///
+/// Values are handed over as a [`serde_json::Value`] so serializers can
+/// be stored as trait objects.
+pub trait Serializer: Send + Sync + 'static {
+    /// The media type produced by this serializer (e.g., "application/json").
+    fn content_type(&self) -> &'static str;
+
+    /// Serializes the value into the response body.
+    fn serialize(&self, value: &serde_json::Value) -> Result<Vec<u8>, String>;
+}
+
+/// The built-in `application/json` serializer.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonSerializer;
+
+impl Serializer for JsonSerializer {
+    fn content_type(&self) -> &'static str {
+        "application/json"
+    }
+
+    fn serialize(&self, value: &serde_json::Value) -> Result<Vec<u8>, String> {
+        serde_json::to_vec(value).map_err(|e| e.to_string())
+    }
+}
+
+/// The built-in `application/msgpack` serializer.
+///
+/// Encodes objects as MessagePack maps. Requires the `msgpack` feature.
+#[cfg(feature = "msgpack")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MsgPackSerializer;
+
+#[cfg(feature = "msgpack")]
+impl Serializer for MsgPackSerializer {
+    fn content_type(&self) -> &'static str {
+        crate::extract::MSGPACK_CONTENT_TYPE
+    }
+
+    fn serialize(&self, value: &serde_json::Value) -> Result<Vec<u8>, String> {
+        rmp_serde::to_vec_named(value).map_err(|e| e.to_string())
+    }
+}
+
+/// The set of serializers available for content negotiation.
+///
+/// Always contains [`JsonSerializer`], which is used as the default. With
+/// the `msgpack` feature it also contains [`MsgPackSerializer`].
+#[derive(Clone)]
+pub struct Serializers {
+    serializers: Vec<Arc<dyn Serializer>>,
+}
+
+impl Serializers {
+    /// Creates a registry containing the built-in serializers.
+    pub fn new() -> Self {
+        Self {
+            serializers: vec![
+                Arc::new(JsonSerializer),
+                #[cfg(feature = "msgpack")]
+                Arc::new(MsgPackSerializer),
+            ],
+        }
+    }
+
+    /// Registers an additional serializer.
+    ///
+    /// A serializer for a media type that is already registered replaces it.
+    pub fn with<S: Serializer>(mut self, serializer: S) -> Self {
+        self.serializers
+            .retain(|s| s.content_type() != serializer.content_type());
+        self.serializers.push(Arc::new(serializer));
+        self
+    }
+
+    /// Returns the media types of all registered serializers.
+    pub fn content_types(&self) -> Vec<&'static str> {
+        self.serializers.iter().map(|s| s.content_type()).collect()
+    }
+
+    /// Selects the serializer best matching the `Accept` header.
+    ///
+    /// Returns the default serializer when `accept` is `None`.
+    pub fn select(&self, accept: Option<&Accept>) -> Option<&dyn Serializer> {
+        let Some(accept) = accept else {
+            return self.serializers.first().map(|s| s.as_ref());
+        };
+
+        accept
+            .media_ranges()
+            .iter()
+            .filter(|range| range.quality > 0.0)
+            .find_map(|range| {
+                self.serializers
+                    .iter()
+                    .find(|s| range.matches(s.content_type()))
+            })
+            .map(|s| s.as_ref())
+    }
+}
+
+impl Default for Serializers {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A response whose format is chosen by the request's `Accept` header.
+///
+/// Serializes as JSON by default. Returns 406 Not Acceptable if the client
+/// accepts none of the registered formats.
+#[derive(Debug)]
+pub struct Negotiate<T>(pub T);
+
+/// Marker stored in response extensions so the router can re-render the body.
+#[derive(Clone)]
+pub(crate) struct Negotiable(serde_json::Value);
+
+impl<T: Serialize> IntoResponse for (StatusCode, Negotiate<T>) {
+    fn into_response(self) -> Response<BoxBody> {
+        let value = match serde_json::to_value(&(self.1).0) {
+            Ok(value) => value,
+            Err(e) => {
+                return Error::internal(format!("serialization failed: {}", e)).into_response();
+            }
+        };
+
+        let mut response = render(self.0, &JsonSerializer, &value);
+        response.extensions_mut().insert(Negotiable(value));
+        response
+    }
+}
+
+impl<T: Serialize> IntoResponse for Negotiate<T> {
+    fn into_response(self) -> Response<BoxBody> {
+        (StatusCode::OK, self).into_response()
+    }
+}
+
+fn render(
+    status: StatusCode,
+    serializer: &dyn Serializer,
+    value: &serde_json::Value,
+) -> Response<BoxBody> {
+    match serializer.serialize(value) {
+        Ok(body) => full_response(status, serializer.content_type(), body),
+        Err(e) => Error::internal(format!("serialization failed: {}", e)).into_response(),
+    }
+}
+
+/// Re-renders a [`Negotiate`] response for the given `Accept` header.
+///
+/// Responses not produced by `Negotiate` are returned unchanged. Falls back
+/// to the default serializers when none were registered.
+pub(crate) fn negotiate(
+    mut response: Response<BoxBody>,
+    accept: Option<&HeaderValue>,
+    serializers: Option<&Serializers>,
+) -> Response<BoxBody> {
+    let Some(Negotiable(value)) = response.extensions_mut().remove::<Negotiable>() else {
+        return response;
+    };
+
+    let default;
+    let serializers = match serializers {
+        Some(serializers) => serializers,
+        None => {
+            default = Serializers::new();
+            &default
+        }
+    };
+
+    // A malformed Accept header is treated as if it were absent
+    let accept = accept.and_then(|v| Accept::decode(v).ok());
+
+    match serializers.select(accept.as_ref()) {
+        Some(serializer) => render(response.status(), serializer, &value),
+        None => Error::new(
+            406,
+            "NOT_ACCEPTABLE",
+            "no acceptable representation available",
+        )
+        .with_details(serde_json::json!({ "supported": serializers.content_types() }))
+        .into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct PlainSerializer;
+
+    impl Serializer for PlainSerializer {
+        fn content_type(&self) -> &'static str {
+            "text/plain"
+        }
+
+        fn serialize(&self, value: &serde_json::Value) -> Result<Vec<u8>, String> {
+            Ok(value.to_string().into_bytes())
+        }
+    }
+
+    fn accept(value: &'static str) -> Accept {
+        Accept::decode(&HeaderValue::from_static(value)).unwrap()
+    }
+
+    #[test]
+    fn test_select_defaults_to_json() {
+        let serializers = Serializers::new();
+        let selected = serializers.select(None).unwrap();
+        assert_eq!(selected.content_type(), "application/json");
+    }
+
+    #[test]
+    fn test_select_wildcard_uses_first_registered() {
+        let serializers = Serializers::new().with(PlainSerializer);
+        let selected = serializers.select(Some(&accept("*/*"))).unwrap();
+        assert_eq!(selected.content_type(), "application/json");
+    }
+
+    #[test]
+    fn test_select_respects_quality() {
+        let serializers = Serializers::new().with(PlainSerializer);
+        let selected = serializers
+            .select(Some(&accept("application/json;q=0.5, text/plain")))
+            .unwrap();
+        assert_eq!(selected.content_type(), "text/plain");
+    }
+
+    #[test]
+    fn test_select_unsupported() {
+        let serializers = Serializers::new();
+        assert!(
+            serializers
+                .select(Some(&accept("application/xml")))
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_negotiate_unsupported_returns_406() {
+        let response = Negotiate(serde_json::json!({"ok": true})).into_response();
+        let accept = HeaderValue::from_static("application/xml");
+        let response = negotiate(response, Some(&accept), None);
+        assert_eq!(response.status(), StatusCode::NOT_ACCEPTABLE);
+    }
+
+    #[test]
+    fn test_negotiate_leaves_other_responses_untouched() {
+        let response = "hello".into_response();
+        let accept = HeaderValue::from_static("application/xml");
+        let response = negotiate(response, Some(&accept), None);
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[test]
+    fn test_negotiate_preserves_status() {
+        let response = (StatusCode::CREATED, Negotiate("created")).into_response();
+        let accept = HeaderValue::from_static("text/plain");
+        let serializers = Serializers::new().with(PlainSerializer);
+        let response = negotiate(response, Some(&accept), Some(&serializers));
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert_eq!(response.headers()["content-type"], "text/plain");
+    }
+}
@@ -2,6 +2,10 @@
 //!
 //! This module provides tools for logging, tracing, and monitoring.
 
+#[cfg(feature = "otel")]
+mod otel;
 mod tracing;
 
+#[cfg(feature = "otel")]
+pub use self::otel::OtelMiddleware;
 pub use self::tracing::TracingConfig;
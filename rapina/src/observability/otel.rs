@@ -0,0 +1,149 @@
+//! OpenTelemetry trace export over OTLP.
+//!
+//! Enabled with the `otel` feature. [`TracingConfig::with_otlp`] installs a
+//! tracing layer that exports spans to an OTLP/HTTP collector (Jaeger,
+//! Tempo, the OpenTelemetry Collector, ...), and [`OtelMiddleware`] creates
+//! one server span per request.
+//!
+//! [`TracingConfig::with_otlp`]: super::TracingConfig::with_otlp
+
+use hyper::body::Incoming;
+use hyper::{Request, Response};
+use opentelemetry::propagation::{Extractor, TextMapPropagator};
+use opentelemetry::trace::{TraceContextExt, TracerProvider};
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::trace::{SdkTracerProvider, Tracer};
+use tracing::{Instrument, field, info_span};
+use tracing_opentelemetry::{OpenTelemetryLayer, OpenTelemetrySpanExt};
+use tracing_subscriber::registry::LookupSpan;
+
+use crate::context::RequestContext;
+use crate::middleware::{BoxFuture, Middleware, Next};
+use crate::response::BoxBody;
+use crate::router::MatchedRoute;
+
+/// Builds a tracing layer exporting spans to the given OTLP/HTTP endpoint.
+///
+/// # Panics
+///
+/// Panics if the exporter cannot be created.
+pub(crate) fn otlp_layer<S>(endpoint: &str) -> OpenTelemetryLayer<S, Tracer>
+where
+    S: tracing::Subscriber + for<'span> LookupSpan<'span>,
+{
+    use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+
+    let exporter = SpanExporter::builder()
+        .with_http()
+        .with_endpoint(endpoint)
+        .build()
+        .expect("failed to create OTLP exporter");
+
+    let provider = SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .build();
+
+    opentelemetry::global::set_text_map_propagator(TraceContextPropagator::new());
+    opentelemetry::global::set_tracer_provider(provider.clone());
+
+    tracing_opentelemetry::layer().with_tracer(provider.tracer("rapina"))
+}
+
+/// Reads propagation headers from an HTTP header map.
+struct HeaderExtractor<'a>(&'a http::HeaderMap);
+
+impl Extractor for HeaderExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|v| v.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(|k| k.as_str()).collect()
+    }
+}
+
+/// Middleware that creates an OpenTelemetry server span for each request.
+///
+/// The span continues the trace from an incoming W3C `traceparent` header
+/// when present, and records the method, matched route, status code,
+/// Rapina trace ID and latency. Added automatically by
+/// [`Rapina::with_tracing`](crate::app::Rapina::with_tracing) when OTLP
+/// export is configured.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OtelMiddleware;
+
+impl OtelMiddleware {
+    /// Creates a new OpenTelemetry middleware.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Middleware for OtelMiddleware {
+    fn handle<'a>(
+        &'a self,
+        req: Request<Incoming>,
+        ctx: &'a RequestContext,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, Response<BoxBody>> {
+        let method = req.method().clone();
+        let path = req.uri().path().to_string();
+
+        let span = info_span!(
+            "request",
+            otel.name = %method,
+            otel.kind = "server",
+            http.request.method = %method,
+            url.path = %path,
+            http.route = field::Empty,
+            http.response.status_code = field::Empty,
+            trace_id = %ctx.trace_id,
+            latency_ms = field::Empty,
+        );
+
+        let parent = TraceContextPropagator::new().extract(&HeaderExtractor(req.headers()));
+        let _ = span.set_parent(parent);
+
+        let recorder = span.clone();
+        Box::pin(
+            async move {
+                let response = next.run(req).await;
+
+                if let Some(route) = response.extensions().get::<MatchedRoute>() {
+                    // The span has already started, so rename it through its context
+                    recorder
+                        .context()
+                        .span()
+                        .update_name(format!("{} {}", method, route.as_str()));
+                    recorder.record("http.route", route.as_str());
+                }
+                recorder.record("http.response.status_code", response.status().as_u16());
+                recorder.record("latency_ms", ctx.elapsed().as_millis() as u64);
+
+                response
+            }
+            .instrument(span),
+        )
+    }
+
+    // This span, with the trace ID, must be the root span exported for the request
+    fn opens_request_span(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_header_extractor() {
+        let mut headers = http::HeaderMap::new();
+        headers.insert("traceparent", "00-abc-def-01".parse().unwrap());
+
+        let extractor = HeaderExtractor(&headers);
+        assert_eq!(extractor.get("traceparent"), Some("00-abc-def-01"));
+        assert_eq!(extractor.get("missing"), None);
+        assert_eq!(extractor.keys(), vec!["traceparent"]);
+    }
+}
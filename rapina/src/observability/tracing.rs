@@ -1,4 +1,13 @@
-use tracing::Level;
+use std::fmt as std_fmt;
+
+use serde_json::{Map, Value};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::fmt::format::{JsonFields, Writer};
+use tracing_subscriber::fmt::time::{FormatTime, SystemTime};
+use tracing_subscriber::fmt::{FmtContext, FormatEvent, FormatFields, FormattedFields};
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::registry::LookupSpan;
 use tracing_subscriber::{EnvFilter, fmt};
 
 /// Configuration for the tracing/logging system.
@@ -23,12 +32,17 @@ pub struct TracingConfig {
     pub json: bool,
     /// The minimum log level.
     pub level: Level,
+    /// Filter directives (e.g., `"info,my_app=debug"`), overriding `level`.
+    pub filter: Option<String>,
     /// Include the target (module path) in logs.
     pub with_target: bool,
     /// Include the source file in logs.
     pub with_file: bool,
     /// Include line numbers in logs.
     pub with_line_number: bool,
+    /// OTLP/HTTP endpoint to export spans to.
+    #[cfg(feature = "otel")]
+    pub otlp_endpoint: Option<String>,
 }
 
 impl Default for TracingConfig {
@@ -36,9 +50,12 @@ impl Default for TracingConfig {
         Self {
             json: false,
             level: Level::INFO,
+            filter: None,
             with_target: true,
             with_file: false,
             with_line_number: false,
+            #[cfg(feature = "otel")]
+            otlp_endpoint: None,
         }
     }
 }
@@ -50,6 +67,10 @@ impl TracingConfig {
     }
 
     /// Enables JSON output format.
+    ///
+    /// Each event is written as a single JSON object with `timestamp`,
+    /// `level`, the event fields, and the `trace_id` of the enclosing
+    /// request span when there is one.
     pub fn json(mut self) -> Self {
         self.json = true;
         self
@@ -61,6 +82,15 @@ impl TracingConfig {
         self
     }
 
+    /// Sets filter directives in [`EnvFilter`] syntax, e.g. `"info,hyper=warn"`.
+    ///
+    /// Takes precedence over [`level`](Self::level). The `RUST_LOG`
+    /// environment variable still overrides both.
+    pub fn filter(mut self, directives: impl Into<String>) -> Self {
+        self.filter = Some(directives.into());
+        self
+    }
+
     /// Configures whether to include the target in logs.
     pub fn with_target(mut self, enabled: bool) -> Self {
         self.with_target = enabled;
@@ -79,27 +109,158 @@ impl TracingConfig {
         self
     }
 
+    /// Exports spans to an OpenTelemetry collector over OTLP/HTTP.
+    ///
+    /// The endpoint is the full traces URL, e.g. `http://localhost:4318/v1/traces`.
+    /// When used with [`Rapina::with_tracing`](crate::app::Rapina::with_tracing),
+    /// a span is created for every request and incoming `traceparent`
+    /// headers are honored.
+    #[cfg(feature = "otel")]
+    pub fn with_otlp(mut self, endpoint: impl Into<String>) -> Self {
+        self.otlp_endpoint = Some(endpoint.into());
+        self
+    }
+
     /// Initializes the tracing subscriber with this configuration.
+    ///
+    /// Only the first call installs a subscriber. Later calls, or calls made
+    /// after another global subscriber was set, are ignored.
     pub fn init(self) {
-        let filter = EnvFilter::try_from_default_env()
-            .unwrap_or_else(|_| EnvFilter::new(self.level.to_string()));
+        let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| {
+            EnvFilter::new(
+                self.filter
+                    .clone()
+                    .unwrap_or_else(|| self.level.to_string()),
+            )
+        });
 
-        if self.json {
-            fmt()
-                .with_env_filter(filter)
-                .with_target(self.with_target)
-                .with_file(self.with_file)
-                .with_line_number(self.with_line_number)
-                .json()
-                .init();
+        let fmt_layer = if self.json {
+            fmt::layer()
+                .fmt_fields(JsonFields::new())
+                .event_format(JsonFormat::from_config(&self))
+                .boxed()
         } else {
-            fmt()
-                .with_env_filter(filter)
+            fmt::layer()
                 .with_target(self.with_target)
                 .with_file(self.with_file)
                 .with_line_number(self.with_line_number)
-                .init();
+                .boxed()
+        };
+
+        let subscriber = tracing_subscriber::registry().with(filter).with(fmt_layer);
+
+        #[cfg(feature = "otel")]
+        let subscriber =
+            subscriber.with(self.otlp_endpoint.as_deref().map(super::otel::otlp_layer));
+
+        let _ = subscriber.try_init();
+    }
+}
+
+/// Formats events as single-line JSON objects.
+///
+/// Event fields are flattened into the object. The `trace_id` recorded on
+/// the nearest enclosing span is lifted to a top-level field so every log
+/// line emitted while handling a request can be correlated.
+pub(crate) struct JsonFormat {
+    with_target: bool,
+    with_file: bool,
+    with_line_number: bool,
+}
+
+impl JsonFormat {
+    pub(crate) fn from_config(config: &TracingConfig) -> Self {
+        Self {
+            with_target: config.with_target,
+            with_file: config.with_file,
+            with_line_number: config.with_line_number,
+        }
+    }
+}
+
+impl<S, N> FormatEvent<S, N> for JsonFormat
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, N>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> std_fmt::Result {
+        let meta = event.metadata();
+        let mut object = Map::new();
+
+        let mut timestamp = String::new();
+        if SystemTime
+            .format_time(&mut Writer::new(&mut timestamp))
+            .is_ok()
+        {
+            object.insert("timestamp".into(), Value::String(timestamp));
+        }
+        object.insert("level".into(), Value::String(meta.level().to_string()));
+        if self.with_target {
+            object.insert("target".into(), Value::String(meta.target().to_string()));
+        }
+        if self.with_file
+            && let Some(file) = meta.file()
+        {
+            object.insert("file".into(), Value::String(file.to_string()));
+        }
+        if self.with_line_number
+            && let Some(line) = meta.line()
+        {
+            object.insert("line".into(), Value::from(line));
         }
+
+        event.record(&mut JsonVisitor(&mut object));
+
+        if let Some(scope) = ctx.event_scope() {
+            for span in scope {
+                let extensions = span.extensions();
+                let trace_id = extensions
+                    .get::<FormattedFields<N>>()
+                    .and_then(|fields| serde_json::from_str::<Map<String, Value>>(fields).ok())
+                    .and_then(|mut fields| fields.remove("trace_id"));
+                if let Some(trace_id) = trace_id {
+                    object.insert("trace_id".into(), trace_id);
+                    break;
+                }
+            }
+        }
+
+        writeln!(writer, "{}", Value::Object(object))
+    }
+}
+
+/// Records event fields into a JSON object.
+struct JsonVisitor<'a>(&'a mut Map<String, Value>);
+
+impl Visit for JsonVisitor<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().into(), Value::from(value));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.insert(field.name().into(), Value::from(value));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0.insert(field.name().into(), Value::from(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.insert(field.name().into(), Value::from(value));
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.0.insert(field.name().into(), Value::from(value));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std_fmt::Debug) {
+        self.0
+            .insert(field.name().into(), Value::from(format!("{:?}", value)));
     }
 }
 
@@ -129,6 +290,81 @@ mod tests {
         assert!(config.json);
     }
 
+    #[test]
+    fn test_tracing_config_filter() {
+        let config = TracingConfig::new().filter("info,hyper=warn");
+        assert_eq!(config.filter.as_deref(), Some("info,hyper=warn"));
+    }
+
+    #[test]
+    fn test_tracing_config_init_twice_does_not_panic() {
+        TracingConfig::new().init();
+        TracingConfig::new().json().init();
+    }
+
+    #[derive(Clone, Default)]
+    struct BufferWriter(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl std::io::Write for BufferWriter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn capture_json(f: impl FnOnce()) -> Vec<Value> {
+        let buffer = BufferWriter::default();
+        let writer = buffer.clone();
+        let layer = fmt::layer()
+            .fmt_fields(JsonFields::new())
+            .event_format(JsonFormat::from_config(&TracingConfig::new()))
+            .with_writer(move || writer.clone());
+        let subscriber = tracing_subscriber::registry().with(layer);
+
+        tracing::subscriber::with_default(subscriber, f);
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        output
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("log line is not valid JSON"))
+            .collect()
+    }
+
+    #[test]
+    fn test_json_format_output() {
+        let lines = capture_json(|| {
+            tracing::info!(user_id = 42, "user created");
+        });
+
+        assert_eq!(lines.len(), 1);
+        let line = &lines[0];
+        assert_eq!(line["level"], "INFO");
+        assert_eq!(line["message"], "user created");
+        assert_eq!(line["user_id"], 42);
+        assert!(line["timestamp"].is_string());
+        assert!(line["target"].is_string());
+        assert!(line.get("trace_id").is_none());
+    }
+
+    #[test]
+    fn test_json_format_includes_trace_id_from_span() {
+        let lines = capture_json(|| {
+            let span = tracing::info_span!("request", trace_id = "abc-123", method = "GET");
+            let _enter = span.enter();
+            let inner = tracing::info_span!("handler");
+            let _inner = inner.enter();
+            tracing::info!("handling request");
+        });
+
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0]["trace_id"], "abc-123");
+        assert_eq!(lines[0]["message"], "handling request");
+    }
+
     #[test]
     fn test_tracing_config_level() {
         let config = TracingConfig::new().level(Level::DEBUG);
@@ -150,4 +386,14 @@ mod tests {
         assert!(config.with_file);
         assert!(config.with_line_number);
     }
+
+    #[cfg(feature = "otel")]
+    #[test]
+    fn test_tracing_config_with_otlp() {
+        let config = TracingConfig::new().with_otlp("http://localhost:4318/v1/traces");
+        assert_eq!(
+            config.otlp_endpoint.as_deref(),
+            Some("http://localhost:4318/v1/traces")
+        );
+    }
 }
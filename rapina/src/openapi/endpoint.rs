@@ -2,25 +2,37 @@
 
 use std::sync::Arc;
 
+use bytes::Bytes;
 use http::{Request, Response, StatusCode};
 use hyper::body::Incoming;
 
+use crate::response::full_response;
 use crate::{extract::PathParams, openapi::OpenApiSpec, response::BoxBody, state::AppState};
 
 /// Registry for storing the OpenAPI spec
+///
+/// The spec is serialized once, when the registry is created, and every
+/// request is answered with the same bytes.
 #[derive(Debug, Clone)]
 pub struct OpenApiRegistry {
     spec: OpenApiSpec,
+    json: Bytes,
 }
 
 impl OpenApiRegistry {
     pub fn new(spec: OpenApiSpec) -> Self {
-        Self { spec }
+        let json = serde_json::to_vec_pretty(&spec).unwrap_or_default().into();
+        Self { spec, json }
     }
 
     pub fn spec(&self) -> &OpenApiSpec {
         &self.spec
     }
+
+    /// Returns the spec serialized as pretty-printed JSON.
+    pub fn json(&self) -> &Bytes {
+        &self.json
+    }
 }
 
 /// Handler for the OpenAPI endpoint
@@ -35,19 +47,12 @@ pub async fn openapi_spec(
 
     match registry {
         Some(registry) => {
-            let json = serde_json::to_vec_pretty(registry.spec()).unwrap_or_default();
-            Response::builder()
-                .status(StatusCode::OK)
-                .header("content-type", "application/json")
-                .body(http_body_util::Full::new(bytes::Bytes::from(json)))
-                .unwrap()
+            full_response(StatusCode::OK, "application/json", registry.json().clone())
         }
         None => Response::builder()
             .status(StatusCode::NOT_FOUND)
             .header("content-type", "application/json")
-            .body(http_body_util::Full::new(bytes::Bytes::from(
-                r#"{"error": "OpenAPI spec not configured"}"#,
-            )))
+            .body(BoxBody::from(r#"{"error": "OpenAPI spec not configured"}"#))
             .unwrap(),
     }
 }
@@ -1,15 +1,21 @@
 //! OpenAPI 3.0 specification structures
 
 use serde::Serialize;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::error::ErrorVariant;
 
 #[derive(Debug, Clone, Serialize)]
 pub struct OpenApiSpec {
     pub openapi: String,
     pub info: Info,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub servers: Vec<Server>,
     pub paths: BTreeMap<String, PathItem>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub components: Option<Components>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<Tag>,
 }
 
 impl OpenApiSpec {
@@ -21,10 +27,72 @@ impl OpenApiSpec {
                 version: version.into(),
                 description: None,
             },
+            servers: Vec::new(),
             paths: BTreeMap::new(),
             components: None,
+            tags: Vec::new(),
+        }
+    }
+
+    /// Sets the description of a tag, adding the tag if no operation uses it.
+    pub fn describe_tag(&mut self, name: &str, description: impl Into<String>) {
+        let description = Some(description.into());
+        match self.tags.iter_mut().find(|t| t.name == name) {
+            Some(tag) => tag.description = description,
+            None => self.tags.push(Tag {
+                name: name.to_string(),
+                description,
+            }),
         }
     }
+
+    /// Adds a server the API is reachable at.
+    pub fn add_server(&mut self, url: impl Into<String>, description: Option<String>) {
+        self.servers.push(Server {
+            url: url.into(),
+            description,
+        });
+    }
+
+    /// Prefixes every path with `base_path`, e.g. `/api` turns `/users` into `/api/users`.
+    pub fn apply_base_path(&mut self, base_path: &str) {
+        let base = base_path.trim_end_matches('/');
+        if base.is_empty() {
+            return;
+        }
+        let base = if base.starts_with('/') {
+            base.to_string()
+        } else {
+            format!("/{}", base)
+        };
+        self.paths = std::mem::take(&mut self.paths)
+            .into_iter()
+            .map(|(path, item)| {
+                let path = if path == "/" {
+                    base.clone()
+                } else {
+                    format!("{}{}", base, path)
+                };
+                (path, item)
+            })
+            .collect();
+    }
+}
+
+/// A server the API is served from
+#[derive(Debug, Clone, Serialize)]
+pub struct Server {
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// A tag grouping operations in documentation
+#[derive(Debug, Clone, Serialize)]
+pub struct Tag {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
 }
 
 /// API metadata
@@ -47,6 +115,31 @@ pub struct PathItem {
     pub put: Option<Operation>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub delete: Option<Operation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub patch: Option<Operation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub head: Option<Operation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub options: Option<Operation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trace: Option<Operation>,
+}
+
+impl PathItem {
+    /// Returns the operation slot for an HTTP method, if OpenAPI defines one.
+    pub fn operation_mut(&mut self, method: &str) -> Option<&mut Option<Operation>> {
+        match method.to_uppercase().as_str() {
+            "GET" => Some(&mut self.get),
+            "POST" => Some(&mut self.post),
+            "PUT" => Some(&mut self.put),
+            "DELETE" => Some(&mut self.delete),
+            "PATCH" => Some(&mut self.patch),
+            "HEAD" => Some(&mut self.head),
+            "OPTIONS" => Some(&mut self.options),
+            "TRACE" => Some(&mut self.trace),
+            _ => None,
+        }
+    }
 }
 
 /// A single API operation (endpoint)
@@ -63,6 +156,12 @@ pub struct Operation {
     #[serde(rename = "requestBody", skip_serializing_if = "Option::is_none")]
     pub request_body: Option<RequestBody>,
     pub responses: BTreeMap<String, Response>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub deprecated: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub servers: Vec<Server>,
 }
 
 impl Default for Operation {
@@ -82,6 +181,9 @@ impl Default for Operation {
             parameters: Vec::new(),
             request_body: None,
             responses,
+            deprecated: false,
+            tags: Vec::new(),
+            servers: Vec::new(),
         }
     }
 }
@@ -124,10 +226,12 @@ pub struct Response {
     pub content: Option<BTreeMap<String, MediaType>>,
 }
 
-/// MediaType with schema
+/// MediaType with schema and optional example
 #[derive(Debug, Clone, Serialize)]
 pub struct MediaType {
     pub schema: Schema,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub example: Option<serde_json::Value>,
 }
 
 /// JSON Schema (simplified)
@@ -175,6 +279,7 @@ fn error_response_ref() -> Response {
             schema: Schema::Ref {
                 reference: "#/components/schemas/ErrorResponse".to_string(),
             },
+            example: None,
         },
     );
     Response {
@@ -183,6 +288,53 @@ fn error_response_ref() -> Response {
     }
 }
 
+/// Response for documented errors sharing a status.
+///
+/// The body extends `ErrorResponse`, narrowing `error.code` to the codes the
+/// variants declare so client generators can model them.
+fn documented_error_response(variants: &[&ErrorVariant]) -> Response {
+    let codes: Vec<&str> = variants.iter().map(|v| v.code).collect();
+    let schema = serde_json::json!({
+        "allOf": [
+            {"$ref": "#/components/schemas/ErrorResponse"},
+            {
+                "type": "object",
+                "properties": {
+                    "error": {
+                        "type": "object",
+                        "properties": {
+                            "code": {"type": "string", "enum": codes}
+                        }
+                    }
+                }
+            }
+        ]
+    });
+    let first = variants[0];
+    let example = serde_json::json!({
+        "error": {"code": first.code, "message": first.description},
+        "trace_id": "00000000-0000-0000-0000-000000000000"
+    });
+
+    let mut content = BTreeMap::new();
+    content.insert(
+        "application/json".to_string(),
+        MediaType {
+            schema: Schema::Inline(schema),
+            example: Some(example),
+        },
+    );
+    let description = variants
+        .iter()
+        .map(|v| v.description)
+        .collect::<Vec<_>>()
+        .join("; ");
+    Response {
+        description,
+        content: Some(content),
+    }
+}
+
 /// Convert a snake_case handler name to a human-readable summary.
 /// e.g., "list_todos" -> "List todos", "get_todo" -> "Get todo"
 fn humanize_handler_name(name: &str) -> String {
@@ -205,6 +357,113 @@ fn humanize_handler_name(name: &str) -> String {
     result
 }
 
+/// Build the success responses declared with `#[responses(...)]`, grouping
+/// content types that share a status code.
+fn declared_responses(
+    responses: &[crate::introspection::ResponseInfo],
+) -> BTreeMap<String, Response> {
+    let mut result: BTreeMap<String, Response> = BTreeMap::new();
+
+    for declared in responses {
+        let response = result
+            .entry(declared.status.to_string())
+            .or_insert_with(|| Response {
+                description: http::StatusCode::from_u16(declared.status)
+                    .ok()
+                    .and_then(|s| s.canonical_reason())
+                    .unwrap_or("Success")
+                    .to_string(),
+                content: None,
+            });
+
+        if let Some(schema) = &declared.schema {
+            response.content.get_or_insert_with(BTreeMap::new).insert(
+                declared.content_type.clone(),
+                MediaType {
+                    schema: Schema::Inline(schema.clone()),
+                    example: None,
+                },
+            );
+        }
+    }
+
+    result
+}
+
+/// Registers a named schema under `components.schemas` and returns a `$ref` to it.
+///
+/// Schemas are keyed by their schemars `title`, so a type used by several
+/// operations is described once. Definitions in `$defs` are hoisted next to
+/// it. Schemas without a usable title, or whose name is already taken by a
+/// different schema, stay inline.
+fn component_schema(
+    schemas: &mut BTreeMap<String, serde_json::Value>,
+    schema: &serde_json::Value,
+) -> Schema {
+    let inline = || Schema::Inline(schema.clone());
+    let Some(title) = schema.get("title").and_then(|t| t.as_str()) else {
+        return inline();
+    };
+    if !is_component_name(title) {
+        return inline();
+    }
+
+    let mut component = schema.clone();
+    let Some(object) = component.as_object_mut() else {
+        return inline();
+    };
+    object.remove("$schema");
+    let defs = match object.remove("$defs") {
+        Some(serde_json::Value::Object(defs)) => defs,
+        _ => serde_json::Map::new(),
+    };
+    rewrite_def_refs(&mut component);
+
+    let mut entries = vec![(title.to_string(), component)];
+    for (name, mut def) in defs {
+        rewrite_def_refs(&mut def);
+        entries.push((name, def));
+    }
+    let conflicts = entries
+        .iter()
+        .any(|(name, value)| schemas.get(name).is_some_and(|existing| existing != value));
+    if conflicts || !entries.iter().all(|(name, _)| is_component_name(name)) {
+        return inline();
+    }
+
+    schemas.extend(entries);
+    Schema::Ref {
+        reference: format!("#/components/schemas/{}", title),
+    }
+}
+
+/// Whether `name` is a valid key for `components.schemas`.
+fn is_component_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'))
+}
+
+/// Points `#/$defs/...` references at the hoisted component schemas.
+fn rewrite_def_refs(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(object) => {
+            for (key, value) in object.iter_mut() {
+                if key == "$ref"
+                    && let Some(name) = value.as_str().and_then(|r| r.strip_prefix("#/$defs/"))
+                {
+                    *value = format!("#/components/schemas/{}", name).into();
+                } else {
+                    rewrite_def_refs(value);
+                }
+            }
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(rewrite_def_refs),
+        _ => {}
+    }
+}
+
 pub fn build_openapi_spec(
     title: &str,
     version: &str,
@@ -214,28 +473,59 @@ pub fn build_openapi_spec(
 
     let mut schemas = BTreeMap::new();
     schemas.insert("ErrorResponse".to_string(), error_response_schema());
-
-    spec.components = Some(Components { schemas });
+    let mut operation_ids = BTreeSet::new();
 
     for route in routes {
-        // skip internal rapina routes
-        if route.path.starts_with("/__rapina") {
+        // skip internal rapina routes and routes marked #[openapi(hidden)]
+        if route.path.starts_with("/__rapina") || route.hidden {
             continue;
         }
         // Extract path parameters (e.g., :id -> id)
-        let params: Vec<Parameter> = route
+        let mut params: Vec<Parameter> = route
             .path
             .split('/')
             .filter(|s| s.starts_with(':'))
-            .map(|s| Parameter {
-                name: s.trim_start_matches(':').to_string(),
-                location: ParameterLocation::Path,
-                description: None,
-                required: true,
-                schema: None,
+            .map(|s| {
+                let name = s.trim_start_matches(':');
+                let schema = route
+                    .path_params
+                    .iter()
+                    .find(|p| p.name == name)
+                    .and_then(|p| p.schema.clone());
+                Parameter {
+                    name: name.to_string(),
+                    location: ParameterLocation::Path,
+                    description: None,
+                    required: true,
+                    schema: schema.map(Schema::Inline),
+                }
             })
             .collect();
 
+        params.extend(route.query_params.iter().map(|p| Parameter {
+            name: p.name.clone(),
+            location: ParameterLocation::Query,
+            description: None,
+            required: p.required,
+            schema: p.schema.clone().map(Schema::Inline),
+        }));
+
+        let request_body = route.request_schema.as_ref().map(|schema| {
+            let mut content = BTreeMap::new();
+            content.insert(
+                "application/json".to_string(),
+                MediaType {
+                    schema: component_schema(&mut schemas, schema),
+                    example: route.request_example.clone(),
+                },
+            );
+            RequestBody {
+                description: None,
+                required: true,
+                content,
+            }
+        });
+
         // Convert :param to {param} for OpenAPI format
         let openapi_path = route
             .path
@@ -256,6 +546,7 @@ pub fn build_openapi_spec(
                 "application/json".to_string(),
                 MediaType {
                     schema: Schema::Inline(schema.clone()),
+                    example: None,
                 },
             );
             Response {
@@ -271,36 +562,56 @@ pub fn build_openapi_spec(
 
         let summary = humanize_handler_name(&route.handler_name);
 
+        // A handler registered for several methods needs a unique id per operation
+        let operation_id = if operation_ids.insert(route.handler_name.clone()) {
+            route.handler_name.clone()
+        } else {
+            format!("{}_{}", route.handler_name, route.method.to_lowercase())
+        };
+
         let mut operation = Operation {
             summary: Some(summary),
-            operation_id: Some(route.handler_name.clone()),
+            operation_id: Some(operation_id),
             parameters: params,
+            request_body,
+            deprecated: route.deprecated,
+            tags: route.tags.clone(),
+            // Routes of a host router are served only for that host
+            servers: route
+                .host
+                .iter()
+                .map(|host| Server {
+                    url: format!("//{}", host),
+                    description: None,
+                })
+                .collect(),
             ..Default::default()
         };
 
-        operation
-            .responses
-            .insert("200".to_string(), success_response);
+        if route.responses.is_empty() {
+            operation
+                .responses
+                .insert("200".to_string(), success_response);
+        } else {
+            operation.responses = declared_responses(&route.responses);
+        }
 
-        // Add documented error responses
+        // Add documented error responses, one per status listing its codes
+        let mut documented: Vec<(u16, Vec<&ErrorVariant>)> = Vec::new();
         for error in &route.error_responses {
-            let status_key = error.status.to_string();
-            let error_desc = error.description.to_string();
-            operation.responses.entry(status_key).or_insert_with(|| {
-                let mut content = BTreeMap::new();
-                content.insert(
-                    "application/json".to_string(),
-                    MediaType {
-                        schema: Schema::Ref {
-                            reference: "#/components/schemas/ErrorResponse".to_string(),
-                        },
-                    },
-                );
-                Response {
-                    description: error_desc,
-                    content: Some(content),
-                }
-            });
+            match documented
+                .iter_mut()
+                .find(|(status, _)| *status == error.status)
+            {
+                Some((_, variants)) => variants.push(error),
+                None => documented.push((error.status, vec![error])),
+            }
+        }
+        for (status, variants) in documented {
+            operation
+                .responses
+                .entry(status.to_string())
+                .or_insert_with(|| documented_error_response(&variants));
         }
 
         // Add default error response for undocumented errors
@@ -308,24 +619,32 @@ pub fn build_openapi_spec(
             .responses
             .insert("default".to_string(), error_response_ref());
 
-        let path_item = spec.paths.entry(openapi_path).or_default();
+        for tag in &route.tags {
+            if !spec.tags.iter().any(|t| &t.name == tag) {
+                spec.tags.push(Tag {
+                    name: tag.clone(),
+                    description: None,
+                });
+            }
+        }
 
-        match route.method.to_uppercase().as_str() {
-            "GET" => path_item.get = Some(operation),
-            "POST" => path_item.post = Some(operation),
-            "PUT" => path_item.put = Some(operation),
-            "DELETE" => path_item.delete = Some(operation),
-            _ => {}
+        // Routes on the same path share one PathItem. When a method is
+        // registered twice the first route handles requests, so keep it.
+        let path_item = spec.paths.entry(openapi_path).or_default();
+        if let Some(slot) = path_item.operation_mut(&route.method)
+            && slot.is_none()
+        {
+            *slot = Some(operation);
         }
     }
 
+    spec.components = Some(Components { schemas });
     spec
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::error::ErrorVariant;
     use crate::introspection::RouteInfo;
 
     #[test]
@@ -387,6 +706,411 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_build_openapi_spec_error_codes_per_status() {
+        let errors = vec![
+            ErrorVariant {
+                status: 409,
+                code: "CONFLICT",
+                description: "Email already taken",
+            },
+            ErrorVariant {
+                status: 404,
+                code: "NOT_FOUND",
+                description: "User not found",
+            },
+            ErrorVariant {
+                status: 409,
+                code: "USERNAME_TAKEN",
+                description: "Username already taken",
+            },
+        ];
+        let routes = vec![RouteInfo::new(
+            "POST",
+            "/users",
+            "create_user",
+            None,
+            errors,
+        )];
+        let spec = build_openapi_spec("Test API", "1.0.0", &routes);
+        let json = serde_json::to_value(&spec).unwrap();
+        let responses = &json["paths"]["/users"]["post"]["responses"];
+
+        let conflict = &responses["409"];
+        assert_eq!(
+            conflict["description"],
+            "Email already taken; Username already taken"
+        );
+        let media = &conflict["content"]["application/json"];
+        assert_eq!(
+            media["schema"]["allOf"][0]["$ref"],
+            "#/components/schemas/ErrorResponse"
+        );
+        assert_eq!(
+            media["schema"]["allOf"][1]["properties"]["error"]["properties"]["code"]["enum"],
+            serde_json::json!(["CONFLICT", "USERNAME_TAKEN"])
+        );
+        assert_eq!(media["example"]["error"]["code"], "CONFLICT");
+
+        let not_found = &responses["404"]["content"]["application/json"];
+        assert_eq!(
+            not_found["schema"]["allOf"][1]["properties"]["error"]["properties"]["code"]["enum"],
+            serde_json::json!(["NOT_FOUND"])
+        );
+
+        // Undocumented errors keep the shared schema
+        assert_eq!(
+            responses["default"]["content"]["application/json"]["schema"]["$ref"],
+            "#/components/schemas/ErrorResponse"
+        );
+    }
+
+    #[test]
+    fn test_build_openapi_spec_with_params_and_body() {
+        use crate::introspection::ParamInfo;
+
+        let routes = vec![
+            RouteInfo::new("PUT", "/users/:id", "update_user", None, Vec::new())
+                .with_path_params(vec![ParamInfo::new(
+                    "id",
+                    true,
+                    Some(serde_json::json!({"type": "integer"})),
+                )])
+                .with_query_params(vec![ParamInfo::new(
+                    "notify",
+                    false,
+                    Some(serde_json::json!({"type": "boolean"})),
+                )])
+                .with_request_schema(Some(serde_json::json!({"type": "object"}))),
+        ];
+        let spec = build_openapi_spec("Test API", "1.0.0", &routes);
+        let json = serde_json::to_value(&spec).unwrap();
+        let op = &json["paths"]["/users/{id}"]["put"];
+
+        assert_eq!(op["parameters"][0]["name"], "id");
+        assert_eq!(op["parameters"][0]["in"], "path");
+        assert_eq!(op["parameters"][0]["schema"]["type"], "integer");
+        assert_eq!(op["parameters"][1]["name"], "notify");
+        assert_eq!(op["parameters"][1]["in"], "query");
+        assert_eq!(op["parameters"][1]["required"], false);
+        assert_eq!(
+            op["requestBody"]["content"]["application/json"]["schema"]["type"],
+            "object"
+        );
+    }
+
+    #[test]
+    fn test_spec_servers() {
+        let mut spec = OpenApiSpec::new("Test API", "1.0.0");
+        let json = serde_json::to_value(&spec).unwrap();
+        assert!(json.get("servers").is_none());
+
+        spec.add_server("https://api.example.com", Some("Production".to_string()));
+        spec.add_server("http://localhost:3000", None);
+        let json = serde_json::to_value(&spec).unwrap();
+
+        assert_eq!(json["servers"][0]["url"], "https://api.example.com");
+        assert_eq!(json["servers"][0]["description"], "Production");
+        assert_eq!(json["servers"][1]["url"], "http://localhost:3000");
+        assert!(json["servers"][1].get("description").is_none());
+    }
+
+    #[test]
+    fn test_spec_base_path() {
+        let routes = vec![
+            RouteInfo::new("GET", "/", "index", None, Vec::new()),
+            RouteInfo::new("GET", "/users/:id", "get_user", None, Vec::new()),
+        ];
+        for base in ["/api/v1", "api/v1/", "/api/v1/"] {
+            let mut spec = build_openapi_spec("Test API", "1.0.0", &routes);
+            spec.apply_base_path(base);
+            let paths: Vec<_> = spec.paths.keys().cloned().collect();
+            assert_eq!(paths, ["/api/v1", "/api/v1/users/{id}"], "{}", base);
+        }
+
+        let mut spec = build_openapi_spec("Test API", "1.0.0", &routes);
+        spec.apply_base_path("/");
+        assert!(spec.paths.contains_key("/users/{id}"));
+    }
+
+    #[test]
+    fn test_build_openapi_spec_shared_request_body_component() {
+        let create_user = serde_json::json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "title": "CreateUser",
+            "type": "object",
+            "properties": {
+                "name": {"type": "string"},
+                "address": {"$ref": "#/$defs/Address"}
+            },
+            "$defs": {
+                "Address": {"type": "object", "properties": {"city": {"type": "string"}}}
+            }
+        });
+        let routes = vec![
+            RouteInfo::new("POST", "/users", "create_user", None, Vec::new())
+                .with_request_schema(Some(create_user.clone())),
+            RouteInfo::new(
+                "POST",
+                "/admin/users",
+                "admin_create_user",
+                None,
+                Vec::new(),
+            )
+            .with_request_schema(Some(create_user)),
+        ];
+        let spec = build_openapi_spec("Test API", "1.0.0", &routes);
+        let json = serde_json::to_value(&spec).unwrap();
+
+        for path in ["/users", "/admin/users"] {
+            assert_eq!(
+                json["paths"][path]["post"]["requestBody"]["content"]["application/json"]["schema"],
+                serde_json::json!({"$ref": "#/components/schemas/CreateUser"})
+            );
+        }
+
+        let schemas = json["components"]["schemas"].as_object().unwrap();
+        let names: Vec<_> = schemas.keys().map(String::as_str).collect();
+        assert_eq!(names, ["Address", "CreateUser", "ErrorResponse"]);
+        let component = &schemas["CreateUser"];
+        assert!(component.get("$schema").is_none());
+        assert!(component.get("$defs").is_none());
+        assert_eq!(
+            component["properties"]["address"]["$ref"],
+            "#/components/schemas/Address"
+        );
+    }
+
+    #[test]
+    fn test_build_openapi_spec_conflicting_titles_stay_inline() {
+        let routes = vec![
+            RouteInfo::new("POST", "/a", "create_a", None, Vec::new()).with_request_schema(Some(
+                serde_json::json!({"title": "Input", "type": "object"}),
+            )),
+            RouteInfo::new("POST", "/b", "create_b", None, Vec::new()).with_request_schema(Some(
+                serde_json::json!({"title": "Input", "type": "string"}),
+            )),
+            RouteInfo::new("POST", "/c", "create_c", None, Vec::new()).with_request_schema(Some(
+                serde_json::json!({"title": "ErrorResponse", "type": "string"}),
+            )),
+        ];
+        let spec = build_openapi_spec("Test API", "1.0.0", &routes);
+        let json = serde_json::to_value(&spec).unwrap();
+        let schema = |path: &str| {
+            json["paths"][path]["post"]["requestBody"]["content"]["application/json"]["schema"]
+                .clone()
+        };
+
+        assert_eq!(schema("/a")["$ref"], "#/components/schemas/Input");
+        assert_eq!(schema("/b")["type"], "string");
+        assert_eq!(schema("/c")["type"], "string");
+        assert_eq!(
+            json["components"]["schemas"]["ErrorResponse"]["required"],
+            serde_json::json!(["error", "trace_id"])
+        );
+    }
+
+    #[test]
+    fn test_build_openapi_spec_request_example() {
+        let routes = vec![
+            RouteInfo::new("POST", "/users", "create_user", None, Vec::new())
+                .with_request_schema(Some(serde_json::json!({"type": "object"})))
+                .with_request_example(Some(serde_json::json!({"email": "a@b.com"}))),
+            RouteInfo::new("PUT", "/users", "replace_user", None, Vec::new())
+                .with_request_schema(Some(serde_json::json!({"type": "object"}))),
+        ];
+        let spec = build_openapi_spec("Test API", "1.0.0", &routes);
+        let json = serde_json::to_value(&spec).unwrap();
+
+        let media = &json["paths"]["/users"]["post"]["requestBody"]["content"]["application/json"];
+        assert_eq!(media["example"]["email"], "a@b.com");
+        let media = &json["paths"]["/users"]["put"]["requestBody"]["content"]["application/json"];
+        assert!(media.get("example").is_none());
+    }
+
+    #[test]
+    fn test_build_openapi_spec_with_declared_responses() {
+        use crate::introspection::ResponseInfo;
+
+        let routes = vec![
+            RouteInfo::new("POST", "/users", "create_user", None, Vec::new()).with_responses(vec![
+                ResponseInfo::new(
+                    201,
+                    "application/json",
+                    Some(serde_json::json!({"title": "CreatedUser"})),
+                ),
+                ResponseInfo::new(
+                    200,
+                    "application/json",
+                    Some(serde_json::json!({"title": "User"})),
+                ),
+                ResponseInfo::new(200, "text/csv", Some(serde_json::json!({"type": "string"}))),
+            ]),
+        ];
+        let spec = build_openapi_spec("Test API", "1.0.0", &routes);
+        let json = serde_json::to_value(&spec).unwrap();
+        let responses = &json["paths"]["/users"]["post"]["responses"];
+
+        assert_eq!(responses["201"]["description"], "Created");
+        assert_eq!(
+            responses["201"]["content"]["application/json"]["schema"]["title"],
+            "CreatedUser"
+        );
+        assert_eq!(
+            responses["200"]["content"]["application/json"]["schema"]["title"],
+            "User"
+        );
+        assert_eq!(
+            responses["200"]["content"]["text/csv"]["schema"]["type"],
+            "string"
+        );
+        assert!(responses.get("default").is_some());
+    }
+
+    #[test]
+    fn test_build_openapi_spec_declared_responses_replace_default() {
+        use crate::introspection::ResponseInfo;
+
+        let routes = vec![
+            RouteInfo::new("POST", "/jobs", "create_job", None, Vec::new())
+                .with_responses(vec![ResponseInfo::new(202, "application/json", None)]),
+        ];
+        let spec = build_openapi_spec("Test API", "1.0.0", &routes);
+        let op = spec.paths["/jobs"].post.as_ref().unwrap();
+
+        assert!(!op.responses.contains_key("200"));
+        assert_eq!(op.responses["202"].description, "Accepted");
+        assert!(op.responses["202"].content.is_none());
+    }
+
+    #[test]
+    fn test_build_openapi_spec_deprecated() {
+        let routes = vec![
+            RouteInfo::new("GET", "/v1/users", "list_users_v1", None, Vec::new())
+                .with_deprecated(true),
+            RouteInfo::new("GET", "/v2/users", "list_users", None, Vec::new()),
+        ];
+        let spec = build_openapi_spec("Test API", "1.0.0", &routes);
+        let json = serde_json::to_value(&spec).unwrap();
+
+        assert_eq!(json["paths"]["/v1/users"]["get"]["deprecated"], true);
+        assert!(
+            json["paths"]["/v2/users"]["get"]
+                .get("deprecated")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_build_openapi_spec_tags() {
+        let routes = vec![
+            RouteInfo::new("GET", "/users", "list_users", None, Vec::new())
+                .with_tags(vec!["users".to_string()]),
+            RouteInfo::new("GET", "/admin/users", "admin_users", None, Vec::new())
+                .with_tags(vec!["admin".to_string(), "users".to_string()]),
+            RouteInfo::new("GET", "/health", "health", None, Vec::new()),
+        ];
+        let mut spec = build_openapi_spec("Test API", "1.0.0", &routes);
+        spec.describe_tag("users", "User management");
+        spec.describe_tag("internal", "Unused tag");
+        let json = serde_json::to_value(&spec).unwrap();
+
+        assert_eq!(
+            json["paths"]["/users"]["get"]["tags"],
+            serde_json::json!(["users"])
+        );
+        assert_eq!(
+            json["paths"]["/admin/users"]["get"]["tags"],
+            serde_json::json!(["admin", "users"])
+        );
+        assert!(json["paths"]["/health"]["get"].get("tags").is_none());
+        assert_eq!(
+            json["tags"],
+            serde_json::json!([
+                {"name": "users", "description": "User management"},
+                {"name": "admin"},
+                {"name": "internal", "description": "Unused tag"}
+            ])
+        );
+    }
+
+    #[test]
+    fn test_build_openapi_spec_merges_methods_on_same_path() {
+        let routes = vec![
+            RouteInfo::new("GET", "/users/:id", "get_user", None, Vec::new()),
+            RouteInfo::new("DELETE", "/users/:id", "delete_user", None, Vec::new()),
+        ];
+        let spec = build_openapi_spec("Test API", "1.0.0", &routes);
+
+        assert_eq!(spec.paths.len(), 1);
+        let path = &spec.paths["/users/{id}"];
+        assert_eq!(
+            path.get.as_ref().unwrap().operation_id.as_deref(),
+            Some("get_user")
+        );
+        assert_eq!(
+            path.delete.as_ref().unwrap().operation_id.as_deref(),
+            Some("delete_user")
+        );
+    }
+
+    #[test]
+    fn test_build_openapi_spec_one_handler_many_methods() {
+        let routes = vec![
+            RouteInfo::new("GET", "/proxy", "proxy", None, Vec::new()),
+            RouteInfo::new("POST", "/proxy", "proxy", None, Vec::new()),
+        ];
+        let spec = build_openapi_spec("Test API", "1.0.0", &routes);
+
+        let path = &spec.paths["/proxy"];
+        assert_eq!(
+            path.get.as_ref().unwrap().operation_id.as_deref(),
+            Some("proxy")
+        );
+        assert_eq!(
+            path.post.as_ref().unwrap().operation_id.as_deref(),
+            Some("proxy_post")
+        );
+    }
+
+    #[test]
+    fn test_build_openapi_spec_all_verbs() {
+        let verbs = [
+            "GET", "POST", "PUT", "DELETE", "PATCH", "HEAD", "OPTIONS", "TRACE",
+        ];
+        let routes: Vec<_> = verbs
+            .iter()
+            .map(|m| RouteInfo::new(*m, "/items/:id", m.to_lowercase(), None, Vec::new()))
+            .collect();
+        let spec = build_openapi_spec("Test API", "1.0.0", &routes);
+        let json = serde_json::to_value(&spec).unwrap();
+        let path = json["paths"]["/items/{id}"].as_object().unwrap();
+
+        for verb in verbs {
+            let key = verb.to_lowercase();
+            assert_eq!(path[&key]["operationId"], key);
+        }
+    }
+
+    #[test]
+    fn test_build_openapi_spec_duplicate_method_keeps_first() {
+        let routes = vec![
+            RouteInfo::new("GET", "/users", "list_users", None, Vec::new()),
+            RouteInfo::new("GET", "/users", "list_users_shadowed", None, Vec::new()),
+        ];
+        let spec = build_openapi_spec("Test API", "1.0.0", &routes);
+
+        assert_eq!(
+            spec.paths["/users"]
+                .get
+                .as_ref()
+                .unwrap()
+                .operation_id
+                .as_deref(),
+            Some("list_users")
+        );
+    }
+
     #[test]
     fn test_build_openapi_spec_skips_internal_routes() {
         let routes = vec![
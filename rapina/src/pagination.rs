@@ -0,0 +1,336 @@
+//! Paginated list responses.
+//!
+//! The [`Pagination`] extractor reads `page` and `per_page` from the query
+//! string, and [`Page`] returns one page of results in a standard envelope
+//! with RFC 8288 `Link` headers pointing at the first, previous, next and
+//! last pages.
+//!
+//! # Examples
+//!
+//! ```ignore
+//! use rapina::prelude::*;
+//!
+//! #[get("/users")]
+//! async fn list_users(pagination: Pagination, db: Db) -> Result<Page<User>> {
+//!     let users = User::find()
+//!         .offset(pagination.offset())
+//!         .limit(pagination.limit())
+//!         .all(db.conn())
+//!         .await?;
+//!     let total = User::find().count(db.conn()).await?;
+//!     Ok(Page::new(users, pagination.page, pagination.per_page, total))
+//! }
+//! ```
+
+use std::borrow::Cow;
+use std::sync::Arc;
+
+use http::{HeaderValue, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, ValidationMessages};
+use crate::extract::{FromRequestParts, Json, PathParams, Query};
+use crate::response::{BoxBody, IntoResponse};
+use crate::state::AppState;
+
+/// Limits for the [`Pagination`] extractor.
+///
+/// Register it with [`Rapina::with_pagination`](crate::app::Rapina::with_pagination).
+/// Without it, pages have 20 items by default and at most 100.
+///
+/// # Examples
+///
+/// ```
+/// use rapina::pagination::PaginationConfig;
+///
+/// let config = PaginationConfig::new().default_per_page(50).max_per_page(500);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct PaginationConfig {
+    default_per_page: u64,
+    max_per_page: u64,
+}
+
+impl PaginationConfig {
+    /// Creates the default config: 20 items per page, at most 100.
+    pub fn new() -> Self {
+        Self {
+            default_per_page: 20,
+            max_per_page: 100,
+        }
+    }
+
+    /// Sets the page size used when the request doesn't set `per_page`.
+    pub fn default_per_page(mut self, per_page: u64) -> Self {
+        self.default_per_page = per_page.max(1);
+        self
+    }
+
+    /// Sets the largest page size a client can ask for. Larger values are
+    /// lowered to it.
+    pub fn max_per_page(mut self, per_page: u64) -> Self {
+        self.max_per_page = per_page.max(1);
+        self
+    }
+}
+
+impl Default for PaginationConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Extracts the requested page from the `page` and `per_page` query
+/// parameters.
+///
+/// Pages are numbered from 1. A missing `page` is the first page and a
+/// missing `per_page` is the [`PaginationConfig`] default; a `per_page`
+/// above the maximum is lowered to it. A value that isn't a number is a 400,
+/// and a `page` or `per_page` of 0 is a 422. Other query parameters are
+/// ignored, so `Pagination` can be combined with a [`Query`] of filters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pagination {
+    /// The page number, starting at 1.
+    pub page: u64,
+    /// The number of items per page.
+    pub per_page: u64,
+}
+
+impl Pagination {
+    /// Returns how many items come before this page, for a query's `OFFSET`.
+    pub fn offset(&self) -> u64 {
+        (self.page - 1).saturating_mul(self.per_page)
+    }
+
+    /// Returns the page size, for a query's `LIMIT`.
+    pub fn limit(&self) -> u64 {
+        self.per_page
+    }
+}
+
+#[derive(Deserialize)]
+struct PaginationParams {
+    page: Option<u64>,
+    per_page: Option<u64>,
+}
+
+impl FromRequestParts for Pagination {
+    async fn from_request_parts(
+        parts: &http::request::Parts,
+        params: &PathParams,
+        state: &Arc<AppState>,
+    ) -> Result<Self, Error> {
+        let Query(query) =
+            Query::<PaginationParams>::from_request_parts(parts, params, state).await?;
+        let config = state
+            .get_or_init::<PaginationConfig>()
+            .await
+            .copied()
+            .unwrap_or_default();
+
+        let mut failures = Vec::new();
+        for (field, value) in [("page", query.page), ("per_page", query.per_page)] {
+            if value == Some(0) {
+                let mut error = validator::ValidationError::new("range");
+                error.add_param(Cow::Borrowed("min"), &1);
+                failures.push((field.to_string(), error));
+            }
+        }
+        if !failures.is_empty() {
+            let messages = state
+                .get_or_init::<ValidationMessages>()
+                .await
+                .cloned()
+                .unwrap_or_default();
+            return Err(messages.fields_error(&failures));
+        }
+
+        Ok(Pagination {
+            page: query.page.unwrap_or(1),
+            per_page: query
+                .per_page
+                .unwrap_or(config.default_per_page)
+                .min(config.max_per_page),
+        })
+    }
+}
+
+/// One page of a list, with the position of the page in the whole list.
+///
+/// Serialized as an envelope:
+///
+/// ```json
+/// { "items": [...], "page": 2, "per_page": 20, "total": 95, "total_pages": 5 }
+/// ```
+///
+/// The response also gets a `Link` header with `first` and `last` links, and
+/// `prev` and `next` links where those pages exist. Links keep the path and
+/// the other query parameters of the request, so filters carry over.
+#[derive(Debug, Clone)]
+pub struct Page<T> {
+    /// The items on this page.
+    pub items: Vec<T>,
+    /// The page number, starting at 1.
+    pub page: u64,
+    /// The number of items per page.
+    pub per_page: u64,
+    /// The number of items across all pages.
+    pub total: u64,
+}
+
+impl<T> Page<T> {
+    /// Creates a page of `items` out of `total` items.
+    pub fn new(items: Vec<T>, page: u64, per_page: u64, total: u64) -> Self {
+        Self {
+            items,
+            page,
+            per_page,
+            total,
+        }
+    }
+
+    /// Returns the number of pages, 0 for an empty list.
+    pub fn total_pages(&self) -> u64 {
+        self.total.div_ceil(self.per_page.max(1))
+    }
+}
+
+#[derive(Serialize)]
+struct Envelope<'a, T> {
+    items: &'a [T],
+    page: u64,
+    per_page: u64,
+    total: u64,
+    total_pages: u64,
+}
+
+impl<T: Serialize> IntoResponse for Page<T> {
+    fn into_response(self) -> Response<BoxBody> {
+        let envelope = Envelope {
+            items: &self.items,
+            page: self.page,
+            per_page: self.per_page,
+            total: self.total,
+            total_pages: self.total_pages(),
+        };
+        let mut response = (StatusCode::OK, Json(envelope)).into_response();
+        if response.status().is_success() {
+            response.extensions_mut().insert(PageLinks {
+                page: self.page,
+                per_page: self.per_page,
+                last: self.total_pages().max(1),
+            });
+        }
+        response
+    }
+}
+
+/// The page a [`Page`] response holds, left for the router to turn into a
+/// `Link` header once it knows the request URI.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct PageLinks {
+    page: u64,
+    per_page: u64,
+    last: u64,
+}
+
+impl PageLinks {
+    /// Adds the `Link` header for the page to `response`, with links built
+    /// from the request `uri`.
+    pub(crate) fn apply(&self, uri: &http::Uri, response: &mut Response<BoxBody>) {
+        let mut links = vec![(1, "first")];
+        if self.page > 1 {
+            links.push(((self.page - 1).min(self.last), "prev"));
+        }
+        if self.page < self.last {
+            links.push((self.page + 1, "next"));
+        }
+        links.push((self.last, "last"));
+
+        let header = links
+            .into_iter()
+            .map(|(page, rel)| format!("<{}>; rel=\"{}\"", self.url(uri, page), rel))
+            .collect::<Vec<_>>()
+            .join(", ");
+        if let Ok(value) = HeaderValue::from_str(&header) {
+            response.headers_mut().append(http::header::LINK, value);
+        }
+    }
+
+    fn url(&self, uri: &http::Uri, page: u64) -> String {
+        let mut query = form_urlencoded::Serializer::new(String::new());
+        for (key, value) in form_urlencoded::parse(uri.query().unwrap_or("").as_bytes()) {
+            if key != "page" && key != "per_page" {
+                query.append_pair(&key, &value);
+            }
+        }
+        query
+            .append_pair("page", &page.to_string())
+            .append_pair("per_page", &self.per_page.to_string());
+        format!("{}?{}", uri.path(), query.finish())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::TestRequest;
+
+    async fn extract(uri: &str) -> Result<Pagination, Error> {
+        let (parts, _) = TestRequest::get(uri).into_parts();
+        Pagination::from_request_parts(&parts, &PathParams::new(), &Arc::new(AppState::new())).await
+    }
+
+    #[tokio::test]
+    async fn test_pagination_defaults_and_caps() {
+        assert_eq!(
+            extract("/users").await.unwrap(),
+            Pagination {
+                page: 1,
+                per_page: 20
+            }
+        );
+        let pagination = extract("/users?page=3&per_page=1000&sort=name")
+            .await
+            .unwrap();
+        assert_eq!(pagination.per_page, 100);
+        assert_eq!(pagination.offset(), 200);
+    }
+
+    #[tokio::test]
+    async fn test_pagination_rejects_invalid_values() {
+        assert_eq!(extract("/users?page=abc").await.unwrap_err().status, 400);
+        let err = extract("/users?page=0&per_page=0").await.unwrap_err();
+        assert_eq!(err.status, 422);
+        let details = err.details.unwrap();
+        assert_eq!(details["page"][0]["code"], "range");
+        assert_eq!(details["per_page"][0]["message"], "must be at least 1");
+    }
+
+    #[test]
+    fn test_page_links() {
+        let links = PageLinks {
+            page: 2,
+            per_page: 10,
+            last: 5,
+        };
+        let uri: http::Uri = "/users?q=a+b&page=2".parse().unwrap();
+        let mut response = Response::new(BoxBody::default());
+        links.apply(&uri, &mut response);
+
+        assert_eq!(
+            response.headers()["link"],
+            "</users?q=a+b&page=1&per_page=10>; rel=\"first\", \
+             </users?q=a+b&page=1&per_page=10>; rel=\"prev\", \
+             </users?q=a+b&page=3&per_page=10>; rel=\"next\", \
+             </users?q=a+b&page=5&per_page=10>; rel=\"last\""
+        );
+    }
+
+    #[test]
+    fn test_total_pages() {
+        assert_eq!(Page::<u8>::new(vec![], 1, 20, 0).total_pages(), 0);
+        assert_eq!(Page::<u8>::new(vec![], 1, 20, 20).total_pages(), 1);
+        assert_eq!(Page::<u8>::new(vec![], 1, 20, 21).total_pages(), 2);
+    }
+}
@@ -3,12 +3,132 @@
 //! This module defines the [`IntoResponse`] trait which allows various types
 //! to be converted into HTTP responses.
 
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
 use bytes::Bytes;
+use futures_util::{Stream, StreamExt, TryStreamExt, future, stream};
+use http::header::{HeaderName, HeaderValue};
 use http::{Response, StatusCode};
-use http_body_util::Full;
+use http_body::{Body, Frame, SizeHint};
+use http_body_util::combinators::UnsyncBoxBody;
+use http_body_util::{BodyExt, Empty, Full, StreamBody};
+
+use crate::error::Error;
+
+mod json;
+
+pub use json::JsonConfig;
+
+/// An error raised while producing a response body.
+pub type BoxError = Box<dyn std::error::Error + Send + Sync>;
 
 /// The body type used for HTTP responses.
-pub type BoxBody = Full<Bytes>;
+///
+/// Holds either a complete buffer or a stream of chunks sent as they are
+/// produced. Build one from bytes or a string with `From`, or from a stream
+/// with [`BoxBody::from_stream`]. An error from a stream aborts the response.
+///
+/// # Examples
+///
+/// ```
+/// use rapina::response::BoxBody;
+///
+/// let body = BoxBody::from("hello");
+/// let empty = BoxBody::empty();
+/// ```
+pub struct BoxBody(UnsyncBoxBody<Bytes, BoxError>);
+
+impl BoxBody {
+    /// Wraps any body with `Bytes` chunks.
+    pub fn new<B>(body: B) -> Self
+    where
+        B: Body<Data = Bytes> + Send + 'static,
+        B::Error: Into<BoxError>,
+    {
+        Self(body.map_err(Into::into).boxed_unsync())
+    }
+
+    /// Returns a body with no content.
+    pub fn empty() -> Self {
+        Self::new(Empty::new())
+    }
+
+    /// Streams each chunk of `stream` as it is produced.
+    pub fn from_stream<S, E>(stream: S) -> Self
+    where
+        S: Stream<Item = Result<Bytes, E>> + Send + 'static,
+        E: Into<BoxError> + 'static,
+    {
+        Self::new(StreamBody::new(
+            stream.map_ok(Frame::data).map_err(Into::into),
+        ))
+    }
+}
+
+impl Default for BoxBody {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl std::fmt::Debug for BoxBody {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BoxBody")
+            .field("size_hint", &self.0.size_hint())
+            .finish()
+    }
+}
+
+impl Body for BoxBody {
+    type Data = Bytes;
+    type Error = BoxError;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Bytes>, BoxError>>> {
+        Pin::new(&mut self.get_mut().0).poll_frame(cx)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.0.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.0.size_hint()
+    }
+}
+
+impl From<Full<Bytes>> for BoxBody {
+    fn from(body: Full<Bytes>) -> Self {
+        Self::new(body)
+    }
+}
+
+impl From<Bytes> for BoxBody {
+    fn from(bytes: Bytes) -> Self {
+        Self::new(Full::new(bytes))
+    }
+}
+
+impl From<Vec<u8>> for BoxBody {
+    fn from(bytes: Vec<u8>) -> Self {
+        Bytes::from(bytes).into()
+    }
+}
+
+impl From<String> for BoxBody {
+    fn from(text: String) -> Self {
+        Bytes::from(text).into()
+    }
+}
+
+impl From<&'static str> for BoxBody {
+    fn from(text: &'static str) -> Self {
+        Bytes::from_static(text.as_bytes()).into()
+    }
+}
 
 /// Trait for types that can be converted into an HTTP response.
 ///
@@ -43,23 +163,40 @@ impl IntoResponse for Response<BoxBody> {
     }
 }
 
+impl IntoResponse for Response<Full<Bytes>> {
+    fn into_response(self) -> Response<BoxBody> {
+        self.map(BoxBody::from)
+    }
+}
+
+/// Builds a response with a complete body, setting `Content-Type` and a
+/// `Content-Length` that matches the body.
+///
+/// The length is sent explicitly so it is also present on `HEAD` responses,
+/// whose body is dropped, and survives proxies that don't count bytes.
+pub(crate) fn full_response(
+    status: StatusCode,
+    content_type: &str,
+    body: impl Into<Bytes>,
+) -> Response<BoxBody> {
+    let body = body.into();
+    Response::builder()
+        .status(status)
+        .header(http::header::CONTENT_TYPE, content_type)
+        .header(http::header::CONTENT_LENGTH, body.len())
+        .body(BoxBody::from(body))
+        .unwrap()
+}
+
 impl IntoResponse for &str {
     fn into_response(self) -> Response<BoxBody> {
-        Response::builder()
-            .status(StatusCode::OK)
-            .header("content-type", "text/plain; charset=utf-8")
-            .body(Full::new(Bytes::from(self.to_owned())))
-            .unwrap()
+        self.to_owned().into_response()
     }
 }
 
 impl IntoResponse for String {
     fn into_response(self) -> Response<BoxBody> {
-        Response::builder()
-            .status(StatusCode::OK)
-            .header("content-type", "text/plain; charset=utf-8")
-            .body(Full::new(Bytes::from(self.to_owned())))
-            .unwrap()
+        (StatusCode::OK, self).into_response()
     }
 }
 
@@ -67,18 +204,14 @@ impl IntoResponse for StatusCode {
     fn into_response(self) -> Response<BoxBody> {
         Response::builder()
             .status(self)
-            .body(Full::new(Bytes::new()))
+            .body(BoxBody::empty())
             .unwrap()
     }
 }
 
 impl IntoResponse for (StatusCode, String) {
     fn into_response(self) -> Response<BoxBody> {
-        Response::builder()
-            .status(self.0)
-            .header("content-type", "text/plain; charset=utf-8")
-            .body(Full::new(Bytes::from(self.1)))
-            .unwrap()
+        full_response(self.0, "text/plain; charset=utf-8", self.1)
     }
 }
 
@@ -91,6 +224,396 @@ impl<T: IntoResponse, E: IntoResponse> IntoResponse for std::result::Result<T, E
     }
 }
 
+/// Wraps a response and appends extra headers to it.
+///
+/// Headers are appended rather than inserted, so repeating a name (or
+/// adding one the inner response already sets) produces multiple values.
+/// An invalid header name or value turns the response into a 500 error.
+///
+/// # Examples
+///
+/// ```
+/// use rapina::prelude::*;
+///
+/// #[get("/users")]
+/// async fn list_users() -> WithHeaders<Json<Vec<String>>> {
+///     WithHeaders::new(Json(vec!["alice".to_string()]))
+///         .header("x-total-count", "1")
+///         .header("link", "</users?page=2>; rel=\"next\"")
+/// }
+/// ```
+#[derive(Debug)]
+pub struct WithHeaders<T> {
+    inner: T,
+    headers: Vec<(String, String)>,
+    cookies: Vec<crate::cookie::SetCookie>,
+}
+
+impl<T: IntoResponse> WithHeaders<T> {
+    /// Wraps a response with no extra headers.
+    pub fn new(inner: T) -> Self {
+        Self {
+            inner,
+            headers: Vec::new(),
+            cookies: Vec::new(),
+        }
+    }
+
+    /// Appends a header to the response.
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Appends a `Set-Cookie` header for `cookie`.
+    ///
+    /// An invalid cookie turns the response into a 500 error, see
+    /// [`SetCookie::validate`](crate::cookie::SetCookie::validate).
+    pub fn cookie(mut self, cookie: crate::cookie::SetCookie) -> Self {
+        self.cookies.push(cookie);
+        self
+    }
+}
+
+impl<T: IntoResponse> IntoResponse for WithHeaders<T> {
+    fn into_response(self) -> Response<BoxBody> {
+        let mut response = self.inner.into_response();
+        for (name, value) in self.headers {
+            let (Ok(name), Ok(value)) = (
+                HeaderName::from_bytes(name.as_bytes()),
+                HeaderValue::from_str(&value),
+            ) else {
+                return Error::internal(format!("Invalid response header '{}'", name))
+                    .into_response();
+            };
+            response.headers_mut().append(name, value);
+        }
+        crate::cookie::append_cookies(response, &self.cookies)
+    }
+}
+
+/// An empty `204 No Content` response.
+///
+/// # Examples
+///
+/// ```
+/// use rapina::prelude::*;
+///
+/// #[delete("/users/:id")]
+/// async fn delete_user(id: Path<u64>) -> NoContent {
+///     NoContent
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoContent;
+
+impl IntoResponse for NoContent {
+    fn into_response(self) -> Response<BoxBody> {
+        StatusCode::NO_CONTENT.into_response()
+    }
+}
+
+/// A `201 Created` response with an optional `Location` header and JSON body.
+///
+/// # Examples
+///
+/// ```
+/// use rapina::prelude::*;
+///
+/// #[derive(Serialize)]
+/// struct User {
+///     id: u64,
+/// }
+///
+/// // Body and Location header
+/// let created = Created::new(User { id: 1 }).location("/users/1");
+///
+/// // Location header only
+/// let created: Created = Created::with_location("/users/1");
+/// ```
+#[derive(Debug)]
+pub struct Created<T = ()> {
+    body: Option<T>,
+    location: Option<String>,
+}
+
+impl<T> Created<T> {
+    /// Creates a response with the given JSON body.
+    pub fn new(body: T) -> Self {
+        Self {
+            body: Some(body),
+            location: None,
+        }
+    }
+
+    /// Creates a response with a `Location` header and no body.
+    pub fn with_location(location: impl Into<String>) -> Self {
+        Self {
+            body: None,
+            location: Some(location.into()),
+        }
+    }
+
+    /// Sets the `Location` header.
+    pub fn location(mut self, location: impl Into<String>) -> Self {
+        self.location = Some(location.into());
+        self
+    }
+}
+
+impl<T: serde::Serialize> IntoResponse for Created<T> {
+    fn into_response(self) -> Response<BoxBody> {
+        let mut response = match self.body {
+            Some(body) => (StatusCode::CREATED, crate::extract::Json(body)).into_response(),
+            None => StatusCode::CREATED.into_response(),
+        };
+
+        if let Some(location) = self.location {
+            match HeaderValue::from_str(&location) {
+                Ok(value) => {
+                    response.headers_mut().insert(http::header::LOCATION, value);
+                }
+                Err(_) => {
+                    return Error::internal(format!("Invalid Location header '{}'", location))
+                        .into_response();
+                }
+            }
+        }
+        response
+    }
+}
+
+/// A JSON array streamed to the client one element at a time.
+///
+/// Unlike `Json(Vec<T>)`, the array is never held in memory: each item is
+/// serialized as the stream yields it and sent as its own chunk. An empty
+/// stream produces `[]`. If an item fails to serialize, the response is
+/// aborted, so the client sees a truncated body rather than a complete one.
+///
+/// # Examples
+///
+/// ```
+/// use rapina::prelude::*;
+/// use futures_util::stream;
+///
+/// #[get("/export")]
+/// async fn export() -> JsonStream<impl futures_util::Stream<Item = u64> + Send> {
+///     JsonStream::new(stream::iter(0..100_000))
+/// }
+/// ```
+pub struct JsonStream<S>(pub S);
+
+impl<S> JsonStream<S> {
+    /// Streams the items of `stream` as a JSON array.
+    pub fn new(stream: S) -> Self {
+        Self(stream)
+    }
+}
+
+impl<S, T> IntoResponse for JsonStream<S>
+where
+    S: Stream<Item = T> + Send + 'static,
+    T: serde::Serialize,
+{
+    fn into_response(self) -> Response<BoxBody> {
+        let mut first = true;
+        let items = self.0.map(move |item| {
+            let mut chunk = if std::mem::replace(&mut first, false) {
+                Vec::new()
+            } else {
+                vec![b',']
+            };
+            serde_json::to_writer(&mut chunk, &item)?;
+            Ok::<_, serde_json::Error>(Bytes::from(chunk))
+        });
+        let body = stream::once(future::ready(Ok(Bytes::from_static(b"["))))
+            .chain(items)
+            .chain(stream::once(future::ready(Ok(Bytes::from_static(b"]")))));
+
+        Response::builder()
+            .status(StatusCode::OK)
+            .header(http::header::CONTENT_TYPE, "application/json")
+            .body(BoxBody::from_stream(body))
+            .unwrap()
+    }
+}
+
+/// A file download: a body sent with `Content-Disposition: attachment` so
+/// browsers save it under `filename` instead of displaying it.
+///
+/// The content type is guessed from the filename's extension unless set
+/// with [`content_type`](Self::content_type), falling back to
+/// `application/octet-stream`. The filename is sanitized before it goes in
+/// the header: quotes, backslashes, path separators and control characters
+/// are replaced, and a non-ASCII name is also sent percent-encoded in
+/// `filename*` so clients that support it keep the original.
+///
+/// # Examples
+///
+/// ```
+/// use rapina::prelude::*;
+/// use bytes::Bytes;
+/// use futures_util::stream;
+///
+/// #[get("/reports/latest")]
+/// async fn latest_report() -> Download {
+///     let rows = stream::iter(["id,name\n", "1,Ada\n"].map(|row| {
+///         Ok::<_, std::io::Error>(Bytes::from(row))
+///     }));
+///     Download::from_stream("report.csv", rows)
+/// }
+/// ```
+pub struct Download {
+    filename: String,
+    content_type: Option<String>,
+    body: BoxBody,
+}
+
+impl Download {
+    /// Sends `body` as a file named `filename`.
+    pub fn new(filename: impl Into<String>, body: impl Into<Bytes>) -> Self {
+        Self {
+            filename: filename.into(),
+            content_type: None,
+            body: BoxBody::from(body.into()),
+        }
+    }
+
+    /// Streams the chunks of `stream` as a file named `filename`.
+    ///
+    /// An error from the stream aborts the response, so the client sees a
+    /// truncated download.
+    pub fn from_stream<S, E>(filename: impl Into<String>, stream: S) -> Self
+    where
+        S: Stream<Item = Result<Bytes, E>> + Send + 'static,
+        E: Into<BoxError> + 'static,
+    {
+        Self {
+            filename: filename.into(),
+            content_type: None,
+            body: BoxBody::from_stream(stream),
+        }
+    }
+
+    /// Sets the `Content-Type` instead of guessing it from the filename.
+    pub fn content_type(mut self, content_type: impl Into<String>) -> Self {
+        self.content_type = Some(content_type.into());
+        self
+    }
+}
+
+impl std::fmt::Debug for Download {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Download")
+            .field("filename", &self.filename)
+            .field("content_type", &self.content_type)
+            .finish_non_exhaustive()
+    }
+}
+
+impl IntoResponse for Download {
+    fn into_response(self) -> Response<BoxBody> {
+        let content_type = self
+            .content_type
+            .unwrap_or_else(|| guess_content_type(&self.filename).to_string());
+        let Ok(content_type) = HeaderValue::from_str(&content_type) else {
+            return Error::internal(format!("Invalid Content-Type '{}'", content_type))
+                .into_response();
+        };
+        let disposition = HeaderValue::from_str(&content_disposition(&self.filename))
+            .expect("sanitized filename is a valid header value");
+
+        let mut response = Response::builder()
+            .status(StatusCode::OK)
+            .header(http::header::CONTENT_TYPE, content_type)
+            .header(http::header::CONTENT_DISPOSITION, disposition);
+        if let Some(len) = self.body.size_hint().exact() {
+            response = response.header(http::header::CONTENT_LENGTH, len);
+        }
+        response.body(self.body).unwrap()
+    }
+}
+
+/// Builds an `attachment` disposition, with an ASCII `filename` any client
+/// understands and the exact name in `filename*` when it isn't plain ASCII.
+fn content_disposition(filename: &str) -> String {
+    // Only the last path component, so `../../etc/passwd` can't suggest a
+    // location outside the download folder
+    let name = filename.rsplit(['/', '\\']).next().unwrap_or_default();
+    let fallback: String = name
+        .chars()
+        .map(|c| match c {
+            ' '..='~' if c != '"' => c,
+            _ => '_',
+        })
+        .collect();
+    let fallback = if fallback.is_empty() {
+        "download".to_string()
+    } else {
+        fallback
+    };
+
+    if name.is_ascii() && !name.chars().any(|c| c.is_ascii_control()) {
+        return format!("attachment; filename=\"{}\"", fallback);
+    }
+
+    let mut encoded = String::new();
+    for byte in name.bytes() {
+        match byte {
+            b'A'..=b'Z'
+            | b'a'..=b'z'
+            | b'0'..=b'9'
+            | b'!'
+            | b'#'
+            | b'$'
+            | b'&'
+            | b'+'
+            | b'-'
+            | b'.'
+            | b'^'
+            | b'_'
+            | b'`'
+            | b'|'
+            | b'~' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    format!(
+        "attachment; filename=\"{}\"; filename*=UTF-8''{}",
+        fallback, encoded
+    )
+}
+
+/// Returns the content type for a filename's extension, or
+/// `application/octet-stream` when it isn't recognized.
+fn guess_content_type(filename: &str) -> &'static str {
+    let extension = filename
+        .rsplit_once('.')
+        .map(|(_, ext)| ext.to_ascii_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "csv" => "text/csv; charset=utf-8",
+        "txt" | "log" => "text/plain; charset=utf-8",
+        "html" | "htm" => "text/html; charset=utf-8",
+        "css" => "text/css; charset=utf-8",
+        "js" => "text/javascript; charset=utf-8",
+        "json" => "application/json",
+        "ndjson" => "application/x-ndjson",
+        "xml" => "application/xml",
+        "pdf" => "application/pdf",
+        "zip" => "application/zip",
+        "gz" => "application/gzip",
+        "tar" => "application/x-tar",
+        "xlsx" => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "svg" => "image/svg+xml",
+        "webp" => "image/webp",
+        _ => "application/octet-stream",
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -122,6 +645,41 @@ mod tests {
         assert_eq!(&body[..], b"world");
     }
 
+    async fn assert_content_length(response: Response<BoxBody>) {
+        let declared: usize = response.headers()[http::header::CONTENT_LENGTH]
+            .to_str()
+            .unwrap()
+            .parse()
+            .unwrap();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        assert_eq!(declared, body.len());
+    }
+
+    #[tokio::test]
+    async fn test_full_responses_set_content_length() {
+        assert_content_length("héllo".into_response()).await;
+        assert_content_length("world".to_string().into_response()).await;
+        assert_content_length((StatusCode::CREATED, "created".to_string()).into_response()).await;
+        assert_content_length(
+            crate::extract::Json(serde_json::json!({ "id": 1, "name": "ñandú" })).into_response(),
+        )
+        .await;
+        assert_content_length(Error::not_found("user not found").into_response()).await;
+        assert_content_length(Download::new("report.csv", "a,b\n1,2\n").into_response()).await;
+    }
+
+    #[test]
+    fn test_streamed_download_has_no_content_length() {
+        let chunks = stream::iter([Ok::<_, Error>(Bytes::from("a"))]);
+        let response = Download::from_stream("report.csv", chunks).into_response();
+        assert!(
+            response
+                .headers()
+                .get(http::header::CONTENT_LENGTH)
+                .is_none()
+        );
+    }
+
     #[tokio::test]
     async fn test_status_code_into_response() {
         let response = StatusCode::NOT_FOUND.into_response();
@@ -195,4 +753,180 @@ mod tests {
         let response = result.into_response();
         assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
     }
+
+    #[tokio::test]
+    async fn test_with_headers_keeps_body_and_adds_headers() {
+        let response = WithHeaders::new(crate::extract::Json(serde_json::json!({"id": 1})))
+            .header("x-total-count", "42")
+            .header("x-page", "1")
+            .into_response();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers().get("content-type").unwrap(),
+            "application/json"
+        );
+        assert_eq!(response.headers().get("x-total-count").unwrap(), "42");
+        assert_eq!(response.headers().get("x-page").unwrap(), "1");
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        assert_eq!(&body[..], br#"{"id":1}"#);
+    }
+
+    #[test]
+    fn test_with_headers_appends_duplicates() {
+        let response = WithHeaders::new("ok")
+            .header("set-cookie", "a=1")
+            .header("set-cookie", "b=2")
+            .header("content-type", "text/html")
+            .into_response();
+
+        let cookies: Vec<_> = response.headers().get_all("set-cookie").iter().collect();
+        assert_eq!(cookies, vec!["a=1", "b=2"]);
+
+        let content_types: Vec<_> = response.headers().get_all("content-type").iter().collect();
+        assert_eq!(
+            content_types,
+            vec!["text/plain; charset=utf-8", "text/html"]
+        );
+    }
+
+    #[test]
+    fn test_with_headers_preserves_status() {
+        let response = WithHeaders::new(StatusCode::ACCEPTED)
+            .header("location", "/jobs/1")
+            .into_response();
+
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+        assert_eq!(response.headers().get("location").unwrap(), "/jobs/1");
+    }
+
+    #[test]
+    fn test_with_headers_invalid_header() {
+        let response = WithHeaders::new("ok")
+            .header("bad header", "value")
+            .into_response();
+
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[tokio::test]
+    async fn test_no_content_into_response() {
+        let response = NoContent.into_response();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert!(response.headers().get("content-type").is_none());
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        assert!(body.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_created_with_body_and_location() {
+        let response = Created::new(serde_json::json!({"id": 1}))
+            .location("/users/1")
+            .into_response();
+
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert_eq!(response.headers().get("location").unwrap(), "/users/1");
+        assert_eq!(
+            response.headers().get("content-type").unwrap(),
+            "application/json"
+        );
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        assert_eq!(&body[..], br#"{"id":1}"#);
+    }
+
+    #[tokio::test]
+    async fn test_created_with_location_only() {
+        let response = Created::<()>::with_location("/users/1").into_response();
+
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert_eq!(response.headers().get("location").unwrap(), "/users/1");
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        assert!(body.is_empty());
+    }
+
+    #[test]
+    fn test_created_without_location() {
+        let response = Created::new("alice").into_response();
+
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert!(response.headers().get("location").is_none());
+    }
+
+    #[test]
+    fn test_created_invalid_location() {
+        let response = Created::<()>::with_location("/users/\n1").into_response();
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[tokio::test]
+    async fn test_json_stream_into_response() {
+        let response = JsonStream::new(stream::iter([1, 2, 3])).into_response();
+        assert_eq!(
+            response.headers().get("content-type").unwrap(),
+            "application/json"
+        );
+        assert_eq!(response.body().size_hint().exact(), None);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        assert_eq!(&body[..], b"[1,2,3]");
+    }
+
+    #[tokio::test]
+    async fn test_box_body_from_bytes_has_exact_size() {
+        let body = BoxBody::from("hello");
+        assert_eq!(body.size_hint().exact(), Some(5));
+        assert_eq!(&body.collect().await.unwrap().to_bytes()[..], b"hello");
+    }
+
+    #[test]
+    fn test_download_disposition_is_sanitized() {
+        assert_eq!(
+            content_disposition("report.csv"),
+            "attachment; filename=\"report.csv\""
+        );
+        assert_eq!(
+            content_disposition("a\"b\r\nSet-Cookie: x=1.txt"),
+            "attachment; filename=\"a_b__Set-Cookie: x=1.txt\"; \
+             filename*=UTF-8''a%22b%0D%0ASet-Cookie%3A%20x%3D1.txt"
+        );
+        assert_eq!(
+            content_disposition("../../etc/passwd"),
+            "attachment; filename=\"passwd\""
+        );
+        assert_eq!(
+            content_disposition("dir/"),
+            "attachment; filename=\"download\""
+        );
+        assert_eq!(
+            content_disposition("résumé.pdf"),
+            "attachment; filename=\"r_sum_.pdf\"; filename*=UTF-8''r%C3%A9sum%C3%A9.pdf"
+        );
+    }
+
+    #[test]
+    fn test_download_content_type() {
+        let response = Download::new("data.CSV", "a,b").into_response();
+        assert_eq!(
+            response.headers()["content-type"],
+            "text/csv; charset=utf-8"
+        );
+
+        let response = Download::new("blob", "x").into_response();
+        assert_eq!(
+            response.headers()["content-type"],
+            "application/octet-stream"
+        );
+
+        let response = Download::new("data.csv", "x")
+            .content_type("application/vnd.ms-excel")
+            .into_response();
+        assert_eq!(
+            response.headers()["content-type"],
+            "application/vnd.ms-excel"
+        );
+    }
 }
@@ -0,0 +1,666 @@
+//! App-wide settings for [`Json`](crate::extract::Json) responses.
+//!
+//! The settings are applied while the value is serialized, by wrapping the
+//! serializer: struct field names are renamed and `null` fields skipped as
+//! serde hands them over, and everything else is forwarded untouched. Keys
+//! of maps go through `serialize_key`, not `serialize_field`, so they are
+//! never renamed, and fields keep their declaration order.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{LazyLock, RwLock};
+
+use serde::ser::{
+    self, Impossible, Serialize, SerializeMap, SerializeSeq, SerializeStruct,
+    SerializeStructVariant, SerializeTuple, SerializeTupleStruct, SerializeTupleVariant,
+    Serializer,
+};
+
+tokio::task_local! {
+    static CONFIG: JsonConfig;
+}
+
+/// App-wide settings for how [`Json`](crate::extract::Json) responses are
+/// serialized.
+///
+/// Register it with [`Rapina::with_json_config`](crate::app::Rapina::with_json_config).
+/// The settings apply to struct fields as serde serializes them, after serde
+/// attributes have been applied:
+///
+/// - [`camel_case`](Self::camel_case) renames `snake_case` struct fields to
+///   `camelCase`. Names that aren't `snake_case`, e.g. from
+///   `#[serde(rename = "ID")]` or a struct-level `#[serde(rename_all = "...")]`,
+///   are left as they are, so the attributes win. Keys of maps are data and
+///   are never renamed.
+/// - [`skip_nulls`](Self::skip_nulls) leaves out struct fields whose value is
+///   `null`, like `#[serde(skip_serializing_if = "Option::is_none")]` on
+///   every field. `null`s in arrays and maps are kept.
+///
+/// # Examples
+///
+/// ```
+/// use rapina::response::JsonConfig;
+///
+/// let config = JsonConfig::new().camel_case(true).skip_nulls(true);
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonConfig {
+    camel_case: bool,
+    skip_nulls: bool,
+}
+
+impl JsonConfig {
+    /// Creates a config that leaves responses as serde produces them.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether `snake_case` struct fields are renamed to `camelCase`.
+    pub fn camel_case(mut self, enabled: bool) -> Self {
+        self.camel_case = enabled;
+        self
+    }
+
+    /// Sets whether struct fields with a `null` value are left out.
+    pub fn skip_nulls(mut self, enabled: bool) -> Self {
+        self.skip_nulls = enabled;
+        self
+    }
+
+    /// Runs `future` with this config applied to the `Json` responses it
+    /// builds.
+    pub(crate) async fn scope<F: Future>(self, future: F) -> F::Output {
+        CONFIG.scope(self, future).await
+    }
+
+    /// Serializes `value` with the config of the current request, if any.
+    pub(crate) fn to_vec<T: Serialize + ?Sized>(value: &T) -> serde_json::Result<Vec<u8>> {
+        match CONFIG.try_with(|config| *config) {
+            Ok(config) if config.camel_case || config.skip_nulls => {
+                serde_json::to_vec(&Configured { value, config })
+            }
+            _ => serde_json::to_vec(value),
+        }
+    }
+
+    fn field_name(&self, name: &'static str) -> &'static str {
+        if self.camel_case {
+            camel_case_name(name)
+        } else {
+            name
+        }
+    }
+}
+
+/// A value serialized with a [`JsonConfig`].
+struct Configured<'a, T: ?Sized> {
+    value: &'a T,
+    config: JsonConfig,
+}
+
+impl<T: Serialize + ?Sized> Serialize for Configured<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.value.serialize(ConfiguredSerializer {
+            inner: serializer,
+            config: self.config,
+        })
+    }
+}
+
+/// Forwards to `inner`, wrapping nested values so the config reaches them.
+struct ConfiguredSerializer<S> {
+    inner: S,
+    config: JsonConfig,
+}
+
+/// Wraps a compound serializer of `inner`, applying the config to its
+/// elements.
+struct Compound<C> {
+    inner: C,
+    config: JsonConfig,
+}
+
+impl<C> Compound<C> {
+    fn wrap<'a, T: ?Sized>(&self, value: &'a T) -> Configured<'a, T> {
+        Configured {
+            value,
+            config: self.config,
+        }
+    }
+
+    fn skips<T: Serialize + ?Sized>(&self, value: &T) -> bool {
+        self.config.skip_nulls && value.serialize(NullProbe).is_ok()
+    }
+}
+
+macro_rules! forward {
+    ($($method:ident($($arg:ident: $ty:ty),*);)*) => {
+        $(
+            fn $method(self, $($arg: $ty),*) -> Result<S::Ok, S::Error> {
+                self.inner.$method($($arg),*)
+            }
+        )*
+    };
+}
+
+impl<S: Serializer> Serializer for ConfiguredSerializer<S> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+    type SerializeSeq = Compound<S::SerializeSeq>;
+    type SerializeTuple = Compound<S::SerializeTuple>;
+    type SerializeTupleStruct = Compound<S::SerializeTupleStruct>;
+    type SerializeTupleVariant = Compound<S::SerializeTupleVariant>;
+    type SerializeMap = Compound<S::SerializeMap>;
+    type SerializeStruct = Compound<S::SerializeStruct>;
+    type SerializeStructVariant = Compound<S::SerializeStructVariant>;
+
+    forward! {
+        serialize_bool(v: bool);
+        serialize_i8(v: i8);
+        serialize_i16(v: i16);
+        serialize_i32(v: i32);
+        serialize_i64(v: i64);
+        serialize_i128(v: i128);
+        serialize_u8(v: u8);
+        serialize_u16(v: u16);
+        serialize_u32(v: u32);
+        serialize_u64(v: u64);
+        serialize_u128(v: u128);
+        serialize_f32(v: f32);
+        serialize_f64(v: f64);
+        serialize_char(v: char);
+        serialize_str(v: &str);
+        serialize_bytes(v: &[u8]);
+        serialize_none();
+        serialize_unit();
+        serialize_unit_struct(name: &'static str);
+        serialize_unit_variant(name: &'static str, index: u32, variant: &'static str);
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<S::Ok, S::Error> {
+        let config = self.config;
+        self.inner.serialize_some(&Configured { value, config })
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<S::Ok, S::Error> {
+        let config = self.config;
+        self.inner
+            .serialize_newtype_struct(name, &Configured { value, config })
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<S::Ok, S::Error> {
+        let config = self.config;
+        self.inner
+            .serialize_newtype_variant(name, index, variant, &Configured { value, config })
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, S::Error> {
+        Ok(Compound {
+            inner: self.inner.serialize_seq(len)?,
+            config: self.config,
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, S::Error> {
+        Ok(Compound {
+            inner: self.inner.serialize_tuple(len)?,
+            config: self.config,
+        })
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, S::Error> {
+        Ok(Compound {
+            inner: self.inner.serialize_tuple_struct(name, len)?,
+            config: self.config,
+        })
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, S::Error> {
+        Ok(Compound {
+            inner: self
+                .inner
+                .serialize_tuple_variant(name, index, variant, len)?,
+            config: self.config,
+        })
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, S::Error> {
+        Ok(Compound {
+            inner: self.inner.serialize_map(len)?,
+            config: self.config,
+        })
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, S::Error> {
+        Ok(Compound {
+            inner: self.inner.serialize_struct(name, len)?,
+            config: self.config,
+        })
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, S::Error> {
+        Ok(Compound {
+            inner: self
+                .inner
+                .serialize_struct_variant(name, index, variant, len)?,
+            config: self.config,
+        })
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.inner.is_human_readable()
+    }
+}
+
+macro_rules! compound {
+    ($($trait:ident::$method:ident;)*) => {
+        $(
+            impl<C: $trait> $trait for Compound<C> {
+                type Ok = C::Ok;
+                type Error = C::Error;
+
+                fn $method<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), C::Error> {
+                    let value = self.wrap(value);
+                    self.inner.$method(&value)
+                }
+
+                fn end(self) -> Result<C::Ok, C::Error> {
+                    self.inner.end()
+                }
+            }
+        )*
+    };
+}
+
+compound! {
+    SerializeSeq::serialize_element;
+    SerializeTuple::serialize_element;
+    SerializeTupleStruct::serialize_field;
+    SerializeTupleVariant::serialize_field;
+}
+
+impl<C: SerializeMap> SerializeMap for Compound<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), C::Error> {
+        self.inner.serialize_key(key)
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), C::Error> {
+        let value = self.wrap(value);
+        self.inner.serialize_value(&value)
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
+
+impl<C: SerializeStruct> SerializeStruct for Compound<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), C::Error> {
+        if self.skips(value) {
+            return self.inner.skip_field(key);
+        }
+        let value = self.wrap(value);
+        self.inner
+            .serialize_field(self.config.field_name(key), &value)
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), C::Error> {
+        self.inner.skip_field(key)
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
+
+impl<C: SerializeStructVariant> SerializeStructVariant for Compound<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), C::Error> {
+        if self.skips(value) {
+            return self.inner.skip_field(key);
+        }
+        let value = self.wrap(value);
+        self.inner
+            .serialize_field(self.config.field_name(key), &value)
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), C::Error> {
+        self.inner.skip_field(key)
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
+
+/// Serializes successfully only for values that serde_json writes as `null`,
+/// without visiting the contents of anything else.
+struct NullProbe;
+
+#[derive(Debug)]
+struct NotNull;
+
+impl std::fmt::Display for NotNull {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("not null")
+    }
+}
+
+impl std::error::Error for NotNull {}
+
+impl ser::Error for NotNull {
+    fn custom<T: std::fmt::Display>(_: T) -> Self {
+        NotNull
+    }
+}
+
+macro_rules! not_null {
+    ($($method:ident($($ty:ty),*);)*) => {
+        $(
+            fn $method(self, $(_: $ty),*) -> Result<(), NotNull> {
+                Err(NotNull)
+            }
+        )*
+    };
+}
+
+impl Serializer for NullProbe {
+    type Ok = ();
+    type Error = NotNull;
+    type SerializeSeq = Impossible<(), NotNull>;
+    type SerializeTuple = Impossible<(), NotNull>;
+    type SerializeTupleStruct = Impossible<(), NotNull>;
+    type SerializeTupleVariant = Impossible<(), NotNull>;
+    type SerializeMap = Impossible<(), NotNull>;
+    type SerializeStruct = Impossible<(), NotNull>;
+    type SerializeStructVariant = Impossible<(), NotNull>;
+
+    not_null! {
+        serialize_bool(bool);
+        serialize_i8(i8);
+        serialize_i16(i16);
+        serialize_i32(i32);
+        serialize_i64(i64);
+        serialize_i128(i128);
+        serialize_u8(u8);
+        serialize_u16(u16);
+        serialize_u32(u32);
+        serialize_u64(u64);
+        serialize_u128(u128);
+        serialize_char(char);
+        serialize_str(&str);
+        serialize_bytes(&[u8]);
+        serialize_unit_variant(&'static str, u32, &'static str);
+    }
+
+    // serde_json writes non-finite floats as `null`
+    fn serialize_f32(self, v: f32) -> Result<(), NotNull> {
+        if v.is_finite() { Err(NotNull) } else { Ok(()) }
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<(), NotNull> {
+        if v.is_finite() { Err(NotNull) } else { Ok(()) }
+    }
+
+    fn serialize_none(self) -> Result<(), NotNull> {
+        Ok(())
+    }
+
+    fn serialize_unit(self) -> Result<(), NotNull> {
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> Result<(), NotNull> {
+        Ok(())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<(), NotNull> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> Result<(), NotNull> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: &T,
+    ) -> Result<(), NotNull> {
+        Err(NotNull)
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq, NotNull> {
+        Err(NotNull)
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<Self::SerializeTuple, NotNull> {
+        Err(NotNull)
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleStruct, NotNull> {
+        Err(NotNull)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleVariant, NotNull> {
+        Err(NotNull)
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap, NotNull> {
+        Err(NotNull)
+    }
+
+    fn serialize_struct(self, _: &'static str, _: usize) -> Result<Self::SerializeStruct, NotNull> {
+        Err(NotNull)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStructVariant, NotNull> {
+        Err(NotNull)
+    }
+}
+
+/// Field names already converted to `camelCase`.
+///
+/// Serializers take field names as `&'static str`, so each converted name is
+/// leaked once. Field names come from types in the program, so the set is
+/// bounded.
+static CAMEL_CASE_NAMES: LazyLock<RwLock<HashMap<&'static str, &'static str>>> =
+    LazyLock::new(Default::default);
+
+fn camel_case_name(name: &'static str) -> &'static str {
+    if let Some(camel) = CAMEL_CASE_NAMES.read().unwrap().get(name) {
+        return camel;
+    }
+    let camel = match to_camel_case(name) {
+        Some(camel) => Box::leak(camel.into_boxed_str()),
+        None => name,
+    };
+    CAMEL_CASE_NAMES
+        .write()
+        .unwrap()
+        .entry(name)
+        .or_insert(camel)
+}
+
+/// Converts a `snake_case` key to `camelCase`, or returns `None` if the key
+/// isn't `snake_case`. Leading underscores are kept.
+fn to_camel_case(key: &str) -> Option<String> {
+    let trimmed = key.trim_start_matches('_');
+    let is_snake_case = trimmed.contains('_')
+        && trimmed
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'_');
+    if !is_snake_case {
+        return None;
+    }
+
+    let mut camel = key[..key.len() - trimmed.len()].to_string();
+    for (i, word) in trimmed.split('_').filter(|w| !w.is_empty()).enumerate() {
+        if i == 0 {
+            camel.push_str(word);
+        } else {
+            let mut chars = word.chars();
+            camel.extend(chars.next().map(|c| c.to_ascii_uppercase()));
+            camel.push_str(chars.as_str());
+        }
+    }
+    Some(camel)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn render<T: Serialize>(config: JsonConfig, value: &T) -> String {
+        String::from_utf8(serde_json::to_vec(&Configured { value, config }).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_to_camel_case() {
+        assert_eq!(to_camel_case("user_id").as_deref(), Some("userId"));
+        assert_eq!(
+            to_camel_case("address_line_2").as_deref(),
+            Some("addressLine2")
+        );
+        assert_eq!(
+            to_camel_case("_private_key").as_deref(),
+            Some("_privateKey")
+        );
+        assert_eq!(to_camel_case("name"), None);
+        assert_eq!(to_camel_case("userId"), None);
+        assert_eq!(to_camel_case("USER_ID"), None);
+    }
+
+    #[test]
+    fn test_json_config_applies_to_nested_values() {
+        #[derive(serde::Serialize)]
+        struct User {
+            user_name: &'static str,
+            deleted_at: Option<u64>,
+            tags: Vec<Option<Tag>>,
+        }
+
+        #[derive(serde::Serialize)]
+        struct Tag {
+            tag_name: &'static str,
+            color: Option<&'static str>,
+        }
+
+        let user = User {
+            user_name: "ada",
+            deleted_at: None,
+            tags: vec![
+                Some(Tag {
+                    tag_name: "x",
+                    color: None,
+                }),
+                None,
+            ],
+        };
+        let config = JsonConfig::new().camel_case(true).skip_nulls(true);
+        assert_eq!(
+            render(config, &user),
+            r#"{"userName":"ada","tags":[{"tagName":"x"},null]}"#
+        );
+    }
+
+    #[test]
+    fn test_json_config_keeps_renames_map_keys_and_order() {
+        #[derive(serde::Serialize)]
+        struct Report {
+            zone_id: u64,
+            #[serde(rename = "Display_Name")]
+            display_name: &'static str,
+            by_region: BTreeMap<&'static str, Option<u64>>,
+        }
+
+        let report = Report {
+            zone_id: 1,
+            display_name: "north",
+            by_region: BTreeMap::from([("eu_west", Some(2)), ("us_east", None)]),
+        };
+        let config = JsonConfig::new().camel_case(true).skip_nulls(true);
+        assert_eq!(
+            render(config, &report),
+            r#"{"zoneId":1,"Display_Name":"north","byRegion":{"eu_west":2,"us_east":null}}"#
+        );
+    }
+
+    #[test]
+    fn test_json_config_outside_a_request_is_a_no_op() {
+        let value = serde_json::json!({ "note": null, "user_id": 1 });
+        assert_eq!(
+            JsonConfig::to_vec(&value).unwrap(),
+            br#"{"note":null,"user_id":1}"#
+        );
+    }
+}
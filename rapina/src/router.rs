@@ -7,32 +7,209 @@ use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
 
-use http::{Method, Request, Response, StatusCode};
+use http::{HeaderValue, Method, Request, Response, StatusCode};
 use hyper::body::Incoming;
 
 use crate::error::ErrorVariant;
-use crate::extract::{PathParams, extract_path_params};
-use crate::handler::Handler;
-use crate::introspection::RouteInfo;
-use crate::response::{BoxBody, IntoResponse};
+use crate::extract::{Constraint, PathParams, match_path, parse_pattern, request_host};
+use crate::handler::{Handler, HandlerFn};
+use crate::introspection::{ParamInfo, ResponseInfo, RouteInfo};
+use crate::negotiate::{Serializers, negotiate};
+use crate::pagination::PageLinks;
+use crate::response::{BoxBody, IntoResponse, JsonConfig};
 use crate::state::AppState;
 
 type BoxFuture = Pin<Box<dyn Future<Output = Response<BoxBody>> + Send>>;
-type HandlerFn =
-    Box<dyn Fn(Request<Incoming>, PathParams, Arc<AppState>) -> BoxFuture + Send + Sync>;
+pub(crate) type BoxedHandler =
+    Arc<dyn Fn(Request<Incoming>, PathParams, Arc<AppState>) -> BoxFuture + Send + Sync>;
+
+/// Boxes a handler that takes extractors as arguments.
+pub(crate) fn boxed_handler_fn<H, Args>(handler: H) -> BoxedHandler
+where
+    H: HandlerFn<Args>,
+    Args: 'static,
+{
+    Arc::new(move |req, params, state| handler.call(req, params, state))
+}
+
+/// Methods registered by [`Router::any`].
+const ANY_METHODS: &[Method] = &[
+    Method::GET,
+    Method::POST,
+    Method::PUT,
+    Method::PATCH,
+    Method::DELETE,
+    Method::HEAD,
+    Method::OPTIONS,
+];
 
 pub(crate) struct Route {
     pub(crate) pattern: String,
     pub(crate) handler_name: String,
     pub(crate) response_schema: Option<serde_json::Value>,
     pub(crate) error_responses: Vec<ErrorVariant>,
-    handler: HandlerFn,
+    pub(crate) request_schema: Option<serde_json::Value>,
+    pub(crate) request_example: Option<serde_json::Value>,
+    pub(crate) query_schema: Option<serde_json::Value>,
+    pub(crate) path_schema: Option<serde_json::Value>,
+    pub(crate) responses: Vec<ResponseInfo>,
+    pub(crate) deprecated: bool,
+    pub(crate) hidden: bool,
+    pub(crate) tags: Vec<String>,
+    constraints: Vec<(String, Constraint)>,
+    handler: BoxedHandler,
+}
+
+impl Route {
+    /// Describes the `:param` segments of the pattern.
+    ///
+    /// A struct read by the `Path` extractor gives each parameter the schema
+    /// of its field; any other type describes the pattern's only parameter.
+    fn path_params(&self) -> Vec<ParamInfo> {
+        let names: Vec<&str> = self
+            .pattern
+            .split('/')
+            .filter_map(|s| s.strip_prefix(':'))
+            .collect();
+        let properties = self
+            .path_schema
+            .as_ref()
+            .and_then(|schema| schema.get("properties"))
+            .and_then(|p| p.as_object());
+        let single = names.len() == 1;
+
+        names
+            .into_iter()
+            .map(|name| {
+                let schema = match properties {
+                    Some(properties) => properties.get(name).cloned(),
+                    None if single => self.path_schema.clone(),
+                    None => None,
+                };
+                ParamInfo::new(name, true, schema)
+            })
+            .collect()
+    }
+
+    /// Describes the properties of the query schema as parameters.
+    fn query_params(&self) -> Vec<ParamInfo> {
+        let Some(schema) = &self.query_schema else {
+            return Vec::new();
+        };
+        let Some(properties) = schema.get("properties").and_then(|p| p.as_object()) else {
+            return Vec::new();
+        };
+        let required: Vec<&str> = schema
+            .get("required")
+            .and_then(|r| r.as_array())
+            .map(|r| r.iter().filter_map(|v| v.as_str()).collect())
+            .unwrap_or_default();
+
+        properties
+            .iter()
+            .map(|(name, schema)| {
+                ParamInfo::new(
+                    name.as_str(),
+                    required.contains(&name.as_str()),
+                    Some(schema.clone()),
+                )
+            })
+            .collect()
+    }
+}
+
+/// The route pattern that matched a request (e.g., `/users/:id`).
+///
+/// Inserted into both request and response extensions by the router so
+/// handlers and middleware can label requests by route rather than by raw
+/// path. Handlers read it through the
+/// [`MatchedPath`](crate::extract::MatchedPath) extractor.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatchedRoute(pub(crate) String);
+
+impl MatchedRoute {
+    /// Returns the matched route pattern.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// How the router treats a trailing slash in the request path.
+///
+/// Set with [`Rapina::with_trailing_slash`](crate::app::Rapina::with_trailing_slash).
+/// The root path `/` is never affected.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TrailingSlash {
+    /// `/users` and `/users/` are different routes.
+    #[default]
+    Strict,
+    /// A request for `/users/` is answered with a `308 Permanent Redirect`
+    /// to `/users` when only the form without a slash has a route.
+    RedirectToNonSlash,
+    /// `/users` and `/users/` match the same route.
+    Ignore,
+}
+
+/// A route that never matches because an equally specific route registered
+/// before it, with the same method, takes all of its requests.
+///
+/// Returned by [`Router::validate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShadowedRoute {
+    /// The method both routes are registered for.
+    pub method: Method,
+    /// The pattern of the unreachable route, e.g. `/users/:name`.
+    pub pattern: String,
+    /// The pattern of the earlier route that matches first, e.g. `/users/:id`.
+    pub shadowed_by: String,
+}
+
+impl std::fmt::Display for ShadowedRoute {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} is unreachable: {} {} is registered before it and matches the same requests",
+            self.method, self.pattern, self.method, self.shadowed_by
+        )
+    }
+}
+
+/// Ranks a route for matching: one flag per segment, `true` where the
+/// segment is static, so comparing two ranks finds the route with a static
+/// segment at the first position where they differ.
+fn specificity(route: &Route) -> Vec<bool> {
+    route
+        .pattern
+        .split('/')
+        .map(|segment| !segment.starts_with(':'))
+        .collect()
+}
+
+/// Removes a trailing slash, leaving the root path alone.
+fn trim_trailing_slash(path: &str) -> &str {
+    match path.strip_suffix('/') {
+        Some(trimmed) if !trimmed.is_empty() => trimmed,
+        _ => path,
+    }
 }
 
 /// The HTTP router for matching requests to handlers.
 ///
-/// Routes are matched in the order they are added. Use path parameters
-/// with the `:param` syntax.
+/// Use path parameters with the `:param` syntax, optionally constrained by
+/// a regular expression, `:id(\d+)`, or a type, `:id<u64>`. A segment that
+/// doesn't satisfy the constraint falls through to other routes.
+///
+/// When several routes match a request, the most specific one wins,
+/// whatever order they were added in: segments are compared from left to
+/// right and the first route with a static segment where the other has a
+/// parameter is chosen, so `/users/me` beats `/users/:id` and
+/// `/users/:id/posts` beats `/:kind/:id/posts`. Routes that are equally
+/// specific match in the order they were added.
+///
+/// # Panics
+///
+/// Adding a route panics if a constraint is malformed or names an unknown
+/// type.
 ///
 /// # Examples
 ///
@@ -55,12 +232,43 @@ pub(crate) struct Route {
 /// ```
 pub struct Router {
     pub(crate) routes: Vec<(Method, Route)>,
+    pub(crate) trailing_slash: TrailingSlash,
+    case_insensitive: bool,
+    pub(crate) fallback: Option<BoxedHandler>,
+    pub(crate) method_not_allowed: Option<BoxedHandler>,
+    pub(crate) hosts: Vec<(String, Router)>,
 }
 
 impl Router {
     /// Creates a new empty router.
     pub fn new() -> Self {
-        Self { routes: Vec::new() }
+        Self {
+            routes: Vec::new(),
+            trailing_slash: TrailingSlash::default(),
+            case_insensitive: false,
+            fallback: None,
+            method_not_allowed: None,
+            hosts: Vec::new(),
+        }
+    }
+
+    /// Matches the static segments of every route without regard to case.
+    ///
+    /// `/USERS/42` then matches `/users/:id`, with `id` captured as sent.
+    /// Routing is case-sensitive by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rapina::prelude::*;
+    ///
+    /// let router = Router::new()
+    ///     .case_insensitive(true)
+    ///     .get_fn("/users/:id", |id: Path<u64>| async move { id.into_inner().to_string() });
+    /// ```
+    pub fn case_insensitive(mut self, enabled: bool) -> Self {
+        self.case_insensitive = enabled;
+        self
     }
 
     /// Adds a route with the given HTTP method, pattern, and handler name.
@@ -80,7 +288,7 @@ impl Router {
         Fut: Future<Output = Out> + Send + 'static,
         Out: IntoResponse + 'static,
     {
-        let handler = Box::new(
+        let handler = Arc::new(
             move |req: Request<Incoming>, params: PathParams, state: Arc<AppState>| {
                 let handler = handler.clone();
                 Box::pin(async move {
@@ -90,11 +298,22 @@ impl Router {
             },
         );
 
+        let (pattern, constraints) = parse_pattern(pattern)
+            .unwrap_or_else(|e| panic!("invalid route pattern '{}': {}", pattern, e));
         let route = Route {
-            pattern: pattern.to_string(),
+            pattern,
             handler_name: handler_name.to_string(),
             response_schema,
             error_responses,
+            request_schema: None,
+            request_example: None,
+            query_schema: None,
+            path_schema: None,
+            responses: Vec::new(),
+            deprecated: false,
+            hidden: false,
+            tags: Vec::new(),
+            constraints,
             handler,
         };
 
@@ -151,53 +370,120 @@ impl Router {
 
     /// Adds a GET route with a Handler.
     pub fn get<H: Handler>(self, pattern: &str, handler: H) -> Self {
-        self.route_named(
-            Method::GET,
-            pattern,
-            H::NAME,
-            H::response_schema(),
-            H::error_responses(),
-            move |req, params, state| {
-                let h = handler.clone();
-                async move { h.call(req, params, state).await }
-            },
-        )
+        self.handler_route(Method::GET, pattern, handler)
     }
 
     /// Adds a POST route with a Handler.
     pub fn post<H: Handler>(self, pattern: &str, handler: H) -> Self {
-        self.route_named(
-            Method::POST,
-            pattern,
-            H::NAME,
-            H::response_schema(),
-            H::error_responses(),
-            move |req, params, state| {
-                let h = handler.clone();
-                async move { h.call(req, params, state).await }
-            },
-        )
+        self.handler_route(Method::POST, pattern, handler)
     }
 
     /// Adds a PUT route with a Handler.
     pub fn put<H: Handler>(self, pattern: &str, handler: H) -> Self {
-        self.route_named(
-            Method::PUT,
-            pattern,
-            H::NAME,
-            H::response_schema(),
-            H::error_responses(),
-            move |req, params, state| {
-                let h = handler.clone();
-                async move { h.call(req, params, state).await }
-            },
-        )
+        self.handler_route(Method::PUT, pattern, handler)
     }
 
     /// Adds a DELETE route with a Handler.
     pub fn delete<H: Handler>(self, pattern: &str, handler: H) -> Self {
-        self.route_named(
-            Method::DELETE,
+        self.handler_route(Method::DELETE, pattern, handler)
+    }
+
+    /// Adds a route with a Handler for each of the given methods.
+    ///
+    /// Introspection and OpenAPI list one route per method.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rapina::prelude::*;
+    ///
+    /// #[put("/items/:id")]
+    /// async fn write_item() -> StatusCode { StatusCode::NO_CONTENT }
+    ///
+    /// let router = Router::new().on(&[Method::PUT, Method::PATCH], "/items/:id", write_item);
+    /// assert_eq!(router.routes().len(), 2);
+    /// ```
+    pub fn on<H: Handler>(mut self, methods: &[Method], pattern: &str, handler: H) -> Self {
+        for method in methods {
+            self = self.handler_route(method.clone(), pattern, handler.clone());
+        }
+        self
+    }
+
+    /// Adds a route with a Handler for every standard HTTP method.
+    ///
+    /// Covers GET, POST, PUT, PATCH, DELETE, HEAD and OPTIONS, which suits
+    /// proxy-style endpoints that forward whatever they receive.
+    pub fn any<H: Handler>(self, pattern: &str, handler: H) -> Self {
+        self.on(ANY_METHODS, pattern, handler)
+    }
+
+    /// Adds a route whose handler takes extractors as arguments.
+    ///
+    /// Works like the route macros without needing a named function: the
+    /// arguments are extracted from the request, and any extraction error is
+    /// returned as the response. The handler name defaults to "handler".
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rapina::prelude::*;
+    ///
+    /// let router = Router::new()
+    ///     .route_fn(Method::GET, "/users/:id", |id: Path<u64>| async move {
+    ///         format!("User {}", id.into_inner())
+    ///     });
+    /// ```
+    pub fn route_fn<H, Args>(self, method: Method, pattern: &str, handler: H) -> Self
+    where
+        H: HandlerFn<Args>,
+        Args: 'static,
+    {
+        self.route(method, pattern, move |req, params, state| {
+            handler.call(req, params, state)
+        })
+    }
+
+    /// Adds a GET route whose handler takes extractors as arguments.
+    pub fn get_fn<H, Args>(self, pattern: &str, handler: H) -> Self
+    where
+        H: HandlerFn<Args>,
+        Args: 'static,
+    {
+        self.route_fn(Method::GET, pattern, handler)
+    }
+
+    /// Adds a POST route whose handler takes extractors as arguments.
+    pub fn post_fn<H, Args>(self, pattern: &str, handler: H) -> Self
+    where
+        H: HandlerFn<Args>,
+        Args: 'static,
+    {
+        self.route_fn(Method::POST, pattern, handler)
+    }
+
+    /// Adds a PUT route whose handler takes extractors as arguments.
+    pub fn put_fn<H, Args>(self, pattern: &str, handler: H) -> Self
+    where
+        H: HandlerFn<Args>,
+        Args: 'static,
+    {
+        self.route_fn(Method::PUT, pattern, handler)
+    }
+
+    /// Adds a DELETE route whose handler takes extractors as arguments.
+    pub fn delete_fn<H, Args>(self, pattern: &str, handler: H) -> Self
+    where
+        H: HandlerFn<Args>,
+        Args: 'static,
+    {
+        self.route_fn(Method::DELETE, pattern, handler)
+    }
+
+    /// Adds a route with a Handler, recording its schemas for introspection.
+    fn handler_route<H: Handler>(self, method: Method, pattern: &str, handler: H) -> Self {
+        let mut router = self.route_named(
+            method,
             pattern,
             H::NAME,
             H::response_schema(),
@@ -206,7 +492,63 @@ impl Router {
                 let h = handler.clone();
                 async move { h.call(req, params, state).await }
             },
-        )
+        );
+
+        if let Some((_, route)) = router.routes.last_mut() {
+            route.request_schema = H::request_schema();
+            route.request_example = H::request_example();
+            route.query_schema = H::query_schema();
+            route.path_schema = H::path_schema();
+            route.responses = H::responses();
+            route.deprecated = H::DEPRECATED;
+            route.hidden = H::HIDDEN;
+            route.tags = H::TAGS.iter().map(|t| t.to_string()).collect();
+        }
+        router
+    }
+
+    /// Checks for routes that an earlier route makes unreachable.
+    ///
+    /// Equally specific routes match in the order they are added, so
+    /// registering `/users/:id` before `/users/:name` sends every request to
+    /// the first one. Each such pair is returned, as is a pattern added
+    /// twice. A route whose parameters are constrained doesn't hide later
+    /// routes, since requests it rejects fall through. The app runs this
+    /// check at startup and logs a warning for every conflict.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rapina::prelude::*;
+    ///
+    /// let router = Router::new()
+    ///     .get_named("/users/:id", "get_user", |_, _, _| async { "user" })
+    ///     .get_named("/users/:name", "get_user_by_name", |_, _, _| async { "user" });
+    ///
+    /// let shadowed = router.validate().unwrap_err();
+    /// assert_eq!(shadowed[0].pattern, "/users/:name");
+    /// assert_eq!(shadowed[0].shadowed_by, "/users/:id");
+    /// ```
+    pub fn validate(&self) -> Result<(), Vec<ShadowedRoute>> {
+        let mut shadowed = Vec::new();
+        for (i, (method, route)) in self.routes.iter().enumerate() {
+            let earlier = self.routes[..i]
+                .iter()
+                .find(|(m, r)| m == method && self.covers(r, route));
+            if let Some((_, earlier)) = earlier {
+                shadowed.push(ShadowedRoute {
+                    method: method.clone(),
+                    pattern: route.pattern.clone(),
+                    shadowed_by: earlier.pattern.clone(),
+                });
+            }
+        }
+
+        if shadowed.is_empty() {
+            Ok(())
+        } else {
+            Err(shadowed)
+        }
     }
 
     /// Returns metadata about all registered routes.
@@ -229,19 +571,37 @@ impl Router {
     /// assert_eq!(routes[0].path, "/users");
     /// assert_eq!(routes[0].handler_name, "list_users");
     /// ```
+    ///
+    /// Routes of the routers added with [`host`](Self::host) follow, with
+    /// their host set.
     pub fn routes(&self) -> Vec<RouteInfo> {
-        self.routes
-            .iter()
-            .map(|(method, route)| {
-                RouteInfo::new(
-                    method.as_str(),
-                    &route.pattern,
-                    &route.handler_name,
-                    route.response_schema.clone(),
-                    route.error_responses.clone(),
-                )
-            })
-            .collect()
+        let hosts = self.hosts.iter().flat_map(|(host, router)| {
+            router
+                .own_routes()
+                .map(move |route| route.with_host(host.as_str()))
+        });
+        self.own_routes().chain(hosts).collect()
+    }
+
+    /// Returns metadata about the routes of this router, without its hosts.
+    fn own_routes(&self) -> impl Iterator<Item = RouteInfo> + '_ {
+        self.routes.iter().map(|(method, route)| {
+            RouteInfo::new(
+                method.as_str(),
+                &route.pattern,
+                &route.handler_name,
+                route.response_schema.clone(),
+                route.error_responses.clone(),
+            )
+            .with_path_params(route.path_params())
+            .with_query_params(route.query_params())
+            .with_request_schema(route.request_schema.clone())
+            .with_request_example(route.request_example.clone())
+            .with_responses(route.responses.clone())
+            .with_deprecated(route.deprecated)
+            .with_hidden(route.hidden)
+            .with_tags(route.tags.clone())
+        })
     }
 
     /// Adds all routes from another router with a path prefix to compose a group of endpoints.
@@ -263,32 +623,221 @@ impl Router {
         if !prefix_pattern.starts_with("/") {
             panic!("A group's prefix pattern must start with /");
         }
+        let (prefix_pattern, prefix_constraints) = parse_pattern(prefix_pattern)
+            .unwrap_or_else(|e| panic!("invalid group prefix '{}': {}", prefix_pattern, e));
 
         for (method, mut route) in router.routes {
-            let joined_route_path = Self::join_group_route_pattern(prefix_pattern, &route.pattern);
+            let joined_route_path = Self::join_group_route_pattern(&prefix_pattern, &route.pattern);
             route.pattern = joined_route_path;
+            route.constraints.extend(prefix_constraints.iter().cloned());
             self.routes.push((method, route));
         }
 
         self
     }
 
+    /// Serves requests for another host name with a separate router.
+    ///
+    /// A request whose `Host` matches `host`, ignoring case and port, is
+    /// routed only within `router`. Requests for any other host use the
+    /// routes of this router.
+    ///
+    /// When the app starts, `router` takes this router's fallback,
+    /// `405` handler and trailing slash handling, and matches without
+    /// regard to case if this router does. Its routes are listed by
+    /// [`routes`](Self::routes) with their host.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rapina::prelude::*;
+    ///
+    /// let api = Router::new().get_named("/", "api_root", |_, _, _| async { "api" });
+    ///
+    /// let router = Router::new()
+    ///     .get_named("/", "home", |_, _, _| async { "home" })
+    ///     .host("api.example.com", api);
+    /// ```
+    pub fn host(mut self, host: &str, router: Router) -> Self {
+        self.hosts.push((host.to_ascii_lowercase(), router));
+        self
+    }
+
+    /// Passes this router's fallback, `405` handler, trailing slash
+    /// handling and case-insensitivity on to its host routers.
+    pub(crate) fn share_settings_with_hosts(&mut self) {
+        for (_, router) in &mut self.hosts {
+            router.trailing_slash = self.trailing_slash;
+            router.case_insensitive |= self.case_insensitive;
+            if router.fallback.is_none() {
+                router.fallback = self.fallback.clone();
+            }
+            if router.method_not_allowed.is_none() {
+                router.method_not_allowed = self.method_not_allowed.clone();
+            }
+        }
+    }
+
     /// Handles an incoming request by matching it to a route.
     pub async fn handle(&self, req: Request<Incoming>, state: &Arc<AppState>) -> Response<BoxBody> {
+        let router = request_host(req.headers(), req.uri())
+            .and_then(|host| {
+                self.hosts
+                    .iter()
+                    .find(|(name, _)| name.eq_ignore_ascii_case(host))
+            })
+            .map_or(self, |(_, router)| router);
+        router.dispatch(req, state).await
+    }
+
+    /// Routes a request within this router, ignoring its hosts.
+    async fn dispatch(&self, req: Request<Incoming>, state: &Arc<AppState>) -> Response<BoxBody> {
         let method = req.method().clone();
         let path = req.uri().path().to_string();
 
-        for (route_method, route) in &self.routes {
-            if *route_method != method {
+        if let Some((route, params)) = self.find(&method, &path) {
+            let accept = req.headers().get(http::header::ACCEPT).cloned();
+            let uri = req.uri().clone();
+            let mut req = req;
+            req.extensions_mut()
+                .insert(MatchedRoute(route.pattern.clone()));
+            let handler = (route.handler)(req, params, state.clone());
+            let response = match state.get_or_init::<JsonConfig>().await {
+                Some(config) => config.scope(handler).await,
+                None => handler.await,
+            };
+            let mut response = negotiate(response, accept.as_ref(), state.get::<Serializers>());
+            if let Some(links) = response.extensions_mut().remove::<PageLinks>() {
+                links.apply(&uri, &mut response);
+            }
+            response
+                .extensions_mut()
+                .insert(MatchedRoute(route.pattern.clone()));
+            return response;
+        }
+
+        if self.trailing_slash == TrailingSlash::RedirectToNonSlash {
+            let canonical = trim_trailing_slash(&path);
+            // Browsers read `//host` and `/\host` as another site, so never
+            // redirect there, e.g. `//evil.com/` matching `/:org/:repo`
+            let off_site = canonical.starts_with("//") || canonical.starts_with("/\\");
+            if canonical != path && !off_site && self.find(&method, canonical).is_some() {
+                let location = match req.uri().query() {
+                    Some(query) => format!("{}?{}", canonical, query),
+                    None => canonical.to_string(),
+                };
+                let mut response = StatusCode::PERMANENT_REDIRECT.into_response();
+                if let Ok(value) = HeaderValue::from_str(&location) {
+                    response.headers_mut().insert(http::header::LOCATION, value);
+                }
+                return response;
+            }
+        }
+
+        if let Some(handler) = &self.method_not_allowed {
+            let allowed = self.allowed_methods(&path);
+            if !allowed.is_empty() {
+                let mut response = handler(req, PathParams::new(), state.clone()).await;
+                let allow = allowed
+                    .iter()
+                    .map(Method::as_str)
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                if let Ok(value) = HeaderValue::from_str(&allow) {
+                    response
+                        .headers_mut()
+                        .entry(http::header::ALLOW)
+                        .or_insert(value);
+                }
+                return response;
+            }
+        }
+
+        match &self.fallback {
+            Some(handler) => handler(req, PathParams::new(), state.clone()).await,
+            None => StatusCode::NOT_FOUND.into_response(),
+        }
+    }
+
+    /// Finds the most specific route for `method` whose pattern matches
+    /// `path`, preferring the first one added among equals.
+    fn find(&self, method: &Method, path: &str) -> Option<(&Route, PathParams)> {
+        let mut best: Option<(&Route, PathParams)> = None;
+        for (_, route) in self.routes.iter().filter(|(m, _)| m == method) {
+            let Some(params) = self.match_route(route, path) else {
                 continue;
+            };
+            if best
+                .as_ref()
+                .is_none_or(|(current, _)| specificity(route) > specificity(current))
+            {
+                best = Some((route, params));
             }
+        }
+        best
+    }
+
+    /// Returns whether `earlier` matches every request `later` matches and
+    /// is as specific, so `later` is never chosen.
+    fn covers(&self, earlier: &Route, later: &Route) -> bool {
+        let (earlier_pattern, later_pattern) = if self.trailing_slash == TrailingSlash::Ignore {
+            (
+                trim_trailing_slash(&earlier.pattern),
+                trim_trailing_slash(&later.pattern),
+            )
+        } else {
+            (earlier.pattern.as_str(), later.pattern.as_str())
+        };
+        let earlier_segments: Vec<&str> = earlier_pattern.split('/').collect();
+        let later_segments: Vec<&str> = later_pattern.split('/').collect();
+        if earlier_segments.len() != later_segments.len() {
+            return false;
+        }
 
-            if let Some(params) = extract_path_params(&route.pattern, &path) {
-                return (route.handler)(req, params, state.clone()).await;
+        earlier_segments.iter().zip(&later_segments).all(|(a, b)| {
+            match (a.strip_prefix(':'), b.strip_prefix(':')) {
+                (Some(name), Some(_)) => !earlier.constraints.iter().any(|(n, _)| n == name),
+                (None, None) => a == b || self.case_insensitive && a.eq_ignore_ascii_case(b),
+                _ => false,
+            }
+        })
+    }
+
+    /// Returns the methods of every route whose pattern matches `path`.
+    fn allowed_methods(&self, path: &str) -> Vec<Method> {
+        let mut methods: Vec<Method> = Vec::new();
+        for (method, route) in &self.routes {
+            if !methods.contains(method) && self.match_route(route, path).is_some() {
+                methods.push(method.clone());
             }
         }
+        methods
+    }
+
+    /// Matches `path` against one route, applying the router's matching options.
+    fn match_route(&self, route: &Route, path: &str) -> Option<PathParams> {
+        self.match_pattern(&route.pattern, &route.constraints, path)
+    }
 
-        StatusCode::NOT_FOUND.into_response()
+    /// Matches `path` against a parsed pattern the way a route would be
+    /// matched, so middleware that looks up routes agrees with the router on
+    /// trailing slashes and case.
+    pub(crate) fn match_pattern(
+        &self,
+        pattern: &str,
+        constraints: &[(String, Constraint)],
+        path: &str,
+    ) -> Option<PathParams> {
+        if self.trailing_slash == TrailingSlash::Ignore {
+            match_path(
+                trim_trailing_slash(pattern),
+                trim_trailing_slash(path),
+                self.case_insensitive,
+                constraints,
+            )
+        } else {
+            match_path(pattern, path, self.case_insensitive, constraints)
+        }
     }
 
     fn join_group_route_pattern(prefix: &str, route_path: &str) -> String {
@@ -315,6 +864,75 @@ impl Default for Router {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_validate_flags_route_shadowed_by_equally_specific_route() {
+        let router = Router::new()
+            .route(Method::GET, "/users/:id", |_, _, _| async { "user" })
+            .route(Method::POST, "/users/:name", |_, _, _| async { "post" })
+            .route(Method::GET, "/users/:name", |_, _, _| async { "name" });
+
+        let shadowed = router.validate().unwrap_err();
+        assert_eq!(
+            shadowed,
+            vec![ShadowedRoute {
+                method: Method::GET,
+                pattern: "/users/:name".to_string(),
+                shadowed_by: "/users/:id".to_string(),
+            }]
+        );
+        assert_eq!(
+            shadowed[0].to_string(),
+            "GET /users/:name is unreachable: GET /users/:id is registered before it and matches the same requests"
+        );
+    }
+
+    #[test]
+    fn test_validate_accepts_static_route_in_any_order() {
+        let router = Router::new()
+            .route(Method::GET, "/users/:id", |_, _, _| async { "user" })
+            .route(Method::GET, "/users/me", |_, _, _| async { "me" });
+        assert!(router.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_respects_constraints() {
+        let router = Router::new()
+            .route(Method::GET, "/users/:id<u64>", |_, _, _| async { "user" })
+            .route(Method::GET, "/users/:name", |_, _, _| async { "name" });
+        assert!(router.validate().is_ok());
+    }
+
+    #[test]
+    fn test_find_prefers_static_segments() {
+        for patterns in [["/users/me", "/users/:id"], ["/users/:id", "/users/me"]] {
+            let router = patterns.iter().fold(Router::new(), |router, pattern| {
+                router.route(Method::GET, pattern, |_, _, _| async { "" })
+            });
+            let (route, _) = router.find(&Method::GET, "/users/me").unwrap();
+            assert_eq!(route.pattern, "/users/me");
+            let (route, _) = router.find(&Method::GET, "/users/42").unwrap();
+            assert_eq!(route.pattern, "/users/:id");
+        }
+    }
+
+    #[test]
+    fn test_find_compares_segments_left_to_right() {
+        let router = Router::new()
+            .route(Method::GET, "/:org/repos/:repo", |_, _, _| async { "" })
+            .route(Method::GET, "/users/:id/:tab", |_, _, _| async { "" });
+        let (route, _) = router.find(&Method::GET, "/users/repos/x").unwrap();
+        assert_eq!(route.pattern, "/users/:id/:tab");
+    }
+
+    #[test]
+    fn test_validate_flags_duplicate_static_route() {
+        let router = Router::new()
+            .route(Method::GET, "/health", |_, _, _| async { "a" })
+            .route(Method::GET, "/health", |_, _, _| async { "b" });
+        let shadowed = router.validate().unwrap_err();
+        assert_eq!(shadowed[0].shadowed_by, "/health");
+    }
+
     #[test]
     fn test_router_new() {
         let router = Router::new();
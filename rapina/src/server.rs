@@ -1,39 +1,279 @@
+//! The HTTP server that runs a [`Rapina`](crate::app::Rapina) application.
+
+use std::future::Future;
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
 
 use hyper::Request;
 use hyper::body::Incoming;
-use hyper::server::conn::http1;
 use hyper::service::service_fn;
-use hyper_util::rt::TokioIo;
-use tokio::net::TcpListener;
+use hyper_util::rt::{TokioExecutor, TokioIo, TokioTimer};
+use hyper_util::server::conn::auto;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::{TcpListener, TcpStream};
+#[cfg(unix)]
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::Semaphore;
 
 use crate::context::RequestContext;
+use crate::extract::ConnectInfo;
 use crate::middleware::MiddlewareStack;
 use crate::router::Router;
 use crate::state::AppState;
 
+#[cfg(feature = "tls")]
+mod tls;
+
+#[cfg(feature = "tls")]
+pub(crate) use tls::TlsListener;
+
+const DEFAULT_HEADER_READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// HTTP versions the server accepts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HttpProtocol {
+    /// HTTP/1.1 only.
+    #[default]
+    Http1,
+    /// HTTP/2 only. Plaintext clients must use prior knowledge (h2c).
+    Http2,
+    /// HTTP/1.1 and HTTP/2. Over TLS the version is negotiated with ALPN;
+    /// on plaintext connections HTTP/2 is detected from the client's
+    /// connection preface.
+    Auto,
+}
+
+/// Connection-level settings for the HTTP server.
+///
+/// The defaults serve HTTP/1.1 only, keep connections alive, close
+/// connections that take more than 30 seconds to send request headers, and
+/// accept any number of concurrent connections.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use std::time::Duration;
+///
+/// use rapina::prelude::*;
+/// use rapina::server::ServerConfig;
+///
+/// # async fn run(router: Router) -> std::io::Result<()> {
+/// Rapina::new()
+///     .with_server_config(
+///         ServerConfig::new()
+///             .header_read_timeout(Duration::from_secs(10))
+///             .max_connections(10_000),
+///     )
+///     .router(router)
+///     .listen("127.0.0.1:3000")
+///     .await
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ServerConfig {
+    /// HTTP versions the server accepts.
+    pub protocol: HttpProtocol,
+    /// Whether HTTP/1.1 connections are kept alive between requests.
+    pub keep_alive: bool,
+    /// How long a client may take to send request headers.
+    ///
+    /// The timer also runs while a kept-alive connection waits for its next
+    /// request, so this doubles as the idle keep-alive timeout, and it bounds
+    /// the TLS handshake of new connections. `None` disables it.
+    pub header_read_timeout: Option<Duration>,
+    /// Maximum number of connections served at once.
+    ///
+    /// When reached, new connections wait in the listen backlog until one
+    /// closes. `None` means unlimited.
+    pub max_connections: Option<usize>,
+}
+
+impl ServerConfig {
+    /// Creates a config with the default settings.
+    pub fn new() -> Self {
+        Self {
+            protocol: HttpProtocol::Http1,
+            keep_alive: true,
+            header_read_timeout: Some(DEFAULT_HEADER_READ_TIMEOUT),
+            max_connections: None,
+        }
+    }
+
+    /// Sets the HTTP versions the server accepts.
+    pub fn protocol(mut self, protocol: HttpProtocol) -> Self {
+        self.protocol = protocol;
+        self
+    }
+
+    /// Sets whether connections are kept alive between requests.
+    pub fn keep_alive(mut self, keep_alive: bool) -> Self {
+        self.keep_alive = keep_alive;
+        self
+    }
+
+    /// Sets how long a client may take to send request headers, which also
+    /// bounds how long an idle kept-alive connection stays open.
+    pub fn header_read_timeout(mut self, timeout: Duration) -> Self {
+        self.header_read_timeout = Some(timeout);
+        self
+    }
+
+    /// Disables the header read timeout.
+    pub fn without_header_read_timeout(mut self) -> Self {
+        self.header_read_timeout = None;
+        self
+    }
+
+    /// Caps the number of connections served at once.
+    pub fn max_connections(mut self, max: usize) -> Self {
+        self.max_connections = Some(max);
+        self
+    }
+
+    /// Builds the hyper connection builder for this config.
+    fn connection_builder(&self) -> auto::Builder<TokioExecutor> {
+        let mut builder = auto::Builder::new(TokioExecutor::new());
+        builder
+            .http1()
+            .timer(TokioTimer::new())
+            .keep_alive(self.keep_alive)
+            .header_read_timeout(self.header_read_timeout);
+        builder.http2().timer(TokioTimer::new());
+
+        match self.protocol {
+            HttpProtocol::Http1 => builder.http1_only(),
+            HttpProtocol::Http2 => builder.http2_only(),
+            HttpProtocol::Auto => builder,
+        }
+    }
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 pub async fn serve(
     router: Router,
     state: AppState,
     middlewares: MiddlewareStack,
     addr: SocketAddr,
+    config: ServerConfig,
 ) -> std::io::Result<()> {
-    let router = Arc::new(router);
-    let state = Arc::new(state);
-    let middlewares = Arc::new(middlewares);
     let listener = TcpListener::bind(addr).await?;
 
     println!("Rapina listening on http://{}", addr);
 
+    serve_listener(listener, router, state, middlewares, config).await
+}
+
+/// A source of client connections.
+pub(crate) trait Listener: Send + 'static {
+    /// The connection returned by `accept`.
+    type Stream: Send + 'static;
+    /// The transport HTTP is served over once the handshake completes.
+    type Io: AsyncRead + AsyncWrite + Unpin + Send + 'static;
+
+    /// Waits for the next connection, returning it with the client's address
+    /// when the transport has one.
+    fn accept(
+        &self,
+    ) -> impl Future<Output = std::io::Result<(Self::Stream, Option<SocketAddr>)>> + Send;
+
+    /// Prepares an accepted connection for serving, e.g. by running a TLS handshake.
+    ///
+    /// Runs on the connection's own task so slow clients don't block `accept`.
+    fn handshake(
+        &self,
+        stream: Self::Stream,
+    ) -> impl Future<Output = std::io::Result<Self::Io>> + Send + 'static;
+}
+
+impl Listener for TcpListener {
+    type Stream = TcpStream;
+    type Io = TcpStream;
+
+    async fn accept(&self) -> std::io::Result<(TcpStream, Option<SocketAddr>)> {
+        TcpListener::accept(self)
+            .await
+            .map(|(stream, addr)| (stream, Some(addr)))
+    }
+
+    fn handshake(
+        &self,
+        stream: TcpStream,
+    ) -> impl Future<Output = std::io::Result<TcpStream>> + Send + 'static {
+        std::future::ready(Ok(stream))
+    }
+}
+
+#[cfg(unix)]
+impl Listener for UnixListener {
+    type Stream = UnixStream;
+    type Io = UnixStream;
+
+    async fn accept(&self) -> std::io::Result<(UnixStream, Option<SocketAddr>)> {
+        UnixListener::accept(self)
+            .await
+            .map(|(stream, _)| (stream, None))
+    }
+
+    fn handshake(
+        &self,
+        stream: UnixStream,
+    ) -> impl Future<Output = std::io::Result<UnixStream>> + Send + 'static {
+        std::future::ready(Ok(stream))
+    }
+}
+
+/// Serves connections accepted from an already bound listener.
+pub(crate) async fn serve_listener<L: Listener>(
+    listener: L,
+    router: Router,
+    state: AppState,
+    middlewares: MiddlewareStack,
+    config: ServerConfig,
+) -> std::io::Result<()> {
+    let router = Arc::new(router);
+    let state = Arc::new(state);
+    let middlewares = Arc::new(middlewares);
+    let builder = Arc::new(config.connection_builder());
+    let connection_limit = config
+        .max_connections
+        .map(|max| Arc::new(Semaphore::new(max)));
+    let handshake_timeout = config.header_read_timeout;
+
     loop {
-        let (stream, _) = listener.accept().await?;
-        let io = TokioIo::new(stream);
+        // Wait for a free slot before accepting, so excess clients queue in the backlog
+        let permit = match &connection_limit {
+            Some(limit) => Some(
+                limit
+                    .clone()
+                    .acquire_owned()
+                    .await
+                    .expect("connection semaphore is never closed"),
+            ),
+            None => None,
+        };
+
+        let (stream, remote_addr) = listener.accept().await?;
+        let handshake = listener.handshake(stream);
         let router = router.clone();
         let state = state.clone();
         let middlewares = middlewares.clone();
+        let builder = builder.clone();
 
         tokio::spawn(async move {
+            let _permit = permit;
+            let io = match complete_handshake(handshake, handshake_timeout).await {
+                Ok(io) => TokioIo::new(io),
+                Err(e) => {
+                    eprintln!("connection error: {}", e);
+                    return;
+                }
+            };
             let service = service_fn(move |mut req: Request<Incoming>| {
                 let router = router.clone();
                 let state = state.clone();
@@ -42,6 +282,9 @@ pub async fn serve(
                 // Create and inject RequestContext at request start
                 let ctx = RequestContext::new();
                 req.extensions_mut().insert(ctx.clone());
+                if let Some(addr) = remote_addr {
+                    req.extensions_mut().insert(ConnectInfo(addr));
+                }
 
                 async move {
                     let response = middlewares.execute(req, &router, &state, &ctx).await;
@@ -49,9 +292,216 @@ pub async fn serve(
                 }
             });
 
-            if let Err(e) = http1::Builder::new().serve_connection(io, service).await {
+            if let Err(e) = builder.serve_connection(io, service).await {
                 eprintln!("connection error: {}", e);
             }
         });
     }
 }
+
+/// Waits for a connection's handshake, giving up after `timeout` so a client
+/// that stalls mid-handshake doesn't hold a connection slot.
+async fn complete_handshake<T>(
+    handshake: impl Future<Output = std::io::Result<T>>,
+    timeout: Option<Duration>,
+) -> std::io::Result<T> {
+    let Some(timeout) = timeout else {
+        return handshake.await;
+    };
+    tokio::time::timeout(timeout, handshake)
+        .await
+        .unwrap_or_else(|_| {
+            Err(std::io::Error::new(
+                std::io::ErrorKind::TimedOut,
+                "handshake timed out",
+            ))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpStream;
+
+    async fn start(config: ServerConfig) -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let router = Router::new().route(http::Method::GET, "/", |_, _, _| async { "ok" });
+        tokio::spawn(serve_listener(
+            listener,
+            router,
+            AppState::new(),
+            MiddlewareStack::new(),
+            config,
+        ));
+        addr
+    }
+
+    async fn get(stream: &mut TcpStream) -> String {
+        stream
+            .write_all(b"GET / HTTP/1.1\r\nhost: localhost\r\n\r\n")
+            .await
+            .unwrap();
+        let mut buf = [0u8; 1024];
+        let n = stream.read(&mut buf).await.unwrap();
+        String::from_utf8_lossy(&buf[..n]).to_string()
+    }
+
+    #[test]
+    fn test_config_defaults() {
+        let config = ServerConfig::default();
+        assert_eq!(config.protocol, HttpProtocol::Http1);
+        assert!(config.keep_alive);
+        assert_eq!(config.header_read_timeout, Some(Duration::from_secs(30)));
+        assert_eq!(config.max_connections, None);
+    }
+
+    #[test]
+    fn test_config_builders() {
+        let config = ServerConfig::new()
+            .keep_alive(false)
+            .header_read_timeout(Duration::from_secs(5))
+            .max_connections(100);
+        assert!(!config.keep_alive);
+        assert_eq!(config.header_read_timeout, Some(Duration::from_secs(5)));
+        assert_eq!(config.max_connections, Some(100));
+
+        let config = config.without_header_read_timeout();
+        assert_eq!(config.header_read_timeout, None);
+    }
+
+    #[test]
+    fn test_connection_builder_uses_config() {
+        let config = ServerConfig::new()
+            .keep_alive(false)
+            .header_read_timeout(Duration::from_secs(7));
+        let builder = config.connection_builder();
+        assert!(builder.is_http1_available());
+        assert!(!builder.is_http2_available());
+
+        let builder = format!("{:?}", builder);
+
+        assert!(builder.contains("h1_keep_alive: false"), "{}", builder);
+        assert!(builder.contains("7s"), "{}", builder);
+    }
+
+    #[tokio::test]
+    async fn test_header_read_timeout_closes_idle_connection() {
+        let addr = start(ServerConfig::new().header_read_timeout(Duration::from_millis(100))).await;
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+
+        assert!(get(&mut stream).await.starts_with("HTTP/1.1 200"));
+
+        // The kept-alive connection is closed once it idles past the timeout
+        let mut buf = [0u8; 64];
+        let read = tokio::time::timeout(Duration::from_secs(2), stream.read(&mut buf))
+            .await
+            .expect("connection should be closed by the server");
+        assert_eq!(read.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_keep_alive_disabled_closes_after_response() {
+        let addr = start(ServerConfig::new().keep_alive(false)).await;
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+
+        let response = get(&mut stream).await;
+        assert!(response.starts_with("HTTP/1.1 200"));
+        assert!(response.contains("connection: close"));
+    }
+
+    #[tokio::test]
+    async fn test_max_connections_queues_excess_clients() {
+        let addr = start(ServerConfig::new().max_connections(1)).await;
+
+        let mut first = TcpStream::connect(addr).await.unwrap();
+        assert!(get(&mut first).await.starts_with("HTTP/1.1 200"));
+
+        // The second connection sits in the backlog while the first is open
+        let mut second = TcpStream::connect(addr).await.unwrap();
+        second
+            .write_all(b"GET / HTTP/1.1\r\nhost: localhost\r\n\r\n")
+            .await
+            .unwrap();
+        let mut buf = [0u8; 1024];
+        let blocked = tokio::time::timeout(Duration::from_millis(200), second.read(&mut buf)).await;
+        assert!(blocked.is_err());
+
+        drop(first);
+        let n = tokio::time::timeout(Duration::from_secs(2), second.read(&mut buf))
+            .await
+            .expect("second connection should be served once the first closes")
+            .unwrap();
+        let response = String::from_utf8_lossy(&buf[..n]);
+        assert!(response.starts_with("HTTP/1.1 200"));
+    }
+
+    async fn h2_get(addr: SocketAddr) -> (http::Version, String) {
+        use http_body_util::{BodyExt, Empty};
+
+        let stream = TcpStream::connect(addr).await.unwrap();
+        let (mut sender, conn) =
+            hyper::client::conn::http2::handshake(TokioExecutor::new(), TokioIo::new(stream))
+                .await
+                .unwrap();
+        tokio::spawn(conn);
+
+        let req = Request::builder()
+            .uri(format!("http://{}/", addr))
+            .body(Empty::<bytes::Bytes>::new())
+            .unwrap();
+        let response = sender.send_request(req).await.unwrap();
+        let version = response.version();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        (version, String::from_utf8_lossy(&body).to_string())
+    }
+
+    #[test]
+    fn test_connection_builder_protocols() {
+        let builder = ServerConfig::new()
+            .protocol(HttpProtocol::Http2)
+            .connection_builder();
+        assert!(!builder.is_http1_available());
+        assert!(builder.is_http2_available());
+
+        let builder = ServerConfig::new()
+            .protocol(HttpProtocol::Auto)
+            .connection_builder();
+        assert!(builder.is_http1_available());
+        assert!(builder.is_http2_available());
+    }
+
+    #[tokio::test]
+    async fn test_http2_prior_knowledge() {
+        let addr = start(ServerConfig::new().protocol(HttpProtocol::Http2)).await;
+
+        let (version, body) = h2_get(addr).await;
+        assert_eq!(version, http::Version::HTTP_2);
+        assert_eq!(body, "ok");
+    }
+
+    #[tokio::test]
+    async fn test_auto_serves_both_versions() {
+        let addr = start(ServerConfig::new().protocol(HttpProtocol::Auto)).await;
+
+        let (version, body) = h2_get(addr).await;
+        assert_eq!(version, http::Version::HTTP_2);
+        assert_eq!(body, "ok");
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        assert!(get(&mut stream).await.starts_with("HTTP/1.1 200"));
+    }
+
+    #[tokio::test]
+    async fn test_handshake_times_out() {
+        let stalled = std::future::pending::<std::io::Result<()>>();
+        let err = complete_handshake(stalled, Some(Duration::from_millis(10)))
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::TimedOut);
+
+        let ready = std::future::ready(Ok(1));
+        assert_eq!(complete_handshake(ready, None).await.unwrap(), 1);
+    }
+}
@@ -0,0 +1,197 @@
+//! TLS termination with rustls.
+//!
+//! Enabled with the `tls` feature. Certificates and keys are loaded from PEM
+//! files when the server starts; reloading them requires a restart.
+
+use std::future::Future;
+use std::io;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::Arc;
+
+use tokio::net::{TcpListener, TcpStream};
+use tokio_rustls::TlsAcceptor;
+use tokio_rustls::rustls;
+use tokio_rustls::rustls::pki_types::pem::PemObject;
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use tokio_rustls::server::TlsStream;
+
+use super::{HttpProtocol, Listener};
+
+/// A TCP listener that performs a TLS handshake on each connection.
+pub(crate) struct TlsListener {
+    listener: TcpListener,
+    acceptor: TlsAcceptor,
+}
+
+impl TlsListener {
+    /// Wraps a listener, loading the certificate chain and private key from PEM files.
+    ///
+    /// The ALPN protocols offered to clients follow `protocol`, so HTTP/2 is
+    /// negotiated during the handshake when enabled.
+    pub(crate) fn new(
+        listener: TcpListener,
+        cert_pem: &Path,
+        key_pem: &Path,
+        protocol: HttpProtocol,
+    ) -> io::Result<Self> {
+        let config = server_config(cert_pem, key_pem, protocol)?;
+        Ok(Self {
+            listener,
+            acceptor: TlsAcceptor::from(Arc::new(config)),
+        })
+    }
+}
+
+impl Listener for TlsListener {
+    type Stream = TcpStream;
+    type Io = TlsStream<TcpStream>;
+
+    async fn accept(&self) -> io::Result<(TcpStream, Option<SocketAddr>)> {
+        self.listener
+            .accept()
+            .await
+            .map(|(stream, addr)| (stream, Some(addr)))
+    }
+
+    fn handshake(
+        &self,
+        stream: TcpStream,
+    ) -> impl Future<Output = io::Result<TlsStream<TcpStream>>> + Send + 'static {
+        self.acceptor.accept(stream)
+    }
+}
+
+/// Builds the rustls server config from PEM files.
+fn server_config(
+    cert_pem: &Path,
+    key_pem: &Path,
+    protocol: HttpProtocol,
+) -> io::Result<rustls::ServerConfig> {
+    let certs = CertificateDer::pem_file_iter(cert_pem)
+        .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| {
+            invalid_data(format!(
+                "failed to read certificates from {}: {}",
+                cert_pem.display(),
+                e
+            ))
+        })?;
+    if certs.is_empty() {
+        return Err(invalid_data(format!(
+            "no certificates found in {}",
+            cert_pem.display()
+        )));
+    }
+
+    let key = PrivateKeyDer::from_pem_file(key_pem).map_err(|e| {
+        invalid_data(format!(
+            "failed to read private key from {}: {}",
+            key_pem.display(),
+            e
+        ))
+    })?;
+
+    // Pick the provider explicitly so it doesn't depend on which rustls
+    // backends other dependencies enable
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let mut config = rustls::ServerConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()
+        .map_err(|e| invalid_data(format!("invalid TLS configuration: {}", e)))?
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .map_err(|e| {
+            invalid_data(format!(
+                "private key in {} does not match the certificate in {}: {}",
+                key_pem.display(),
+                cert_pem.display(),
+                e
+            ))
+        })?;
+
+    config.alpn_protocols = match protocol {
+        HttpProtocol::Http1 => vec![b"http/1.1".to_vec()],
+        HttpProtocol::Http2 => vec![b"h2".to_vec()],
+        HttpProtocol::Auto => vec![b"h2".to_vec(), b"http/1.1".to_vec()],
+    };
+    Ok(config)
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/tls");
+
+    fn fixture(name: &str) -> std::path::PathBuf {
+        Path::new(FIXTURES).join(name)
+    }
+
+    #[test]
+    fn test_server_config_loads_pem_files() {
+        let config = server_config(
+            &fixture("cert.pem"),
+            &fixture("key.pem"),
+            HttpProtocol::Auto,
+        )
+        .unwrap();
+        assert_eq!(
+            config.alpn_protocols,
+            vec![b"h2".to_vec(), b"http/1.1".to_vec()]
+        );
+    }
+
+    #[test]
+    fn test_server_config_alpn_follows_protocol() {
+        let config = server_config(
+            &fixture("cert.pem"),
+            &fixture("key.pem"),
+            HttpProtocol::Http1,
+        )
+        .unwrap();
+        assert_eq!(config.alpn_protocols, vec![b"http/1.1".to_vec()]);
+    }
+
+    #[test]
+    fn test_server_config_missing_file() {
+        let err = server_config(
+            &fixture("missing.pem"),
+            &fixture("key.pem"),
+            HttpProtocol::Http1,
+        )
+        .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(err.to_string().contains("missing.pem"), "{}", err);
+    }
+
+    #[test]
+    fn test_server_config_no_private_key() {
+        // A certificate file contains no private key
+        let err = server_config(
+            &fixture("cert.pem"),
+            &fixture("cert.pem"),
+            HttpProtocol::Http1,
+        )
+        .unwrap_err();
+        assert!(
+            err.to_string().contains("failed to read private key"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_server_config_mismatched_key() {
+        let err = server_config(
+            &fixture("cert.pem"),
+            &fixture("other-key.pem"),
+            HttpProtocol::Http1,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("does not match"), "{}", err);
+    }
+}
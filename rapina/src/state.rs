@@ -2,10 +2,22 @@
 
 use std::any::{Any, TypeId};
 use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
 use std::sync::Arc;
 
+use tokio::sync::OnceCell;
+
 type StateMap = HashMap<TypeId, Arc<dyn Any + Send + Sync>>;
 
+type Initializer<T> = Box<dyn Fn() -> Pin<Box<dyn Future<Output = T> + Send>> + Send + Sync>;
+
+/// A value built by its initializer on first access.
+struct Lazy<T> {
+    cell: OnceCell<T>,
+    init: Initializer<T>,
+}
+
 /// A type-safe container for sharing state across request handlers.
 ///
 /// `AppState` stores values indexed by their [`TypeId`], allowing
@@ -44,6 +56,7 @@ type StateMap = HashMap<TypeId, Arc<dyn Any + Send + Sync>>;
 #[derive(Default, Clone)]
 pub struct AppState {
     inner: StateMap,
+    lazy: StateMap,
 }
 
 impl AppState {
@@ -51,6 +64,7 @@ impl AppState {
     pub fn new() -> Self {
         Self {
             inner: HashMap::new(),
+            lazy: HashMap::new(),
         }
     }
 
@@ -58,17 +72,90 @@ impl AppState {
     ///
     /// If a value of the same type already exists, it will be overwritten.
     pub fn with<T: Send + Sync + 'static>(mut self, value: T) -> Self {
+        self.lazy.remove(&TypeId::of::<T>());
         self.inner.insert(TypeId::of::<T>(), Arc::new(value));
         self
     }
 
+    /// Registers an async initializer for a value of type `T`.
+    ///
+    /// The initializer runs once, on the first [`get_or_init`](Self::get_or_init),
+    /// and every later access shares its result. Concurrent first accesses
+    /// wait for the same run. Clones of the state share the value too.
+    ///
+    /// If a value of the same type already exists, it will be overwritten.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rapina::state::AppState;
+    ///
+    /// struct HttpClient {
+    ///     base_url: String,
+    /// }
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let state = AppState::new().with_lazy(|| async {
+    ///     HttpClient {
+    ///         base_url: "https://api.example.com".to_string(),
+    ///     }
+    /// });
+    ///
+    /// // Not built until first access
+    /// assert!(state.get::<HttpClient>().is_none());
+    ///
+    /// let client = state.get_or_init::<HttpClient>().await.unwrap();
+    /// assert_eq!(client.base_url, "https://api.example.com");
+    /// # });
+    /// ```
+    pub fn with_lazy<T, F, Fut>(mut self, init: F) -> Self
+    where
+        T: Send + Sync + 'static,
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = T> + Send + 'static,
+    {
+        let lazy = Lazy {
+            cell: OnceCell::new(),
+            init: Box::new(move || Box::pin(init())),
+        };
+        self.inner.remove(&TypeId::of::<T>());
+        self.lazy.insert(TypeId::of::<T>(), Arc::new(lazy));
+        self
+    }
+
+    /// Returns `true` if a value of type `T` has been registered, eagerly or
+    /// lazily.
+    pub fn contains<T: Send + Sync + 'static>(&self) -> bool {
+        self.inner.contains_key(&TypeId::of::<T>()) || self.lazy.contains_key(&TypeId::of::<T>())
+    }
+
     /// Retrieves a reference to a value of type `T`, if registered.
     ///
-    /// Returns `None` if no value of type `T` has been added.
+    /// Returns `None` if no value of type `T` has been added, or if it was
+    /// added with [`with_lazy`](Self::with_lazy) and hasn't been built yet.
     pub fn get<T: Send + Sync + 'static>(&self) -> Option<&T> {
         self.inner
             .get(&TypeId::of::<T>())
             .and_then(|arc| arc.downcast_ref::<T>())
+            .or_else(|| self.lazy::<T>().and_then(|lazy| lazy.cell.get()))
+    }
+
+    /// Retrieves a reference to a value of type `T`, running its lazy
+    /// initializer first if it hasn't run yet.
+    ///
+    /// Returns `None` if no value of type `T` has been added.
+    pub async fn get_or_init<T: Send + Sync + 'static>(&self) -> Option<&T> {
+        if let Some(value) = self.get::<T>() {
+            return Some(value);
+        }
+        let lazy = self.lazy::<T>()?;
+        Some(lazy.cell.get_or_init(|| (lazy.init)()).await)
+    }
+
+    fn lazy<T: Send + Sync + 'static>(&self) -> Option<&Lazy<T>> {
+        self.lazy
+            .get(&TypeId::of::<T>())
+            .and_then(|arc| arc.downcast_ref::<Lazy<T>>())
     }
 }
 
@@ -111,6 +198,16 @@ mod tests {
         assert!(state.get::<Missing>().is_none());
     }
 
+    #[test]
+    fn test_app_state_contains() {
+        struct Config;
+        struct Missing;
+
+        let state = AppState::new().with(Config);
+        assert!(state.contains::<Config>());
+        assert!(!state.contains::<Missing>());
+    }
+
     #[test]
     fn test_app_state_multiple_types() {
         #[derive(Debug)]
@@ -170,4 +267,48 @@ mod tests {
         assert_eq!(state.get::<f64>(), Some(&3.0));
         assert_eq!(state.get::<String>(), Some(&"test".to_string()));
     }
+
+    #[tokio::test]
+    async fn test_app_state_lazy_is_built_on_first_access() {
+        let state = AppState::new().with_lazy(|| async { 42i32 });
+
+        assert!(state.contains::<i32>());
+        assert_eq!(state.get::<i32>(), None);
+        assert_eq!(state.get_or_init::<i32>().await, Some(&42));
+        assert_eq!(state.get::<i32>(), Some(&42));
+        assert_eq!(state.get_or_init::<i64>().await, None);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_app_state_lazy_runs_once() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        static RUNS: AtomicUsize = AtomicUsize::new(0);
+        let state = Arc::new(AppState::new().with_lazy(|| async {
+            RUNS.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            "pool".to_string()
+        }));
+
+        let tasks: Vec<_> = (0..16)
+            .map(|_| {
+                let state = state.clone();
+                tokio::spawn(async move { state.get_or_init::<String>().await.cloned() })
+            })
+            .collect();
+        for task in tasks {
+            assert_eq!(task.await.unwrap().as_deref(), Some("pool"));
+        }
+        assert_eq!(RUNS.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_app_state_lazy_and_eager_overwrite_each_other() {
+        let state = AppState::new().with_lazy(|| async { 1i32 }).with(2i32);
+        assert_eq!(state.get_or_init::<i32>().await, Some(&2));
+
+        let state = AppState::new().with(1i32).with_lazy(|| async { 2i32 });
+        assert_eq!(state.get::<i32>(), None);
+        assert_eq!(state.get_or_init::<i32>().await, Some(&2));
+    }
 }
@@ -1,7 +1,8 @@
 //! Test client for integration testing Rapina applications.
 
+use std::collections::BTreeMap;
 use std::net::SocketAddr;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use bytes::Bytes;
 use http::{HeaderMap, HeaderName, HeaderValue, Method, StatusCode};
@@ -16,7 +17,9 @@ use serde::{Serialize, de::DeserializeOwned};
 use tokio::net::TcpListener;
 use tokio::sync::oneshot;
 
+use crate::auth::AuthConfig;
 use crate::context::RequestContext;
+use crate::extract::ConnectInfo;
 use crate::middleware::MiddlewareStack;
 use crate::router::Router;
 use crate::state::AppState;
@@ -47,6 +50,9 @@ use crate::state::AppState;
 pub struct TestClient {
     addr: SocketAddr,
     client: Client<hyper_util::client::legacy::connect::HttpConnector, Full<Bytes>>,
+    cookies: Option<Mutex<BTreeMap<String, String>>>,
+    auth_config: Option<AuthConfig>,
+    bearer_token: Option<String>,
     _shutdown: oneshot::Sender<()>,
 }
 
@@ -55,8 +61,11 @@ impl TestClient {
     ///
     /// This spawns a background server on a random available port.
     pub async fn new(app: crate::app::Rapina) -> Self {
+        let auth_config = app.auth_config.clone();
         let app = app.prepare();
-        Self::from_parts(app.router, app.state, app.middlewares).await
+        let mut client = Self::from_parts(app.router, app.state, app.middlewares).await;
+        client.auth_config = auth_config;
+        client
     }
 
     /// Creates a test client from router, state, and middlewares.
@@ -78,7 +87,7 @@ impl TestClient {
                 tokio::select! {
                     result = listener.accept() => {
                         match result {
-                            Ok((stream, _)) => {
+                            Ok((stream, remote_addr)) => {
                                 let io = TokioIo::new(stream);
                                 let router = router.clone();
                                 let state = state.clone();
@@ -92,6 +101,7 @@ impl TestClient {
 
                                         let ctx = RequestContext::new();
                                         req.extensions_mut().insert(ctx.clone());
+                                        req.extensions_mut().insert(ConnectInfo(remote_addr));
 
                                         async move {
                                             let response = middlewares.execute(req, &router, &state, &ctx).await;
@@ -119,10 +129,142 @@ impl TestClient {
         Self {
             addr,
             client,
+            cookies: None,
+            auth_config: None,
+            bearer_token: None,
             _shutdown: shutdown_tx,
         }
     }
 
+    /// Authenticates every request as the given user.
+    ///
+    /// Signs a token with the app's [`AuthConfig`] and sends it as
+    /// `Authorization: Bearer <token>` on each request, unless the request
+    /// sets its own `Authorization` header.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the app was not configured with
+    /// [`Rapina::with_auth`](crate::app::Rapina::with_auth).
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let client = TestClient::new(app).await.authenticated_as("user-1");
+    /// let response = client.get("/me").send().await;
+    /// ```
+    pub fn authenticated_as(mut self, user_id: impl Into<String>) -> Self {
+        let config = self
+            .auth_config
+            .as_ref()
+            .expect("authenticated_as requires an app configured with `with_auth`");
+        let token = config
+            .create_token(user_id)
+            .expect("failed to sign test token");
+        self.bearer_token = Some(token);
+        self
+    }
+
+    /// Enables a cookie jar for this client.
+    ///
+    /// Cookies set by responses via `Set-Cookie` are stored and sent back
+    /// on subsequent requests, unless a request sets its own `Cookie` header.
+    /// Cookies are tracked by name only; `Domain` and `Path` are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let client = TestClient::new(app).await.with_cookie_store();
+    ///
+    /// client.post("/login").send().await;
+    /// assert!(client.cookie("session").is_some());
+    ///
+    /// // The session cookie is sent automatically
+    /// let response = client.get("/dashboard").send().await;
+    /// ```
+    pub fn with_cookie_store(mut self) -> Self {
+        self.cookies = Some(Mutex::new(BTreeMap::new()));
+        self
+    }
+
+    /// Returns the value of a stored cookie.
+    ///
+    /// Always `None` unless the cookie store is enabled.
+    pub fn cookie(&self, name: &str) -> Option<String> {
+        self.cookies
+            .as_ref()
+            .and_then(|jar| jar.lock().unwrap().get(name).cloned())
+    }
+
+    /// Returns all stored cookies as name/value pairs.
+    pub fn cookies(&self) -> BTreeMap<String, String> {
+        self.cookies
+            .as_ref()
+            .map(|jar| jar.lock().unwrap().clone())
+            .unwrap_or_default()
+    }
+
+    /// Stores a cookie as if it had been set by the server.
+    ///
+    /// Has no effect unless the cookie store is enabled.
+    pub fn set_cookie(&self, name: &str, value: &str) {
+        if let Some(jar) = &self.cookies {
+            jar.lock()
+                .unwrap()
+                .insert(name.to_string(), value.to_string());
+        }
+    }
+
+    /// Removes all stored cookies.
+    pub fn clear_cookies(&self) {
+        if let Some(jar) = &self.cookies {
+            jar.lock().unwrap().clear();
+        }
+    }
+
+    fn cookie_header(&self) -> Option<HeaderValue> {
+        let jar = self.cookies.as_ref()?.lock().unwrap();
+        if jar.is_empty() {
+            return None;
+        }
+        let header = jar
+            .iter()
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect::<Vec<_>>()
+            .join("; ");
+        HeaderValue::from_str(&header).ok()
+    }
+
+    fn store_cookies(&self, headers: &HeaderMap) {
+        let Some(jar) = &self.cookies else {
+            return;
+        };
+        let mut jar = jar.lock().unwrap();
+
+        for set_cookie in headers.get_all(http::header::SET_COOKIE) {
+            let Ok(set_cookie) = set_cookie.to_str() else {
+                continue;
+            };
+            let mut attributes = set_cookie.split(';').map(str::trim);
+            let Some((name, value)) = attributes.next().and_then(|pair| pair.split_once('='))
+            else {
+                continue;
+            };
+
+            let expired = attributes.any(|attr| {
+                attr.split_once('=').is_some_and(|(key, value)| {
+                    key.eq_ignore_ascii_case("max-age") && value.trim().starts_with(['0', '-'])
+                })
+            });
+
+            if expired {
+                jar.remove(name.trim());
+            } else {
+                jar.insert(name.trim().to_string(), value.trim().to_string());
+            }
+        }
+    }
+
     /// Creates a GET request builder.
     pub fn get(&self, path: &str) -> TestRequestBuilder<'_> {
         self.request(Method::GET, path)
@@ -188,6 +330,11 @@ impl<'a> TestRequestBuilder<'a> {
         self
     }
 
+    /// Sets an `Authorization: Bearer <token>` header on the request.
+    pub fn bearer(self, token: &str) -> Self {
+        self.header("authorization", &format!("Bearer {}", token))
+    }
+
     /// Sets a JSON body on the request.
     pub fn json<T: Serialize>(mut self, body: &T) -> Self {
         self.body = Bytes::from(serde_json::to_vec(body).unwrap());
@@ -224,12 +371,25 @@ impl<'a> TestRequestBuilder<'a> {
             builder = builder.header(key, value);
         }
 
+        if !self.headers.contains_key(http::header::AUTHORIZATION)
+            && let Some(token) = &self.client.bearer_token
+        {
+            builder = builder.header(http::header::AUTHORIZATION, format!("Bearer {}", token));
+        }
+
+        if !self.headers.contains_key(http::header::COOKIE)
+            && let Some(cookies) = self.client.cookie_header()
+        {
+            builder = builder.header(http::header::COOKIE, cookies);
+        }
+
         let request = builder.body(Full::new(self.body)).unwrap();
 
         let response = self.client.client.request(request).await.unwrap();
 
         let status = response.status();
         let headers = response.headers().clone();
+        self.client.store_cookies(&headers);
         let body = response.into_body().collect().await.unwrap().to_bytes();
 
         TestResponse {
@@ -277,6 +437,127 @@ impl TestResponse {
     pub fn try_json<T: DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
         serde_json::from_slice(&self.body)
     }
+
+    /// Asserts the response has the given status code.
+    ///
+    /// # Panics
+    ///
+    /// Panics with the actual status and body if the status differs.
+    #[track_caller]
+    pub fn assert_status(&self, expected: StatusCode) -> &Self {
+        assert!(
+            self.status == expected,
+            "expected status {}, got {}\nbody: {}",
+            expected,
+            self.status,
+            self.text()
+        );
+        self
+    }
+
+    /// Asserts the response has a header with the given value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the header is missing or has a different value.
+    #[track_caller]
+    pub fn assert_header(&self, name: &str, expected: &str) -> &Self {
+        match self.headers.get(name) {
+            Some(actual) => assert!(
+                actual.as_bytes() == expected.as_bytes(),
+                "expected header '{}' to be {:?}, got {:?}",
+                name,
+                expected,
+                actual
+            ),
+            None => panic!(
+                "expected header '{}' to be {:?}, but it is missing\nheaders: {:?}",
+                name, expected, self.headers
+            ),
+        }
+        self
+    }
+
+    /// Asserts the JSON body contains the expected value.
+    ///
+    /// Objects match if every expected key is present with a matching value;
+    /// extra keys in the response are ignored. Arrays must have the same
+    /// length and match element by element. Other values must be equal.
+    ///
+    /// # Panics
+    ///
+    /// Panics with the mismatching path and the actual body if the body is
+    /// not JSON or does not contain the expected value.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// client
+    ///     .get("/users/1")
+    ///     .send()
+    ///     .await
+    ///     .assert_status(StatusCode::OK)
+    ///     .assert_json_contains(serde_json::json!({"name": "Alice"}));
+    /// ```
+    #[track_caller]
+    pub fn assert_json_contains(&self, expected: serde_json::Value) -> &Self {
+        let actual: serde_json::Value = match self.try_json() {
+            Ok(actual) => actual,
+            Err(e) => panic!(
+                "response body is not valid JSON ({})\nbody: {}",
+                e,
+                self.text()
+            ),
+        };
+
+        if let Err(mismatch) = json_contains(&actual, &expected, "$") {
+            panic!(
+                "JSON body does not contain expected value: {}\nexpected: {}\nbody: {}",
+                mismatch,
+                expected,
+                self.text()
+            );
+        }
+        self
+    }
+}
+
+/// Checks that `actual` contains `expected`, returning the first mismatch.
+fn json_contains(
+    actual: &serde_json::Value,
+    expected: &serde_json::Value,
+    path: &str,
+) -> Result<(), String> {
+    use serde_json::Value;
+
+    match (actual, expected) {
+        (Value::Object(actual), Value::Object(expected)) => {
+            for (key, expected) in expected {
+                let path = format!("{}.{}", path, key);
+                match actual.get(key) {
+                    Some(actual) => json_contains(actual, expected, &path)?,
+                    None => return Err(format!("missing key at {}", path)),
+                }
+            }
+            Ok(())
+        }
+        (Value::Array(actual), Value::Array(expected)) => {
+            if actual.len() != expected.len() {
+                return Err(format!(
+                    "array at {} has {} elements, expected {}",
+                    path,
+                    actual.len(),
+                    expected.len()
+                ));
+            }
+            for (i, (actual, expected)) in actual.iter().zip(expected).enumerate() {
+                json_contains(actual, expected, &format!("{}[{}]", path, i))?;
+            }
+            Ok(())
+        }
+        (actual, expected) if actual == expected => Ok(()),
+        (actual, expected) => Err(format!("at {} expected {}, got {}", path, expected, actual)),
+    }
 }
 
 #[cfg(test)]
@@ -346,6 +627,174 @@ mod tests {
         assert_eq!(response.text(), "Bearer token123");
     }
 
+    fn session_app() -> Rapina {
+        Rapina::new().with_introspection(false).router(
+            Router::new()
+                .route(http::Method::POST, "/login", |_, _, _| async {
+                    http::Response::builder()
+                        .header("set-cookie", "session=abc123; Path=/; HttpOnly")
+                        .header("set-cookie", "theme=dark")
+                        .body(Full::new(Bytes::new()))
+                        .unwrap()
+                })
+                .route(http::Method::POST, "/logout", |_, _, _| async {
+                    http::Response::builder()
+                        .header("set-cookie", "session=; Max-Age=0")
+                        .body(Full::new(Bytes::new()))
+                        .unwrap()
+                })
+                .route(http::Method::GET, "/dashboard", |req, _, _| async move {
+                    let cookies = req
+                        .headers()
+                        .get("cookie")
+                        .and_then(|v| v.to_str().ok())
+                        .unwrap_or("");
+                    if cookies.contains("session=abc123") {
+                        (StatusCode::OK, "welcome".to_string())
+                    } else {
+                        (StatusCode::UNAUTHORIZED, "login required".to_string())
+                    }
+                }),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_client_cookie_store_persists_session() {
+        let client = TestClient::new(session_app()).await.with_cookie_store();
+
+        let response = client.get("/dashboard").send().await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        client.post("/login").send().await;
+        assert_eq!(client.cookie("session").as_deref(), Some("abc123"));
+        assert_eq!(client.cookies().len(), 2);
+
+        let response = client.get("/dashboard").send().await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.text(), "welcome");
+    }
+
+    #[tokio::test]
+    async fn test_client_cookie_store_removes_expired() {
+        let client = TestClient::new(session_app()).await.with_cookie_store();
+
+        client.post("/login").send().await;
+        client.post("/logout").send().await;
+
+        assert!(client.cookie("session").is_none());
+        assert_eq!(client.cookie("theme").as_deref(), Some("dark"));
+        let response = client.get("/dashboard").send().await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_client_without_cookie_store_ignores_cookies() {
+        let client = TestClient::new(session_app()).await;
+
+        client.post("/login").send().await;
+
+        assert!(client.cookie("session").is_none());
+        let response = client.get("/dashboard").send().await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_request_builder_bearer() {
+        let app = Rapina::new()
+            .with_introspection(false)
+            .router(
+                Router::new().route(http::Method::GET, "/auth", |req, _, _| async move {
+                    req.headers()
+                        .get("authorization")
+                        .and_then(|v| v.to_str().ok())
+                        .unwrap_or("")
+                        .to_string()
+                }),
+            );
+
+        let client = TestClient::new(app).await;
+        let response = client.get("/auth").bearer("token123").send().await;
+
+        assert_eq!(response.text(), "Bearer token123");
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "with_auth")]
+    async fn test_authenticated_as_requires_auth_config() {
+        let app = Rapina::new()
+            .with_introspection(false)
+            .router(Router::new());
+        let _client = TestClient::new(app).await.authenticated_as("user-1");
+    }
+
+    fn response(status: StatusCode, body: &str) -> TestResponse {
+        let mut headers = HeaderMap::new();
+        headers.insert("content-type", HeaderValue::from_static("application/json"));
+        TestResponse {
+            status,
+            headers,
+            body: Bytes::from(body.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_assert_status_passes() {
+        response(StatusCode::CREATED, "{}").assert_status(StatusCode::CREATED);
+    }
+
+    #[test]
+    #[should_panic(expected = "expected status 200 OK, got 404 Not Found")]
+    fn test_assert_status_fails() {
+        response(StatusCode::NOT_FOUND, r#"{"error":"missing"}"#).assert_status(StatusCode::OK);
+    }
+
+    #[test]
+    fn test_assert_header_passes() {
+        response(StatusCode::OK, "{}").assert_header("content-type", "application/json");
+    }
+
+    #[test]
+    #[should_panic(expected = "expected header 'x-missing'")]
+    fn test_assert_header_missing() {
+        response(StatusCode::OK, "{}").assert_header("x-missing", "value");
+    }
+
+    #[test]
+    #[should_panic(expected = "expected header 'content-type' to be \"text/plain\"")]
+    fn test_assert_header_wrong_value() {
+        response(StatusCode::OK, "{}").assert_header("content-type", "text/plain");
+    }
+
+    #[test]
+    fn test_assert_json_contains_subset() {
+        response(
+            StatusCode::OK,
+            r#"{"id":1,"user":{"name":"Alice","email":"a@example.com"},"tags":["a","b"]}"#,
+        )
+        .assert_status(StatusCode::OK)
+        .assert_json_contains(serde_json::json!({"user": {"name": "Alice"}, "tags": ["a", "b"]}));
+    }
+
+    #[test]
+    #[should_panic(expected = "at $.user.name expected \"Bob\", got \"Alice\"")]
+    fn test_assert_json_contains_wrong_value() {
+        response(StatusCode::OK, r#"{"user":{"name":"Alice"}}"#)
+            .assert_json_contains(serde_json::json!({"user": {"name": "Bob"}}));
+    }
+
+    #[test]
+    #[should_panic(expected = "missing key at $.email")]
+    fn test_assert_json_contains_missing_key() {
+        response(StatusCode::OK, r#"{"name":"Alice"}"#)
+            .assert_json_contains(serde_json::json!({"email": "a@example.com"}));
+    }
+
+    #[test]
+    #[should_panic(expected = "body: not json")]
+    fn test_assert_json_contains_invalid_body() {
+        response(StatusCode::OK, "not json").assert_json_contains(serde_json::json!({}));
+    }
+
     #[tokio::test]
     async fn test_client_not_found() {
         let app = Rapina::new()
@@ -0,0 +1,109 @@
+//! Integration tests for authentication.
+
+use http::StatusCode;
+use rapina::extract::FromRequestParts;
+use rapina::prelude::*;
+use rapina::testing::TestClient;
+
+const SECRET: &str = "test-secret";
+
+fn app() -> Rapina {
+    Rapina::new()
+        .with_introspection(false)
+        .with_auth(AuthConfig::new(SECRET, 3600))
+        .public_route("GET", "/greeting")
+        .router(
+            Router::new()
+                .route(
+                    http::Method::GET,
+                    "/greeting",
+                    |req, params, state| async move {
+                        let (parts, _) = req.into_parts();
+                        let user =
+                            Option::<CurrentUser>::from_request_parts(&parts, &params, &state)
+                                .await
+                                .unwrap();
+                        match user {
+                            Some(user) => format!("Hello, {}!", user.id),
+                            None => "Hello, guest!".to_string(),
+                        }
+                    },
+                )
+                .route(http::Method::GET, "/me", |req, params, state| async move {
+                    let (parts, _) = req.into_parts();
+                    match CurrentUser::from_request_parts(&parts, &params, &state).await {
+                        Ok(user) => user.id.into_response(),
+                        Err(e) => e.into_response(),
+                    }
+                }),
+        )
+}
+
+#[tokio::test]
+async fn test_public_route_anonymous() {
+    let client = TestClient::new(app()).await;
+    let response = client.get("/greeting").send().await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.text(), "Hello, guest!");
+}
+
+#[tokio::test]
+async fn test_public_route_with_token() {
+    let token = AuthConfig::new(SECRET, 3600).create_token("alice").unwrap();
+
+    let client = TestClient::new(app()).await;
+    let response = client
+        .get("/greeting")
+        .header("authorization", &format!("Bearer {}", token))
+        .send()
+        .await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.text(), "Hello, alice!");
+}
+
+#[tokio::test]
+async fn test_public_route_with_invalid_token() {
+    let client = TestClient::new(app()).await;
+    let response = client
+        .get("/greeting")
+        .header("authorization", "Bearer not-a-jwt")
+        .send()
+        .await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.text(), "Hello, guest!");
+}
+
+#[tokio::test]
+async fn test_protected_route_requires_token() {
+    let client = TestClient::new(app()).await;
+    let response = client.get("/me").send().await;
+
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_protected_route_with_bearer() {
+    let token = AuthConfig::new(SECRET, 3600).create_token("bob").unwrap();
+
+    let client = TestClient::new(app()).await;
+    let response = client.get("/me").bearer(&token).send().await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.text(), "bob");
+}
+
+#[tokio::test]
+async fn test_protected_route_authenticated_as() {
+    let client = TestClient::new(app()).await.authenticated_as("carol");
+
+    let response = client.get("/me").send().await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.text(), "carol");
+
+    // The signed token also identifies the user on public routes
+    let response = client.get("/greeting").send().await;
+    assert_eq!(response.text(), "Hello, carol!");
+}
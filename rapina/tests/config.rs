@@ -0,0 +1,88 @@
+//! Integration tests for `#[derive(Config)]`.
+
+use rapina::prelude::*;
+
+#[derive(Debug, Config)]
+struct FeatureConfig {
+    #[env = "RAPINA_TEST_FEATURE_FLAG"]
+    flag: bool,
+}
+
+#[derive(Debug, Config)]
+struct DefaultedConfig {
+    #[env = "RAPINA_TEST_DEFAULTED_FLAG"]
+    #[default = "yes"]
+    flag: bool,
+}
+
+// Each test owns its variables, since tests run in parallel threads
+fn set_env(key: &str, value: &str) {
+    unsafe { std::env::set_var(key, value) };
+}
+
+#[test]
+fn test_bool_field_accepts_common_spellings() {
+    let cases = [
+        ("true", true),
+        ("false", false),
+        ("1", true),
+        ("0", false),
+        ("yes", true),
+        ("no", false),
+        ("on", true),
+        ("off", false),
+        ("YES", true),
+        ("Off", false),
+    ];
+
+    for (value, expected) in cases {
+        set_env("RAPINA_TEST_FEATURE_FLAG", value);
+        let config = FeatureConfig::from_env().unwrap();
+        assert_eq!(config.flag, expected, "{:?}", value);
+    }
+}
+
+#[test]
+fn test_bool_field_rejects_unknown_value() {
+    #[derive(Debug, Config)]
+    struct InvalidConfig {
+        #[env = "RAPINA_TEST_INVALID_FLAG"]
+        #[allow(dead_code)]
+        flag: bool,
+    }
+
+    set_env("RAPINA_TEST_INVALID_FLAG", "maybe");
+    let err = InvalidConfig::from_env().unwrap_err();
+    assert!(
+        matches!(&err, ConfigError::ParseError { var, value, expected_type: "bool" } if var == "RAPINA_TEST_INVALID_FLAG" && value == "maybe"),
+        "{:?}",
+        err
+    );
+}
+
+#[test]
+fn test_parse_error_names_variable_value_and_type() {
+    #[derive(Debug, Config)]
+    struct PortConfig {
+        #[env = "RAPINA_TEST_DERIVE_PORT"]
+        #[allow(dead_code)]
+        port: u16,
+    }
+
+    set_env("RAPINA_TEST_DERIVE_PORT", "abc");
+    let err = PortConfig::from_env().unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Invalid value 'abc' for environment variable 'RAPINA_TEST_DERIVE_PORT': expected u16"
+    );
+}
+
+#[test]
+fn test_bool_field_default() {
+    let config = DefaultedConfig::from_env().unwrap();
+    assert!(config.flag);
+
+    set_env("RAPINA_TEST_DEFAULTED_FLAG", "off");
+    let config = DefaultedConfig::from_env().unwrap();
+    assert!(!config.flag);
+}
@@ -0,0 +1,170 @@
+//! Integration tests for signed and private cookies.
+
+use http::StatusCode;
+use rapina::cookie::CookieKey;
+use rapina::prelude::*;
+use rapina::testing::TestClient;
+
+#[post("/login")]
+async fn login(cookies: SignedCookies) -> WithHeaders<&'static str> {
+    WithHeaders::new("welcome")
+        .cookie(cookies.sign(SetCookie::new("user_id", "42").http_only(true)))
+}
+
+#[get("/me")]
+async fn me(cookies: SignedCookies) -> Result<String> {
+    cookies
+        .get("user_id")
+        .ok_or_else(|| Error::unauthorized("not logged in"))
+}
+
+#[post("/session")]
+async fn start_session(cookies: PrivateCookies) -> WithHeaders<&'static str> {
+    WithHeaders::new("started").cookie(cookies.encrypt(SetCookie::new("session", "cart=3")))
+}
+
+#[get("/session")]
+async fn read_session(cookies: PrivateCookies) -> Result<String> {
+    cookies
+        .get("session")
+        .ok_or_else(|| Error::unauthorized("no session"))
+}
+
+fn app() -> Rapina {
+    Rapina::new()
+        .with_introspection(false)
+        .with_cookie_key(CookieKey::new("integration-test-cookie-secret-0123456789"))
+        .router(
+            Router::new()
+                .post("/login", login)
+                .get("/me", me)
+                .post("/session", start_session)
+                .get("/session", read_session),
+        )
+}
+
+#[tokio::test]
+async fn test_signed_cookie_round_trip() {
+    let client = TestClient::new(app()).await.with_cookie_store();
+
+    let response = client.post("/login").send().await;
+    let set_cookie = response.headers()["set-cookie"].to_str().unwrap();
+    assert!(set_cookie.starts_with("user_id=42."), "{}", set_cookie);
+    assert!(set_cookie.ends_with("; HttpOnly"), "{}", set_cookie);
+
+    let response = client.get("/me").send().await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.text(), "42");
+}
+
+#[tokio::test]
+async fn test_signed_cookie_tampering_is_rejected() {
+    let client = TestClient::new(app()).await.with_cookie_store();
+
+    client.post("/login").send().await;
+    let signed = client.cookie("user_id").unwrap();
+    client.set_cookie("user_id", &signed.replacen("42", "1", 1));
+
+    let response = client.get("/me").send().await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    // An unsigned value is rejected as well
+    client.set_cookie("user_id", "42");
+    let response = client.get("/me").send().await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_private_cookie_round_trip() {
+    let client = TestClient::new(app()).await.with_cookie_store();
+
+    client.post("/session").send().await;
+    let encrypted = client.cookie("session").unwrap();
+    assert!(!encrypted.contains("cart"));
+
+    let response = client.get("/session").send().await;
+    assert_eq!(response.text(), "cart=3");
+
+    client.set_cookie("session", &format!("A{}", encrypted));
+    let response = client.get("/session").send().await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_cookie_key_derived_from_auth_secret() {
+    let app = Rapina::new()
+        .with_introspection(false)
+        .with_auth(AuthConfig::new("auth-secret", 3600))
+        .public_route("POST", "/login")
+        .public_route("GET", "/me")
+        .router(Router::new().post("/login", login).get("/me", me));
+    let client = TestClient::new(app).await.with_cookie_store();
+
+    client.post("/login").send().await;
+    let response = client.get("/me").send().await;
+    assert_eq!(response.text(), "42");
+}
+
+#[tokio::test]
+async fn test_missing_cookie_key_is_server_error() {
+    let app = Rapina::new()
+        .with_introspection(false)
+        .router(Router::new().get("/me", me));
+    let client = TestClient::new(app).await;
+
+    let response = client.get("/me").send().await;
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+}
+
+#[tokio::test]
+async fn test_cookie_jar_sets_multiple_cookies() {
+    let app = Rapina::new()
+        .with_introspection(false)
+        .router(Router::new().post_fn("/preferences", || async {
+            let jar = CookieJar::new()
+                .cookie(
+                    SetCookie::new("theme", "dark")
+                        .path("/")
+                        .max_age(std::time::Duration::from_secs(86400))
+                        .same_site(rapina::cookie::SameSite::Lax),
+                )
+                .cookie(SetCookie::new("lang", "en").secure(true).http_only(true))
+                .remove("legacy");
+            (jar, "saved")
+        }));
+    let client = TestClient::new(app).await;
+
+    let response = client.post("/preferences").send().await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.text(), "saved");
+
+    let cookies: Vec<_> = response
+        .headers()
+        .get_all("set-cookie")
+        .iter()
+        .map(|v| v.to_str().unwrap())
+        .collect();
+    assert_eq!(
+        cookies,
+        [
+            "theme=dark; Max-Age=86400; Path=/; SameSite=Lax",
+            "lang=en; HttpOnly; Secure",
+            "legacy=; Expires=Thu, 01 Jan 1970 00:00:00 GMT; Max-Age=0",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_invalid_cookie_value_is_error() {
+    let app =
+        Rapina::new()
+            .with_introspection(false)
+            .router(Router::new().get_fn("/bad", || async {
+                WithHeaders::new("ok").cookie(SetCookie::new("note", "hello; Secure"))
+            }));
+    let client = TestClient::new(app).await;
+
+    let response = client.get("/bad").send().await;
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    assert!(response.headers().get("set-cookie").is_none());
+}
@@ -0,0 +1,156 @@
+//! Integration tests for mapping database errors to API errors and for
+//! versioned updates.
+
+#![cfg(feature = "sqlite")]
+
+use rapina::database::{DbError, update_versioned};
+use rapina::error::IntoApiError;
+use rapina::sea_orm::ActiveValue::Set;
+use rapina::sea_orm::{
+    ConnectionTrait, Database, DatabaseConnection, EntityTrait, IntoActiveModel,
+};
+
+mod note {
+    use rapina::sea_orm;
+    use sea_orm::entity::prelude::*;
+
+    #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+    #[sea_orm(table_name = "notes")]
+    pub struct Model {
+        #[sea_orm(primary_key)]
+        pub id: i32,
+        pub body: String,
+        pub version: i32,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+async fn connect() -> DatabaseConnection {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    db.execute_unprepared(
+        "PRAGMA foreign_keys = ON;
+         CREATE TABLE users (id INTEGER PRIMARY KEY, email TEXT NOT NULL UNIQUE);
+         CREATE TABLE posts (id INTEGER PRIMARY KEY, user_id INTEGER NOT NULL REFERENCES users(id));",
+    )
+    .await
+    .unwrap();
+    db
+}
+
+#[tokio::test]
+async fn test_unique_violation_maps_to_conflict() {
+    let db = connect().await;
+    db.execute_unprepared("INSERT INTO users (email) VALUES ('a@example.com')")
+        .await
+        .unwrap();
+
+    let err = db
+        .execute_unprepared("INSERT INTO users (email) VALUES ('a@example.com')")
+        .await
+        .unwrap_err();
+    let api_err = DbError(err).into_api_error();
+
+    assert_eq!(api_err.status, 409);
+    assert_eq!(api_err.code, "CONFLICT");
+    assert!(!api_err.message.contains("users.email"));
+}
+
+#[tokio::test]
+async fn test_duplicate_primary_key_maps_to_conflict() {
+    let db = connect().await;
+    db.execute_unprepared("INSERT INTO users (id, email) VALUES (1, 'a@example.com')")
+        .await
+        .unwrap();
+
+    let err = db
+        .execute_unprepared("INSERT INTO users (id, email) VALUES (1, 'b@example.com')")
+        .await
+        .unwrap_err();
+
+    assert_eq!(DbError(err).into_api_error().status, 409);
+}
+
+#[tokio::test]
+async fn test_foreign_key_violation_maps_to_conflict() {
+    let db = connect().await;
+
+    let err = db
+        .execute_unprepared("INSERT INTO posts (user_id) VALUES (42)")
+        .await
+        .unwrap_err();
+
+    assert_eq!(DbError(err).into_api_error().status, 409);
+}
+
+#[tokio::test]
+async fn test_other_query_errors_stay_internal() {
+    let db = connect().await;
+
+    let err = db
+        .execute_unprepared("SELECT * FROM missing_table")
+        .await
+        .unwrap_err();
+
+    assert_eq!(DbError(err).into_api_error().status, 500);
+}
+
+#[tokio::test]
+async fn test_update_versioned_rejects_stale_version() {
+    let db = connect().await;
+    db.execute_unprepared(
+        "CREATE TABLE notes (id INTEGER PRIMARY KEY, body TEXT NOT NULL, version INTEGER NOT NULL DEFAULT 1);
+         INSERT INTO notes (id, body) VALUES (1, 'draft');",
+    )
+    .await
+    .unwrap();
+
+    // Two clients read version 1; the first update wins and bumps the version
+    let item = note::Entity::find_by_id(1).one(&db).await.unwrap().unwrap();
+    let mut active = item.clone().into_active_model();
+    active.body = Set("first".to_string());
+    let updated = update_versioned(active, item.version, &db).await.unwrap();
+    assert_eq!(updated.body, "first");
+    assert_eq!(updated.version, 2);
+
+    let mut active = item.clone().into_active_model();
+    active.body = Set("second".to_string());
+    let err = update_versioned(active, item.version, &db)
+        .await
+        .unwrap_err();
+    assert_eq!(err.status, 409);
+    assert_eq!(err.code, "CONFLICT");
+
+    let stored = note::Entity::find_by_id(1).one(&db).await.unwrap().unwrap();
+    assert_eq!(stored.body, "first");
+    assert_eq!(stored.version, 2);
+}
+
+#[tokio::test]
+async fn test_lazily_registered_pool_reaches_db_extractor() {
+    use rapina::database::Db;
+    use rapina::prelude::*;
+    use rapina::testing::TestClient;
+
+    let app = Rapina::new()
+        .with_introspection(false)
+        .state_with(connect)
+        .router(Router::new().get_fn("/users", |db: Db| async move {
+            let result = db
+                .conn()
+                .execute_unprepared("INSERT INTO users (email) VALUES ('ada@example.com')")
+                .await;
+            match result {
+                Ok(result) => result.rows_affected().to_string(),
+                Err(e) => e.to_string(),
+            }
+        }));
+    let client = TestClient::new(app).await;
+
+    let response = client.get("/users").send().await;
+    assert_eq!(response.status(), http::StatusCode::OK);
+    assert_eq!(response.text(), "1");
+}
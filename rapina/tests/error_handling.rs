@@ -311,6 +311,50 @@ async fn test_custom_error_status() {
     assert_eq!(json["error"]["message"], "I'm a teapot");
 }
 
+#[tokio::test]
+async fn test_error_410_gone_and_503_unavailable() {
+    let app = Rapina::new().with_introspection(false).router(
+        Router::new()
+            .route(http::Method::GET, "/old", |_, _, _| async {
+                Error::gone("this endpoint was retired")
+            })
+            .route(http::Method::GET, "/busy", |_, _, _| async {
+                Error::from_status(503, "down for maintenance")
+            }),
+    );
+
+    let client = TestClient::new(app).await;
+
+    let response = client.get("/old").send().await;
+    assert_eq!(response.status(), StatusCode::GONE);
+    let json: serde_json::Value = response.json();
+    assert_eq!(json["error"]["code"], "GONE");
+
+    let response = client.get("/busy").send().await;
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    let json: serde_json::Value = response.json();
+    assert_eq!(json["error"]["code"], "SERVICE_UNAVAILABLE");
+    assert_eq!(json["error"]["message"], "down for maintenance");
+}
+
+#[tokio::test]
+async fn test_error_503_with_retry_after() {
+    let app = Rapina::new()
+        .with_introspection(false)
+        .router(
+            Router::new().route(http::Method::GET, "/reports", |_, _, _| async {
+                Error::service_unavailable("down for maintenance")
+                    .with_retry_after(std::time::Duration::from_secs(300))
+            }),
+        );
+
+    let client = TestClient::new(app).await;
+    let response = client.get("/reports").send().await;
+
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(response.headers()["retry-after"], "300");
+}
+
 #[tokio::test]
 async fn test_error_without_details_omits_field() {
     let app = Rapina::new()
@@ -365,3 +409,164 @@ async fn test_router_404_response() {
     // Router returns plain 404, not JSON error
     assert_eq!(response.status(), StatusCode::NOT_FOUND);
 }
+
+/// Domain error used to reshape extractor rejections.
+struct ApiError(Error);
+
+impl From<Error> for ApiError {
+    fn from(err: Error) -> Self {
+        ApiError(err)
+    }
+}
+
+impl IntoApiError for ApiError {
+    fn into_api_error(self) -> Error {
+        Error::new(
+            self.0.status,
+            "INVALID_REQUEST",
+            "the request could not be read",
+        )
+        .with_details(serde_json::json!({ "reason": self.0.code }))
+    }
+}
+
+#[derive(Deserialize)]
+#[allow(dead_code)]
+struct CreateItem {
+    name: String,
+}
+
+#[post("/items")]
+async fn create_item(body: WithRejection<Json<CreateItem>, ApiError>) -> String {
+    body.into_inner().into_inner().name
+}
+
+#[tokio::test]
+async fn test_with_rejection_maps_json_parse_failure() {
+    let app = Rapina::new()
+        .with_introspection(false)
+        .router(Router::new().post("/items", create_item));
+
+    let client = TestClient::new(app).await;
+    let response = client
+        .post("/items")
+        .header("content-type", "application/json")
+        .body("{not json")
+        .send()
+        .await;
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let json: serde_json::Value = response.json();
+    assert_eq!(json["error"]["code"], "INVALID_REQUEST");
+    assert_eq!(json["error"]["message"], "the request could not be read");
+    assert_eq!(json["error"]["details"]["reason"], "BAD_REQUEST");
+
+    let response = client
+        .post("/items")
+        .json(&serde_json::json!({ "name": "widget" }))
+        .send()
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.text(), "widget");
+}
+
+#[tokio::test]
+async fn test_with_rejection_parts_extractor() {
+    let app =
+        Rapina::new()
+            .with_introspection(false)
+            .router(Router::new().get_fn(
+                "/items/:id",
+                |id: WithRejection<Path<u64>, ApiError>| async move {
+                    id.into_inner().into_inner().to_string()
+                },
+            ));
+
+    let client = TestClient::new(app).await;
+    let response = client.get("/items/abc").send().await;
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let json: serde_json::Value = response.json();
+    assert_eq!(json["error"]["code"], "INVALID_REQUEST");
+}
+
+#[tokio::test]
+async fn test_map_rejections_applies_to_all_handlers() {
+    let app = Rapina::new()
+        .with_introspection(false)
+        .map_rejections(|err| Error::new(err.status, "REJECTED", err.message))
+        .router(
+            Router::new().post_fn("/items", |body: Json<CreateItem>| async move {
+                body.into_inner().name
+            }),
+        );
+
+    let client = TestClient::new(app).await;
+    let response = client
+        .post("/items")
+        .header("content-type", "application/json")
+        .body("{not json")
+        .send()
+        .await;
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let json: serde_json::Value = response.json();
+    assert_eq!(json["error"]["code"], "REJECTED");
+}
+
+enum AccountError {
+    NotFound(u64),
+    Locked,
+}
+
+impl IntoApiError for AccountError {
+    fn into_api_error(self) -> Error {
+        match self {
+            AccountError::NotFound(id) => Error::not_found(format!("account {} not found", id)),
+            AccountError::Locked => Error::new(423, "ACCOUNT_LOCKED", "account is locked"),
+        }
+    }
+}
+
+#[derive(Serialize, JsonSchema)]
+struct Account {
+    id: u64,
+}
+
+fn find_account(id: u64) -> std::result::Result<Account, AccountError> {
+    match id {
+        1 => Ok(Account { id }),
+        2 => Err(AccountError::Locked),
+        _ => Err(AccountError::NotFound(id)),
+    }
+}
+
+#[get("/accounts/:id")]
+async fn get_account(id: Path<u64>) -> std::result::Result<Json<Account>, AccountError> {
+    let account = find_account(id.into_inner())?;
+    Ok(Json(account))
+}
+
+#[tokio::test]
+async fn test_handler_returns_domain_error() {
+    let app = Rapina::new()
+        .with_introspection(false)
+        .router(Router::new().get("/accounts/:id", get_account));
+
+    let client = TestClient::new(app).await;
+
+    let response = client.get("/accounts/1").send().await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.json::<serde_json::Value>()["id"], 1);
+
+    let response = client.get("/accounts/7").send().await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let json: serde_json::Value = response.json();
+    assert_eq!(json["error"]["code"], "NOT_FOUND");
+    assert_eq!(json["error"]["message"], "account 7 not found");
+
+    let response = client.get("/accounts/2").send().await;
+    assert_eq!(response.status().as_u16(), 423);
+    let json: serde_json::Value = response.json();
+    assert_eq!(json["error"]["code"], "ACCOUNT_LOCKED");
+}
@@ -69,6 +69,128 @@ async fn test_json_extraction_invalid_json() {
     assert_eq!(response.status(), StatusCode::BAD_REQUEST);
 }
 
+#[post("/users")]
+async fn create_user(user: Json<User>) -> String {
+    user.into_inner().name
+}
+
+fn create_user_app() -> Rapina {
+    Rapina::new()
+        .with_introspection(false)
+        .router(Router::new().post("/users", create_user))
+}
+
+#[tokio::test]
+async fn test_json_extractor_json_body() {
+    let client = TestClient::new(create_user_app()).await;
+    let response = client
+        .post("/users")
+        .header("content-type", "application/json; charset=utf-8")
+        .body(r#"{"name":"Alice","email":"alice@example.com"}"#)
+        .send()
+        .await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.text(), "Alice");
+}
+
+#[tokio::test]
+async fn test_json_extractor_form_body_unsupported() {
+    let client = TestClient::new(create_user_app()).await;
+    let response = client
+        .post("/users")
+        .form(&User {
+            name: "Alice".to_string(),
+            email: "alice@example.com".to_string(),
+        })
+        .send()
+        .await;
+
+    assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    let json: serde_json::Value = response.json();
+    assert_eq!(json["error"]["code"], "UNSUPPORTED_MEDIA_TYPE");
+}
+
+#[tokio::test]
+async fn test_json_extractor_without_content_type() {
+    let client = TestClient::new(create_user_app()).await;
+    let response = client
+        .post("/users")
+        .body(r#"{"name":"Bob","email":"bob@example.com"}"#)
+        .send()
+        .await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.text(), "Bob");
+}
+
+#[post("/login")]
+async fn limited_login(user: ContentLengthLimit<Json<User>, 64>) -> String {
+    user.into_inner().into_inner().name
+}
+
+fn limited_app() -> Rapina {
+    Rapina::new()
+        .with_introspection(false)
+        .router(Router::new().post("/login", limited_login))
+}
+
+#[tokio::test]
+async fn test_content_length_limit_under_limit() {
+    let client = TestClient::new(limited_app()).await;
+    let response = client
+        .post("/login")
+        .json(&User {
+            name: "Alice".to_string(),
+            email: "a@example.com".to_string(),
+        })
+        .send()
+        .await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.text(), "Alice");
+}
+
+#[tokio::test]
+async fn test_content_length_limit_over_limit() {
+    let client = TestClient::new(limited_app()).await;
+    let response = client
+        .post("/login")
+        .json(&User {
+            name: "A".repeat(100),
+            email: "a@example.com".to_string(),
+        })
+        .send()
+        .await;
+
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    let json: serde_json::Value = response.json();
+    assert_eq!(json["error"]["code"], "PAYLOAD_TOO_LARGE");
+    assert!(
+        json["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("64 bytes")
+    );
+    assert_eq!(json["error"]["details"]["max_bytes"], 64);
+}
+
+#[tokio::test]
+async fn test_content_length_limit_stricter_than_global() {
+    let app = limited_app().middleware(rapina::middleware::BodyLimitMiddleware::new(1024));
+    let client = TestClient::new(app).await;
+    let response = client
+        .post("/login")
+        .json(&User {
+            name: "A".repeat(100),
+            email: "a@example.com".to_string(),
+        })
+        .send()
+        .await;
+
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+}
+
 #[tokio::test]
 async fn test_json_response() {
     let app = Rapina::new()
@@ -134,6 +256,37 @@ async fn test_query_extraction() {
     assert_eq!(response.text(), "page=2, limit=20");
 }
 
+#[tokio::test]
+async fn test_raw_query_alongside_typed_query() {
+    let app = Rapina::new()
+        .with_introspection(false)
+        .router(Router::new().get_fn(
+            "/items",
+            |query: Query<Pagination>, raw: RawQuery| async move {
+                format!(
+                    "page={} raw={}",
+                    query.0.page.unwrap_or(1),
+                    raw.into_inner().unwrap_or_default()
+                )
+            },
+        ));
+
+    let client = TestClient::new(app).await;
+    let response = client
+        .get("/items?page=2&filter%5Bstatus%5D=open&utm_source=mail")
+        .send()
+        .await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.text(),
+        "page=2 raw=page=2&filter%5Bstatus%5D=open&utm_source=mail"
+    );
+
+    let response = client.get("/items").send().await;
+    assert_eq!(response.text(), "page=1 raw=");
+}
+
 #[tokio::test]
 async fn test_query_extraction_optional_params() {
     let app = Rapina::new()
@@ -164,6 +317,91 @@ async fn test_query_extraction_optional_params() {
     assert_eq!(response.text(), "page=5, limit=10");
 }
 
+#[derive(Debug, Deserialize)]
+struct TagFilter {
+    #[serde(default)]
+    tag: Vec<String>,
+    page: Option<u32>,
+}
+
+#[tokio::test]
+async fn test_query_extraction_repeated_keys() {
+    let app = Rapina::new()
+        .with_introspection(false)
+        .router(
+            Router::new().get_fn("/posts", |query: Query<TagFilter>| async move {
+                format!(
+                    "tags=[{}], page={}",
+                    query.0.tag.join(","),
+                    query.0.page.unwrap_or(1)
+                )
+            }),
+        );
+
+    let client = TestClient::new(app).await;
+
+    let response = client.get("/posts?tag=rust&page=2&tag=web").send().await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.text(), "tags=[rust,web], page=2");
+
+    let response = client.get("/posts?tag=rust").send().await;
+    assert_eq!(response.text(), "tags=[rust], page=1");
+
+    let response = client.get("/posts").send().await;
+    assert_eq!(response.text(), "tags=[], page=1");
+
+    // A repeated key for a single-value field is rejected
+    let response = client.get("/posts?page=1&page=2").send().await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchParams {
+    limit: u32,
+    #[serde(default)]
+    range: Option<PriceRange>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PriceRange {
+    min: u32,
+}
+
+#[tokio::test]
+async fn test_query_rejection_names_parameter() {
+    let app = Rapina::new()
+        .with_introspection(false)
+        .router(
+            Router::new().get_fn("/search", |query: Query<SearchParams>| async move {
+                format!("{} {:?}", query.0.limit, query.0.range.map(|r| r.min))
+            }),
+        );
+    let client = TestClient::new(app).await;
+
+    let response = client.get("/search?limit=ten").send().await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let json: serde_json::Value = response.json();
+    assert_eq!(
+        json["error"]["message"],
+        "Invalid query parameter 'limit': invalid type: string \"ten\", expected u32"
+    );
+    assert_eq!(json["error"]["details"]["source"], "query");
+    assert_eq!(json["error"]["details"]["field"], "limit");
+
+    let response = client.get("/search").send().await;
+    let json: serde_json::Value = response.json();
+    assert_eq!(json["error"]["message"], "Missing query parameter 'limit'");
+    assert_eq!(json["error"]["details"]["field"], "limit");
+
+    let response = client.get("/search?limit=5&range[min]=low").send().await;
+    let json: serde_json::Value = response.json();
+    assert_eq!(
+        json["error"]["message"],
+        "Invalid query parameter 'range[min]': invalid type: string \"low\", expected u32"
+    );
+    assert_eq!(json["error"]["details"]["field"], "range[min]");
+}
+
 // Path Extractor Tests
 
 #[tokio::test]
@@ -225,6 +463,34 @@ async fn test_path_extraction_multiple_params() {
     assert_eq!(response.text(), "User 10 - Post 99");
 }
 
+#[derive(Debug, Deserialize)]
+struct PostPath {
+    user_id: u64,
+    post_id: u64,
+}
+
+#[tokio::test]
+async fn test_path_extraction_struct_by_name() {
+    let app = Rapina::new()
+        .with_introspection(false)
+        .router(Router::new().get_fn(
+            "/users/:user_id/posts/:post_id",
+            |path: Path<PostPath>| async move {
+                let path = path.into_inner();
+                format!("User {} - Post {}", path.user_id, path.post_id)
+            },
+        ));
+
+    let client = TestClient::new(app).await;
+
+    let response = client.get("/users/10/posts/99").send().await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.text(), "User 10 - Post 99");
+
+    let response = client.get("/users/10/posts/latest").send().await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
 // Headers Extractor Tests
 
 #[tokio::test]
@@ -347,6 +613,79 @@ async fn test_form_extraction() {
     assert_eq!(response.text(), "Welcome, alice!");
 }
 
+#[derive(Debug, Deserialize)]
+struct ShippingAddress {
+    street: String,
+    city: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct CheckoutForm {
+    name: String,
+    address: ShippingAddress,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+#[tokio::test]
+async fn test_form_extraction_nested_and_array_fields() {
+    let app = Rapina::new()
+        .with_introspection(false)
+        .router(
+            Router::new().post_fn("/checkout", |form: Form<CheckoutForm>| async move {
+                let form = form.into_inner();
+                format!(
+                    "{} at {}, {} [{}]",
+                    form.name,
+                    form.address.street,
+                    form.address.city,
+                    form.tags.join(",")
+                )
+            }),
+        );
+
+    let client = TestClient::new(app).await;
+    let response = client
+        .post("/checkout")
+        .header("content-type", "application/x-www-form-urlencoded")
+        .body("name=Ada&address%5Bstreet%5D=1+Main+St&address[city]=London&tags[]=gift&tags[]=express")
+        .send()
+        .await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.text(), "Ada at 1 Main St, London [gift,express]");
+
+    // Flat forms still work, and a missing nested struct is rejected
+    let response = client
+        .post("/checkout")
+        .form(&serde_json::json!({ "name": "Ada" }))
+        .send()
+        .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_form_extraction_flat_form() {
+    let app = Rapina::new().with_introspection(false).router(
+        Router::new().post_fn("/login", |form: Form<LoginForm>| async move {
+            format!("Welcome, {}!", form.0.username)
+        }),
+    );
+
+    let client = TestClient::new(app).await;
+    let response = client
+        .post("/login")
+        .form(&serde_json::json!({
+            "username": "alice",
+            "password": "secret123"
+        }))
+        .send()
+        .await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.text(), "Welcome, alice!");
+}
+
 // State Extractor Tests
 
 #[derive(Clone)]
@@ -559,6 +898,263 @@ async fn test_validated_extraction_empty_name() {
     assert_eq!(response.status(), 422); // Validation error
 }
 
+#[derive(Debug, Deserialize, Validate)]
+struct LineItem {
+    #[validate(length(min = 1))]
+    name: String,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+struct CreateOrder {
+    #[validate(email)]
+    email: String,
+    #[validate(range(min = 1, max = 10))]
+    quantity: u32,
+    #[validate(nested)]
+    items: Vec<LineItem>,
+}
+
+#[tokio::test]
+async fn test_deserialization_failure_is_400_and_validation_failure_is_422() {
+    let app = Rapina::new().with_introspection(false).router(
+        Router::new().post_fn("/users", |body: Validated<Json<CreateUser>>| async move {
+            body.into_inner().0.name
+        }),
+    );
+    let client = TestClient::new(app).await;
+
+    let response = client
+        .post("/users")
+        .header("content-type", "application/json")
+        .body("{\"name\": \"Alice\",")
+        .send()
+        .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(
+        response.json::<serde_json::Value>()["error"]["code"],
+        "BAD_REQUEST"
+    );
+
+    let response = client
+        .post("/users")
+        .json(&serde_json::json!({ "name": "Alice" }))
+        .send()
+        .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = client
+        .post("/users")
+        .json(&serde_json::json!({ "name": 42, "email": "alice@example.com" }))
+        .send()
+        .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = client
+        .post("/users")
+        .json(&serde_json::json!({ "name": "Alice", "email": "not-an-email" }))
+        .send()
+        .await;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(
+        response.json::<serde_json::Value>()["error"]["code"],
+        "VALIDATION_ERROR"
+    );
+}
+
+#[derive(Debug, Deserialize, Validate)]
+struct PageParams {
+    #[validate(range(min = 1, max = 100))]
+    limit: u32,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+struct SlugPath {
+    #[validate(length(min = 3))]
+    slug: String,
+}
+
+#[tokio::test]
+async fn test_validated_query_and_path() {
+    let app = Rapina::new().with_introspection(false).router(
+        Router::new()
+            .get_fn("/items", |page: Validated<Query<PageParams>>| async move {
+                format!("limit {}", page.into_inner().0.limit)
+            })
+            .get_fn(
+                "/tags/:slug",
+                |path: Validated<Path<SlugPath>>| async move { path.into_inner().0.slug },
+            ),
+    );
+    let client = TestClient::new(app).await;
+
+    let response = client.get("/items?limit=10").send().await;
+    assert_eq!(response.text(), "limit 10");
+    let response = client.get("/items?limit=ten").send().await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let response = client.get("/items?limit=500").send().await;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let json: serde_json::Value = response.json();
+    assert_eq!(json["error"]["details"]["limit"][0]["code"], "range");
+
+    let response = client.get("/tags/rust").send().await;
+    assert_eq!(response.text(), "rust");
+    let response = client.get("/tags/go").send().await;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct SchemaPageParams {
+    #[schemars(range(min = 1, max = 100))]
+    limit: Option<u32>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct SchemaSlugPath {
+    #[schemars(length(min = 3), regex(pattern = "^[a-z-]+$"))]
+    slug: String,
+}
+
+#[tokio::test]
+async fn test_schema_validated_query_and_path() {
+    let app =
+        Rapina::new().with_introspection(false).router(
+            Router::new()
+                .get_fn(
+                    "/items",
+                    |page: SchemaValidated<Query<SchemaPageParams>>| async move {
+                        format!("limit {:?}", page.into_inner().0.limit)
+                    },
+                )
+                .get_fn(
+                    "/tags/:slug",
+                    |path: SchemaValidated<Path<SchemaSlugPath>>| async move {
+                        path.into_inner().0.slug
+                    },
+                ),
+        );
+    let client = TestClient::new(app).await;
+
+    let response = client.get("/items?limit=100").send().await;
+    assert_eq!(response.text(), "limit Some(100)");
+    let response = client.get("/items").send().await;
+    assert_eq!(response.text(), "limit None");
+    let response = client.get("/items?limit=ten").send().await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    for limit in ["0", "101"] {
+        let response = client.get(&format!("/items?limit={}", limit)).send().await;
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let json: serde_json::Value = response.json();
+        assert_eq!(json["error"]["code"], "VALIDATION_ERROR");
+        assert_eq!(json["error"]["details"]["limit"][0]["code"], "range");
+        assert_eq!(
+            json["error"]["details"]["limit"][0]["message"],
+            "must be between 1 and 100"
+        );
+    }
+
+    let response = client.get("/tags/rust-lang").send().await;
+    assert_eq!(response.text(), "rust-lang");
+    let response = client.get("/tags/Rust").send().await;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let json: serde_json::Value = response.json();
+    assert_eq!(json["error"]["details"]["slug"][0]["code"], "regex");
+    let response = client.get("/tags/go").send().await;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn test_validated_extraction_field_details() {
+    let app = Rapina::new().with_introspection(false).router(
+        Router::new().post_fn("/orders", |body: Validated<Json<CreateOrder>>| async move {
+            body.into_inner().0.email
+        }),
+    );
+
+    let client = TestClient::new(app).await;
+    let response = client
+        .post("/orders")
+        .json(&serde_json::json!({
+            "email": "not-an-email",
+            "quantity": 0,
+            "items": [{ "name": "book" }, { "name": "" }]
+        }))
+        .send()
+        .await;
+
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let json: serde_json::Value = response.json();
+    assert_eq!(json["error"]["code"], "VALIDATION_ERROR");
+
+    let details = &json["error"]["details"];
+    assert_eq!(details.as_object().unwrap().len(), 3);
+    assert_eq!(
+        details["email"],
+        serde_json::json!([{ "code": "email", "message": "must be a valid email address" }])
+    );
+    assert_eq!(
+        details["quantity"],
+        serde_json::json!([{ "code": "range", "message": "must be between 1 and 10" }])
+    );
+    assert_eq!(details["items[1].name"][0]["code"], "length");
+    assert_eq!(
+        details["items[1].name"][0]["message"],
+        "must be at least 1 characters long"
+    );
+}
+
+#[derive(Debug, Deserialize, Validate)]
+struct SignUp {
+    #[validate(email)]
+    email: String,
+    #[validate(length(min = 8))]
+    password: String,
+    #[validate(length(min = 1, message = "Tell us your name"))]
+    name: String,
+}
+
+#[tokio::test]
+async fn test_validated_extraction_custom_messages() {
+    let app = Rapina::new()
+        .with_introspection(false)
+        .with_validation_messages(
+            ValidationMessages::new()
+                .code("email", "Please enter a valid email address")
+                .code("length", "Too short")
+                .field("password", "length", "Use at least 8 characters"),
+        )
+        .router(
+            Router::new().post_fn("/signup", |body: Validated<Json<SignUp>>| async move {
+                body.into_inner().0.email
+            }),
+        );
+
+    let client = TestClient::new(app).await;
+    let response = client
+        .post("/signup")
+        .json(&serde_json::json!({
+            "email": "nope",
+            "password": "short",
+            "name": ""
+        }))
+        .send()
+        .await;
+
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let json: serde_json::Value = response.json();
+    let details = &json["error"]["details"];
+    assert_eq!(
+        details["email"][0]["message"],
+        "Please enter a valid email address"
+    );
+    assert_eq!(
+        details["password"][0]["message"],
+        "Use at least 8 characters"
+    );
+    // The message set on the constraint wins over the app-level map
+    assert_eq!(details["name"][0]["message"], "Tell us your name");
+}
+
 // Cookie Extractor Tests
 
 #[tokio::test]
@@ -664,3 +1260,214 @@ async fn test_cookie_extraction_missing() {
 
     assert_eq!(response.status(), StatusCode::BAD_REQUEST);
 }
+
+#[tokio::test]
+async fn test_connect_info_extractor() {
+    let app = Rapina::new().with_introspection(false).router(
+        Router::new().get_fn("/ip", |client: ConnectInfo| async move {
+            client.0.ip().to_string()
+        }),
+    );
+    let client = TestClient::new(app).await;
+
+    let response = client.get("/ip").send().await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.text(), "127.0.0.1");
+}
+
+#[tokio::test]
+async fn test_lazy_state_is_built_once() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[derive(Clone)]
+    struct Pool {
+        id: usize,
+    }
+
+    let runs = Arc::new(AtomicUsize::new(0));
+    let counter = runs.clone();
+    let app = Rapina::new()
+        .with_introspection(false)
+        .state_with(move || {
+            let counter = counter.clone();
+            async move {
+                tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+                Pool {
+                    id: counter.fetch_add(1, Ordering::SeqCst),
+                }
+            }
+        })
+        .router(
+            Router::new().get_fn("/pool", |pool: State<Pool>| async move {
+                pool.into_inner().id.to_string()
+            }),
+        );
+    let client = TestClient::new(app).await;
+    assert_eq!(runs.load(Ordering::SeqCst), 0);
+
+    let responses =
+        futures_util::future::join_all((0..8).map(|_| client.get("/pool").send())).await;
+    for response in responses {
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.text(), "0");
+    }
+    assert_eq!(runs.load(Ordering::SeqCst), 1);
+}
+
+#[derive(Deserialize, Serialize)]
+struct NewComment {
+    text: String,
+}
+
+#[derive(FromRequest)]
+struct CreateComment {
+    #[from(path)]
+    post_id: u64,
+    #[from(query)]
+    notify: Option<bool>,
+    #[from(header = "x-api-key")]
+    api_key: String,
+    #[from(header)]
+    user_agent: Option<String>,
+    #[from(body)]
+    comment: NewComment,
+    ctx: Context,
+}
+
+#[derive(FromRequest)]
+struct ListComments {
+    #[from(path = "post_id")]
+    post: u64,
+    #[from(query)]
+    page: u32,
+}
+
+#[post("/posts/:post_id/comments")]
+async fn create_comment(req: CreateComment) -> String {
+    format!(
+        "{} {:?} {} {:?} {} {}",
+        req.post_id,
+        req.notify,
+        req.api_key,
+        req.user_agent,
+        req.comment.text,
+        req.ctx.trace_id().is_empty()
+    )
+}
+
+#[tokio::test]
+async fn test_derive_from_request_combines_sources() {
+    let app = Rapina::new().with_introspection(false).router(
+        Router::new()
+            .post("/posts/:post_id/comments", create_comment)
+            .get_fn(
+                "/posts/:post_id/comments",
+                |list: ListComments,
+                 Query(raw): Query<std::collections::HashMap<String, String>>| async move {
+                    format!("{} {} {}", list.post, list.page, raw.len())
+                },
+            ),
+    );
+    let client = TestClient::new(app).await;
+
+    let response = client
+        .post("/posts/7/comments?notify=true")
+        .header("x-api-key", "secret")
+        .header("user-agent", "tests")
+        .json(&NewComment {
+            text: "hello".to_string(),
+        })
+        .send()
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.text(),
+        "7 Some(true) secret Some(\"tests\") hello false"
+    );
+
+    // A parts-only struct can sit in front of other extractors
+    let response = client.get("/posts/7/comments?page=2").send().await;
+    assert_eq!(response.text(), "7 2 1");
+}
+
+#[tokio::test]
+async fn test_derive_from_request_rejections() {
+    let app = Rapina::new()
+        .with_introspection(false)
+        .router(Router::new().post("/posts/:post_id/comments", create_comment));
+    let client = TestClient::new(app).await;
+
+    let comment = NewComment {
+        text: "hello".to_string(),
+    };
+    let response = client.post("/posts/7/comments").json(&comment).send().await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let json: serde_json::Value = response.json();
+    assert_eq!(
+        json["error"]["message"],
+        "Missing required header 'x-api-key'"
+    );
+
+    let response = client
+        .post("/posts/abc/comments")
+        .header("x-api-key", "secret")
+        .json(&comment)
+        .send()
+        .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = client
+        .post("/posts/7/comments")
+        .header("x-api-key", "secret")
+        .body("not json")
+        .send()
+        .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_derive_from_request_rejection_names_field_and_source() {
+    let app = Rapina::new()
+        .with_introspection(false)
+        .router(Router::new().post("/posts/:post_id/comments", create_comment));
+    let client = TestClient::new(app).await;
+
+    let comment = NewComment {
+        text: "hello".to_string(),
+    };
+    let response = client.post("/posts/7/comments").json(&comment).send().await;
+    let json: serde_json::Value = response.json();
+    assert_eq!(json["error"]["details"]["source"], "header");
+    assert_eq!(json["error"]["details"]["field"], "x-api-key");
+
+    let response = client
+        .post("/posts/abc/comments")
+        .header("x-api-key", "secret")
+        .json(&comment)
+        .send()
+        .await;
+    let json: serde_json::Value = response.json();
+    assert_eq!(
+        json["error"]["message"],
+        "Invalid path parameter 'post_id': invalid type: string \"abc\", expected u64"
+    );
+    assert_eq!(json["error"]["details"]["source"], "path");
+    assert_eq!(json["error"]["details"]["field"], "post_id");
+
+    let response = client
+        .post("/posts/7/comments?notify=maybe")
+        .header("x-api-key", "secret")
+        .json(&comment)
+        .send()
+        .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let json: serde_json::Value = response.json();
+    assert_eq!(json["error"]["details"]["source"], "query");
+    assert_eq!(json["error"]["details"]["field"], "notify");
+    let message = json["error"]["message"].as_str().unwrap();
+    assert!(
+        message.starts_with("Invalid query parameter 'notify':"),
+        "{}",
+        message
+    );
+}
@@ -0,0 +1,414 @@
+//! Integration tests for route introspection metadata.
+
+use rapina::middleware::{CompressionConfig, CorsConfig};
+use rapina::prelude::*;
+
+#[derive(Deserialize, JsonSchema)]
+#[allow(dead_code)]
+struct CreateUser {
+    name: String,
+    email: String,
+}
+
+#[derive(Serialize, JsonSchema)]
+struct User {
+    id: u64,
+    name: String,
+}
+
+#[derive(Deserialize)]
+#[allow(dead_code)]
+struct UndocumentedBody {
+    value: String,
+}
+
+#[derive(Deserialize, JsonSchema)]
+#[allow(dead_code)]
+struct ListFilters {
+    page: u32,
+    search: Option<String>,
+}
+
+#[post("/users")]
+async fn create_user(body: Json<CreateUser>) -> Json<User> {
+    Json(User {
+        id: 1,
+        name: body.0.name,
+    })
+}
+
+#[get("/users/:id")]
+async fn get_user(id: Path<u64>) -> Json<User> {
+    Json(User {
+        id: id.into_inner(),
+        name: "Alice".to_string(),
+    })
+}
+
+#[derive(Deserialize, JsonSchema)]
+#[allow(dead_code)]
+struct PostPath {
+    user_id: u64,
+    slug: String,
+}
+
+#[get("/users/:user_id/posts/:slug")]
+async fn get_post(path: Path<PostPath>) -> String {
+    path.into_inner().slug
+}
+
+#[get("/users")]
+async fn list_users(filters: Query<ListFilters>) -> String {
+    format!("page {}", filters.0.page)
+}
+
+#[post("/undocumented")]
+async fn undocumented(body: Json<UndocumentedBody>) -> String {
+    body.0.value
+}
+
+#[test]
+fn test_post_handler_request_schema() {
+    let routes = Router::new().post("/users", create_user).routes();
+
+    let schema = routes[0].request_schema.as_ref().unwrap();
+    assert_eq!(schema["title"], "CreateUser");
+    assert_eq!(schema["properties"]["name"]["type"], "string");
+    assert_eq!(schema["properties"]["email"]["type"], "string");
+    assert!(routes[0].response_schema.is_some());
+}
+
+#[test]
+fn test_path_param_schema() {
+    let routes = Router::new().get("/users/:id", get_user).routes();
+
+    assert_eq!(routes[0].path_params.len(), 1);
+    assert_eq!(routes[0].path_params[0].name, "id");
+    assert!(routes[0].path_params[0].required);
+    assert_eq!(
+        routes[0].path_params[0].schema.as_ref().unwrap()["type"],
+        "integer"
+    );
+    assert!(routes[0].request_schema.is_none());
+}
+
+#[test]
+fn test_path_params_from_struct_schema() {
+    let routes = Router::new()
+        .get("/users/:user_id/posts/:slug", get_post)
+        .routes();
+    let params = &routes[0].path_params;
+
+    assert_eq!(params.len(), 2);
+    assert_eq!(params[0].name, "user_id");
+    assert_eq!(params[0].schema.as_ref().unwrap()["type"], "integer");
+    assert_eq!(params[1].name, "slug");
+    assert_eq!(params[1].schema.as_ref().unwrap()["type"], "string");
+}
+
+#[test]
+fn test_query_params_from_schema() {
+    let routes = Router::new().get("/users", list_users).routes();
+    let params = &routes[0].query_params;
+
+    let page = params.iter().find(|p| p.name == "page").unwrap();
+    assert!(page.required);
+    let search = params.iter().find(|p| p.name == "search").unwrap();
+    assert!(!search.required);
+}
+
+#[test]
+fn test_body_without_json_schema() {
+    let routes = Router::new().post("/undocumented", undocumented).routes();
+
+    assert!(routes[0].request_schema.is_none());
+}
+
+#[test]
+fn test_group_keeps_schemas() {
+    let routes = Router::new()
+        .group("/api", Router::new().post("/users", create_user))
+        .routes();
+
+    assert_eq!(routes[0].path, "/api/users");
+    assert!(routes[0].request_schema.is_some());
+}
+
+#[derive(Serialize, JsonSchema)]
+struct CreatedUser {
+    id: u64,
+    location: String,
+}
+
+#[post("/users/upsert")]
+#[responses((201, CreatedUser), (200, User))]
+async fn upsert_user(body: Json<CreateUser>) -> (StatusCode, Json<User>) {
+    (
+        StatusCode::OK,
+        Json(User {
+            id: 1,
+            name: body.0.name,
+        }),
+    )
+}
+
+#[test]
+fn test_declared_responses() {
+    let routes = Router::new().post("/users/upsert", upsert_user).routes();
+
+    let responses = &routes[0].responses;
+    assert_eq!(responses.len(), 2);
+    assert_eq!(responses[0].status, 201);
+    assert_eq!(responses[0].content_type, "application/json");
+    assert_eq!(
+        responses[0].schema.as_ref().unwrap()["title"],
+        "CreatedUser"
+    );
+    assert_eq!(responses[1].status, 200);
+    assert_eq!(responses[1].schema.as_ref().unwrap()["title"], "User");
+
+    let spec = rapina::openapi::build_openapi_spec("Test", "1.0.0", &routes);
+    let op = spec.paths["/users/upsert"].post.as_ref().unwrap();
+    assert!(op.responses.contains_key("200"));
+    assert!(op.responses.contains_key("201"));
+}
+
+#[get("/v1/users")]
+#[deprecated(note = "use /users instead")]
+async fn list_users_v1() -> &'static str {
+    "users"
+}
+
+#[test]
+fn test_deprecated_route() {
+    let router = Router::new()
+        .get("/v1/users", list_users_v1)
+        .get("/users", list_users);
+    let routes = router.routes();
+
+    assert!(routes[0].deprecated);
+    assert!(!routes[1].deprecated);
+
+    let spec = rapina::openapi::build_openapi_spec("Test", "1.0.0", &routes);
+    assert!(spec.paths["/v1/users"].get.as_ref().unwrap().deprecated);
+    assert!(!spec.paths["/users"].get.as_ref().unwrap().deprecated);
+}
+
+#[tokio::test]
+async fn test_deprecated_route_still_serves() {
+    let app = Rapina::new()
+        .with_introspection(false)
+        .router(Router::new().get("/v1/users", list_users_v1));
+
+    let client = rapina::testing::TestClient::new(app).await;
+    client
+        .get("/v1/users")
+        .send()
+        .await
+        .assert_status(StatusCode::OK);
+}
+
+#[get("/admin/stats")]
+#[openapi(hidden)]
+async fn admin_stats() -> &'static str {
+    "stats"
+}
+
+#[tokio::test]
+async fn test_hidden_route_is_left_out_of_openapi() {
+    let router = Router::new()
+        .get("/admin/stats", admin_stats)
+        .get("/users", list_users);
+    let routes = router.routes();
+
+    assert!(routes[0].hidden);
+    assert_eq!(routes[0].path, "/admin/stats");
+
+    let spec = rapina::openapi::build_openapi_spec("Test", "1.0.0", &routes);
+    assert!(!spec.paths.contains_key("/admin/stats"));
+    assert!(spec.paths.contains_key("/users"));
+
+    // Still registered and served
+    let app = Rapina::new().with_introspection(false).router(router);
+    let client = rapina::testing::TestClient::new(app).await;
+    assert_eq!(client.get("/admin/stats").send().await.text(), "stats");
+}
+
+#[get("/teams")]
+#[tag("teams")]
+async fn list_teams() -> &'static str {
+    "teams"
+}
+
+#[tokio::test]
+async fn test_tagged_route_in_openapi() {
+    let app = Rapina::new()
+        .with_introspection(false)
+        .openapi("Test", "1.0.0")
+        .openapi_tag("teams", "Team management")
+        .router(Router::new().get("/teams", list_teams));
+
+    let client = rapina::testing::TestClient::new(app).await;
+    let response = client.get("/__rapina/openapi.json").send().await;
+    let spec: serde_json::Value = response.json();
+
+    assert_eq!(
+        spec["paths"]["/teams"]["get"]["tags"],
+        serde_json::json!(["teams"])
+    );
+    assert_eq!(spec["tags"][0]["name"], "teams");
+    assert_eq!(spec["tags"][0]["description"], "Team management");
+}
+
+#[tokio::test]
+async fn test_openapi_servers_and_base_path() {
+    let app = Rapina::new()
+        .with_introspection(false)
+        .openapi("Test", "1.0.0")
+        .openapi_server("https://api.example.com", "Production")
+        .openapi_server("http://localhost:3000", "")
+        .openapi_base_path("/api")
+        .router(Router::new().get("/teams", list_teams));
+
+    let client = rapina::testing::TestClient::new(app).await;
+    let response = client.get("/__rapina/openapi.json").send().await;
+    let spec: serde_json::Value = response.json();
+
+    assert_eq!(
+        spec["servers"],
+        serde_json::json!([
+            {"url": "https://api.example.com", "description": "Production"},
+            {"url": "http://localhost:3000"}
+        ])
+    );
+    assert!(spec["paths"].get("/api/teams").is_some());
+    assert!(spec["paths"].get("/teams").is_none());
+
+    // The base path only affects the spec
+    client
+        .get("/teams")
+        .send()
+        .await
+        .assert_status(StatusCode::OK);
+}
+
+#[post("/users/examples")]
+#[example(request = r#"{"name": "Alice", "email": "alice@example.com"}"#)]
+async fn create_user_with_example(body: Json<CreateUser>) -> Json<User> {
+    Json(User {
+        id: 1,
+        name: body.0.name,
+    })
+}
+
+#[test]
+fn test_request_example_in_openapi() {
+    let routes = Router::new()
+        .post("/users/examples", create_user_with_example)
+        .routes();
+    assert_eq!(
+        routes[0].request_example.as_ref().unwrap()["email"],
+        "alice@example.com"
+    );
+
+    let spec = rapina::openapi::build_openapi_spec("Test", "1.0.0", &routes);
+    let op = spec.paths["/users/examples"].post.as_ref().unwrap();
+    let media = &op.request_body.as_ref().unwrap().content["application/json"];
+    assert_eq!(media.example.as_ref().unwrap()["name"], "Alice");
+}
+
+#[test]
+fn test_shared_request_body_is_one_component() {
+    let routes = Router::new()
+        .post("/users", create_user)
+        .post("/users/upsert", upsert_user)
+        .routes();
+    let spec = rapina::openapi::build_openapi_spec("Test", "1.0.0", &routes);
+    let json = serde_json::to_value(&spec).unwrap();
+
+    let refs: Vec<_> = ["/users", "/users/upsert"]
+        .iter()
+        .map(|path| {
+            json["paths"][path]["post"]["requestBody"]["content"]["application/json"]["schema"]
+                ["$ref"]
+                .clone()
+        })
+        .collect();
+    assert_eq!(refs, ["#/components/schemas/CreateUser"; 2]);
+
+    let schemas = json["components"]["schemas"].as_object().unwrap();
+    assert_eq!(
+        schemas.keys().filter(|name| *name == "CreateUser").count(),
+        1
+    );
+    assert_eq!(
+        schemas["CreateUser"]["required"],
+        serde_json::json!(["name", "email"])
+    );
+}
+
+#[tokio::test]
+async fn test_app_info_lists_middleware() {
+    let app = Rapina::new()
+        .with_introspection(true)
+        .with_cors(CorsConfig::permissive())
+        .with_compression(CompressionConfig::default())
+        .router(Router::new().get("/users/:id", get_user));
+
+    let client = rapina::testing::TestClient::new(app).await;
+    let response = client.get("/__rapina/info").send().await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let info: serde_json::Value = response.json();
+    let middleware: Vec<&str> = info["middleware"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|m| m.as_str().unwrap())
+        .collect();
+    assert!(middleware.contains(&"CorsMiddleware"), "{:?}", middleware);
+    assert!(
+        middleware.contains(&"CompressionMiddleware"),
+        "{:?}",
+        middleware
+    );
+    assert_eq!(info["features"]["cors"], true);
+    assert_eq!(info["features"]["compression"], true);
+    assert_eq!(info["features"]["auth"], false);
+    assert_eq!(info["features"]["rate_limit"], false);
+    assert_eq!(info["rapina_version"], env!("CARGO_PKG_VERSION"));
+}
+
+#[tokio::test]
+async fn test_app_info_disabled_without_introspection() {
+    let app = Rapina::new()
+        .with_introspection(false)
+        .router(Router::new().get("/users/:id", get_user));
+
+    let client = rapina::testing::TestClient::new(app).await;
+    let response = client.get("/__rapina/info").send().await;
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_openapi_served_bytes_are_cached_and_match_export() {
+    let app = || {
+        Rapina::new()
+            .with_introspection(false)
+            .openapi("Test", "1.0.0")
+            .openapi_tag("teams", "Team management")
+            .router(Router::new().get("/teams", list_teams))
+    };
+
+    let exported = app().export_openapi();
+    let spec: serde_json::Value = serde_json::from_str(&exported).unwrap();
+    assert_eq!(spec["info"]["title"], "Test");
+    assert!(spec["paths"]["/teams"].is_object());
+
+    let client = rapina::testing::TestClient::new(app()).await;
+    let first = client.get("/__rapina/openapi.json").send().await;
+    let second = client.get("/__rapina/openapi.json").send().await;
+    assert_eq!(first.bytes(), second.bytes());
+    assert_eq!(first.text(), exported);
+}
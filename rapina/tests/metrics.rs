@@ -131,6 +131,230 @@ async fn test_metrics_numeric_path_segments_normalised() {
     assert!(body.contains(r#"path="/users/:id""#));
 }
 
+// ── custom metrics ────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn test_custom_counter_exported() {
+    let app = Rapina::new()
+        .with_introspection(false)
+        .with_metrics(true)
+        .router(
+            Router::new().route(http::Method::POST, "/orders", |_, _, state| async move {
+                let metrics = state.get::<MetricsRegistry>().unwrap();
+                metrics
+                    .counter("orders_created_total", "Orders created", &["plan"])
+                    .inc(&["pro"]);
+                StatusCode::CREATED
+            }),
+        );
+
+    let client = TestClient::new(app).await;
+
+    client.post("/orders").send().await;
+    client.post("/orders").send().await;
+
+    let body = client.get("/metrics").send().await.text();
+    assert!(body.contains("# HELP orders_created_total Orders created"));
+    assert!(body.contains(r#"orders_created_total{plan="pro"} 2"#));
+}
+
+// ── route pattern labels ──────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn test_metrics_labeled_by_route_pattern() {
+    let client = TestClient::new(app_with_metrics()).await;
+
+    // Non-numeric ids are not caught by path normalisation, only by the route pattern
+    client.get("/users/alice").send().await;
+    client.get("/users/bob").send().await;
+    client.post("/users").send().await;
+
+    let body = client.get("/metrics").send().await.text();
+    assert!(!body.contains(r#"path="/users/alice""#));
+    assert!(body.contains(r#"http_requests_total{method="GET",path="/users/:id",status="200"} 2"#));
+    assert!(body.contains(r#"http_requests_total{method="POST",path="/users",status="201"} 1"#));
+    assert!(body.contains(
+        r#"http_request_duration_seconds_count{method="GET",path="/users/:id",status="200"} 2"#
+    ));
+}
+
+#[tokio::test]
+async fn test_metrics_duration_sum_is_plausible() {
+    let client = TestClient::new(app_with_metrics()).await;
+
+    client.get("/health").send().await;
+
+    let body = client.get("/metrics").send().await.text();
+    let sum: f64 = body
+        .lines()
+        .find(|line| {
+            line.starts_with(
+                r#"http_request_duration_seconds_sum{method="GET",path="/health",status="200"}"#,
+            )
+        })
+        .and_then(|line| line.rsplit(' ').next())
+        .and_then(|value| value.parse().ok())
+        .expect("duration sum series missing");
+
+    assert!(sum > 0.0 && sum < 5.0);
+}
+
+// ── in-flight gauge and error counter ────────────────────────────────────────
+
+fn metric_value(body: &str, series: &str) -> f64 {
+    body.lines()
+        .find(|line| line.starts_with(series) && line[series.len()..].starts_with(' '))
+        .and_then(|line| line.rsplit(' ').next())
+        .and_then(|value| value.parse().ok())
+        .unwrap_or_else(|| panic!("series {} missing", series))
+}
+
+#[tokio::test]
+async fn test_metrics_in_flight_counts_concurrent_requests() {
+    let app = Rapina::new()
+        .with_introspection(false)
+        .with_metrics(true)
+        .router(
+            Router::new().route(http::Method::GET, "/slow", |_, _, _| async {
+                tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+                "done"
+            }),
+        );
+    let client = TestClient::new(app).await;
+
+    let scrape = async {
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        client.get("/metrics").send().await.text()
+    };
+    let (_, _, _, body) = tokio::join!(
+        client.get("/slow").send(),
+        client.get("/slow").send(),
+        client.get("/slow").send(),
+        scrape,
+    );
+    // The scrape itself is in flight too
+    assert_eq!(metric_value(&body, "http_requests_in_flight"), 4.0);
+
+    let body = client.get("/metrics").send().await.text();
+    assert_eq!(metric_value(&body, "http_requests_in_flight"), 1.0);
+}
+
+#[tokio::test]
+async fn test_metrics_errors_counted_by_status_class() {
+    let app = Rapina::new()
+        .with_introspection(false)
+        .with_metrics(true)
+        .router(
+            Router::new()
+                .route(http::Method::GET, "/fail", |_, _, _| async {
+                    StatusCode::SERVICE_UNAVAILABLE
+                })
+                .route(http::Method::GET, "/health", |_, _, _| async { "ok" }),
+        );
+    let client = TestClient::new(app).await;
+
+    client.get("/fail").send().await;
+    client.get("/fail").send().await;
+    client.get("/health").send().await;
+    client.get("/missing").send().await;
+
+    let body = client.get("/metrics").send().await.text();
+    assert_eq!(
+        metric_value(
+            &body,
+            r#"http_request_errors_total{class="5xx",method="GET",path="/fail"}"#
+        ),
+        2.0
+    );
+    assert_eq!(
+        metric_value(
+            &body,
+            r#"http_request_errors_total{class="4xx",method="GET",path="/missing"}"#
+        ),
+        1.0
+    );
+    assert!(!body.contains(r#"class="2xx""#));
+}
+
+// ── separate metrics listener ────────────────────────────────────────────────
+
+async fn raw_get(addr: std::net::SocketAddr, path: &str) -> String {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    // The listeners start on a spawned task, so retry until they accept
+    let mut stream = loop {
+        match tokio::net::TcpStream::connect(addr).await {
+            Ok(stream) => break stream,
+            Err(_) => tokio::time::sleep(std::time::Duration::from_millis(10)).await,
+        }
+    };
+    let request = format!(
+        "GET {} HTTP/1.1\r\nhost: localhost\r\nconnection: close\r\n\r\n",
+        path
+    );
+    stream.write_all(request.as_bytes()).await.unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    response
+}
+
+#[tokio::test]
+async fn test_metrics_bind_hides_endpoint_from_app() {
+    let app = Rapina::new()
+        .with_introspection(false)
+        .with_metrics_bind("127.0.0.1:0")
+        .router(Router::new().route(http::Method::GET, "/health", |_, _, _| async { "ok" }));
+    let client = TestClient::new(app).await;
+
+    let response = client.get("/metrics").send().await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let response = client.get("/health").send().await;
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_metrics_bind_serves_on_separate_listener() {
+    let metrics_addr = {
+        let probe = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        probe.local_addr().unwrap()
+    };
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let app_addr = listener.local_addr().unwrap();
+    let app = Rapina::new()
+        .with_introspection(false)
+        .with_metrics_bind(&metrics_addr.to_string())
+        .router(Router::new().route(http::Method::GET, "/health", |_, _, _| async { "ok" }));
+    tokio::spawn(app.listen_on(listener));
+
+    let response = raw_get(app_addr, "/health").await;
+    assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+    let response = raw_get(app_addr, "/metrics").await;
+    assert!(response.starts_with("HTTP/1.1 404"), "{}", response);
+
+    let response = raw_get(metrics_addr, "/metrics").await;
+    assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+    assert!(
+        response.contains(r#"http_requests_total{method="GET",path="/health",status="200"} 1"#),
+        "{}",
+        response
+    );
+    let response = raw_get(metrics_addr, "/health").await;
+    assert!(response.starts_with("HTTP/1.1 404"), "{}", response);
+}
+
+#[tokio::test]
+async fn test_metrics_bind_failure_stops_startup() {
+    let taken = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let app = Rapina::new()
+        .with_introspection(false)
+        .with_metrics_bind(&taken.local_addr().unwrap().to_string())
+        .router(Router::new().route(http::Method::GET, "/health", |_, _, _| async { "ok" }));
+
+    let err = app.listen_on(listener).await.unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::AddrInUse);
+}
+
 // ── disabled by default ───────────────────────────────────────────────────────
 
 #[tokio::test]
@@ -145,6 +369,19 @@ async fn test_metrics_disabled_by_default() {
     assert_eq!(response.status(), StatusCode::NOT_FOUND);
 }
 
+#[tokio::test]
+async fn test_exported_openapi_matches_served_spec() {
+    let app = || app_with_metrics().openapi("Test", "1.0.0");
+
+    let exported = app().export_openapi();
+    let spec: serde_json::Value = serde_json::from_str(&exported).unwrap();
+    assert!(spec["paths"]["/metrics"].is_object());
+
+    let client = TestClient::new(app()).await;
+    let served = client.get("/__rapina/openapi.json").send().await;
+    assert_eq!(served.text(), exported);
+}
+
 // ── MetricsRegistry unit-level via state ─────────────────────────────────────
 
 #[test]
@@ -0,0 +1,126 @@
+//! Integration tests for MessagePack bodies.
+
+#![cfg(feature = "msgpack")]
+
+use http::StatusCode;
+use rapina::extract::{Body, MsgPack};
+use rapina::prelude::*;
+use rapina::testing::TestClient;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Reading {
+    sensor: String,
+    value: f64,
+}
+
+fn reading() -> Reading {
+    Reading {
+        sensor: "t1".to_string(),
+        value: 21.5,
+    }
+}
+
+#[tokio::test]
+async fn test_msgpack_round_trip() {
+    let app = Rapina::new()
+        .with_introspection(false)
+        .router(Router::new().route(
+            http::Method::POST,
+            "/readings",
+            |req, params, state| async move {
+                use rapina::extract::FromRequest;
+                let reading = MsgPack::<Reading>::from_request(req, &params, &state).await?;
+                Ok::<_, Error>((StatusCode::CREATED, reading))
+            },
+        ));
+    let client = TestClient::new(app).await;
+
+    let response = client
+        .post("/readings")
+        .header("content-type", "application/msgpack")
+        .body(rmp_serde::to_vec_named(&reading()).unwrap())
+        .send()
+        .await;
+
+    assert_eq!(response.status(), StatusCode::CREATED);
+    assert_eq!(response.headers()["content-type"], "application/msgpack");
+    let decoded: Reading = rmp_serde::from_slice(response.bytes()).unwrap();
+    assert_eq!(decoded, reading());
+}
+
+#[tokio::test]
+async fn test_msgpack_rejects_other_content_type() {
+    let app = Rapina::new()
+        .with_introspection(false)
+        .router(Router::new().route(
+            http::Method::POST,
+            "/readings",
+            |req, params, state| async move {
+                use rapina::extract::FromRequest;
+                MsgPack::<Reading>::from_request(req, &params, &state).await
+            },
+        ));
+    let client = TestClient::new(app).await;
+
+    let response = client.post("/readings").json(&reading()).send().await;
+    assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+}
+
+fn echo_app() -> Rapina {
+    Rapina::new()
+        .with_introspection(false)
+        .router(Router::new().route(
+            http::Method::POST,
+            "/echo",
+            |req, params, state| async move {
+                use rapina::extract::FromRequest;
+                Body::<Reading>::from_request(req, &params, &state).await
+            },
+        ))
+}
+
+#[tokio::test]
+async fn test_body_negotiates_request_and_response_formats() {
+    let client = TestClient::new(echo_app()).await;
+
+    // JSON in, MessagePack out
+    let response = client
+        .post("/echo")
+        .header("accept", "application/msgpack")
+        .json(&reading())
+        .send()
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["content-type"], "application/msgpack");
+    let decoded: Reading = rmp_serde::from_slice(response.bytes()).unwrap();
+    assert_eq!(decoded, reading());
+
+    // MessagePack in, JSON out by default
+    let response = client
+        .post("/echo")
+        .header("content-type", "application/msgpack")
+        .body(rmp_serde::to_vec_named(&reading()).unwrap())
+        .send()
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["content-type"], "application/json");
+    assert_eq!(response.json::<Reading>(), reading());
+}
+
+#[tokio::test]
+async fn test_body_rejects_unsupported_content_type() {
+    let client = TestClient::new(echo_app()).await;
+
+    let response = client
+        .post("/echo")
+        .header("content-type", "text/plain")
+        .body("sensor=t1")
+        .send()
+        .await;
+    assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    let json: serde_json::Value = response.json();
+    assert_eq!(
+        json["error"]["details"]["supported"],
+        serde_json::json!(["application/json", "application/msgpack"])
+    );
+}
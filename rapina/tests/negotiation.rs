@@ -0,0 +1,114 @@
+//! Integration tests for content negotiation.
+
+use http::StatusCode;
+use rapina::negotiate::Serializer;
+use rapina::prelude::*;
+use rapina::testing::TestClient;
+
+#[derive(Serialize)]
+struct User {
+    id: u64,
+    name: String,
+}
+
+fn user_router() -> Router {
+    Router::new().route(http::Method::GET, "/user", |_, _, _| async {
+        Negotiate(User {
+            id: 1,
+            name: "Alice".to_string(),
+        })
+    })
+}
+
+struct CsvSerializer;
+
+impl Serializer for CsvSerializer {
+    fn content_type(&self) -> &'static str {
+        "text/csv"
+    }
+
+    fn serialize(&self, value: &serde_json::Value) -> std::result::Result<Vec<u8>, String> {
+        let object = value
+            .as_object()
+            .ok_or_else(|| "expected an object".to_string())?;
+        let header: Vec<_> = object.keys().map(String::as_str).collect();
+        let row: Vec<_> = object
+            .values()
+            .map(|v| v.as_str().map(str::to_string).unwrap_or(v.to_string()))
+            .collect();
+        Ok(format!("{}\n{}", header.join(","), row.join(",")).into_bytes())
+    }
+}
+
+#[tokio::test]
+async fn test_negotiate_defaults_to_json() {
+    let app = Rapina::new()
+        .with_introspection(false)
+        .router(user_router());
+
+    let client = TestClient::new(app).await;
+    let response = client.get("/user").send().await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get("content-type").unwrap(),
+        "application/json"
+    );
+    let json: serde_json::Value = response.json();
+    assert_eq!(json["name"], "Alice");
+}
+
+#[tokio::test]
+async fn test_negotiate_json_accept() {
+    let app = Rapina::new()
+        .with_introspection(false)
+        .router(user_router());
+
+    let client = TestClient::new(app).await;
+    let response = client
+        .get("/user")
+        .header("accept", "text/html;q=0.9, application/json")
+        .send()
+        .await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let json: serde_json::Value = response.json();
+    assert_eq!(json["id"], 1);
+}
+
+#[tokio::test]
+async fn test_negotiate_unsupported_type() {
+    let app = Rapina::new()
+        .with_introspection(false)
+        .router(user_router());
+
+    let client = TestClient::new(app).await;
+    let response = client
+        .get("/user")
+        .header("accept", "application/xml")
+        .send()
+        .await;
+
+    assert_eq!(response.status(), StatusCode::NOT_ACCEPTABLE);
+    let json: serde_json::Value = response.json();
+    assert_eq!(json["error"]["code"], "NOT_ACCEPTABLE");
+}
+
+#[tokio::test]
+async fn test_negotiate_registered_serializer() {
+    let app = Rapina::new()
+        .with_introspection(false)
+        .serializer(CsvSerializer)
+        .router(user_router());
+
+    let client = TestClient::new(app).await;
+    let response = client
+        .get("/user")
+        .header("accept", "text/csv")
+        .send()
+        .await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers().get("content-type").unwrap(), "text/csv");
+    assert_eq!(response.text(), "id,name\n1,Alice");
+}
@@ -0,0 +1,107 @@
+//! Integration tests for OpenTelemetry span export.
+
+#![cfg(feature = "otel")]
+
+use std::sync::{Arc, Mutex};
+
+use http::StatusCode;
+use opentelemetry::trace::{SpanKind, TracerProvider};
+use opentelemetry_sdk::error::OTelSdkResult;
+use opentelemetry_sdk::trace::{SdkTracerProvider, SpanData, SpanExporter};
+use rapina::observability::OtelMiddleware;
+use rapina::prelude::*;
+use rapina::testing::TestClient;
+use tracing_subscriber::prelude::*;
+
+/// Collects exported spans in memory.
+#[derive(Debug, Clone, Default)]
+struct TestExporter {
+    spans: Arc<Mutex<Vec<SpanData>>>,
+}
+
+impl SpanExporter for TestExporter {
+    async fn export(&self, batch: Vec<SpanData>) -> OTelSdkResult {
+        self.spans.lock().unwrap().extend(batch);
+        Ok(())
+    }
+}
+
+fn app() -> Rapina {
+    Rapina::new()
+        .with_introspection(false)
+        .middleware(OtelMiddleware::new())
+        .router(
+            Router::new().route(http::Method::GET, "/users/:id", |_, _, _| async {
+                StatusCode::OK
+            }),
+        )
+}
+
+fn attribute(span: &SpanData, key: &str) -> Option<String> {
+    span.attributes
+        .iter()
+        .find(|kv| kv.key.as_str() == key)
+        .map(|kv| kv.value.to_string())
+}
+
+#[tokio::test]
+async fn test_span_exported_per_request() {
+    let exporter = TestExporter::default();
+    let provider = SdkTracerProvider::builder()
+        .with_simple_exporter(exporter.clone())
+        .build();
+    let subscriber = tracing_subscriber::registry()
+        .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("test")));
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    let client = TestClient::new(app()).await;
+    client.get("/users/1").send().await;
+    client.get("/users/2").send().await;
+
+    let spans = exporter.spans.lock().unwrap().clone();
+    assert_eq!(spans.len(), 2);
+
+    let span = &spans[0];
+    assert_eq!(span.name, "GET /users/:id");
+    assert_eq!(span.span_kind, SpanKind::Server);
+    assert_eq!(
+        attribute(span, "http.request.method").as_deref(),
+        Some("GET")
+    );
+    assert_eq!(attribute(span, "http.route").as_deref(), Some("/users/:id"));
+    assert_eq!(
+        attribute(span, "http.response.status_code").as_deref(),
+        Some("200")
+    );
+    assert!(attribute(span, "trace_id").is_some());
+    assert!(attribute(span, "latency_ms").is_some());
+}
+
+#[tokio::test]
+async fn test_span_continues_incoming_traceparent() {
+    let exporter = TestExporter::default();
+    let provider = SdkTracerProvider::builder()
+        .with_simple_exporter(exporter.clone())
+        .build();
+    let subscriber = tracing_subscriber::registry()
+        .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("test")));
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    let client = TestClient::new(app()).await;
+    client
+        .get("/users/1")
+        .header(
+            "traceparent",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+        )
+        .send()
+        .await;
+
+    let spans = exporter.spans.lock().unwrap().clone();
+    assert_eq!(spans.len(), 1);
+    assert_eq!(
+        spans[0].span_context.trace_id().to_string(),
+        "4bf92f3577b34da6a3ce929d0e0e4736"
+    );
+    assert_eq!(spans[0].parent_span_id.to_string(), "00f067aa0ba902b7");
+}
@@ -0,0 +1,84 @@
+//! Integration tests for paginated responses.
+
+use http::StatusCode;
+use rapina::prelude::*;
+use rapina::testing::TestClient;
+
+fn app() -> Rapina {
+    let router = Router::new().get_fn("/numbers", |pagination: Pagination| async move {
+        let total = 45;
+        let items: Vec<u64> = (pagination.offset()..total)
+            .take(pagination.limit() as usize)
+            .collect();
+        Page::new(items, pagination.page, pagination.per_page, total)
+    });
+    Rapina::new().with_introspection(false).router(router)
+}
+
+#[tokio::test]
+async fn test_page_envelope_shape() {
+    let client = TestClient::new(app()).await;
+
+    let response = client.get("/numbers?page=2&per_page=10").send().await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let json: serde_json::Value = response.json();
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "items": [10, 11, 12, 13, 14, 15, 16, 17, 18, 19],
+            "page": 2,
+            "per_page": 10,
+            "total": 45,
+            "total_pages": 5
+        })
+    );
+}
+
+#[tokio::test]
+async fn test_page_link_header() {
+    let client = TestClient::new(app()).await;
+
+    let response = client
+        .get("/numbers?filter=odd&page=2&per_page=10")
+        .send()
+        .await;
+    let link = response.headers()["link"].to_str().unwrap();
+    assert!(link.contains("</numbers?filter=odd&page=1&per_page=10>; rel=\"prev\""));
+    assert!(link.contains("</numbers?filter=odd&page=3&per_page=10>; rel=\"next\""));
+    assert!(link.contains("</numbers?filter=odd&page=5&per_page=10>; rel=\"last\""));
+
+    // The first page has no prev link, the last page no next link
+    let response = client.get("/numbers?per_page=10").send().await;
+    let link = response.headers()["link"].to_str().unwrap();
+    assert!(!link.contains("rel=\"prev\""));
+    assert!(link.contains("</numbers?page=2&per_page=10>; rel=\"next\""));
+
+    let response = client.get("/numbers?page=5&per_page=10").send().await;
+    let link = response.headers()["link"].to_str().unwrap();
+    assert!(link.contains("rel=\"prev\""));
+    assert!(!link.contains("rel=\"next\""));
+}
+
+#[tokio::test]
+async fn test_pagination_defaults_and_config() {
+    let client = TestClient::new(app()).await;
+    let json: serde_json::Value = client.get("/numbers").send().await.json();
+    assert_eq!(json["per_page"], 20);
+    assert_eq!(json["items"].as_array().unwrap().len(), 20);
+
+    let response = client.get("/numbers?page=0").send().await;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let app = app().with_pagination(
+        rapina::pagination::PaginationConfig::new()
+            .default_per_page(5)
+            .max_per_page(8),
+    );
+    let client = TestClient::new(app).await;
+    let json: serde_json::Value = client.get("/numbers").send().await.json();
+    assert_eq!(json["per_page"], 5);
+    let json: serde_json::Value = client.get("/numbers?per_page=50").send().await.json();
+    assert_eq!(json["per_page"], 8);
+    assert_eq!(json["total_pages"], 6);
+}
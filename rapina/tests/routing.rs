@@ -179,6 +179,167 @@ async fn test_route_with_trailing_slash() {
     assert_eq!(response.status(), StatusCode::NOT_FOUND);
 }
 
+fn trailing_slash_app(policy: TrailingSlash) -> Rapina {
+    Rapina::new()
+        .with_introspection(false)
+        .with_trailing_slash(policy)
+        .router(
+            Router::new()
+                .get_fn("/users", || async { "users list" })
+                .get_fn("/teams/", || async { "teams list" }),
+        )
+}
+
+#[tokio::test]
+async fn test_trailing_slash_strict() {
+    let client = TestClient::new(trailing_slash_app(TrailingSlash::Strict)).await;
+
+    assert_eq!(client.get("/users").send().await.status(), StatusCode::OK);
+    assert_eq!(
+        client.get("/users/").send().await.status(),
+        StatusCode::NOT_FOUND
+    );
+    assert_eq!(
+        client.get("/teams").send().await.status(),
+        StatusCode::NOT_FOUND
+    );
+}
+
+#[tokio::test]
+async fn test_trailing_slash_ignore() {
+    let client = TestClient::new(trailing_slash_app(TrailingSlash::Ignore)).await;
+
+    assert_eq!(client.get("/users").send().await.text(), "users list");
+    assert_eq!(client.get("/users/").send().await.text(), "users list");
+    assert_eq!(client.get("/teams").send().await.text(), "teams list");
+    assert_eq!(client.get("/teams/").send().await.text(), "teams list");
+}
+
+#[tokio::test]
+async fn test_trailing_slash_redirect_to_non_slash() {
+    let client = TestClient::new(trailing_slash_app(TrailingSlash::RedirectToNonSlash)).await;
+
+    let response = client.get("/users/?page=2").send().await;
+    assert_eq!(response.status(), StatusCode::PERMANENT_REDIRECT);
+    response.assert_header("location", "/users?page=2");
+
+    assert_eq!(client.get("/users").send().await.text(), "users list");
+
+    // A route registered with the slash is still served as is
+    assert_eq!(client.get("/teams/").send().await.text(), "teams list");
+
+    // No redirect for a method without a route
+    assert_eq!(
+        client.post("/users/").send().await.status(),
+        StatusCode::NOT_FOUND
+    );
+}
+
+#[tokio::test]
+async fn test_trailing_slash_redirect_stays_on_site() {
+    let app = Rapina::new()
+        .with_introspection(false)
+        .with_trailing_slash(TrailingSlash::RedirectToNonSlash)
+        .router(Router::new().get_fn("/:org/:repo", || async { "repo" }));
+    let client = TestClient::new(app).await;
+
+    let response = client.get("/acme/rapina/").send().await;
+    response.assert_header("location", "/acme/rapina");
+
+    // `//evil.com` would be a protocol-relative redirect to another site
+    let response = client.get("//evil.com/").send().await;
+    assert_ne!(response.status(), StatusCode::PERMANENT_REDIRECT);
+    assert!(response.headers().get("location").is_none());
+}
+
+#[tokio::test]
+async fn test_case_insensitive_matching() {
+    fn router() -> Router {
+        Router::new().get_fn(
+            "/users/:id",
+            |id: Path<String>| async move { id.into_inner() },
+        )
+    }
+
+    let app = Rapina::new().with_introspection(false).router(router());
+    let client = TestClient::new(app).await;
+    assert_eq!(
+        client.get("/USERS/42").send().await.status(),
+        StatusCode::NOT_FOUND
+    );
+
+    let app = Rapina::new()
+        .with_introspection(false)
+        .router(router().case_insensitive(true));
+    let client = TestClient::new(app).await;
+    let response = client.get("/USERS/42").send().await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.text(), "42");
+
+    // Captured values keep their case
+    assert_eq!(client.get("/Users/AbC").send().await.text(), "AbC");
+}
+
+#[tokio::test]
+async fn test_constrained_param_falls_through() {
+    let app = Rapina::new().with_introspection(false).router(
+        Router::new()
+            .get_fn(r"/users/:id(\d+)", |id: Path<u64>| async move {
+                format!("user {}", id.into_inner())
+            })
+            .get_fn("/users/:name", |name: Path<String>| async move {
+                format!("username {}", name.into_inner())
+            }),
+    );
+    let client = TestClient::new(app).await;
+
+    assert_eq!(client.get("/users/42").send().await.text(), "user 42");
+    assert_eq!(
+        client.get("/users/alice").send().await.text(),
+        "username alice"
+    );
+}
+
+#[tokio::test]
+async fn test_typed_param_constraint() {
+    let app = Rapina::new().with_introspection(false).router(
+        Router::new().get_fn("/items/:id<u64>", |route: MatchedPath| async move {
+            route.into_inner()
+        }),
+    );
+    let client = TestClient::new(app).await;
+
+    // The constraint is not part of the matched pattern
+    assert_eq!(client.get("/items/7").send().await.text(), "/items/:id");
+    assert_eq!(
+        client.get("/items/seven").send().await.status(),
+        StatusCode::NOT_FOUND
+    );
+}
+
+#[tokio::test]
+async fn test_constraint_in_group_prefix() {
+    let app = Rapina::new()
+        .with_introspection(false)
+        .router(Router::new().group(
+            "/orgs/:org<u32>",
+            Router::new().get_fn("/members", || async { "members" }),
+        ));
+    let client = TestClient::new(app).await;
+
+    assert_eq!(client.get("/orgs/1/members").send().await.text(), "members");
+    assert_eq!(
+        client.get("/orgs/acme/members").send().await.status(),
+        StatusCode::NOT_FOUND
+    );
+}
+
+#[test]
+#[should_panic(expected = "unknown type `number`")]
+fn test_unknown_constraint_type_panics() {
+    let _ = Router::new().get_fn("/items/:id<number>", || async { "item" });
+}
+
 #[tokio::test]
 async fn test_named_routes_for_introspection() {
     let app = Rapina::new().with_introspection(false).router(
@@ -222,3 +383,431 @@ async fn test_introspection_endpoint() {
     assert!(route_paths.contains(&"/health"));
     assert!(route_paths.contains(&"/users"));
 }
+
+#[derive(Deserialize)]
+struct Pagination {
+    page: u32,
+}
+
+#[derive(Deserialize)]
+struct Rename {
+    name: String,
+}
+
+fn closure_app() -> Rapina {
+    Rapina::new().with_introspection(false).router(
+        Router::new()
+            .get_fn("/", || async { "root" })
+            .get_fn("/users/:id", |id: Path<u64>| async move {
+                format!("user {}", id.into_inner())
+            })
+            .get_fn(
+                "/users/:id/posts",
+                |id: Path<u64>, query: Query<Pagination>| async move {
+                    format!("user {} page {}", id.into_inner(), query.0.page)
+                },
+            )
+            .put_fn(
+                "/users/:id",
+                |id: Path<u64>, query: Query<Pagination>, body: Json<Rename>| async move {
+                    format!(
+                        "user {} renamed to {} on page {}",
+                        id.into_inner(),
+                        body.into_inner().name,
+                        query.0.page
+                    )
+                },
+            )
+            .delete_fn("/users/:id", |_: Path<u64>| async {
+                StatusCode::NO_CONTENT
+            }),
+    )
+}
+
+#[tokio::test]
+async fn test_closure_without_extractors() {
+    let client = TestClient::new(closure_app()).await;
+    let response = client.get("/").send().await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.text(), "root");
+}
+
+#[tokio::test]
+async fn test_closure_with_one_extractor() {
+    let client = TestClient::new(closure_app()).await;
+    let response = client.get("/users/42").send().await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.text(), "user 42");
+}
+
+#[tokio::test]
+async fn test_closure_with_two_extractors() {
+    let client = TestClient::new(closure_app()).await;
+    let response = client.get("/users/7/posts?page=3").send().await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.text(), "user 7 page 3");
+}
+
+#[tokio::test]
+async fn test_closure_with_body_extractor() {
+    let client = TestClient::new(closure_app()).await;
+    let response = client
+        .put("/users/7?page=2")
+        .json(&serde_json::json!({"name": "alice"}))
+        .send()
+        .await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.text(), "user 7 renamed to alice on page 2");
+}
+
+#[tokio::test]
+async fn test_closure_extraction_error_is_returned() {
+    let client = TestClient::new(closure_app()).await;
+
+    let response = client.get("/users/abc").send().await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = client.get("/users/7/posts").send().await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_closure_delete_route() {
+    let client = TestClient::new(closure_app()).await;
+    let response = client.delete("/users/7").send().await;
+
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+}
+
+#[get("/users/:id")]
+async fn show_matched_path(route: MatchedPath) -> String {
+    route.into_inner()
+}
+
+#[tokio::test]
+async fn test_matched_path_extractor() {
+    let app = Rapina::new()
+        .with_introspection(false)
+        .router(Router::new().get("/users/:id", show_matched_path));
+
+    let client = TestClient::new(app).await;
+    let response = client.get("/users/42").send().await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.text(), "/users/:id");
+}
+
+#[tokio::test]
+async fn test_matched_path_in_group() {
+    let app = Rapina::new()
+        .with_introspection(false)
+        .router(Router::new().group(
+            "/api",
+            Router::new().get_fn("/posts/:slug", |route: MatchedPath| async move {
+                route.into_inner()
+            }),
+        ));
+
+    let client = TestClient::new(app).await;
+    let response = client.get("/api/posts/hello-world").send().await;
+
+    assert_eq!(response.text(), "/api/posts/:slug");
+}
+
+#[get("/proxy/:path")]
+async fn proxy(path: Path<String>) -> String {
+    format!("proxied {}", path.into_inner())
+}
+
+#[tokio::test]
+async fn test_any_registers_every_method() {
+    let router = Router::new().any("/proxy/:path", proxy);
+    let methods: Vec<_> = router.routes().into_iter().map(|r| r.method).collect();
+    assert_eq!(
+        methods,
+        ["GET", "POST", "PUT", "PATCH", "DELETE", "HEAD", "OPTIONS"]
+    );
+
+    let app = Rapina::new().with_introspection(false).router(router);
+    let client = TestClient::new(app).await;
+
+    for method in [
+        Method::GET,
+        Method::POST,
+        Method::PUT,
+        Method::PATCH,
+        Method::DELETE,
+    ] {
+        let response = client.request(method.clone(), "/proxy/a").send().await;
+        assert_eq!(response.status(), StatusCode::OK, "{}", method);
+        assert_eq!(response.text(), "proxied a", "{}", method);
+    }
+}
+
+#[tokio::test]
+async fn test_on_registers_only_given_methods() {
+    let app = Rapina::new()
+        .with_introspection(false)
+        .router(Router::new().on(&[Method::PUT, Method::PATCH], "/proxy/:path", proxy));
+    let client = TestClient::new(app).await;
+
+    assert_eq!(client.put("/proxy/b").send().await.text(), "proxied b");
+    assert_eq!(client.patch("/proxy/b").send().await.text(), "proxied b");
+    assert_eq!(
+        client.get("/proxy/b").send().await.status(),
+        StatusCode::NOT_FOUND
+    );
+}
+
+fn fallback_app() -> Rapina {
+    Rapina::new()
+        .with_introspection(false)
+        .fallback(|uri: Uri, ctx: Context| async move {
+            Error::not_found(format!("no route for {}", uri.path())).with_trace_id(ctx.trace_id())
+        })
+        .method_not_allowed(|method: Method| async move {
+            Error::new(
+                405,
+                "METHOD_NOT_ALLOWED",
+                format!("{} is not supported here", method),
+            )
+        })
+        .router(
+            Router::new()
+                .get_fn("/users/:id", || async { "user" })
+                .delete_fn("/users/:id", || async { StatusCode::NO_CONTENT }),
+        )
+}
+
+#[tokio::test]
+async fn test_custom_fallback_returns_json_404() {
+    let client = TestClient::new(fallback_app()).await;
+
+    let response = client.get("/nope?x=1").send().await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert_eq!(response.headers()["content-type"], "application/json");
+    let json: serde_json::Value = response.json();
+    assert_eq!(json["error"]["code"], "NOT_FOUND");
+    assert_eq!(json["error"]["message"], "no route for /nope");
+    assert!(!json["trace_id"].as_str().unwrap().is_empty());
+
+    // Matched routes are unaffected
+    let response = client.get("/users/1").send().await;
+    assert_eq!(response.text(), "user");
+}
+
+#[tokio::test]
+async fn test_custom_method_not_allowed() {
+    let client = TestClient::new(fallback_app()).await;
+
+    let response = client.post("/users/1").send().await;
+    assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+    assert_eq!(response.headers()["allow"], "GET, DELETE");
+    let json: serde_json::Value = response.json();
+    assert_eq!(json["error"]["message"], "POST is not supported here");
+}
+
+#[tokio::test]
+async fn test_wrong_method_uses_fallback_without_method_not_allowed() {
+    let app = Rapina::new()
+        .with_introspection(false)
+        .fallback(|| async { Error::not_found("custom") })
+        .router(Router::new().get_fn("/users", || async { "users" }));
+    let client = TestClient::new(app).await;
+
+    let response = client.post("/users").send().await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert!(response.headers().get("allow").is_none());
+    let json: serde_json::Value = response.json();
+    assert_eq!(json["error"]["message"], "custom");
+}
+
+#[tokio::test]
+async fn test_head_response_declares_body_length() {
+    let app = Rapina::new()
+        .with_introspection(false)
+        .router(
+            Router::new().route(Method::HEAD, "/users/:id", |_, _, _| async {
+                Json(serde_json::json!({ "id": 1, "name": "Ada" }))
+            }),
+        );
+    let client = TestClient::new(app).await;
+
+    let response = client.request(Method::HEAD, "/users/1").send().await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers()["content-length"],
+        r#"{"id":1,"name":"Ada"}"#.len().to_string()
+    );
+    assert!(response.text().is_empty());
+}
+
+#[tokio::test]
+async fn test_json_config_camel_cases_response_keys() {
+    #[derive(Serialize)]
+    struct Order {
+        order_id: u64,
+        shipping_address: Address,
+        coupon_code: Option<String>,
+        #[serde(rename = "SKU")]
+        sku: String,
+    }
+
+    #[derive(Serialize)]
+    struct Address {
+        postal_code: String,
+    }
+
+    let app = Rapina::new()
+        .with_introspection(false)
+        .with_json_config(JsonConfig::new().camel_case(true).skip_nulls(true))
+        .router(Router::new().get_fn("/orders/1", || async {
+            Json(Order {
+                order_id: 1,
+                shipping_address: Address {
+                    postal_code: "10115".to_string(),
+                },
+                coupon_code: None,
+                sku: "A-1".to_string(),
+            })
+        }));
+    let client = TestClient::new(app).await;
+
+    let response = client.get("/orders/1").send().await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers()["content-length"],
+        response.text().len().to_string()
+    );
+    let json: serde_json::Value = response.json();
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "orderId": 1,
+            "shippingAddress": { "postalCode": "10115" },
+            "SKU": "A-1",
+        })
+    );
+}
+
+#[tokio::test]
+async fn test_static_route_wins_regardless_of_order() {
+    let static_first = Router::new()
+        .get_fn("/users/me", || async { "me" })
+        .get_fn("/users/:id", |id: Path<String>| async move {
+            format!("user {}", id.into_inner())
+        });
+    let dynamic_first = Router::new()
+        .get_fn("/users/:id", |id: Path<String>| async move {
+            format!("user {}", id.into_inner())
+        })
+        .get_fn("/users/me", || async { "me" });
+
+    for router in [static_first, dynamic_first] {
+        let app = Rapina::new().with_introspection(false).router(router);
+        let client = TestClient::new(app).await;
+
+        assert_eq!(client.get("/users/me").send().await.text(), "me");
+        assert_eq!(client.get("/users/42").send().await.text(), "user 42");
+    }
+}
+
+#[tokio::test]
+async fn test_host_routing_dispatches_by_host_header() {
+    let api = Router::new().get_fn("/", || async { "api" });
+    let admin = Router::new().get_fn("/", |host: Host| async move {
+        format!("admin at {}", host.as_str())
+    });
+    let app = Rapina::new()
+        .with_introspection(false)
+        .host("api.example.com", api)
+        .host("admin.example.com", admin)
+        .router(Router::new().get_fn("/", || async { "default" }));
+    let client = TestClient::new(app).await;
+
+    let response = client
+        .get("/")
+        .header("host", "api.example.com")
+        .send()
+        .await;
+    assert_eq!(response.text(), "api");
+
+    let response = client
+        .get("/")
+        .header("host", "Admin.Example.com:8080")
+        .send()
+        .await;
+    assert_eq!(response.text(), "admin at Admin.Example.com");
+
+    let response = client
+        .get("/")
+        .header("host", "other.example.com")
+        .send()
+        .await;
+    assert_eq!(response.text(), "default");
+
+    let response = client
+        .get("/missing")
+        .header("host", "api.example.com")
+        .send()
+        .await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_host_router_shares_app_settings() {
+    let api = Router::new().get_fn("/users", || async { "users" });
+    let app = Rapina::new()
+        .with_introspection(false)
+        .fallback(|| async { (StatusCode::NOT_FOUND, "no such page".to_string()) })
+        .host("api.example.com", api)
+        .router(Router::new().case_insensitive(true));
+    let client = TestClient::new(app).await;
+
+    let response = client
+        .get("/USERS")
+        .header("host", "api.example.com")
+        .send()
+        .await;
+    assert_eq!(response.text(), "users");
+
+    let response = client
+        .get("/missing")
+        .header("host", "api.example.com")
+        .send()
+        .await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert_eq!(response.text(), "no such page");
+}
+
+#[tokio::test]
+async fn test_host_routes_are_listed_and_documented() {
+    let app = || {
+        Rapina::new()
+            .openapi("Test", "1.0.0")
+            .host(
+                "api.example.com",
+                Router::new().get_named("/status", "api_status", |_, _, _| async { "ok" }),
+            )
+            .router(Router::new().get_named("/", "home", |_, _, _| async { "home" }))
+    };
+
+    let spec: serde_json::Value = serde_json::from_str(&app().export_openapi()).unwrap();
+    assert_eq!(
+        spec["paths"]["/status"]["get"]["servers"][0]["url"],
+        "//api.example.com"
+    );
+    assert!(spec["paths"]["/"]["get"].get("servers").is_none());
+
+    let client = TestClient::new(app()).await;
+    let routes: Vec<serde_json::Value> = client.get("/__rapina/routes").send().await.json();
+    let status = routes
+        .iter()
+        .find(|route| route["path"] == "/status")
+        .unwrap();
+    assert_eq!(status["host"], "api.example.com");
+}
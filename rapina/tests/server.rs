@@ -0,0 +1,88 @@
+//! Integration tests for starting the server.
+
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use http::StatusCode;
+use rapina::prelude::*;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+fn app() -> Rapina {
+    Rapina::new()
+        .with_introspection(true)
+        .router(Router::new().get_fn("/", || async { "hello" }))
+}
+
+async fn get<S>(stream: &mut S, path: &str) -> String
+where
+    S: AsyncReadExt + AsyncWriteExt + Unpin,
+{
+    stream
+        .write_all(
+            format!(
+                "GET {} HTTP/1.1\r\nhost: localhost\r\nconnection: close\r\n\r\n",
+                path
+            )
+            .as_bytes(),
+        )
+        .await
+        .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    response
+}
+
+#[tokio::test]
+async fn test_listen_on_pre_bound_listener() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr: SocketAddr = listener.local_addr().unwrap();
+    tokio::spawn(app().listen_on(listener));
+
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    let response = get(&mut stream, "/").await;
+
+    assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+    assert!(response.ends_with("hello"), "{}", response);
+}
+
+#[tokio::test]
+async fn test_listen_on_prepares_app() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(app().listen_on(listener));
+
+    // Introspection routes are only added when the app is prepared
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    let response = get(&mut stream, "/__rapina/routes").await;
+
+    assert!(
+        response.starts_with(&format!("HTTP/1.1 {}", StatusCode::OK.as_u16())),
+        "{}",
+        response
+    );
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_listen_unix() {
+    let path = std::env::temp_dir().join(format!("rapina-test-{}.sock", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    tokio::spawn(app().listen_unix(path.clone()));
+
+    let mut stream = None;
+    for _ in 0..50 {
+        if let Ok(s) = tokio::net::UnixStream::connect(&path).await {
+            stream = Some(s);
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    let mut stream = stream.expect("unix socket server did not start");
+    let response = get(&mut stream, "/").await;
+
+    assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+    assert!(response.ends_with("hello"), "{}", response);
+
+    std::fs::remove_file(&path).ok();
+}
@@ -0,0 +1,82 @@
+//! Integration tests for streamed response bodies.
+
+use futures_util::stream;
+use http::StatusCode;
+use rapina::prelude::*;
+use rapina::testing::TestClient;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Row {
+    id: u32,
+    name: String,
+}
+
+#[get("/rows")]
+async fn export_rows() -> JsonStream<impl futures_util::Stream<Item = Row> + Send> {
+    JsonStream::new(stream::iter((0..1000).map(|id| Row {
+        id,
+        name: format!("row {}", id),
+    })))
+}
+
+#[tokio::test]
+async fn test_json_stream_of_many_items() {
+    let app = Rapina::new()
+        .with_introspection(false)
+        .router(Router::new().get("/rows", export_rows));
+    let client = TestClient::new(app).await;
+
+    let response = client.get("/rows").send().await;
+    assert_eq!(response.status(), StatusCode::OK);
+    response.assert_header("content-type", "application/json");
+    assert!(response.headers().get("content-length").is_none());
+    response.assert_header("transfer-encoding", "chunked");
+
+    let rows: Vec<Row> = response.json();
+    assert_eq!(rows.len(), 1000);
+    assert_eq!(
+        rows[999],
+        Row {
+            id: 999,
+            name: "row 999".to_string()
+        }
+    );
+}
+
+#[tokio::test]
+async fn test_json_stream_empty() {
+    let app =
+        Rapina::new()
+            .with_introspection(false)
+            .router(Router::new().get_fn("/rows", || async {
+                JsonStream::new(stream::empty::<Row>())
+            }));
+    let client = TestClient::new(app).await;
+
+    let response = client.get("/rows").send().await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.text(), "[]");
+}
+
+#[tokio::test]
+async fn test_download_streams_with_filename() {
+    let app = Rapina::new()
+        .with_introspection(false)
+        .router(Router::new().get_fn("/export.csv", || async {
+            let rows = stream::iter((0..3).map(|id| {
+                Ok::<_, std::io::Error>(bytes::Bytes::from(format!("{},row {}\n", id, id)))
+            }));
+            Download::from_stream("rows \"2024\".csv", rows)
+        }));
+    let client = TestClient::new(app).await;
+
+    let response = client.get("/export.csv").send().await;
+    assert_eq!(response.status(), StatusCode::OK);
+    response.assert_header("content-type", "text/csv; charset=utf-8");
+    response.assert_header(
+        "content-disposition",
+        "attachment; filename=\"rows _2024_.csv\"",
+    );
+    response.assert_header("transfer-encoding", "chunked");
+    assert_eq!(response.text(), "0,row 0\n1,row 1\n2,row 2\n");
+}
@@ -0,0 +1,130 @@
+//! Integration tests for TLS termination.
+
+#![cfg(feature = "tls")]
+
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use http_body_util::{BodyExt, Empty};
+use hyper_util::rt::{TokioExecutor, TokioIo};
+use rapina::prelude::*;
+use rapina::server::{HttpProtocol, ServerConfig};
+use tokio::net::TcpStream;
+use tokio_rustls::TlsConnector;
+use tokio_rustls::client::TlsStream;
+use tokio_rustls::rustls::pki_types::pem::PemObject;
+use tokio_rustls::rustls::pki_types::{CertificateDer, ServerName};
+use tokio_rustls::rustls::{self, RootCertStore};
+
+fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/tls")
+        .join(name)
+}
+
+fn app() -> Rapina {
+    Rapina::new()
+        .with_introspection(false)
+        .router(Router::new().get_fn("/", || async { "secure" }))
+}
+
+/// Starts the app over TLS on a free port.
+async fn start(app: Rapina) -> SocketAddr {
+    let addr = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+    tokio::spawn(async move {
+        app.listen_tls(&addr.to_string(), fixture("cert.pem"), fixture("key.pem"))
+            .await
+    });
+
+    for _ in 0..50 {
+        if TcpStream::connect(addr).await.is_ok() {
+            return addr;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    panic!("TLS server did not start");
+}
+
+async fn connect(addr: SocketAddr, alpn: &[&[u8]]) -> TlsStream<TcpStream> {
+    let mut roots = RootCertStore::empty();
+    roots
+        .add(CertificateDer::from_pem_file(fixture("ca.pem")).unwrap())
+        .unwrap();
+
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let mut config = rustls::ClientConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()
+        .unwrap()
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    config.alpn_protocols = alpn.iter().map(|p| p.to_vec()).collect();
+
+    let stream = TcpStream::connect(addr).await.unwrap();
+    TlsConnector::from(Arc::new(config))
+        .connect(ServerName::try_from("localhost").unwrap(), stream)
+        .await
+        .unwrap()
+}
+
+fn request(addr: SocketAddr) -> http::Request<Empty<bytes::Bytes>> {
+    http::Request::builder()
+        .uri(format!("https://localhost:{}/", addr.port()))
+        .header("host", "localhost")
+        .body(Empty::new())
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_https_get() {
+    let addr = start(app()).await;
+    let stream = connect(addr, &[b"http/1.1"]).await;
+
+    let (mut sender, conn) = hyper::client::conn::http1::handshake(TokioIo::new(stream))
+        .await
+        .unwrap();
+    tokio::spawn(conn);
+
+    let response = sender.send_request(request(addr)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    assert_eq!(&body[..], b"secure");
+}
+
+#[tokio::test]
+async fn test_https_negotiates_http2() {
+    let addr =
+        start(app().with_server_config(ServerConfig::new().protocol(HttpProtocol::Auto))).await;
+    let stream = connect(addr, &[b"h2", b"http/1.1"]).await;
+    assert_eq!(stream.get_ref().1.alpn_protocol(), Some(&b"h2"[..]));
+
+    let (mut sender, conn) =
+        hyper::client::conn::http2::handshake(TokioExecutor::new(), TokioIo::new(stream))
+            .await
+            .unwrap();
+    tokio::spawn(conn);
+
+    let response = sender.send_request(request(addr)).await.unwrap();
+    assert_eq!(response.version(), http::Version::HTTP_2);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    assert_eq!(&body[..], b"secure");
+}
+
+#[tokio::test]
+async fn test_listen_tls_rejects_bad_key() {
+    let err = app()
+        .listen_tls(
+            "127.0.0.1:0",
+            fixture("cert.pem"),
+            fixture("missing-key.pem"),
+        )
+        .await
+        .unwrap_err();
+
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    assert!(err.to_string().contains("missing-key.pem"), "{}", err);
+}
@@ -0,0 +1,219 @@
+//! Integration tests for correlating log events with requests.
+
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+use rapina::prelude::*;
+use rapina::testing::TestClient;
+use tracing_subscriber::prelude::*;
+
+/// Collects formatted log lines in memory.
+#[derive(Clone, Default)]
+struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Buffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Buffer {
+    fn lines(&self) -> Vec<serde_json::Value> {
+        String::from_utf8(self.0.lock().unwrap().clone())
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+}
+
+#[tokio::test]
+async fn test_handler_events_carry_request_trace_id() {
+    let buffer = Buffer::default();
+    let writer = buffer.clone();
+    let subscriber = tracing_subscriber::registry().with(
+        tracing_subscriber::fmt::layer()
+            .json()
+            .with_span_list(true)
+            .with_writer(move || writer.clone()),
+    );
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    let app = Rapina::new()
+        .with_introspection(false)
+        .router(
+            Router::new().get_fn("/orders/:id", |ctx: Context| async move {
+                tracing::info!(order = 7, "loading order");
+                ctx.trace_id().to_string()
+            }),
+        );
+    let client = TestClient::new(app).await;
+
+    let response = client.get("/orders/7").send().await;
+    let trace_id = response.text();
+
+    let lines = buffer.lines();
+    let event = lines
+        .iter()
+        .find(|line| line["fields"]["message"] == "loading order")
+        .expect("handler event not captured");
+    let span = &event["spans"][0];
+    assert_eq!(span["name"], "request");
+    assert_eq!(span["trace_id"], trace_id.as_str());
+    assert_eq!(span["method"], "GET");
+    assert_eq!(span["path"], "/orders/7");
+}
+
+#[tokio::test]
+async fn test_each_request_gets_its_own_span() {
+    let buffer = Buffer::default();
+    let writer = buffer.clone();
+    let subscriber = tracing_subscriber::registry().with(
+        tracing_subscriber::fmt::layer()
+            .json()
+            .with_writer(move || writer.clone()),
+    );
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    let app = Rapina::new()
+        .with_introspection(false)
+        .router(Router::new().get_fn("/", || async {
+            tracing::info!("handled");
+            "ok"
+        }));
+    let client = TestClient::new(app).await;
+
+    client.get("/").send().await;
+    client.get("/").send().await;
+
+    let trace_ids: Vec<_> = buffer
+        .lines()
+        .iter()
+        .filter(|line| line["fields"]["message"] == "handled")
+        .map(|line| line["span"]["trace_id"].as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(trace_ids.len(), 2);
+    assert_ne!(trace_ids[0], trace_ids[1]);
+}
+
+#[tokio::test]
+async fn test_error_source_is_logged_but_not_returned() {
+    let buffer = Buffer::default();
+    let writer = buffer.clone();
+    let subscriber = tracing_subscriber::registry().with(
+        tracing_subscriber::fmt::layer()
+            .json()
+            .with_writer(move || writer.clone()),
+    );
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    let app = Rapina::new()
+        .with_introspection(false)
+        .router(Router::new().get_fn("/", |ctx: Context| async move {
+            let cause = std::io::Error::other("password authentication failed for user app");
+            Err::<String, _>(
+                Error::internal_with_source("something went wrong", cause)
+                    .with_trace_id(ctx.trace_id()),
+            )
+        }));
+    let client = TestClient::new(app).await;
+
+    let response = client.get("/").send().await;
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    let body = response.text();
+    assert!(!body.contains("password authentication"), "{}", body);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["error"]["message"], "something went wrong");
+
+    let lines = buffer.lines();
+    let event = lines
+        .iter()
+        .find(|line| line["target"] == "rapina::error")
+        .expect("error source not logged");
+    assert_eq!(
+        event["fields"]["source"],
+        "password authentication failed for user app"
+    );
+    assert_eq!(event["fields"]["message"], "something went wrong");
+    assert_eq!(event["fields"]["trace_id"], json["trace_id"]);
+}
+
+#[tokio::test]
+async fn test_body_log_skips_bodies_over_buffer_limit() {
+    let buffer = Buffer::default();
+    let writer = buffer.clone();
+    let subscriber = tracing_subscriber::registry().with(
+        tracing_subscriber::fmt::layer()
+            .json()
+            .with_writer(move || writer.clone()),
+    );
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    let app = Rapina::new()
+        .with_introspection(false)
+        .middleware(rapina::middleware::BodyLogMiddleware::new(1024).buffer_limit(32))
+        .router(
+            Router::new().post_fn("/echo", |body: Json<serde_json::Value>| async move { body }),
+        );
+    let client = TestClient::new(app).await;
+
+    let response = client
+        .post("/echo")
+        .json(&serde_json::json!({ "a": 1 }))
+        .send()
+        .await;
+    assert_eq!(response.text(), r#"{"a":1}"#);
+    let long = serde_json::json!({ "a": "a".repeat(64) });
+    let response = client.post("/echo").json(&long).send().await;
+    assert_eq!(response.json::<serde_json::Value>(), long);
+
+    let bodies: Vec<_> = buffer
+        .lines()
+        .iter()
+        .filter(|line| line["fields"]["message"] == "request body")
+        .map(|line| line["fields"]["body"].as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(bodies, [r#"{"a":1}"#, "[72 bytes, not logged]"]);
+}
+
+#[tokio::test]
+async fn test_request_span_carries_incoming_trace_id() {
+    use rapina::middleware::{TRACE_ID_HEADER, TraceIdMiddleware};
+
+    let buffer = Buffer::default();
+    let writer = buffer.clone();
+    let subscriber = tracing_subscriber::registry().with(
+        tracing_subscriber::fmt::layer()
+            .json()
+            .with_writer(move || writer.clone()),
+    );
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    let app = Rapina::new()
+        .with_introspection(false)
+        .middleware(TraceIdMiddleware::new())
+        .router(Router::new().get_fn("/", |ctx: Context| async move {
+            tracing::info!("handled");
+            ctx.trace_id().to_string()
+        }));
+    let client = TestClient::new(app).await;
+
+    let response = client
+        .get("/")
+        .header(TRACE_ID_HEADER, "upstream-1")
+        .send()
+        .await;
+    assert_eq!(response.text(), "upstream-1");
+
+    let lines = buffer.lines();
+    let event = lines
+        .iter()
+        .find(|line| line["fields"]["message"] == "handled")
+        .expect("handler event not captured");
+    assert_eq!(event["span"]["trace_id"], "upstream-1");
+}